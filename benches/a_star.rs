@@ -28,9 +28,9 @@ pub fn criterion_benchmark(c: &mut Criterion) {
 criterion_group!(benches, criterion_benchmark);
 criterion_main!(benches);
 
-fn bench_scenes(map: &MovingAiMap, scenes: &Vec<SceneRecord>) {
+fn bench_scenes(map: &MovingAiMap, scenes: &[SceneRecord]) {
     for scene in scenes {
-        a_star_path(&map, scene.start_pos, scene.goal_pos);
+        let _ = a_star_path(map, scene.start_pos, scene.goal_pos);
     }
 }
 
@@ -0,0 +1,165 @@
+use crate::grid::Coords2D;
+use crate::grid::Map2D;
+use crate::grid::MovingAiMap;
+
+use crate::utils::distance;
+use crate::{
+    Algorithm, MapDiff, MemoryFootprint, PathError, Route, RouteSteps, SearchContext, SearchOptions,
+};
+
+///What [`ActivePath::update`] had to do to keep the route clear.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PathUpdate {
+    ///The rest of the route is still clear.
+    Unchanged,
+    ///The blocked stretch was bypassed, keeping the rest of the route.
+    Repaired,
+    ///A new route to the goal was planned from the agent's position.
+    Replanned,
+}
+
+///A route being followed by an agent, kept clear as the map changes.
+///
+///Each [`update`](ActivePath::update) drops the steps the agent has passed and checks the tiles
+///and moves left on the route against the current map. When one has become blocked, the stretch
+///up to the last blocked move is searched around and spliced into the route, and if that fails
+///the whole route is replanned. Only the route is checked rather than the whole map, so updating
+///every tick stays cheap, but tiles that open up are not taken as shortcuts.
+///
+///The route lists every tile, whatever [`RouteSteps`] the options ask for.
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+/// use blitz_path::{ActivePath, Algorithm, PathUpdate, SearchOptions};
+///
+/// let map = movingai::parser::parse_map_file(Path::new("./tests/map/maze512-32-9.map")).expect("Could not load map.");
+/// let mut path = ActivePath::new(&map, (1, 1), (8, 1), Algorithm::Dijkstra, &SearchOptions::new()).unwrap();
+///
+/// assert_eq!(path.update(&map, (2, 1)), Ok(PathUpdate::Unchanged));
+/// assert_eq!(path.route().distance(), 6.0);
+/// ```
+pub struct ActivePath {
+    algorithm: Algorithm,
+    options: SearchOptions,
+    context: SearchContext,
+    goal: Coords2D,
+    //Steps left to travel, goal first and the agent's tile last
+    route: Route,
+}
+
+impl ActivePath {
+    ///Plans a route from `start` to `goal` with `algorithm`, using `options` for every later search too.
+    pub fn new(
+        map: &MovingAiMap,
+        start: Coords2D,
+        goal: Coords2D,
+        algorithm: Algorithm,
+        options: &SearchOptions,
+    ) -> Result<ActivePath, PathError> {
+        let options = options
+            .clone()
+            .route_steps(RouteSteps::Cells)
+            .include_start(true)
+            .repeat_goal(false);
+        let mut context = SearchContext::new();
+        let route = algorithm.search(&mut context, map, start, goal, &options)?;
+        Ok(ActivePath {
+            algorithm,
+            options,
+            context,
+            goal,
+            route,
+        })
+    }
+
+    ///Returns the rest of the route, from the agent's last known tile to the goal.
+    pub fn route(&self) -> &Route {
+        &self.route
+    }
+
+    ///Returns the goal the route leads to.
+    pub fn goal(&self) -> Coords2D {
+        self.goal
+    }
+
+    ///Returns true if `diff` changes any tile the rest of the route depends on,
+    ///so only the paths an edit affects need an [`update`](ActivePath::update).
+    pub fn is_affected(&self, diff: &MapDiff) -> bool {
+        diff.touches(&self.route)
+    }
+
+    ///Moves the agent to `position` and makes sure the rest of the route is still clear on `map`,
+    ///repairing or replanning it if not. An agent that has left its route is replanned from `position`.
+    ///
+    ///If no new route can be found the error is returned and the old route is kept,
+    ///so the agent can wait for the way to clear and try again.
+    pub fn update(
+        &mut self,
+        map: &MovingAiMap,
+        position: Coords2D,
+    ) -> Result<PathUpdate, PathError> {
+        let mut steps = self.route.steps();
+        let current = match steps.iter().rposition(|step| *step == position) {
+            Some(current) => current,
+            None => return self.replan(map, position),
+        };
+        steps.truncate(current + 1);
+
+        //Find the move nearest the goal that is no longer possible
+        let blocked = steps
+            .windows(2)
+            .position(|pair| !map.is_traversable_from(pair[1], pair[0]));
+        let rejoin = match blocked {
+            //Rejoin past the blocked move, or past its tile if that is blocked too
+            Some(rejoin) if map.is_traversable(steps[rejoin]) => rejoin,
+            Some(rejoin) => rejoin.saturating_sub(1),
+            None => {
+                self.route = Route::from((length(&steps), steps));
+                return Ok(PathUpdate::Unchanged);
+            }
+        };
+        if rejoin == 0 {
+            return self.replan(map, position);
+        }
+
+        let bypass = match self.algorithm.search(
+            &mut self.context,
+            map,
+            position,
+            steps[rejoin],
+            &self.options,
+        ) {
+            Ok(bypass) => bypass,
+            Err(PathError::Cancelled) => return Err(PathError::Cancelled),
+            Err(_) => return self.replan(map, position),
+        };
+        steps.truncate(rejoin);
+        steps.extend(bypass.steps());
+        self.route = Route::from((length(&steps), steps));
+        Ok(PathUpdate::Repaired)
+    }
+
+    //Plans a new route to the goal, keeping the old one on failure
+    fn replan(&mut self, map: &MovingAiMap, position: Coords2D) -> Result<PathUpdate, PathError> {
+        self.route =
+            self.algorithm
+                .search(&mut self.context, map, position, self.goal, &self.options)?;
+        Ok(PathUpdate::Replanned)
+    }
+}
+
+//Total length of a route listing every tile
+fn length(steps: &[Coords2D]) -> f64 {
+    steps
+        .windows(2)
+        .map(|pair| distance(pair[0], pair[1]))
+        .sum()
+}
+
+impl MemoryFootprint for ActivePath {
+    fn heap_size(&self) -> usize {
+        self.context.heap_size() + self.route.heap_size()
+    }
+}
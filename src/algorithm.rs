@@ -0,0 +1,61 @@
+use crate::grid::Coords2D;
+use crate::grid::MovingAiMap;
+
+use crate::{PathError, Route, SearchContext, SearchOptions};
+
+///The pathfinding algorithms offered by this crate, for choosing one at runtime with [`find_path`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Algorithm {
+    ///A* with a straight line distance heuristic.
+    AStar,
+    ///Dijkstra's algorithm, exploring evenly in every direction.
+    Dijkstra,
+    ///Jump point search, the fastest on uniform cost grids.
+    Jps,
+}
+
+impl Algorithm {
+    //Runs this algorithm, keeping the search state in the context
+    pub(crate) fn search(
+        self,
+        context: &mut SearchContext,
+        map: &MovingAiMap,
+        start: Coords2D,
+        goal: Coords2D,
+        options: &SearchOptions,
+    ) -> Result<Route, PathError> {
+        match self {
+            Algorithm::AStar => context.a_star_path(map, start, goal, options),
+            Algorithm::Dijkstra => context.dijkstra_path(map, start, goal, options),
+            Algorithm::Jps => context.jps_path(map, start, goal, options),
+        }
+    }
+}
+
+///Creates a new route using `algorithm`, configured by `options`, so the algorithm can be chosen at runtime
+///or swapped without changing the call.
+///Use [`SearchContext::find_path`] to keep the search state between searches.
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+/// use blitz_path::{Algorithm, SearchOptions};
+///
+/// let map = movingai::parser::parse_map_file(Path::new("./tests/map/maze512-32-9.map")).expect("Could not load map.");
+///
+/// let options = SearchOptions::new();
+/// for algorithm in [Algorithm::AStar, Algorithm::Dijkstra, Algorithm::Jps] {
+///     let path = blitz_path::find_path(algorithm, &map, (1, 1), (5, 5), &options).unwrap();
+///     assert_eq!(path.distance() as f32, 4.0 * 2f32.sqrt());
+/// }
+/// ```
+pub fn find_path(
+    algorithm: Algorithm,
+    map: &MovingAiMap,
+    start: Coords2D,
+    goal: Coords2D,
+    options: &SearchOptions,
+) -> Result<Route, PathError> {
+    algorithm.search(&mut SearchContext::new(), map, start, goal, options)
+}
@@ -0,0 +1,163 @@
+use crate::grid::Coords2D;
+use crate::grid::MovingAiMap;
+
+use crate::astar::search;
+use crate::trace::instrument;
+use crate::utils::distance;
+use crate::{
+    Direction, DirectionalCosts, MemoryFootprint, PathError, Route, SearchContext, SearchOptions,
+};
+
+///Movement costs that depend on the direction of travel across the whole map, such as wind making
+///eastward moves cheaper, optionally combined with per-tile [`DirectionalCosts`].
+///
+///Unlike plain distance, cheap directions let routes cost less than the distance they cover,
+///so [`a_star_path_anisotropic`] guides its search with [`Anisotropy::lower_bound`], which stays
+///admissible under every configured multiplier rather than assuming each step costs its length.
+///Multipliers must be positive.
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+/// use blitz_path::{Anisotropy, Direction, SearchOptions};
+///
+/// let map = movingai::parser::parse_map_file(Path::new("./tests/map/maze512-32-9.map")).expect("Could not load map.");
+///
+/// // A tail wind from the west halves the cost of heading east
+/// let wind = Anisotropy::wind(Direction::East, 0.5);
+/// let path = blitz_path::a_star_path_anisotropic(&map, (1, 1), (5, 1), &wind, &SearchOptions::new()).unwrap();
+/// assert_eq!(path.distance(), 2.0);
+/// assert_eq!(wind.lower_bound((1, 1), (5, 1)), 2.0);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Anisotropy {
+    //Multiplier for moving in each direction anywhere, in the order of Direction::ALL
+    global: [f64; 8],
+    cells: Option<DirectionalCosts>,
+    //Cheapest multiplier any step in each direction can have, or None if no step may take it
+    lowest: [Option<f64>; 8],
+}
+
+impl Anisotropy {
+    ///Creates costs where a step in each direction, in the order of [`Direction::ALL`],
+    ///costs its distance times the matching multiplier.
+    pub fn new(multipliers: [f64; 8]) -> Anisotropy {
+        Anisotropy {
+            global: multipliers,
+            cells: None,
+            lowest: multipliers.map(Some),
+        }
+    }
+
+    ///Creates costs where steps are cheaper the more closely they follow `direction` and dearer the more
+    ///they oppose it. The multiplier is one minus `strength` times the cosine of the angle between the step
+    ///and the wind, so `strength` should be below 1.0 to keep every step's cost positive.
+    pub fn wind(direction: Direction, strength: f64) -> Anisotropy {
+        Anisotropy::new(Direction::ALL.map(|other| 1.0 - strength * direction.alignment(other)))
+    }
+
+    ///Combines these costs with per-tile `cells`, multiplying the two together for every step.
+    ///Steps `cells` forbids are not allowed.
+    pub fn with_cells(mut self, cells: DirectionalCosts) -> Anisotropy {
+        let global = self.global;
+        self.lowest = Direction::ALL.map(|direction| {
+            cells
+                .lowest(direction)
+                .map(|multiplier| multiplier * global[direction as usize])
+        });
+        self.cells = Some(cells);
+        self
+    }
+
+    ///Returns the multiplier for moving anywhere in `direction`, before any per-tile costs.
+    pub fn multiplier(&self, direction: Direction) -> f64 {
+        self.global[direction as usize]
+    }
+
+    ///Returns the per-tile costs combined with these, if any.
+    pub fn cells(&self) -> Option<&DirectionalCosts> {
+        self.cells.as_ref()
+    }
+
+    ///Returns a step cost for the weighted searches, giving the distance of each step times the multiplier
+    ///for its direction, and times the multiplier for leaving its tile that way if per-tile costs are set.
+    pub fn step_cost(&self) -> impl Fn(Coords2D, Coords2D) -> Option<f64> + '_ {
+        move |from, to| {
+            let direction = Direction::between(from, to)?;
+            let cell = match &self.cells {
+                Some(cells) => cells.get(from, direction)?,
+                None => 1.0,
+            };
+            Some(distance(from, to) * self.global[direction as usize] * cell)
+        }
+    }
+
+    ///Returns a cost no route from `from` to `to` can beat, ignoring walls.
+    ///It is the cheapest way of covering the offset between them with steps in at most two directions,
+    ///each charged at the lowest multiplier any tile has for it, which no mix of more directions can improve on.
+    ///Returns infinity if no allowed direction can reach `to`.
+    pub fn lower_bound(&self, from: Coords2D, to: Coords2D) -> f64 {
+        let offset = (to.0 as f64 - from.0 as f64, to.1 as f64 - from.1 as f64);
+        if offset == (0.0, 0.0) {
+            return 0.0;
+        }
+
+        let mut best = f64::INFINITY;
+        for (i, first) in Direction::ALL.iter().enumerate() {
+            for (j, second) in Direction::ALL.iter().enumerate().skip(i + 1) {
+                let (a, b) = match (self.lowest[i], self.lowest[j]) {
+                    (Some(a), Some(b)) => (a, b),
+                    _ => continue,
+                };
+                let (ux, uy) = first.offset();
+                let (wx, wy) = second.offset();
+                let (ux, uy, wx, wy) = (f64::from(ux), f64::from(uy), f64::from(wx), f64::from(wy));
+                let determinant = ux * wy - uy * wx;
+                if determinant == 0.0 {
+                    continue;
+                }
+                //Number of steps in each direction that add up to the offset
+                let steps_first = (offset.0 * wy - offset.1 * wx) / determinant;
+                let steps_second = (ux * offset.1 - uy * offset.0) / determinant;
+                if steps_first < -1e-9 || steps_second < -1e-9 {
+                    continue;
+                }
+                let cost = steps_first.max(0.0) * libm::sqrt(ux * ux + uy * uy) * a
+                    + steps_second.max(0.0) * libm::sqrt(wx * wx + wy * wy) * b;
+                best = best.min(cost);
+            }
+        }
+        best.max(0.0)
+    }
+}
+
+///Creates a new route using the A* algorithm under direction dependent costs, guided by
+///[`Anisotropy::lower_bound`] so the route found is still the cheapest even where steps cost less than their length.
+///The distance of the route is its total cost.
+pub fn a_star_path_anisotropic(
+    map: &MovingAiMap,
+    start: Coords2D,
+    goal: Coords2D,
+    anisotropy: &Anisotropy,
+    options: &SearchOptions,
+) -> Result<Route, PathError> {
+    instrument("a_star", start, goal, &mut (), |observer| {
+        search(
+            &mut SearchContext::new(),
+            map,
+            start,
+            goal,
+            options,
+            anisotropy.step_cost(),
+            |from, to| anisotropy.lower_bound(from, to),
+            observer,
+        )
+    })
+}
+
+impl MemoryFootprint for Anisotropy {
+    fn heap_size(&self) -> usize {
+        self.cells.heap_size()
+    }
+}
@@ -0,0 +1,80 @@
+//Adapters for maps and costs stored as ndarray arrays
+
+use ndarray::{Array2, ArrayView2};
+
+use crate::grid::{Coords2D, Map2D, MovingAiMap};
+
+use crate::utils::distance;
+
+///Builds a map from an occupancy grid, where `true` marks an occupied, blocked cell.
+///Arrays are indexed `[y, x]`, so each row of the array becomes a row of the map.
+/// # Examples
+///
+/// ```
+/// use ndarray::array;
+///
+/// let occupancy = array![[true, true, true], [false, false, true], [true, false, true]];
+/// let map = blitz_path::map_from_occupancy(occupancy.view());
+///
+/// assert!(blitz_path::is_reachable(&map, (0, 1), (1, 2)));
+/// ```
+pub fn map_from_occupancy(occupancy: ArrayView2<'_, bool>) -> MovingAiMap {
+    let (height, width) = occupancy.dim();
+    let tiles = occupancy
+        .iter()
+        .map(|occupied| if *occupied { '@' } else { '.' })
+        .collect();
+    MovingAiMap::new(String::from("octile"), height, width, tiles)
+}
+
+///Builds a map from a grid of per-cell costs, indexed `[y, x]`.
+///Cells with an infinite, NaN or negative cost are blocked; use [`array_cost`] to search with the costs themselves.
+pub fn map_from_costs(costs: ArrayView2<'_, f32>) -> MovingAiMap {
+    let (height, width) = costs.dim();
+    let tiles = costs
+        .iter()
+        .map(|cost| if passable(*cost) { '.' } else { '@' })
+        .collect();
+    MovingAiMap::new(String::from("octile"), height, width, tiles)
+}
+
+///Returns a step cost for the weighted searches that multiplies the length of each step by the cost
+///of the cell being entered, taken from `costs` indexed `[y, x]`.
+///Cells with an infinite, NaN or negative cost cannot be entered.
+///Costs below 1.0 make steps cheaper than their length, so use
+///[`dijkstra_path_weighted`](crate::dijkstra_path_weighted) rather than A* if any are present.
+/// # Examples
+///
+/// ```
+/// use ndarray::array;
+/// use blitz_path::SearchOptions;
+///
+/// // Going through the middle row is expensive, so the route goes round
+/// let costs = array![[f32::INFINITY; 5], [f32::INFINITY, 1.0, 9.0, 1.0, f32::INFINITY], [f32::INFINITY, 1.0, 1.0, 1.0, f32::INFINITY], [f32::INFINITY; 5]];
+/// let map = blitz_path::map_from_costs(costs.view());
+/// let cost = blitz_path::array_cost(costs.view());
+///
+/// let path = blitz_path::dijkstra_path_weighted(&map, (1, 1), (3, 1), &SearchOptions::new(), cost).unwrap();
+/// assert!(path.steps().contains(&(2, 2)));
+/// ```
+pub fn array_cost(costs: ArrayView2<'_, f32>) -> impl Fn(Coords2D, Coords2D) -> Option<f64> + '_ {
+    move |from, to| {
+        let cost = *costs.get((to.1, to.0))?;
+        if passable(cost) {
+            Some(distance(from, to) * f64::from(cost))
+        } else {
+            None
+        }
+    }
+}
+
+///Converts a map into an occupancy grid indexed `[y, x]`, where `true` marks a blocked tile.
+pub fn occupancy_from_map(map: &MovingAiMap) -> Array2<bool> {
+    Array2::from_shape_fn((map.height(), map.width()), |(y, x)| {
+        !map.is_traversable((x, y))
+    })
+}
+
+fn passable(cost: f32) -> bool {
+    cost.is_finite() && cost >= 0.0
+}
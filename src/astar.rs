@@ -1,15 +1,20 @@
-use std::collections::BinaryHeap;
-
-use movingai::Coords2D;
-use movingai::Map2D;
-use movingai::MovingAiMap;
+use crate::grid::Coords2D;
+use crate::grid::Map2D;
+use crate::grid::MovingAiMap;
 
 use crate::node::Node;
-use crate::utils::{distance, rewind};
-use crate::Route;
+use crate::stats::{timed, track_memory};
+use crate::steps::Step;
+use crate::trace::instrument;
+use crate::utils::{compose, distance, neighbours, prepare, rewind};
+use crate::{PathError, Route, SearchContext, SearchEvent, SearchObserver, SearchOptions};
+
+//The MovingAI tile for swamp, which benchmark routes only cross when there is no other way
+const SWAMP: char = 'S';
 
 ///Creates a new route using the A* algorithm.
 ///Returns a Route struct containing the distance to the goal and number of steps needed to get there.
+///Returns a PathError if either point is out of bounds or blocked, or if no route exists.
 /// # Examples
 ///
 /// ```
@@ -23,39 +28,342 @@ use crate::Route;
 ///
 /// // using as f32 as scene.optimal_length is stored as f64,
 /// // but only seems to have precision to f32
-/// if let Some(path) = path {
+/// if let Ok(path) = path {
 ///     assert_eq!(scene.optimal_length as f32, path.distance() as f32);  
 /// }
 /// ```
+pub fn a_star_path(map: &MovingAiMap, start: Coords2D, goal: Coords2D) -> Result<Route, PathError> {
+    a_star_path_with(map, start, goal, &SearchOptions::default())
+}
+
+///Creates a new route using the A* algorithm, configured by `options`.
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+/// use blitz_path::SearchOptions;
+///
+/// let map = movingai::parser::parse_map_file(Path::new("./tests/map/maze512-32-9.map")).expect("Could not load map.");
+///
+/// // (0, 0) is a wall, so the route starts from the nearest open tile instead
+/// let options = SearchOptions::new().snap_radius(2);
+/// let path = blitz_path::a_star_path_with(&map, (0, 0), (5, 5), &options).unwrap();
+/// assert_eq!(path.steps().last(), Some(&(1, 1)));
+/// ```
+pub fn a_star_path_with(
+    map: &MovingAiMap,
+    start: Coords2D,
+    goal: Coords2D,
+    options: &SearchOptions,
+) -> Result<Route, PathError> {
+    a_star_path_observed(map, start, goal, options, &mut ())
+}
+
+///Creates a new route using the A* algorithm, reporting each step of the search to `observer`.
+///See [`SearchObserver`](crate::SearchObserver) for details of the events raised.
+pub fn a_star_path_observed<O: SearchObserver>(
+    map: &MovingAiMap,
+    start: Coords2D,
+    goal: Coords2D,
+    options: &SearchOptions,
+    observer: &mut O,
+) -> Result<Route, PathError> {
+    instrument("a_star", start, goal, observer, |observer| {
+        search(
+            &mut SearchContext::new(),
+            map,
+            start,
+            goal,
+            options,
+            step_cost,
+            distance,
+            observer,
+        )
+    })
+}
+
+///Creates a new route using the A* algorithm, with the cost of each step given by `cost`.
+///`cost` is called with the tile being left and the tile being entered, and returns None if the move is not allowed.
+///Moves the map itself forbids are never offered to `cost`.
+///
+///The search assumes no step costs less than the straight line distance it covers.
+///If cheaper steps are possible use [`dijkstra_path_weighted`](crate::dijkstra_path_weighted) instead,
+///otherwise the route found may not be the cheapest.
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+/// use blitz_path::SearchOptions;
+///
+/// let map = movingai::parser::parse_map_file(Path::new("./tests/map/maze512-32-9.map")).expect("Could not load map.");
+///
+/// // Moving through column 3 costs double
+/// let cost = |from: (usize, usize), to: (usize, usize)| {
+///     let length = if from.0 != to.0 && from.1 != to.1 { 2f64.sqrt() } else { 1.0 };
+///     Some(if to.0 == 3 { length * 2.0 } else { length })
+/// };
+/// let path = blitz_path::a_star_path_weighted(&map, (1, 1), (5, 1), &SearchOptions::new(), cost).unwrap();
+/// assert!(path.distance() > 4.0);
+/// ```
+pub fn a_star_path_weighted<C>(
+    map: &MovingAiMap,
+    start: Coords2D,
+    goal: Coords2D,
+    options: &SearchOptions,
+    cost: C,
+) -> Result<Route, PathError>
+where
+    C: Fn(Coords2D, Coords2D) -> Option<f64>,
+{
+    instrument("a_star", start, goal, &mut (), |observer| {
+        search(
+            &mut SearchContext::new(),
+            map,
+            start,
+            goal,
+            options,
+            cost,
+            distance,
+            observer,
+        )
+    })
+}
+
+///Finds the length of the shortest route using the A* algorithm, without building the route itself.
+///Cheaper than [`a_star_path_with`] when only the distance matters, such as when scoring many candidate moves.
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+/// use blitz_path::SearchOptions;
+///
+/// let map = movingai::parser::parse_map_file(Path::new("./tests/map/maze512-32-9.map")).expect("Could not load map.");
+/// let scenes = movingai::parser::parse_scen_file(Path::new("./tests/map/maze512-32-9.map.scen")).expect("Could not load scenario.");
+/// let scene = &scenes[0];
+///
+/// let distance = blitz_path::a_star_distance(&map, scene.start_pos, scene.goal_pos, &SearchOptions::new()).unwrap();
+/// assert_eq!(scene.optimal_length as f32, distance as f32);
+/// ```
+pub fn a_star_distance(
+    map: &MovingAiMap,
+    start: Coords2D,
+    goal: Coords2D,
+    options: &SearchOptions,
+) -> Result<f64, PathError> {
+    instrument("a_star", start, goal, &mut (), |observer| {
+        explore(
+            &mut SearchContext::new(),
+            map,
+            start,
+            goal,
+            options,
+            step_cost,
+            distance,
+            observer,
+        )
+        .map(|node| node.g)
+    })
+}
+
+//Default cost of moving between two neighbouring tiles
+pub(crate) fn step_cost(from: Coords2D, to: Coords2D) -> Option<f64> {
+    Some(distance(from, to))
+}
+
+//Best-first search shared by A* and Dijkstra, which passes a heuristic of zero
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn search<C, H, O>(
+    context: &mut SearchContext,
+    map: &MovingAiMap,
+    start: Coords2D,
+    goal: Coords2D,
+    options: &SearchOptions,
+    cost: C,
+    heuristic: H,
+    observer: &mut O,
+) -> Result<Route, PathError>
+where
+    C: Fn(Coords2D, Coords2D) -> Option<f64>,
+    H: Fn(Coords2D, Coords2D) -> f64,
+    O: SearchObserver,
+{
+    let node_goal = explore(
+        context, map, start, goal, options, cost, heuristic, observer,
+    )?;
+    Ok(route(context, &node_goal, options))
+}
+
+//Unwind from the goal through the expanded nodes
+pub(crate) fn route(context: &SearchContext, node_goal: &Node, options: &SearchOptions) -> Route {
+    let find = |position| {
+        context
+            .closed
+            .iter()
+            .find(|x| x.position == position)
+            .copied()
+    };
+    //Every parent of a node was expanded before it, so is always found
+    let path = rewind(node_goal, find).expect("parents are expanded before their children");
+    let path = compose(path, options);
+    Route::from((node_goal.g, path))
+}
 
-pub fn a_star_path(map: &MovingAiMap, start: Coords2D, goal: Coords2D) -> Option<Route> {
-    //Initialize open and closed lists
-    let mut open = BinaryHeap::new();
-    let mut closed = Vec::<Node>::new();
-
-    //Push start node to open list
-    open.push(Node {
-        f: distance(start, goal),
-        g: 0.0,
-        h: distance(start, goal),
-        position: start,
-        parent: start,
+//Runs the search until the goal is reached, returning its node and leaving the expanded nodes in the context.
+//The time taken is recorded in the context's stats.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn explore<C, H, O>(
+    context: &mut SearchContext,
+    map: &MovingAiMap,
+    start: Coords2D,
+    goal: Coords2D,
+    options: &SearchOptions,
+    cost: C,
+    heuristic: H,
+    observer: &mut O,
+) -> Result<Node, PathError>
+where
+    C: Fn(Coords2D, Coords2D) -> Option<f64>,
+    H: Fn(Coords2D, Coords2D) -> f64,
+    O: SearchObserver,
+{
+    let (result, elapsed) = timed(options.measure_time, || {
+        let mut search = BestFirst::start(
+            context, map, start, goal, options, cost, heuristic, observer,
+        )?;
+        loop {
+            if let Step::Finished(result) = search.step(context, observer) {
+                return result;
+            }
+        }
     });
+    context.stats.elapsed = elapsed;
+    track_memory(context);
+    result
+}
+
+//A best-first search in progress, advanced one expansion at a time.
+//The open and closed lists live in the context, so they can be inspected between steps.
+pub(crate) struct BestFirst<'a, C, H> {
+    map: &'a MovingAiMap,
+    goal: Coords2D,
+    options: &'a SearchOptions,
+    cost: C,
+    heuristic: H,
+    pruned: bool,
+    iteration: usize,
+    start: Node,
+    //Whether swamps may be passed through, only after a search keeping out of them has failed
+    swamps: bool,
+}
+
+impl<'a, C, H> BestFirst<'a, C, H>
+where
+    C: Fn(Coords2D, Coords2D) -> Option<f64>,
+    H: Fn(Coords2D, Coords2D) -> f64,
+{
+    //Clear the context and push the start node, ready for the first step
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn start<O: SearchObserver>(
+        context: &mut SearchContext,
+        map: &'a MovingAiMap,
+        start: Coords2D,
+        goal: Coords2D,
+        options: &'a SearchOptions,
+        cost: C,
+        heuristic: H,
+        observer: &mut O,
+    ) -> Result<BestFirst<'a, C, H>, PathError> {
+        //Initialize open and closed lists
+        context.reset(options.capacity(map));
+        let (start, goal) = prepare(map, start, goal, options)?;
+
+        //Even a straight line would be too long
+        if options.exceeds_max_distance(heuristic(start, goal)) {
+            return Err(PathError::ExceedsMaxDistance);
+        }
 
-    //Examine the nodes
-    while let Some(node_current) = open.pop() {
-        //If this is the target node return the distance to get there
+        //Push start node to open list
+        let start_node = Node {
+            f: heuristic(start, goal),
+            g: 0.0,
+            h: heuristic(start, goal),
+            position: start,
+            parent: start,
+        };
+        observer.observe(SearchEvent::Discovered(start_node.info()));
+        context.open.push(start_node);
+        context.stats.generate(context.open.len());
+
+        Ok(BestFirst {
+            map,
+            goal,
+            options,
+            cost,
+            heuristic,
+            pruned: false,
+            iteration: 0,
+            start: start_node,
+            swamps: !options.avoid_swamps,
+        })
+    }
+
+    //Examine the next node, counting the work done in the context's stats
+    pub(crate) fn step<O: SearchObserver>(
+        &mut self,
+        context: &mut SearchContext,
+        observer: &mut O,
+    ) -> Step {
+        //No route keeps out of the swamps, so search again through them
+        if context.open.is_empty() && !self.swamps {
+            self.swamps = true;
+            self.pruned = false;
+            context.open.clear();
+            context.closed.clear();
+            context.open.push(self.start);
+            context.stats.generate(context.open.len());
+        }
+
+        let SearchContext {
+            open,
+            closed,
+            stats,
+        } = context;
+        let (map, goal, options) = (self.map, self.goal, self.options);
+
+        let node_current = match open.pop() {
+            Some(node) => node,
+            None if self.pruned => return Step::Finished(Err(PathError::ExceedsMaxDistance)),
+            None => return Step::Finished(Err(PathError::NoPath)),
+        };
+
+        //Stop if asked to by another thread
+        if options.should_cancel(self.iteration) {
+            return Step::Finished(Err(PathError::Cancelled));
+        }
+        self.iteration += 1;
+
+        //If this is the target node return it
         if node_current.position == goal {
-            let path = rewind(&node_current, &closed);
-            let route = Route::from((node_current.g, path));
-            return Some(route);
+            return Step::Finished(Ok(node_current));
         }
 
+        observer.observe(SearchEvent::Expanded(node_current.info()));
+        stats.expanded += 1;
+
         //Setup successor nodes
-        for successor in map.neighbors(node_current.position) {
-            //Calculate distances
-            let distance_to_goal = distance(successor, goal);
-            let distance_from_parent = distance(node_current.position, successor);
+        for successor in neighbours(map, node_current.position) {
+            if !self.swamps && successor != goal && *map.get(successor) == SWAMP {
+                continue;
+            }
+
+            //Calculate distances, skipping moves the cost function forbids
+            let distance_to_goal = (self.heuristic)(successor, goal);
+            let distance_from_parent = match (self.cost)(node_current.position, successor)
+                .and_then(|step| options.step_cost(node_current.position, successor, step))
+            {
+                Some(step) => step,
+                None => continue,
+            };
             let total_distance = node_current.g + distance_from_parent;
 
             //Check if node is on closed list
@@ -74,6 +382,12 @@ pub fn a_star_path(map: &MovingAiMap, start: Coords2D, goal: Coords2D) -> Option
                 }
             }
 
+            //Skip nodes that can only lead to routes longer than allowed
+            if options.exceeds_max_distance(total_distance + distance_to_goal) {
+                self.pruned = true;
+                continue;
+            }
+
             //If sill best distance build node and push to open list
             //Build node from distances
             let node_successor = Node {
@@ -84,7 +398,9 @@ pub fn a_star_path(map: &MovingAiMap, start: Coords2D, goal: Coords2D) -> Option
                 parent: node_current.position,
             };
 
+            observer.observe(SearchEvent::Discovered(node_successor.info()));
             open.push(node_successor);
+            stats.generate(open.len());
         }
 
         //Push current node to closed list and remove existing entry if duplicate
@@ -95,7 +411,7 @@ pub fn a_star_path(map: &MovingAiMap, start: Coords2D, goal: Coords2D) -> Option
             closed.remove(index);
         }
         closed.push(node_current);
-    }
 
-    None
+        Step::Expanded(node_current)
+    }
 }
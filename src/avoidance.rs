@@ -0,0 +1,91 @@
+use crate::grid::Coords2D;
+use crate::grid::MovingAiMap;
+
+use crate::dijkstra::dijkstra_path_weighted;
+use crate::utils::{distance, validate};
+use crate::{PathError, Route, SearchOptions};
+
+///Suggests a short detour around dynamic `blockers`, such as other units, standing on the next
+///`lookahead` steps of `route`, so an agent can step around them without replanning its whole route.
+///The agent is taken to be on the step of `route` nearest `position`.
+///
+///Returns `Ok(None)` if none of the next steps are blocked. Otherwise returns a route from `position`
+///that rejoins `route` at the first free step past the blockers, only using tiles within `margin`
+///tiles of the part of `route` it replaces, so the global route still serves as the corridor.
+///Returns `PathError::NoPath` if there is no such detour, in which case the agent should wait
+///or replan, and `PathError::GoalBlocked` if a blocker stands on the goal itself.
+///
+///`route` should list every tile, and `options` controls cancellation and the steps listed in the detour.
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+/// use blitz_path::SearchOptions;
+///
+/// let map = movingai::parser::parse_map_file(Path::new("./tests/map/maze512-32-9.map")).expect("Could not load map.");
+/// let options = SearchOptions::new();
+///
+/// let route = blitz_path::jps_path_with(&map, (1, 1), (8, 1), &options).unwrap();
+/// let detour = blitz_path::local_detour(&map, &route, (1, 1), &[(3, 1)], 4, 1, &options)
+///     .unwrap()
+///     .unwrap();
+/// assert!(!detour.steps().contains(&(3, 1)));
+/// assert!(route.steps().contains(&detour.steps()[0]));
+/// ```
+pub fn local_detour(
+    map: &MovingAiMap,
+    route: &Route,
+    position: Coords2D,
+    blockers: &[Coords2D],
+    lookahead: usize,
+    margin: usize,
+    options: &SearchOptions,
+) -> Result<Option<Route>, PathError> {
+    validate(map, position, position)?;
+
+    //Walk the route from the start, which is its last step
+    let mut steps = route.steps();
+    steps.reverse();
+    let current = match steps.iter().enumerate().min_by(|a, b| {
+        distance(*a.1, position)
+            .total_cmp(&distance(*b.1, position))
+            .then(b.0.cmp(&a.0))
+    }) {
+        Some((current, _)) => current,
+        None => return Ok(None),
+    };
+
+    //Find the last blocked step in view, then the first free step after it
+    let ahead = &steps[current + 1..];
+    let last_blocked = match ahead
+        .iter()
+        .take(lookahead)
+        .rposition(|step| blockers.contains(step))
+    {
+        Some(last_blocked) => last_blocked,
+        None => return Ok(None),
+    };
+    let rejoin = match ahead[last_blocked + 1..]
+        .iter()
+        .position(|step| !blockers.contains(step))
+    {
+        Some(offset) => last_blocked + 1 + offset,
+        None => return Err(PathError::GoalBlocked(ahead[ahead.len() - 1])),
+    };
+
+    //Search only near the stretch of route being replaced
+    let corridor = &steps[current..=current + 1 + rejoin];
+    let near_corridor = |tile: Coords2D| {
+        corridor
+            .iter()
+            .any(|step| step.0.abs_diff(tile.0).max(step.1.abs_diff(tile.1)) <= margin)
+    };
+    let cost = |from: Coords2D, to: Coords2D| {
+        if blockers.contains(&to) || !near_corridor(to) {
+            None
+        } else {
+            Some(distance(from, to))
+        }
+    };
+    dijkstra_path_weighted(map, position, ahead[rejoin], options, cost).map(Some)
+}
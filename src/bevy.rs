@@ -0,0 +1,98 @@
+//Integration with the Bevy game engine, solving path requests attached to entities
+
+use std::sync::Arc;
+
+use crate::grid::{Coords2D, MovingAiMap};
+use bevy_app::{App, Plugin, Update};
+use bevy_ecs::prelude::*;
+
+use crate::{PathError, Route, SearchContext, SearchOptions};
+
+///Solves every [`PathRequest`] once per frame against the [`PathGrid`] resource,
+///replacing each request with a [`PathResult`] on the same entity.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct PathfindingPlugin;
+
+impl Plugin for PathfindingPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, solve_path_requests);
+    }
+}
+
+///The map searched by the plugin, shared so it can also be cloned out for use elsewhere.
+#[derive(Resource, Debug, Clone)]
+pub struct PathGrid(pub Arc<MovingAiMap>);
+
+impl PathGrid {
+    ///Builds a grid of `width` by `height` tiles, calling `traversable` with the coordinates of each.
+    ///Useful for converting an existing collision grid or tilemap resource.
+    pub fn from_fn<F: FnMut(Coords2D) -> bool>(
+        width: usize,
+        height: usize,
+        mut traversable: F,
+    ) -> PathGrid {
+        let tiles = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(|tile| if traversable(tile) { '.' } else { '@' })
+            .collect();
+        PathGrid(Arc::new(MovingAiMap::new(
+            String::from("octile"),
+            height,
+            width,
+            tiles,
+        )))
+    }
+}
+
+impl From<MovingAiMap> for PathGrid {
+    fn from(map: MovingAiMap) -> Self {
+        PathGrid(Arc::new(map))
+    }
+}
+
+///Asks for a route to be found using the JPS algorithm. Replaced by a [`PathResult`] once solved.
+#[derive(Component, Debug, Clone)]
+pub struct PathRequest {
+    ///The tile the route starts from.
+    pub start: Coords2D,
+    ///The tile the route should reach.
+    pub goal: Coords2D,
+    ///Options controlling the search.
+    pub options: SearchOptions,
+}
+
+impl PathRequest {
+    ///Creates a request with the default options.
+    pub fn new(start: Coords2D, goal: Coords2D) -> PathRequest {
+        PathRequest {
+            start,
+            goal,
+            options: SearchOptions::default(),
+        }
+    }
+}
+
+///The outcome of a [`PathRequest`].
+#[derive(Component)]
+pub struct PathResult(pub Result<Route, PathError>);
+
+//Reuses one context between frames to avoid reallocating the search lists
+fn solve_path_requests(
+    mut commands: Commands,
+    grid: Option<Res<PathGrid>>,
+    requests: Query<(Entity, &PathRequest)>,
+    mut context: Local<SearchContext>,
+) {
+    let grid = match grid {
+        Some(grid) => grid,
+        None => return,
+    };
+
+    for (entity, request) in &requests {
+        let result = context.jps_path(&grid.0, request.start, request.goal, &request.options);
+        commands
+            .entity(entity)
+            .remove::<PathRequest>()
+            .insert(PathResult(result));
+    }
+}
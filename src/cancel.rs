@@ -0,0 +1,43 @@
+use alloc::sync::Arc;
+use core::sync::atomic::{AtomicBool, Ordering};
+
+///A cheaply cloneable flag used to abort a running search from another thread.
+///Searches poll the token periodically and return `PathError::Cancelled` once it is set.
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+/// use blitz_path::{CancelToken, PathError, SearchOptions};
+///
+/// let map = movingai::parser::parse_map_file(Path::new("./tests/map/maze512-32-9.map")).expect("Could not load map.");
+///
+/// let token = CancelToken::new();
+/// let options = SearchOptions::new().cancel_token(token.clone());
+///
+/// // Usually called from another thread while the search is running
+/// token.cancel();
+///
+/// let path = blitz_path::jps_path_with(&map, (1, 1), (5, 5), &options);
+/// assert_eq!(path.err(), Some(PathError::Cancelled));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken {
+    flag: Arc<AtomicBool>,
+}
+
+impl CancelToken {
+    ///Creates a new token that has not been cancelled.
+    pub fn new() -> CancelToken {
+        CancelToken::default()
+    }
+
+    ///Requests that every search using this token stops as soon as possible.
+    pub fn cancel(&self) {
+        self.flag.store(true, Ordering::Relaxed);
+    }
+
+    ///Returns true if cancel has been called on this token or any of its clones.
+    pub fn is_cancelled(&self) -> bool {
+        self.flag.load(Ordering::Relaxed)
+    }
+}
@@ -0,0 +1,127 @@
+use alloc::vec::Vec;
+
+use crate::grid::Coords2D;
+use crate::grid::Map2D;
+use crate::grid::MovingAiMap;
+
+use crate::flood::flood;
+use crate::memory::vec_size;
+use crate::utils::{distance, index};
+use crate::MemoryFootprint;
+
+///How far every tile of a map is from the nearest obstacle, with the edge of the map counting as one.
+///Tiles touching an obstacle, including diagonally, have a clearance of 1.0 and blocked tiles have 0.0.
+///Distances are measured along open ground, so clearance does not reach through walls.
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+/// use blitz_path::{ClearanceMap, SearchOptions};
+///
+/// let map = movingai::parser::parse_map_file(Path::new("./tests/map/maze512-32-9.map")).expect("Could not load map.");
+/// let clearance = ClearanceMap::new(&map);
+/// assert_eq!(clearance.get((1, 1)), 1.0);
+/// assert_eq!(clearance.get((0, 0)), 0.0);
+///
+/// // Keep away from walls when there is room to
+/// let cost = clearance.wall_penalty(3.0, 2.0);
+/// let path = blitz_path::a_star_path_weighted(&map, (1, 1), (5, 5), &SearchOptions::new(), cost).unwrap();
+/// assert_eq!(path.steps().last(), Some(&(1, 1)));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ClearanceMap {
+    width: usize,
+    height: usize,
+    values: Vec<f64>,
+}
+
+impl ClearanceMap {
+    ///Measures the clearance of every tile in `map`.
+    pub fn new(map: &MovingAiMap) -> ClearanceMap {
+        //Spread outwards from every open tile next to an obstacle or the edge of the map
+        let touching = |(x, y): Coords2D| {
+            (-1..=1).any(|dx| {
+                (-1..=1).any(
+                    |dy| match (x.checked_add_signed(dx), y.checked_add_signed(dy)) {
+                        (Some(x), Some(y)) => !map.is_traversable((x, y)),
+                        _ => true,
+                    },
+                )
+            })
+        };
+        let sources: Vec<(Coords2D, f64)> = map
+            .coords()
+            .filter(|tile| map.is_traversable(*tile) && touching(*tile))
+            .map(|tile| (tile, 1.0))
+            .collect();
+        let reached = flood(
+            map,
+            &sources,
+            f64::INFINITY,
+            |from, to| Some(distance(from, to)),
+            |_, _| false,
+        );
+
+        ClearanceMap {
+            width: map.width(),
+            height: map.height(),
+            values: map
+                .coords()
+                .map(|tile| {
+                    let cost = reached.costs[index(map, tile)];
+                    if cost.is_finite() {
+                        cost
+                    } else {
+                        0.0
+                    }
+                })
+                .collect(),
+        }
+    }
+
+    ///Returns the clearance of a tile, or 0.0 if it is blocked or outside the map.
+    pub fn get(&self, position: Coords2D) -> f64 {
+        if position.0 >= self.width || position.1 >= self.height {
+            return 0.0;
+        }
+        self.values[position.1 * self.width + position.0]
+    }
+
+    ///Returns a step cost for the weighted searches that makes routes keep away from obstacles.
+    ///Entering a tile with clearance within `radius` costs up to `weight` times more the closer it is to an obstacle,
+    ///so routes follow the middle of corridors rather than scraping along walls where there is room to.
+    ///No step ever costs less than the distance it covers, so the cost can be used with
+    ///[`a_star_path_weighted`](crate::a_star_path_weighted).
+    pub fn wall_penalty(
+        &self,
+        radius: f64,
+        weight: f64,
+    ) -> impl Fn(Coords2D, Coords2D) -> Option<f64> + '_ {
+        move |from, to| {
+            let closeness = ((radius + 1.0 - self.get(to)) / radius).clamp(0.0, 1.0);
+            Some(distance(from, to) * (1.0 + weight * closeness))
+        }
+    }
+
+    ///Returns the clearance of every tile in row-major order.
+    pub fn values(&self) -> &[f64] {
+        &self.values
+    }
+
+    ///Returns the width of the underlying map.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    ///Returns the height of the underlying map.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+}
+
+impl MemoryFootprint for ClearanceMap {
+    fn heap_size(&self) -> usize {
+        vec_size(&self.values)
+    }
+}
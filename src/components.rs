@@ -0,0 +1,119 @@
+use alloc::collections::VecDeque;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::grid::Coords2D;
+use crate::grid::Map2D;
+use crate::grid::MovingAiMap;
+
+use crate::memory::vec_size;
+use crate::utils::{index, offset};
+use crate::MemoryFootprint;
+
+//Label given to tiles that are blocked or out of bounds
+const NO_COMPONENT: u32 = u32::MAX;
+
+///Labels every traversable tile of a map with the connected region it belongs to.
+///Build once with [`components`] and use to reject queries between disconnected regions in constant time,
+///either directly through [`same_component`](Components::same_component) or by passing it to
+///[`SearchOptions::components`](crate::SearchOptions::components).
+///
+///Tiles in different regions can never reach each other. Regions are joined by moves made in either direction,
+///so on maps with one-way moves, such as from ground into swamp, two tiles of the same region may only be
+///connected one way round. The labels must be rebuilt if the map changes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Components {
+    width: usize,
+    height: usize,
+    labels: Vec<u32>,
+    count: usize,
+}
+
+///Finds the connected regions of `map`.
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+///
+/// let map = movingai::parser::parse_map_file(Path::new("./tests/map/maze512-32-9.map")).expect("Could not load map.");
+/// let components = blitz_path::components(&map);
+///
+/// assert!(components.same_component((1, 1), (5, 5)));
+/// assert!(!components.same_component((1, 1), (0, 0)));
+/// ```
+pub fn components(map: &MovingAiMap) -> Components {
+    let mut components = Components {
+        width: map.width(),
+        height: map.height(),
+        labels: vec![NO_COMPONENT; map.width() * map.height()],
+        count: 0,
+    };
+    let mut queue = VecDeque::new();
+
+    for tile in map.coords() {
+        if components.labels[index(map, tile)] != NO_COMPONENT || !map.is_traversable(tile) {
+            continue;
+        }
+
+        //Flood the new region breadth first
+        let label = components.count as u32;
+        components.count += 1;
+        components.labels[index(map, tile)] = label;
+        queue.push_back(tile);
+        while let Some(current) = queue.pop_front() {
+            for successor in linked(map, current) {
+                let successor_index = index(map, successor);
+                if components.labels[successor_index] == NO_COMPONENT {
+                    components.labels[successor_index] = label;
+                    queue.push_back(successor);
+                }
+            }
+        }
+    }
+
+    components
+}
+
+//Neighbours that can be stepped to from a tile or can step onto it, so one-way moves still join regions
+fn linked(map: &MovingAiMap, tile: Coords2D) -> impl Iterator<Item = Coords2D> + '_ {
+    (-1..=1)
+        .flat_map(move |dx| (-1..=1).filter_map(move |dy| offset(tile, dx, dy)))
+        .filter(move |other| {
+            map.is_traversable(*other)
+                && (map.is_traversable_from(tile, *other) || map.is_traversable_from(*other, tile))
+        })
+}
+
+impl Components {
+    ///Returns the region a tile belongs to, or None if it is blocked or out of bounds.
+    pub fn label(&self, position: Coords2D) -> Option<usize> {
+        if position.0 >= self.width || position.1 >= self.height {
+            return None;
+        }
+        match self.labels[position.1 * self.width + position.0] {
+            NO_COMPONENT => None,
+            label => Some(label as usize),
+        }
+    }
+
+    ///Returns true if both tiles are traversable and in the same region. A route always exists between them
+    ///on maps where every move can be made in reverse, and never exists between tiles in different regions.
+    pub fn same_component(&self, a: Coords2D, b: Coords2D) -> bool {
+        match (self.label(a), self.label(b)) {
+            (Some(label_a), Some(label_b)) => label_a == label_b,
+            _ => false,
+        }
+    }
+
+    ///Returns the number of separate regions in the map.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+}
+
+impl MemoryFootprint for Components {
+    fn heap_size(&self) -> usize {
+        vec_size(&self.labels)
+    }
+}
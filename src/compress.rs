@@ -0,0 +1,172 @@
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::grid::Coords2D;
+
+use crate::{Route, RouteDecodeError};
+
+//The eight directions a run can take, indexed by the low three bits of each run
+const DIRECTIONS: [(i64, i64); 8] = [
+    (1, 0),
+    (1, 1),
+    (0, 1),
+    (-1, 1),
+    (-1, 0),
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+];
+
+impl Route {
+    ///Encodes the route compactly for sending over a network, as its distance, the start tile,
+    ///and then one run per straight or diagonal line the route follows, giving its direction and length.
+    ///A route along a corridor a hundred tiles long takes a dozen bytes.
+    ///
+    ///Returns `None` if two consecutive steps do not lie on a straight or diagonal line.
+    ///Routes listing only jump points can be compressed, but come back from
+    ///[`from_compressed`](Route::from_compressed) listing every tile.
+    /// # Examples
+    ///
+    /// ```
+    /// use blitz_path::Route;
+    ///
+    /// let steps: Vec<(usize, usize)> = (0..100).rev().map(|x| (x, 7)).collect();
+    /// let route: Route = Route::from((99.0, steps));
+    ///
+    /// let bytes = route.to_compressed().unwrap();
+    /// assert_eq!(bytes.len(), 12);
+    ///
+    /// let decoded = Route::from_compressed(&bytes).unwrap();
+    /// assert_eq!(decoded.steps(), route.steps());
+    /// assert_eq!(decoded.distance(), route.distance());
+    /// ```
+    pub fn to_compressed(&self) -> Option<Vec<u8>> {
+        let mut bytes = self.distance().to_le_bytes().to_vec();
+
+        //Steps are stored goal first, runs are written from the start
+        let steps: Vec<Coords2D> = self.steps().into_iter().rev().collect();
+        let start = match steps.first() {
+            Some(start) => *start,
+            None => return Some(bytes),
+        };
+        write_varint(&mut bytes, start.0 as u64);
+        write_varint(&mut bytes, start.1 as u64);
+
+        let mut run: Option<(usize, u64)> = None;
+        for pair in steps.windows(2) {
+            let (dx, dy) = (
+                pair[1].0 as i64 - pair[0].0 as i64,
+                pair[1].1 as i64 - pair[0].1 as i64,
+            );
+            let length = dx.abs().max(dy.abs());
+
+            //A repeated tile is written as an empty run
+            if length == 0 {
+                if let Some(run) = run.take() {
+                    write_run(&mut bytes, run);
+                }
+                write_run(&mut bytes, (0, 0));
+                continue;
+            }
+            let unit = (dx / length, dy / length);
+            if (unit.0 * length, unit.1 * length) != (dx, dy) {
+                return None;
+            }
+            let direction = DIRECTIONS.iter().position(|offset| *offset == unit)?;
+
+            run = match run {
+                Some((current, total)) if current == direction => {
+                    Some((direction, total + length as u64))
+                }
+                Some(previous) => {
+                    write_run(&mut bytes, previous);
+                    Some((direction, length as u64))
+                }
+                None => Some((direction, length as u64)),
+            };
+        }
+        if let Some(run) = run {
+            write_run(&mut bytes, run);
+        }
+        Some(bytes)
+    }
+
+    ///Decodes a route written by [`to_compressed`](Route::to_compressed), listing every tile it passes through.
+    pub fn from_compressed(bytes: &[u8]) -> Result<Route, RouteDecodeError> {
+        if bytes.len() < 8 {
+            return Err(RouteDecodeError::Malformed(String::from(
+                "missing route distance",
+            )));
+        }
+        let (distance, mut rest) = bytes.split_at(8);
+        let mut distance_bytes = [0; 8];
+        distance_bytes.copy_from_slice(distance);
+        let distance = f64::from_le_bytes(distance_bytes);
+        if rest.is_empty() {
+            return Ok(Route::from((distance, Vec::new())));
+        }
+
+        let x = read_varint(&mut rest)?;
+        let y = read_varint(&mut rest)?;
+        let mut position = (x as i64, y as i64);
+        let mut steps = vec![to_coords(position)?];
+        while !rest.is_empty() {
+            let run = read_varint(&mut rest)?;
+            let (direction, length) = (DIRECTIONS[(run & 7) as usize], run >> 3);
+            if length > u64::from(u32::MAX) {
+                return Err(RouteDecodeError::Invalid("run longer than any map"));
+            }
+            if length == 0 {
+                steps.push(to_coords(position)?);
+            }
+            for _ in 0..length {
+                position = (position.0 + direction.0, position.1 + direction.1);
+                steps.push(to_coords(position)?);
+            }
+        }
+
+        steps.reverse();
+        Ok(Route::from((distance, steps)))
+    }
+}
+
+//A run packs its length above its direction, so short runs take a single byte
+fn write_run(bytes: &mut Vec<u8>, (direction, length): (usize, u64)) {
+    write_varint(bytes, (length << 3) | direction as u64);
+}
+
+//Unsigned LEB128, seven bits per byte with the high bit set on all but the last
+fn write_varint(bytes: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        bytes.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    bytes.push(value as u8);
+}
+
+fn read_varint(bytes: &mut &[u8]) -> Result<u64, RouteDecodeError> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let (byte, rest) = bytes
+            .split_first()
+            .ok_or_else(|| RouteDecodeError::Malformed(String::from("truncated run")))?;
+        *bytes = rest;
+        value |= u64::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err(RouteDecodeError::Malformed(String::from(
+        "run longer than ten bytes",
+    )))
+}
+
+fn to_coords(position: (i64, i64)) -> Result<Coords2D, RouteDecodeError> {
+    if position.0 < 0 || position.1 < 0 {
+        return Err(RouteDecodeError::Invalid(
+            "route leaves the map's coordinates",
+        ));
+    }
+    Ok((position.0 as usize, position.1 as usize))
+}
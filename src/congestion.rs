@@ -0,0 +1,112 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::grid::Coords2D;
+use crate::grid::Map2D;
+use crate::grid::MovingAiMap;
+
+use crate::memory::vec_size;
+use crate::{MemoryFootprint, Route};
+
+///How busy every tile of a map has been recently, for spreading the routes of many agents across
+///parallel corridors rather than sending every one down the same hallway.
+///
+///Each route claimed adds to the congestion of its tiles, and every [`tick`](CongestionMap::tick)
+///the congestion of every tile fades. Pass the map to [`SearchOptions::congestion`](crate::SearchOptions::congestion)
+///and A* and Dijkstra searches make each step cost its length times one plus the congestion of
+///the tile entered. JPS assumes uniform costs and ignores congestion.
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+/// use std::sync::Arc;
+/// use blitz_path::{CongestionMap, SearchOptions};
+///
+/// let map = movingai::parser::parse_map_file(Path::new("./tests/map/maze512-32-9.map")).expect("Could not load map.");
+/// let mut congestion = CongestionMap::new(&map, 0.5, 0.9);
+///
+/// let first = blitz_path::a_star_path(&map, (1, 1), (1, 5)).unwrap();
+/// congestion.claim_route(&first);
+/// assert_eq!(congestion.get((1, 3)), 0.5);
+///
+/// congestion.tick();
+/// assert_eq!(congestion.get((1, 3)), 0.45);
+///
+/// let options = SearchOptions::new().congestion(Arc::new(congestion));
+/// let second = blitz_path::a_star_path_with(&map, (1, 1), (1, 5), &options).unwrap();
+/// assert!(second.distance() > first.distance());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CongestionMap {
+    width: usize,
+    height: usize,
+    values: Vec<f64>,
+    weight: f64,
+    decay: f64,
+}
+
+impl CongestionMap {
+    ///Creates an empty congestion map the size of `map`.
+    ///Each claim on a tile adds `weight` to its congestion, and every tick the congestion of each tile
+    ///is multiplied by `decay`, between 0 and 1.
+    pub fn new(map: &MovingAiMap, weight: f64, decay: f64) -> CongestionMap {
+        CongestionMap {
+            width: map.width(),
+            height: map.height(),
+            values: vec![0.0; map.width() * map.height()],
+            weight,
+            decay,
+        }
+    }
+
+    ///Adds one claim to a tile. Tiles outside the map are ignored.
+    pub fn claim(&mut self, position: Coords2D) {
+        if position.0 < self.width && position.1 < self.height {
+            self.values[position.1 * self.width + position.0] += self.weight;
+        }
+    }
+
+    ///Adds one claim to every step of `route`.
+    pub fn claim_route(&mut self, route: &Route) {
+        for step in route.steps() {
+            self.claim(step);
+        }
+    }
+
+    ///Fades the congestion of every tile by one tick.
+    pub fn tick(&mut self) {
+        for value in &mut self.values {
+            *value *= self.decay;
+        }
+    }
+
+    ///Returns the congestion of a tile, or 0.0 if it is outside the map.
+    pub fn get(&self, position: Coords2D) -> f64 {
+        if position.0 >= self.width || position.1 >= self.height {
+            return 0.0;
+        }
+        self.values[position.1 * self.width + position.0]
+    }
+
+    ///Returns the congestion of every tile in row-major order.
+    pub fn values(&self) -> &[f64] {
+        &self.values
+    }
+
+    ///Returns the width of the underlying map.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    ///Returns the height of the underlying map.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+}
+
+impl MemoryFootprint for CongestionMap {
+    fn heap_size(&self) -> usize {
+        vec_size(&self.values)
+    }
+}
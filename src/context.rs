@@ -0,0 +1,296 @@
+use alloc::collections::BinaryHeap;
+use alloc::vec::Vec;
+
+use crate::grid::Coords2D;
+use crate::grid::Map2D;
+use crate::grid::MovingAiMap;
+
+use crate::node::Node;
+use crate::trace::instrument;
+use crate::utils::distance;
+use crate::{
+    astar, jps, Algorithm, Landmarks, PathError, Route, SearchOptions, SearchStats, SearchSteps,
+};
+
+///Working state for searches that can be kept and reused between queries.
+///After a search finishes, successfully or not, the context can be inspected to see which
+///tiles were explored, for example to draw the area considered by a search.
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+/// use blitz_path::{SearchContext, SearchOptions};
+///
+/// let map = movingai::parser::parse_map_file(Path::new("./tests/map/maze512-32-9.map")).expect("Could not load map.");
+///
+/// let mut context = SearchContext::new();
+/// let path = context.a_star_path(&map, (1, 1), (5, 5), &SearchOptions::new()).unwrap();
+///
+/// // Every tile on the route apart from the goal was expanded
+/// let explored = context.explored();
+/// for step in &path.steps()[1..] {
+///     assert!(explored.iter().any(|(position, _)| position == step));
+/// }
+/// ```
+#[derive(Debug, Default)]
+pub struct SearchContext {
+    pub(crate) open: BinaryHeap<Node>,
+    pub(crate) closed: Vec<Node>,
+    pub(crate) stats: SearchStats,
+}
+
+impl SearchContext {
+    ///Creates a new, empty context.
+    pub fn new() -> SearchContext {
+        SearchContext::default()
+    }
+
+    ///Creates a new context with room for `nodes` nodes in its open and closed lists.
+    pub fn with_capacity(nodes: usize) -> SearchContext {
+        let mut context = SearchContext::new();
+        context.reserve(nodes);
+        context
+    }
+
+    ///Creates a new context with room for a node on every traversable tile of `map`, so no search
+    ///on it needs to grow the closed list, and only searches revisiting many tiles grow the open list.
+    /// # Examples
+    ///
+    /// ```
+    /// use std::path::Path;
+    /// use blitz_path::{SearchContext, SearchOptions};
+    /// use movingai::Map2D;
+    ///
+    /// let map = movingai::parser::parse_map_file(Path::new("./tests/map/maze512-32-9.map")).expect("Could not load map.");
+    ///
+    /// let mut context = SearchContext::for_map(&map);
+    /// assert!(context.capacity() >= map.free_states());
+    /// context.a_star_path(&map, (1, 1), (5, 5), &SearchOptions::new()).unwrap();
+    /// assert!(context.capacity() >= map.free_states());
+    /// ```
+    pub fn for_map(map: &MovingAiMap) -> SearchContext {
+        SearchContext::with_capacity(map.free_states())
+    }
+
+    ///Makes room for at least `nodes` nodes in the open and closed lists, on top of any they hold.
+    ///Room is kept between searches.
+    pub fn reserve(&mut self, nodes: usize) {
+        self.open.reserve(nodes);
+        self.closed.reserve(nodes);
+    }
+
+    ///Returns how many nodes the open and closed lists can each hold without reallocating.
+    pub fn capacity(&self) -> usize {
+        self.open.capacity().min(self.closed.capacity())
+    }
+
+    ///Creates a new route using `algorithm`, keeping the search state in this context.
+    ///See [`find_path`](crate::find_path).
+    pub fn find_path(
+        &mut self,
+        algorithm: Algorithm,
+        map: &MovingAiMap,
+        start: Coords2D,
+        goal: Coords2D,
+        options: &SearchOptions,
+    ) -> Result<Route, PathError> {
+        algorithm.search(self, map, start, goal, options)
+    }
+
+    ///Creates a new route using the A* algorithm, keeping the search state in this context.
+    pub fn a_star_path(
+        &mut self,
+        map: &MovingAiMap,
+        start: Coords2D,
+        goal: Coords2D,
+        options: &SearchOptions,
+    ) -> Result<Route, PathError> {
+        instrument("a_star", start, goal, &mut (), |observer| {
+            astar::search(
+                self,
+                map,
+                start,
+                goal,
+                options,
+                astar::step_cost,
+                distance,
+                observer,
+            )
+        })
+    }
+
+    ///Creates a new route using Dijkstra's algorithm, keeping the search state in this context.
+    pub fn dijkstra_path(
+        &mut self,
+        map: &MovingAiMap,
+        start: Coords2D,
+        goal: Coords2D,
+        options: &SearchOptions,
+    ) -> Result<Route, PathError> {
+        instrument("dijkstra", start, goal, &mut (), |observer| {
+            astar::search(
+                self,
+                map,
+                start,
+                goal,
+                options,
+                astar::step_cost,
+                |_, _| 0.0,
+                observer,
+            )
+        })
+    }
+
+    ///Creates a new route using the JPS algorithm, keeping the search state in this context.
+    pub fn jps_path(
+        &mut self,
+        map: &MovingAiMap,
+        start: Coords2D,
+        goal: Coords2D,
+        options: &SearchOptions,
+    ) -> Result<Route, PathError> {
+        jps::search_with_rule(self, map, start, goal, options, &mut ())
+    }
+
+    ///Creates a new route using the A* algorithm guided by the ALT heuristic of `landmarks`,
+    ///keeping the search state in this context.
+    pub fn a_star_path_landmarks(
+        &mut self,
+        map: &MovingAiMap,
+        start: Coords2D,
+        goal: Coords2D,
+        landmarks: &Landmarks,
+        options: &SearchOptions,
+    ) -> Result<Route, PathError> {
+        instrument("a_star", start, goal, &mut (), |observer| {
+            astar::search(
+                self,
+                map,
+                start,
+                goal,
+                options,
+                astar::step_cost,
+                |from, to| landmarks.heuristic(from, to),
+                observer,
+            )
+        })
+    }
+
+    ///Finds the length of the shortest route using the A* algorithm without building the route,
+    ///keeping the search state in this context.
+    pub fn a_star_distance(
+        &mut self,
+        map: &MovingAiMap,
+        start: Coords2D,
+        goal: Coords2D,
+        options: &SearchOptions,
+    ) -> Result<f64, PathError> {
+        instrument("a_star", start, goal, &mut (), |observer| {
+            astar::explore(
+                self,
+                map,
+                start,
+                goal,
+                options,
+                astar::step_cost,
+                distance,
+                observer,
+            )
+            .map(|node| node.g)
+        })
+    }
+
+    ///Finds the length of the shortest route using the JPS algorithm without building the route,
+    ///keeping the search state in this context.
+    pub fn jps_distance(
+        &mut self,
+        map: &MovingAiMap,
+        start: Coords2D,
+        goal: Coords2D,
+        options: &SearchOptions,
+    ) -> Result<f64, PathError> {
+        jps::distance_with_rule(self, map, start, goal, options)
+    }
+
+    ///Starts an A* search that runs one expansion each time the returned iterator is advanced.
+    pub fn a_star_steps<'a>(
+        &'a mut self,
+        map: &'a MovingAiMap,
+        start: Coords2D,
+        goal: Coords2D,
+        options: &'a SearchOptions,
+    ) -> SearchSteps<'a> {
+        SearchSteps::a_star(self, map, start, goal, options, distance)
+    }
+
+    ///Starts a Dijkstra search that runs one expansion each time the returned iterator is advanced.
+    pub fn dijkstra_steps<'a>(
+        &'a mut self,
+        map: &'a MovingAiMap,
+        start: Coords2D,
+        goal: Coords2D,
+        options: &'a SearchOptions,
+    ) -> SearchSteps<'a> {
+        SearchSteps::a_star(self, map, start, goal, options, |_, _| 0.0)
+    }
+
+    ///Starts a JPS search that runs one expansion each time the returned iterator is advanced.
+    pub fn jps_steps<'a>(
+        &'a mut self,
+        map: &'a MovingAiMap,
+        start: Coords2D,
+        goal: Coords2D,
+        options: &'a SearchOptions,
+    ) -> SearchSteps<'a> {
+        SearchSteps::jps(self, map, start, goal, options)
+    }
+
+    ///Returns every tile expanded by the last search along with the cost of reaching it,
+    ///in the order they were expanded.
+    pub fn explored(&self) -> Vec<(Coords2D, f64)> {
+        self.closed
+            .iter()
+            .map(|node| (node.position, node.g))
+            .collect()
+    }
+
+    ///Returns the tiles that were waiting to be expanded when the last search finished,
+    ///along with the cost of reaching them. A tile may appear more than once.
+    pub fn frontier(&self) -> Vec<(Coords2D, f64)> {
+        let mut frontier: Vec<(Coords2D, f64)> = self
+            .open
+            .iter()
+            .map(|node| (node.position, node.g))
+            .collect();
+        frontier.sort_by(|a, b| a.0.cmp(&b.0).then(a.1.total_cmp(&b.1)));
+        frontier
+    }
+
+    ///Returns the counters recorded by the last search.
+    /// # Examples
+    ///
+    /// ```
+    /// use std::path::Path;
+    /// use blitz_path::{SearchContext, SearchOptions};
+    ///
+    /// let map = movingai::parser::parse_map_file(Path::new("./tests/map/maze512-32-9.map")).expect("Could not load map.");
+    ///
+    /// let mut context = SearchContext::new();
+    /// context.jps_path(&map, (1, 1), (5, 5), &SearchOptions::new()).unwrap();
+    ///
+    /// let stats = context.stats();
+    /// assert!(stats.expanded > 0 && stats.jump_scans > 0);
+    /// assert!(stats.generated >= stats.peak_open);
+    /// ```
+    pub fn stats(&self) -> SearchStats {
+        self.stats
+    }
+
+    //Empty the lists ready for a new search, keeping their allocations and making room for capacity nodes
+    pub(crate) fn reset(&mut self, capacity: usize) {
+        self.open.clear();
+        self.closed.clear();
+        self.reserve(capacity);
+        self.stats = SearchStats::default();
+    }
+}
@@ -0,0 +1,51 @@
+use alloc::vec::Vec;
+
+use crate::grid::Coords2D;
+use crate::grid::Map2D;
+use crate::grid::MovingAiMap;
+
+use crate::flood::flood;
+use crate::utils::{distance, index, validate};
+use crate::PathError;
+
+///Returns every tile lying on at least one shortest route between `start` and `goal`, in row-major order.
+///Shows how much freedom there is in choosing a route, and allows picking between equally short routes
+///on other grounds. Tiles count as on a shortest route if their detour is within rounding error.
+///Assumes every move can also be made in reverse, as with [`components`](crate::components).
+///Returns a PathError if either point is out of bounds or blocked, or if no route exists.
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+///
+/// let map = movingai::parser::parse_map_file(Path::new("./tests/map/maze512-32-9.map")).expect("Could not load map.");
+///
+/// // Along a straight corridor there is only one way to go
+/// let corridor = blitz_path::optimal_corridor(&map, (1, 1), (5, 1)).unwrap();
+/// assert_eq!(corridor, vec![(1, 1), (2, 1), (3, 1), (4, 1), (5, 1)]);
+/// ```
+pub fn optimal_corridor(
+    map: &MovingAiMap,
+    start: Coords2D,
+    goal: Coords2D,
+) -> Result<Vec<Coords2D>, PathError> {
+    validate(map, start, goal)?;
+
+    let step = |from, to| Some(distance(from, to));
+    let from_start = flood(map, &[(start, 0.0)], f64::INFINITY, step, |_, _| false);
+    let total = from_start.costs[index(map, goal)];
+    if !total.is_finite() {
+        return Err(PathError::NoPath);
+    }
+    //Nothing further than the route itself from the goal can be on it
+    let from_goal = flood(map, &[(goal, 0.0)], total, step, |_, _| false);
+
+    let tolerance = 1e-9 * total.max(1.0);
+    Ok(map
+        .coords()
+        .filter(|tile| {
+            let through = from_start.costs[index(map, *tile)] + from_goal.costs[index(map, *tile)];
+            through <= total + tolerance
+        })
+        .collect())
+}
@@ -0,0 +1,133 @@
+use alloc::vec;
+
+use crate::grid::Coords2D;
+use crate::grid::Map2D;
+use crate::grid::MovingAiMap;
+
+use crate::flood::flood;
+use crate::utils::{compose, distance, index, offset, validate};
+use crate::{PathError, Route, SearchOptions};
+
+///Creates a route from `start` that passes through every tile of a region at least once, for sweeping
+///a floor or exploring a room. The region is every tile reachable from `start` without entering a tile
+///for which `within` returns false, and always includes `start`.
+///
+///Tiles are covered boustrophedon style, sweeping back and forth along rows in straight steps and moving
+///down or up a row at the end of each sweep. Where a sweep is boxed in by walls or covered tiles, the route
+///takes the shortest way to the nearest uncovered tile and carries on from there, so it covers the region
+///whatever its shape, with little retracing in open rooms. The route ends on the last tile covered and its
+///distance is the distance walked. Fails with `PathError::NoPath` if part of the region cannot be reached again
+///once left, which can only happen where swamp or water tiles make moves one way.
+///
+///`options` controls cancellation and the steps listed in the route.
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+/// use blitz_path::SearchOptions;
+///
+/// let map = movingai::parser::parse_map_file(Path::new("./tests/map/maze512-32-9.map")).expect("Could not load map.");
+///
+/// //Sweep the 4 by 4 corner of the open room
+/// let room = |(x, y): (usize, usize)| (1..=4).contains(&x) && (1..=4).contains(&y);
+/// let route = blitz_path::coverage_path(&map, (1, 1), room, &SearchOptions::new()).unwrap();
+/// assert_eq!(route.steps().len(), 16);
+/// assert_eq!(route.distance(), 15.0);
+/// ```
+pub fn coverage_path<F: Fn(Coords2D) -> bool>(
+    map: &MovingAiMap,
+    start: Coords2D,
+    within: F,
+    options: &SearchOptions,
+) -> Result<Route, PathError> {
+    validate(map, start, start)?;
+    let inside = |tile: Coords2D| tile == start || within(tile);
+
+    //Find the region so sweeps never count tiles they cannot reach as uncovered
+    let region = flood(
+        map,
+        &[(start, 0.0)],
+        f64::INFINITY,
+        |_, to| inside(to).then_some(0.0),
+        |_, _| false,
+    );
+    let mut uncovered = region.costs.iter().filter(|cost| cost.is_finite()).count() - 1;
+    let mut covered = vec![false; map.width() * map.height()];
+    covered[index(map, start)] = true;
+
+    let mut path = vec![start];
+    let mut walked = 0.0;
+    let mut current = start;
+    //Horizontal direction of the current sweep, and the vertical direction rows are taken in
+    let (mut across, mut down) = (1, 1);
+    let mut iteration = 0;
+
+    while uncovered > 0 {
+        if options.should_cancel(iteration) {
+            return Err(PathError::Cancelled);
+        }
+        iteration += 1;
+
+        //Carry on sweeping if a straight step reaches an uncovered tile
+        let uncovered_at = |tile: Coords2D| {
+            region.costs[index(map, tile)].is_finite() && !covered[index(map, tile)]
+        };
+        let fresh = |tile: Coords2D| uncovered_at(tile) && map.is_traversable_from(current, tile);
+        let candidates = [(across, 0), (0, down), (0, -down), (-across, 0)];
+        let sweep = candidates.iter().find_map(|(dx, dy)| {
+            offset(current, *dx, *dy)
+                .filter(|tile| !map.is_out_of_bound(*tile) && fresh(*tile))
+                .map(|tile| (tile, (*dx, *dy)))
+        });
+
+        let leg = match sweep {
+            Some((tile, (dx, dy))) => {
+                //Turn back along the next row after moving between rows
+                if dy != 0 {
+                    across = -across;
+                    down = dy;
+                } else {
+                    across = dx;
+                }
+                vec![tile]
+            }
+            None => {
+                //Boxed in, so head for the nearest uncovered tile
+                let mut target = None;
+                let reached = flood(
+                    map,
+                    &[(current, 0.0)],
+                    f64::INFINITY,
+                    |from, to| inside(to).then(|| distance(from, to)),
+                    |tile, _| {
+                        if uncovered_at(tile) {
+                            target = Some(tile);
+                        }
+                        target.is_some()
+                    },
+                );
+                let target = target.ok_or(PathError::NoPath)?;
+                let mut leg = reached
+                    .route_to(map, target)
+                    .ok_or(PathError::NoPath)?
+                    .steps();
+                leg.reverse();
+                leg.remove(0);
+                leg
+            }
+        };
+
+        for tile in leg {
+            walked += distance(current, tile);
+            if region.costs[index(map, tile)].is_finite() && !covered[index(map, tile)] {
+                covered[index(map, tile)] = true;
+                uncovered -= 1;
+            }
+            path.push(tile);
+            current = tile;
+        }
+    }
+
+    path.reverse();
+    Ok(Route::from((walked, compose(path, options))))
+}
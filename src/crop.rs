@@ -0,0 +1,102 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::grid::Coords2D;
+use crate::grid::Map2D;
+use crate::grid::MovingAiMap;
+
+use crate::{MemoryFootprint, Route};
+
+///A rectangle cut out of a larger map, remembering where it came from so positions and routes
+///can be moved between the two, for searches known to stay local and for tests and visualisation
+///that only need part of a map.
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+/// use movingai::Map2D;
+///
+/// let map = movingai::parser::parse_map_file(Path::new("./tests/map/maze512-32-9.map")).expect("Could not load map.");
+///
+/// let area = blitz_path::crop(&map, (1, 1), (20, 10)).unwrap();
+/// assert_eq!((area.map().width(), area.map().height()), (20, 10));
+///
+/// let start = area.to_local((2, 2)).unwrap();
+/// let goal = area.to_local((8, 5)).unwrap();
+/// let route = area.route_to_global(blitz_path::a_star_path(area.map(), start, goal).unwrap());
+/// assert_eq!(route.steps().first(), Some(&(8, 5)));
+/// ```
+#[derive(Debug)]
+pub struct CroppedMap {
+    map: MovingAiMap,
+    origin: Coords2D,
+}
+
+impl CroppedMap {
+    ///Returns the cropped map, whose tile (0, 0) is [`origin`](CroppedMap::origin) on the original.
+    pub fn map(&self) -> &MovingAiMap {
+        &self.map
+    }
+
+    ///Returns the tile of the original map at the top-left corner of the crop.
+    pub fn origin(&self) -> Coords2D {
+        self.origin
+    }
+
+    ///Converts a tile of the original map to the cropped map, or `None` if it was cut off.
+    pub fn to_local(&self, position: Coords2D) -> Option<Coords2D> {
+        let local = (
+            position.0.checked_sub(self.origin.0)?,
+            position.1.checked_sub(self.origin.1)?,
+        );
+        if self.map.is_out_of_bound(local) {
+            return None;
+        }
+        Some(local)
+    }
+
+    ///Converts a tile of the cropped map to the original map.
+    pub fn to_global(&self, position: Coords2D) -> Coords2D {
+        (position.0 + self.origin.0, position.1 + self.origin.1)
+    }
+
+    ///Converts a route found on the cropped map to the original map, keeping its distance.
+    pub fn route_to_global(&self, route: Route) -> Route {
+        route.map_steps(|step| self.to_global(step))
+    }
+
+    ///Returns the cropped map, dropping where it came from.
+    pub fn into_map(self) -> MovingAiMap {
+        self.map
+    }
+}
+
+///Copies the rectangle of `map` from `top_left` to `bottom_right`, both included, into a smaller map,
+///clipping it to the map. Returns `None` if no tile of the map lies inside the rectangle.
+///The corners can come straight from [`Route::bounding_box`], to crop the area around a route.
+///The result is octile, like every map built by this crate.
+pub fn crop(map: &MovingAiMap, top_left: Coords2D, bottom_right: Coords2D) -> Option<CroppedMap> {
+    let right = bottom_right.0.min(map.width().checked_sub(1)?);
+    let bottom = bottom_right.1.min(map.height().checked_sub(1)?);
+    if top_left.0 > right || top_left.1 > bottom {
+        return None;
+    }
+
+    let (width, height) = (right - top_left.0 + 1, bottom - top_left.1 + 1);
+    let mut tiles = Vec::with_capacity(width * height);
+    for y in top_left.1..=bottom {
+        for x in top_left.0..=right {
+            tiles.push(*map.get((x, y)));
+        }
+    }
+    Some(CroppedMap {
+        map: MovingAiMap::new(String::from("octile"), height, width, tiles),
+        origin: top_left,
+    })
+}
+
+impl MemoryFootprint for CroppedMap {
+    fn heap_size(&self) -> usize {
+        self.map.heap_size()
+    }
+}
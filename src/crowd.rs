@@ -0,0 +1,281 @@
+use alloc::vec::Vec;
+
+use crate::grid::Coords2D;
+use crate::grid::Map2D;
+use crate::grid::MovingAiMap;
+
+use crate::flood::flood;
+use crate::memory::vec_size;
+use crate::utils::distance;
+use crate::{MapDiff, MemoryFootprint};
+
+///The direction towards the nearest of a set of goals from every tile of a map, found with a single
+///search outwards from the goals. Any number of agents heading for the same goals can then move
+///by looking up their tile, rather than each searching for its own route.
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+/// use blitz_path::FlowField;
+///
+/// let map = movingai::parser::parse_map_file(Path::new("./tests/map/maze512-32-9.map")).expect("Could not load map.");
+/// let field = FlowField::new(&map, &[(5, 1)]);
+///
+/// assert_eq!(field.next((1, 1)), Some((2, 1)));
+/// assert_eq!(field.cost((1, 1)), 4.0);
+/// assert_eq!(field.next((5, 1)), None);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FlowField {
+    width: usize,
+    height: usize,
+    //Cost to the nearest goal, infinite if unreachable
+    costs: Vec<f64>,
+    //Tile to move to next, equal to itself on goals
+    next: Vec<Coords2D>,
+}
+
+impl FlowField {
+    ///Builds the field leading every traversable tile of `map` to the nearest of `goals`.
+    ///Goals that are not traversable are ignored.
+    pub fn new(map: &MovingAiMap, goals: &[Coords2D]) -> FlowField {
+        let sources: Vec<(Coords2D, f64)> = goals.iter().map(|goal| (*goal, 0.0)).collect();
+        let reached = flood(
+            map,
+            &sources,
+            f64::INFINITY,
+            |from, to| Some(distance(from, to)),
+            |_, _| false,
+        );
+        FlowField {
+            width: map.width(),
+            height: map.height(),
+            costs: reached.costs,
+            next: reached.parents,
+        }
+    }
+
+    ///Returns the cost of reaching the nearest goal from a tile,
+    ///or infinity if it cannot reach one or is outside the map.
+    pub fn cost(&self, position: Coords2D) -> f64 {
+        match self.index(position) {
+            Some(index) => self.costs[index],
+            None => f64::INFINITY,
+        }
+    }
+
+    ///Returns the neighbouring tile to move to from a tile, or `None` if it is a goal,
+    ///cannot reach one, or is outside the map.
+    pub fn next(&self, position: Coords2D) -> Option<Coords2D> {
+        let index = self.index(position)?;
+        if !self.costs[index].is_finite() || self.next[index] == position {
+            return None;
+        }
+        Some(self.next[index])
+    }
+
+    ///Returns the unit vector pointing from a tile towards the tile to move to next,
+    ///or `None` where [`next`](FlowField::next) is `None`.
+    pub fn direction(&self, position: Coords2D) -> Option<(f64, f64)> {
+        let next = self.next(position)?;
+        let (dx, dy) = (
+            next.0 as f64 - position.0 as f64,
+            next.1 as f64 - position.1 as f64,
+        );
+        let length = libm::hypot(dx, dy);
+        Some((dx / length, dy / length))
+    }
+
+    ///Returns the cost of every tile in row-major order.
+    pub fn costs(&self) -> &[f64] {
+        &self.costs
+    }
+
+    ///Returns the width of the underlying map.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    ///Returns the height of the underlying map.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    fn index(&self, position: Coords2D) -> Option<usize> {
+        if position.0 >= self.width || position.1 >= self.height {
+            return None;
+        }
+        Some(position.1 * self.width + position.0)
+    }
+}
+
+///An agent moving through a [`Crowd`], at a continuous position where tile `(x, y)`
+///covers `x..x + 1` and `y..y + 1`, so its centre is at `(x + 0.5, y + 0.5)`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CrowdAgent {
+    ///Where the agent currently is.
+    pub position: (f64, f64),
+    ///The fastest the agent may move, in tiles per tick.
+    pub max_speed: f64,
+}
+
+///Many agents heading for the same goals by sampling a shared [`FlowField`] each tick,
+///as in real-time strategy games where whole armies are ordered to one place.
+///
+///The crowd keeps a snapshot of which tiles were traversable when its field was built,
+///and rebuilds the field on the next tick after the map changes.
+///It gives each agent a desired velocity to follow and leaves moving them, and separating them
+///from each other, to the caller.
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+/// use blitz_path::{Crowd, CrowdAgent};
+///
+/// let map = movingai::parser::parse_map_file(Path::new("./tests/map/maze512-32-9.map")).expect("Could not load map.");
+/// let mut crowd = Crowd::new(&map, &[(5, 1)]);
+///
+/// let agents = [
+///     CrowdAgent { position: (1.5, 1.5), max_speed: 0.5 },
+///     CrowdAgent { position: (5.5, 1.5), max_speed: 0.5 },
+/// ];
+/// let velocities = crowd.tick(&map, &agents);
+/// assert_eq!(velocities, vec![(0.5, 0.0), (0.0, 0.0)]);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Crowd {
+    goals: Vec<Coords2D>,
+    field: FlowField,
+    //Traversability of every tile when the field was built, in row-major order
+    tiles: Vec<bool>,
+}
+
+impl Crowd {
+    ///Creates a crowd heading for the nearest of `goals` on `map`.
+    pub fn new(map: &MovingAiMap, goals: &[Coords2D]) -> Crowd {
+        Crowd {
+            goals: goals.to_vec(),
+            field: FlowField::new(map, goals),
+            tiles: snapshot(map),
+        }
+    }
+
+    ///Sends the crowd to new goals, rebuilding its field.
+    pub fn set_goals(&mut self, map: &MovingAiMap, goals: &[Coords2D]) {
+        self.goals = goals.to_vec();
+        self.field = FlowField::new(map, goals);
+        self.tiles = snapshot(map);
+    }
+
+    ///Returns the goals the crowd is heading for.
+    pub fn goals(&self) -> &[Coords2D] {
+        &self.goals
+    }
+
+    ///Returns the field the crowd currently follows.
+    pub fn field(&self) -> &FlowField {
+        &self.field
+    }
+
+    ///Rebuilds the field if any tile of `map` has become traversable or blocked since it was built,
+    ///returning whether it was rebuilt. Called by every [`tick`](Crowd::tick).
+    pub fn refresh(&mut self, map: &MovingAiMap) -> bool {
+        let tiles = snapshot(map);
+        if tiles == self.tiles {
+            return false;
+        }
+        self.field = FlowField::new(map, &self.goals);
+        self.tiles = tiles;
+        true
+    }
+
+    ///Rebuilds the field if `diff`, already applied to give `map`, made any tile traversable or blocked,
+    ///returning whether it was rebuilt. Only the changed tiles are checked, rather than the whole map.
+    pub fn apply_diff(&mut self, map: &MovingAiMap, diff: &MapDiff) -> bool {
+        let width = map.width();
+        //A map of another size cannot be patched, so compare it all
+        if self.tiles.len() != width * map.height() {
+            return self.refresh(map);
+        }
+        let changes = diff
+            .changes()
+            .iter()
+            .filter(|change| !map.is_out_of_bound(change.position));
+        let changed = changes.clone().any(|change| {
+            let (x, y) = change.position;
+            self.tiles[y * width + x] != map.is_traversable(change.position)
+        });
+        if !changed {
+            return false;
+        }
+        self.field = FlowField::new(map, &self.goals);
+        for change in changes {
+            let (x, y) = change.position;
+            self.tiles[y * width + x] = map.is_traversable(change.position);
+        }
+        true
+    }
+
+    ///Returns the velocity each agent wants to move at this tick, in the same order as `agents`.
+    ///Agents move at full speed towards the centre of the next tile of the field, and slow down
+    ///to stop on the centre of a goal. Agents outside the map or unable to reach a goal stand still.
+    pub fn tick(&mut self, map: &MovingAiMap, agents: &[CrowdAgent]) -> Vec<(f64, f64)> {
+        self.refresh(map);
+        agents
+            .iter()
+            .map(|agent| self.desired_velocity(agent))
+            .collect()
+    }
+
+    //Velocity towards the centre of the next tile, or of the goal when standing on it
+    fn desired_velocity(&self, agent: &CrowdAgent) -> (f64, f64) {
+        let (x, y) = agent.position;
+        if x < 0.0 || y < 0.0 {
+            return (0.0, 0.0);
+        }
+        let tile = (x as usize, y as usize);
+        if !self.field.cost(tile).is_finite() {
+            return (0.0, 0.0);
+        }
+
+        let target = self.field.next(tile).unwrap_or(tile);
+        let (dx, dy) = (target.0 as f64 + 0.5 - x, target.1 as f64 + 0.5 - y);
+        let length = libm::hypot(dx, dy);
+        if length == 0.0 {
+            return (0.0, 0.0);
+        }
+        //Only slow down when arriving, so agents do not crawl between tiles
+        let speed = if target == tile {
+            agent.max_speed.min(length)
+        } else {
+            agent.max_speed
+        };
+        (dx / length * speed, dy / length * speed)
+    }
+}
+
+//Whether each tile is traversable, in row-major order
+fn snapshot(map: &MovingAiMap) -> Vec<bool> {
+    let mut tiles = Vec::with_capacity(map.width() * map.height());
+    for y in 0..map.height() {
+        for x in 0..map.width() {
+            tiles.push(map.is_traversable((x, y)));
+        }
+    }
+    tiles
+}
+
+impl MemoryFootprint for FlowField {
+    fn heap_size(&self) -> usize {
+        vec_size(&self.costs) + vec_size(&self.next)
+    }
+}
+
+impl MemoryFootprint for Crowd {
+    fn heap_size(&self) -> usize {
+        vec_size(&self.goals) + self.field.heap_size() + vec_size(&self.tiles)
+    }
+}
@@ -0,0 +1,195 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::grid::Coords2D;
+use crate::grid::Map2D;
+use crate::grid::MovingAiMap;
+
+use crate::flood::flood;
+use crate::memory::vec_size;
+use crate::utils::{distance, neighbours};
+use crate::{MemoryFootprint, Route};
+
+///A Dijkstra map, as used by roguelike AI: a value on every tile that agents lower by stepping downhill.
+///
+///A map built with [`new`](DesireMap::new) holds the distance from each tile to the nearest source,
+///so walking downhill reaches a source by the shortest route. Maps of the same size can be
+///[`scale`](DesireMap::scale)d and [`add`](DesireMap::add)ed, weighing several desires against each other,
+///such as chasing the player while picking up gold on the way. [`flee`](DesireMap::flee) turns a map
+///into one leading away from its sources, around corners rather than into them.
+///
+///Blocked and unreachable tiles hold [`f64::INFINITY`] in every map, and agents never step onto them.
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+/// use blitz_path::DesireMap;
+///
+/// let map = movingai::parser::parse_map_file(Path::new("./tests/map/maze512-32-9.map")).expect("Could not load map.");
+///
+/// let player = DesireMap::new(&map, &[((5, 1), 0.0)]);
+/// let gold = DesireMap::new(&map, &[((1, 5), 0.0)]);
+/// assert_eq!(player.get((1, 1)), 4.0);
+///
+/// //Chase the player, but care twice as much about the gold
+/// let desire = player.add(&gold.scale(2.0));
+/// let route = desire.descent_path(&map, (3, 3), 100);
+/// assert_eq!(route.steps().first(), Some(&(1, 5)));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DesireMap {
+    width: usize,
+    height: usize,
+    values: Vec<f64>,
+}
+
+impl DesireMap {
+    ///Builds a map where each tile holds the cheapest of the initial value of a source plus the distance to it.
+    ///Sources with lower initial values are more attractive. Sources that are blocked or outside the map are ignored.
+    pub fn new(map: &MovingAiMap, sources: &[(Coords2D, f64)]) -> DesireMap {
+        let reached = flood(
+            map,
+            sources,
+            f64::INFINITY,
+            |from, to| Some(distance(from, to)),
+            |_, _| false,
+        );
+        DesireMap {
+            width: map.width(),
+            height: map.height(),
+            values: reached.costs,
+        }
+    }
+
+    ///Returns the value of a tile, or infinity if it is blocked, unreachable or outside the map.
+    pub fn get(&self, position: Coords2D) -> f64 {
+        if position.0 >= self.width || position.1 >= self.height {
+            return f64::INFINITY;
+        }
+        self.values[position.1 * self.width + position.0]
+    }
+
+    ///Returns a map with every value multiplied by `factor`. A negative factor makes agents walk uphill
+    ///on the original map, though usually into the nearest dead end; see [`flee`](DesireMap::flee).
+    pub fn scale(&self, factor: f64) -> DesireMap {
+        self.map_values(|value| value * factor)
+    }
+
+    ///Returns the sum of this map and `other`, with tiles unreachable in either staying unreachable.
+    ///
+    ///# Panics
+    ///
+    ///Panics if the maps are different sizes.
+    pub fn add(&self, other: &DesireMap) -> DesireMap {
+        assert_eq!(
+            (self.width, self.height),
+            (other.width, other.height),
+            "desire maps must be the same size"
+        );
+        let mut sum = self.clone();
+        for (value, other) in sum.values.iter_mut().zip(&other.values) {
+            if other.is_finite() {
+                *value += other;
+            } else {
+                *value = f64::INFINITY;
+            }
+        }
+        sum
+    }
+
+    ///Returns the weighted sum of `maps`, or `None` if there are none.
+    ///
+    ///# Panics
+    ///
+    ///Panics if the maps are different sizes.
+    pub fn combine(maps: &[(&DesireMap, f64)]) -> Option<DesireMap> {
+        maps.iter()
+            .map(|(map, weight)| map.scale(*weight))
+            .reduce(|sum, map| sum.add(&map))
+    }
+
+    ///Lets every tile take the value of a neighbour plus the distance to it when that is lower,
+    ///so values never fall by more than the distance between tiles, and agents walking downhill take
+    ///the shortest route to the lowest values rather than the steepest local slope.
+    pub fn rescan(&self, map: &MovingAiMap) -> DesireMap {
+        let sources: Vec<(Coords2D, f64)> = map
+            .coords()
+            .map(|tile| (tile, self.get(tile)))
+            .filter(|(_, value)| value.is_finite())
+            .collect();
+        DesireMap::new(map, &sources)
+    }
+
+    ///Returns a map leading away from the sources of this one: values are multiplied by `-factor`
+    ///and [`rescan`](DesireMap::rescan)ned, so agents flee towards distant open areas, cutting past
+    ///their pursuer where that leads somewhere safer instead of cornering themselves.
+    ///A factor a little above one, such as 1.2, is usual.
+    pub fn flee(&self, map: &MovingAiMap, factor: f64) -> DesireMap {
+        self.scale(-factor).rescan(map)
+    }
+
+    ///Returns the neighbour of `position` an agent walking downhill steps to, the lowest one lower
+    ///than `position` itself, or `None` if it is a local minimum. Ties go to the first neighbour found.
+    pub fn descend(&self, map: &MovingAiMap, position: Coords2D) -> Option<Coords2D> {
+        let mut best = (self.get(position), None);
+        for next in neighbours(map, position) {
+            let value = self.get(next);
+            if value < best.0 {
+                best = (value, Some(next));
+            }
+        }
+        best.1
+    }
+
+    ///Follows [`descend`](DesireMap::descend) from `start` for at most `max_steps` steps, stopping early
+    ///at a local minimum. The route's distance is the distance walked, and it lists `start` alone
+    ///if no neighbour is lower.
+    pub fn descent_path(&self, map: &MovingAiMap, start: Coords2D, max_steps: usize) -> Route {
+        let mut steps = vec![start];
+        let mut walked = 0.0;
+        let mut current = start;
+        while steps.len() <= max_steps {
+            match self.descend(map, current) {
+                Some(next) => {
+                    walked += distance(current, next);
+                    steps.push(next);
+                    current = next;
+                }
+                None => break,
+            }
+        }
+        steps.reverse();
+        Route::from((walked, steps))
+    }
+
+    ///Returns the value of every tile in row-major order.
+    pub fn values(&self) -> &[f64] {
+        &self.values
+    }
+
+    ///Returns the width of the underlying map.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    ///Returns the height of the underlying map.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    //Applies f to every reachable value
+    fn map_values<F: Fn(f64) -> f64>(&self, f: F) -> DesireMap {
+        let mut mapped = self.clone();
+        for value in mapped.values.iter_mut().filter(|value| value.is_finite()) {
+            *value = f(*value);
+        }
+        mapped
+    }
+}
+
+impl MemoryFootprint for DesireMap {
+    fn heap_size(&self) -> usize {
+        vec_size(&self.values)
+    }
+}
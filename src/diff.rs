@@ -0,0 +1,206 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::grid::Coords2D;
+use crate::grid::Map2D;
+use crate::grid::MovingAiMap;
+
+use crate::memory::vec_size;
+use crate::{MapDiffError, MemoryFootprint, Route};
+
+///A single tile changed by a [`MapDiff`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CellChange {
+    ///The tile that changed.
+    pub position: Coords2D,
+    ///The MovingAI tile character before the change.
+    pub before: char,
+    ///The MovingAI tile character after the change.
+    pub after: char,
+}
+
+///The tiles changed between two revisions of a map, such as a door closing or a wall being built,
+///for systems that need to agree on what changed: invalidating preprocessed data, syncing editable
+///worlds over a network, or telling planners such as [`ActivePath`](crate::ActivePath) and
+///[`Crowd`](crate::Crowd) which routes and fields are stale.
+///
+///Changes are kept in row-major order and remember the tile both before and after,
+///so a diff can be reverted as well as applied, and refuses to apply to a map it does not match.
+///Maps built by applying a diff are octile, like every map built by this crate.
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+/// use blitz_path::MapDiff;
+///
+/// let map = movingai::parser::parse_map_file(Path::new("./tests/map/maze512-32-9.map")).expect("Could not load map.");
+///
+/// // Build a wall across the corridor
+/// let diff = MapDiff::new(&map, &[((3, 1), '@'), ((3, 2), '@')]);
+/// let walled = diff.apply(&map).unwrap();
+/// assert!(blitz_path::a_star_path(&walled, (1, 1), (5, 1)).unwrap().distance() > 4.0);
+///
+/// let restored = diff.revert(&walled).unwrap();
+/// assert!(MapDiff::between(&map, &restored).unwrap().is_empty());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MapDiff {
+    width: usize,
+    height: usize,
+    changes: Vec<CellChange>,
+}
+
+impl MapDiff {
+    ///Describes setting each tile in `edits` to a new MovingAI tile character on `map`.
+    ///Edits outside the map or leaving a tile as it was are ignored, and later edits to a tile replace earlier ones.
+    pub fn new(map: &MovingAiMap, edits: &[(Coords2D, char)]) -> MapDiff {
+        let mut changes: Vec<CellChange> = Vec::new();
+        for (position, after) in edits.iter().rev() {
+            if map.is_out_of_bound(*position)
+                || changes.iter().any(|change| change.position == *position)
+            {
+                continue;
+            }
+            changes.push(CellChange {
+                position: *position,
+                before: *map.get(*position),
+                after: *after,
+            });
+        }
+        changes.retain(|change| change.before != change.after);
+        changes.sort_by_key(|change| (change.position.1, change.position.0));
+        MapDiff {
+            width: map.width(),
+            height: map.height(),
+            changes,
+        }
+    }
+
+    ///Finds every tile that differs between two revisions of a map of the same size.
+    pub fn between(before: &MovingAiMap, after: &MovingAiMap) -> Result<MapDiff, MapDiffError> {
+        check_size((before.width(), before.height()), after)?;
+        let changes = before
+            .coords()
+            .filter(|position| before.get(*position) != after.get(*position))
+            .map(|position| CellChange {
+                position,
+                before: *before.get(position),
+                after: *after.get(position),
+            })
+            .collect();
+        Ok(MapDiff {
+            width: before.width(),
+            height: before.height(),
+            changes,
+        })
+    }
+
+    ///Returns every changed tile, in row-major order.
+    pub fn changes(&self) -> &[CellChange] {
+        &self.changes
+    }
+
+    ///Returns the number of changed tiles.
+    pub fn len(&self) -> usize {
+        self.changes.len()
+    }
+
+    ///Returns true if no tile changed.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    ///Returns true if `position` is one of the changed tiles.
+    pub fn contains(&self, position: Coords2D) -> bool {
+        self.changes
+            .binary_search_by_key(&(position.1, position.0), |change| {
+                (change.position.1, change.position.0)
+            })
+            .is_ok()
+    }
+
+    ///Returns the top-left and bottom-right tiles of the rectangle holding every change,
+    ///or `None` if nothing changed.
+    pub fn bounding_box(&self) -> Option<(Coords2D, Coords2D)> {
+        let first = self.changes.first()?.position;
+        Some(
+            self.changes
+                .iter()
+                .fold((first, first), |(low, high), change| {
+                    let (x, y) = change.position;
+                    ((low.0.min(x), low.1.min(y)), (high.0.max(x), high.1.max(y)))
+                }),
+        )
+    }
+
+    ///Returns true if any changed tile could affect following `route`, including the tiles beside
+    ///its diagonal steps, so only routes that need checking again are searched.
+    pub fn touches(&self, route: &Route) -> bool {
+        route
+            .cells_touched()
+            .into_iter()
+            .any(|position| self.contains(position))
+    }
+
+    ///Returns the diff that undoes this one.
+    pub fn inverse(&self) -> MapDiff {
+        MapDiff {
+            width: self.width,
+            height: self.height,
+            changes: self
+                .changes
+                .iter()
+                .map(|change| CellChange {
+                    position: change.position,
+                    before: change.after,
+                    after: change.before,
+                })
+                .collect(),
+        }
+    }
+
+    ///Returns a copy of `map` with every change made. Fails if the map is a different size,
+    ///or a changed tile does not hold what it held before the change.
+    pub fn apply(&self, map: &MovingAiMap) -> Result<MovingAiMap, MapDiffError> {
+        check_size((self.width, self.height), map)?;
+        let mut tiles: Vec<char> = map.coords().map(|position| *map.get(position)).collect();
+        for change in &self.changes {
+            let found = *map.get(change.position);
+            if found != change.before {
+                return Err(MapDiffError::Conflict {
+                    position: change.position,
+                    expected: change.before,
+                    found,
+                });
+            }
+            tiles[change.position.1 * self.width + change.position.0] = change.after;
+        }
+        Ok(MovingAiMap::new(
+            String::from("octile"),
+            self.height,
+            self.width,
+            tiles,
+        ))
+    }
+
+    ///Returns a copy of `map` with every change undone, failing as [`apply`](MapDiff::apply) does.
+    pub fn revert(&self, map: &MovingAiMap) -> Result<MovingAiMap, MapDiffError> {
+        self.inverse().apply(map)
+    }
+}
+
+fn check_size(expected: (usize, usize), map: &MovingAiMap) -> Result<(), MapDiffError> {
+    let found = (map.width(), map.height());
+    if found != expected {
+        return Err(MapDiffError::SizeMismatch { expected, found });
+    }
+    Ok(())
+}
+
+impl MemoryFootprint for MapDiff {
+    fn heap_size(&self) -> usize {
+        vec_size(&self.changes)
+    }
+}
@@ -0,0 +1,72 @@
+use crate::grid::Coords2D;
+use crate::grid::MovingAiMap;
+
+use crate::astar::search;
+use crate::trace::instrument;
+use crate::utils::distance;
+use crate::{PathError, Route, SearchContext, SearchOptions};
+
+///Creates a new route using Dijkstra's algorithm.
+///Explores evenly in every direction rather than towards the goal, so it is slower than A*,
+///but stays optimal for any non-negative step costs.
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+///
+/// let map = movingai::parser::parse_map_file(Path::new("./tests/map/maze512-32-9.map")).expect("Could not load map.");
+/// let scenes = movingai::parser::parse_scen_file(Path::new("./tests/map/maze512-32-9.map.scen")).expect("Could not load scenario.");
+/// let scene = &scenes[0];
+///
+/// let path = blitz_path::dijkstra_path(&map, scene.start_pos, scene.goal_pos).unwrap();
+/// assert_eq!(scene.optimal_length as f32, path.distance() as f32);
+/// ```
+pub fn dijkstra_path(
+    map: &MovingAiMap,
+    start: Coords2D,
+    goal: Coords2D,
+) -> Result<Route, PathError> {
+    dijkstra_path_weighted(map, start, goal, &SearchOptions::default(), |from, to| {
+        Some(distance(from, to))
+    })
+}
+
+///Creates a new route using Dijkstra's algorithm, with the cost of each step given by `cost`.
+///`cost` is called with the tile being left and the tile being entered, and returns None if the move is not allowed.
+///Costs must not be negative.
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+/// use blitz_path::{PathError, SearchOptions};
+///
+/// let map = movingai::parser::parse_map_file(Path::new("./tests/map/maze512-32-9.map")).expect("Could not load map.");
+///
+/// // A locked door at (3, 1) that this unit has no key for
+/// let cost = |_from: (usize, usize), to: (usize, usize)| if to == (3, 1) { None } else { Some(0.5) };
+/// let path = blitz_path::dijkstra_path_weighted(&map, (1, 1), (5, 1), &SearchOptions::new(), cost).unwrap();
+/// assert!(!path.steps().contains(&(3, 1)));
+/// ```
+pub fn dijkstra_path_weighted<C>(
+    map: &MovingAiMap,
+    start: Coords2D,
+    goal: Coords2D,
+    options: &SearchOptions,
+    cost: C,
+) -> Result<Route, PathError>
+where
+    C: Fn(Coords2D, Coords2D) -> Option<f64>,
+{
+    instrument("dijkstra", start, goal, &mut (), |observer| {
+        search(
+            &mut SearchContext::new(),
+            map,
+            start,
+            goal,
+            options,
+            cost,
+            |_, _| 0.0,
+            observer,
+        )
+    })
+}
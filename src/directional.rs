@@ -0,0 +1,201 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::grid::Coords2D;
+use crate::grid::Map2D;
+use crate::grid::MovingAiMap;
+
+use crate::memory::vec_size;
+use crate::utils::distance;
+use crate::MemoryFootprint;
+
+///The eight directions a step can take. North is towards row 0 and east is towards higher columns.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Direction {
+    ///Up, decreasing y.
+    North,
+    ///Up and right.
+    NorthEast,
+    ///Right, increasing x.
+    East,
+    ///Down and right.
+    SouthEast,
+    ///Down, increasing y.
+    South,
+    ///Down and left.
+    SouthWest,
+    ///Left, decreasing x.
+    West,
+    ///Up and left.
+    NorthWest,
+}
+
+impl Direction {
+    ///Every direction, clockwise from north.
+    pub const ALL: [Direction; 8] = [
+        Direction::North,
+        Direction::NorthEast,
+        Direction::East,
+        Direction::SouthEast,
+        Direction::South,
+        Direction::SouthWest,
+        Direction::West,
+        Direction::NorthWest,
+    ];
+
+    ///Returns the change in x and y of a single step in this direction.
+    pub fn offset(self) -> (i32, i32) {
+        match self {
+            Direction::North => (0, -1),
+            Direction::NorthEast => (1, -1),
+            Direction::East => (1, 0),
+            Direction::SouthEast => (1, 1),
+            Direction::South => (0, 1),
+            Direction::SouthWest => (-1, 1),
+            Direction::West => (-1, 0),
+            Direction::NorthWest => (-1, -1),
+        }
+    }
+
+    ///Returns the direction of a step between two neighbouring tiles,
+    ///or `None` if they are the same tile or not neighbours.
+    pub fn between(from: Coords2D, to: Coords2D) -> Option<Direction> {
+        let offset = (to.0 as i64 - from.0 as i64, to.1 as i64 - from.1 as i64);
+        Direction::ALL.iter().copied().find(|direction| {
+            let (dx, dy) = direction.offset();
+            (i64::from(dx), i64::from(dy)) == offset
+        })
+    }
+
+    //Cosine of the angle between two directions
+    pub(crate) fn alignment(self, other: Direction) -> f64 {
+        let (a, b) = (self.offset(), other.offset());
+        let dot = f64::from(a.0 * b.0 + a.1 * b.1);
+        dot / libm::sqrt(f64::from(a.0 * a.0 + a.1 * a.1) * f64::from(b.0 * b.0 + b.1 * b.1))
+    }
+}
+
+///Step costs that depend on the direction of travel, such as one-way streets, conveyor belts and currents.
+///Each tile holds a multiplier for leaving it in each of the eight directions, or forbids leaving that way.
+///
+///Multipliers below 1.0 make steps cheaper than the distance they cover, so search with
+///[`dijkstra_path_weighted`](crate::dijkstra_path_weighted) rather than A* when using them.
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+/// use blitz_path::{Direction, DirectionalCosts};
+///
+/// let map = movingai::parser::parse_map_file(Path::new("./tests/map/maze512-32-9.map")).expect("Could not load map.");
+///
+/// //Row 1 is a one-way street heading east
+/// let mut costs = DirectionalCosts::new(&map);
+/// for x in 1..10 {
+///     costs.one_way((x, 1), Direction::East);
+/// }
+///
+/// let cost = costs.step_cost();
+/// assert_eq!(cost((2, 1), (3, 1)), Some(1.0));
+/// assert_eq!(cost((3, 1), (2, 1)), None);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DirectionalCosts {
+    width: usize,
+    height: usize,
+    //Multiplier for leaving each tile in each direction, in the order of Direction::ALL
+    multipliers: Vec<[Option<f64>; 8]>,
+}
+
+impl DirectionalCosts {
+    ///Creates costs for every tile of `map` where every direction costs the distance travelled.
+    pub fn new(map: &MovingAiMap) -> DirectionalCosts {
+        DirectionalCosts {
+            width: map.width(),
+            height: map.height(),
+            multipliers: vec![[Some(1.0); 8]; map.width() * map.height()],
+        }
+    }
+
+    ///Returns the multiplier for leaving `tile` in `direction`, or `None` if it is forbidden
+    ///or the tile is outside the map.
+    pub fn get(&self, tile: Coords2D, direction: Direction) -> Option<f64> {
+        let index = self.index(tile)?;
+        self.multipliers[index][direction as usize]
+    }
+
+    ///Sets the multiplier for leaving `tile` in `direction`, or forbids it with `None`.
+    ///Tiles outside the map are ignored.
+    pub fn set(&mut self, tile: Coords2D, direction: Direction, multiplier: Option<f64>) {
+        if let Some(index) = self.index(tile) {
+            self.multipliers[index][direction as usize] = multiplier;
+        }
+    }
+
+    ///Forbids leaving `tile` in any direction heading even partly against `direction`,
+    ///so traffic only flows one way while still being able to turn off sideways.
+    pub fn one_way(&mut self, tile: Coords2D, direction: Direction) {
+        for other in Direction::ALL {
+            if direction.alignment(other) < -1e-9 {
+                self.set(tile, other, None);
+            }
+        }
+    }
+
+    ///Makes leaving `tile` cheaper the more closely a step follows `direction` and dearer the more it opposes it,
+    ///like a conveyor belt or current. The multiplier is one minus `strength` times the cosine of the angle
+    ///between the step and the flow, so `strength` should be below 1.0 to keep every step's cost positive.
+    ///Directions already forbidden stay forbidden.
+    pub fn flow(&mut self, tile: Coords2D, direction: Direction, strength: f64) {
+        for other in Direction::ALL {
+            if self.get(tile, other).is_some() {
+                self.set(
+                    tile,
+                    other,
+                    Some(1.0 - strength * direction.alignment(other)),
+                );
+            }
+        }
+    }
+
+    ///Returns a step cost for the weighted searches, giving the distance of each step times the multiplier
+    ///for leaving its tile in its direction, or `None` where that is forbidden.
+    pub fn step_cost(&self) -> impl Fn(Coords2D, Coords2D) -> Option<f64> + '_ {
+        move |from, to| {
+            let multiplier = self.get(from, Direction::between(from, to)?)?;
+            Some(distance(from, to) * multiplier)
+        }
+    }
+
+    //Cheapest multiplier any tile has for leaving in `direction`, or None if every tile forbids it
+    pub(crate) fn lowest(&self, direction: Direction) -> Option<f64> {
+        self.multipliers
+            .iter()
+            .filter_map(|multipliers| multipliers[direction as usize])
+            .min_by(f64::total_cmp)
+    }
+
+    ///Returns the width of the underlying map.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    ///Returns the height of the underlying map.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    fn index(&self, tile: Coords2D) -> Option<usize> {
+        if tile.0 >= self.width || tile.1 >= self.height {
+            return None;
+        }
+        Some(tile.1 * self.width + tile.0)
+    }
+}
+
+impl MemoryFootprint for DirectionalCosts {
+    fn heap_size(&self) -> usize {
+        vec_size(&self.multipliers)
+    }
+}
@@ -0,0 +1,238 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::grid::Coords2D;
+use crate::grid::Map2D;
+use crate::grid::MovingAiMap;
+
+use crate::node::Node;
+use crate::{Route, SearchContext};
+
+///The value drawn for each expanded tile by [`SearchContext::heatmap_csv`] and [`SearchContext::heatmap_pgm`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum HeatmapValue {
+    ///The cost of reaching the tile from the start.
+    Cost,
+    ///The position of the tile in the order tiles were expanded, starting from 0.
+    ExpansionOrder,
+}
+
+impl SearchContext {
+    ///Draws the area covered by the last search as text, one line per row, for debugging.
+    ///Expanded tiles are drawn as `x`, tiles still in the frontier as `o` and the steps of `route` as `*`,
+    ///with its start as `S` and goal as `G`. Other tiles keep their character from the map.
+    ///
+    ///Only the rectangle around everything the search touched is drawn, with a margin of one tile,
+    ///and the first line gives the map coordinates of its top left corner.
+    /// # Examples
+    ///
+    /// ```
+    /// use std::path::Path;
+    /// use blitz_path::{SearchContext, SearchOptions};
+    ///
+    /// let map = movingai::parser::parse_map_file(Path::new("./tests/map/maze512-32-9.map")).expect("Could not load map.");
+    ///
+    /// let mut context = SearchContext::new();
+    /// let path = context.a_star_path(&map, (1, 1), (4, 2), &SearchOptions::new()).unwrap();
+    ///
+    /// let dump = context.ascii_dump(&map, Some(&path));
+    /// assert_eq!(dump, "(0, 0)\n@@@@@@\n@S*oo.\n@oo*G.\n@.ooo.\n@.....\n");
+    /// ```
+    pub fn ascii_dump(&self, map: &MovingAiMap, route: Option<&Route>) -> String {
+        let route = route.map(Route::steps).unwrap_or_default();
+        let touched: Vec<Coords2D> = self
+            .closed
+            .iter()
+            .chain(self.open.iter())
+            .map(|node| node.position)
+            .chain(route.iter().copied())
+            .filter(|position| !map.is_out_of_bound(*position))
+            .collect();
+        if touched.is_empty() {
+            return String::new();
+        }
+
+        let min_x = touched.iter().map(|position| position.0).min().unwrap_or(0);
+        let max_x = touched.iter().map(|position| position.0).max().unwrap_or(0);
+        let min_y = touched.iter().map(|position| position.1).min().unwrap_or(0);
+        let max_y = touched.iter().map(|position| position.1).max().unwrap_or(0);
+        let (left, top) = (min_x.saturating_sub(1), min_y.saturating_sub(1));
+        let (right, bottom) = (
+            (max_x + 1).min(map.width() - 1),
+            (max_y + 1).min(map.height() - 1),
+        );
+
+        let width = right - left + 1;
+        let mut cells: Vec<char> = (top..=bottom)
+            .flat_map(|y| (left..=right).map(move |x| (x, y)))
+            .map(|position| *map.get(position))
+            .collect();
+        let mut mark = |position: Coords2D, symbol: char| {
+            if (left..=right).contains(&position.0) && (top..=bottom).contains(&position.1) {
+                cells[(position.1 - top) * width + position.0 - left] = symbol;
+            }
+        };
+
+        //Later marks take precedence over earlier ones
+        for node in &self.closed {
+            mark(node.position, 'x');
+        }
+        for node in self.open.iter() {
+            mark(node.position, 'o');
+        }
+        for step in &route {
+            mark(*step, '*');
+        }
+        if let (Some(goal), Some(start)) = (route.first(), route.last()) {
+            mark(*start, 'S');
+            mark(*goal, 'G');
+        }
+
+        let mut dump = format!("({}, {})\n", left, top);
+        for row in cells.chunks(width) {
+            dump.extend(row);
+            dump.push('\n');
+        }
+        dump
+    }
+
+    ///Exports the tree of parent links built by the last search as a Graphviz DOT digraph.
+    ///Each node is labelled with its position and its f, g and h values, with an edge from its parent.
+    ///Nodes still in the frontier are drawn dashed.
+    ///
+    ///Nodes are listed in position order, and a tile reached more than once appears only once,
+    ///preferring the expanded node and then the cheapest, so the output of two searches can be diffed.
+    /// # Examples
+    ///
+    /// ```
+    /// use std::path::Path;
+    /// use blitz_path::{SearchContext, SearchOptions};
+    ///
+    /// let map = movingai::parser::parse_map_file(Path::new("./tests/map/maze512-32-9.map")).expect("Could not load map.");
+    ///
+    /// let mut context = SearchContext::new();
+    /// context.a_star_path(&map, (1, 1), (3, 1), &SearchOptions::new()).unwrap();
+    ///
+    /// let dot = context.to_dot();
+    /// assert!(dot.starts_with("digraph search {"));
+    /// assert!(dot.contains("\"1_1\" -> \"2_1\""));
+    /// ```
+    pub fn to_dot(&self) -> String {
+        //Expanded nodes first, so they win over frontier nodes for the same tile
+        let mut nodes: Vec<(Node, bool)> = self
+            .closed
+            .iter()
+            .map(|node| (*node, true))
+            .chain(self.open.iter().map(|node| (*node, false)))
+            .collect();
+        nodes.sort_by(|a, b| {
+            a.0.position
+                .cmp(&b.0.position)
+                .then(b.1.cmp(&a.1))
+                .then(a.0.g.total_cmp(&b.0.g))
+        });
+        nodes.dedup_by_key(|(node, _)| node.position);
+
+        let mut dot = String::from("digraph search {\n");
+        for (node, expanded) in &nodes {
+            dot.push_str(&format!(
+                "    \"{}_{}\" [label=\"({}, {})\\nf={:.3} g={:.3} h={:.3}\"{}];\n",
+                node.position.0,
+                node.position.1,
+                node.position.0,
+                node.position.1,
+                node.f,
+                node.g,
+                node.h,
+                if *expanded { "" } else { ", style=dashed" }
+            ));
+        }
+        for (node, _) in &nodes {
+            if node.parent != node.position {
+                dot.push_str(&format!(
+                    "    \"{}_{}\" -> \"{}_{}\";\n",
+                    node.parent.0, node.parent.1, node.position.0, node.position.1
+                ));
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    ///Exports a value for every tile expanded by the last search as CSV, with one line per map row
+    ///and one field per column. Tiles that were not expanded are left empty.
+    /// # Examples
+    ///
+    /// ```
+    /// use std::path::Path;
+    /// use blitz_path::{HeatmapValue, SearchContext, SearchOptions};
+    ///
+    /// let map = movingai::parser::parse_map_file(Path::new("./tests/map/maze512-32-9.map")).expect("Could not load map.");
+    ///
+    /// let mut context = SearchContext::new();
+    /// context.a_star_path(&map, (1, 1), (3, 1), &SearchOptions::new()).unwrap();
+    ///
+    /// let csv = context.heatmap_csv(&map, HeatmapValue::ExpansionOrder);
+    /// assert_eq!(csv.lines().count(), 512);
+    /// assert!(csv.lines().nth(1).unwrap().starts_with(",0,1,,"));
+    /// ```
+    pub fn heatmap_csv(&self, map: &MovingAiMap, value: HeatmapValue) -> String {
+        let heat = self.heat(map, value);
+        let mut csv = String::new();
+        for row in heat.chunks(map.width()) {
+            let fields: Vec<String> = row
+                .iter()
+                .map(|cell| match (cell, value) {
+                    (Some(order), HeatmapValue::ExpansionOrder) => format!("{}", order),
+                    (Some(cost), HeatmapValue::Cost) => format!("{:.3}", cost),
+                    (None, _) => String::new(),
+                })
+                .collect();
+            csv.push_str(&fields.join(","));
+            csv.push('\n');
+        }
+        csv
+    }
+
+    ///Exports a value for every tile expanded by the last search as a binary PGM image,
+    ///one pixel per tile. Expanded tiles are scaled from dark grey for the lowest value to white for the highest,
+    ///and tiles that were not expanded are black.
+    ///PGM files can be opened by most image viewers and converted with tools such as ImageMagick.
+    pub fn heatmap_pgm(&self, map: &MovingAiMap, value: HeatmapValue) -> Vec<u8> {
+        let heat = self.heat(map, value);
+        let values = heat.iter().flatten();
+        let low = values.clone().copied().fold(f64::INFINITY, f64::min);
+        let high = values.copied().fold(f64::NEG_INFINITY, f64::max);
+
+        let mut image = format!("P5\n{} {}\n255\n", map.width(), map.height()).into_bytes();
+        image.extend(heat.iter().map(|cell| match cell {
+            Some(value) if high > low => {
+                libm::round(1.0 + (value - low) / (high - low) * 254.0) as u8
+            }
+            Some(_) => 255,
+            None => 0,
+        }));
+        image
+    }
+
+    //The chosen value for each tile of the map in row order, keeping the first expansion of a tile
+    fn heat(&self, map: &MovingAiMap, value: HeatmapValue) -> Vec<Option<f64>> {
+        let mut heat = vec![None; map.width() * map.height()];
+        for (order, node) in self.closed.iter().enumerate() {
+            if map.is_out_of_bound(node.position) {
+                continue;
+            }
+            let cell = &mut heat[node.position.1 * map.width() + node.position.0];
+            if cell.is_none() {
+                *cell = Some(match value {
+                    HeatmapValue::Cost => node.g,
+                    HeatmapValue::ExpansionOrder => order as f64,
+                });
+            }
+        }
+        heat
+    }
+}
@@ -0,0 +1,106 @@
+use alloc::vec::Vec;
+
+use crate::grid::Coords2D;
+use crate::grid::Map2D;
+use crate::grid::MovingAiMap;
+
+use crate::memory::vec_size;
+use crate::utils::distance;
+use crate::MemoryFootprint;
+
+///The elevation of every tile of a map, for outdoor terrain where climbing costs more than walking on the flat
+///and cliffs cannot be crossed at all.
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+/// use blitz_path::{Heightmap, SearchOptions};
+///
+/// let map = movingai::parser::parse_map_file(Path::new("./tests/map/maze512-32-9.map")).expect("Could not load map.");
+///
+/// //A ridge rising towards column 3
+/// let heights = Heightmap::new(&map, |(x, _)| if x == 3 { 2.0 } else { 0.0 });
+/// assert_eq!(heights.slope((2, 1), (3, 1)), 2.0);
+///
+/// let cost = heights.slope_cost(1.0, 0.5, 3.0);
+/// let path = blitz_path::a_star_path_weighted(&map, (1, 1), (5, 1), &SearchOptions::new(), cost).unwrap();
+/// assert_eq!(path.distance(), 4.0 + 2.0 + 1.0);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Heightmap {
+    width: usize,
+    height: usize,
+    values: Vec<f64>,
+}
+
+impl Heightmap {
+    ///Records the elevation of every tile of `map`, as given by `elevation`.
+    pub fn new<F: Fn(Coords2D) -> f64>(map: &MovingAiMap, elevation: F) -> Heightmap {
+        Heightmap {
+            width: map.width(),
+            height: map.height(),
+            values: map.coords().map(elevation).collect(),
+        }
+    }
+
+    ///Returns the elevation of a tile, or 0.0 if it is outside the map.
+    pub fn get(&self, position: Coords2D) -> f64 {
+        if position.0 >= self.width || position.1 >= self.height {
+            return 0.0;
+        }
+        self.values[position.1 * self.width + position.0]
+    }
+
+    ///Returns the slope of a step, as the rise in elevation per tile travelled.
+    ///Negative when going downhill.
+    pub fn slope(&self, from: Coords2D, to: Coords2D) -> f64 {
+        let run = distance(from, to);
+        if run == 0.0 {
+            return 0.0;
+        }
+        (self.get(to) - self.get(from)) / run
+    }
+
+    ///Returns a step cost for the weighted searches derived from the slope of each step.
+    ///A step costs its length times one plus `uphill` times its slope when climbing,
+    ///or `downhill` times its slope when descending, and steps steeper than `max_slope` either way are forbidden.
+    ///With non-negative weights no step costs less than its length, so the cost can be used with
+    ///[`a_star_path_weighted`](crate::a_star_path_weighted).
+    pub fn slope_cost(
+        &self,
+        uphill: f64,
+        downhill: f64,
+        max_slope: f64,
+    ) -> impl Fn(Coords2D, Coords2D) -> Option<f64> + '_ {
+        move |from, to| {
+            let slope = self.slope(from, to);
+            if slope.abs() > max_slope {
+                return None;
+            }
+            let weight = if slope > 0.0 { uphill } else { downhill };
+            Some(distance(from, to) * (1.0 + weight * slope.abs()))
+        }
+    }
+
+    ///Returns the elevation of every tile in row-major order.
+    pub fn values(&self) -> &[f64] {
+        &self.values
+    }
+
+    ///Returns the width of the underlying map.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    ///Returns the height of the underlying map.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+}
+
+impl MemoryFootprint for Heightmap {
+    fn heap_size(&self) -> usize {
+        vec_size(&self.values)
+    }
+}
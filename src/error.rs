@@ -0,0 +1,156 @@
+use alloc::string::String;
+use core::error::Error;
+use core::fmt;
+
+use crate::grid::Coords2D;
+
+use crate::SignedCoords;
+
+///Describes why a route could not be found between two points.
+///Positions are in the coordinates the search was given, which for an [`OffsetMap`](crate::OffsetMap) are world coordinates.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum PathError<C = Coords2D> {
+    ///The start position lies outside the bounds of the map.
+    StartOutOfBounds(C),
+    ///The goal position lies outside the bounds of the map.
+    GoalOutOfBounds(C),
+    ///A world position lies outside the area covered by an `OffsetMap`.
+    OutsideMap(SignedCoords),
+    ///The start position is on a tile that cannot be traversed.
+    StartBlocked(C),
+    ///The goal position is on a tile that cannot be traversed.
+    GoalBlocked(C),
+    ///The search was exhausted without reaching the goal.
+    NoPath,
+    ///Every route to the goal is longer than the configured maximum distance.
+    ExceedsMaxDistance,
+    ///The search was stopped early through its cancel token.
+    Cancelled,
+}
+
+impl<C: fmt::Debug> fmt::Display for PathError<C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PathError::StartOutOfBounds(pos) => write!(f, "start {:?} is out of bounds", pos),
+            PathError::GoalOutOfBounds(pos) => write!(f, "goal {:?} is out of bounds", pos),
+            PathError::OutsideMap(pos) => write!(f, "{:?} is outside the map", pos),
+            PathError::StartBlocked(pos) => write!(f, "start {:?} is not traversable", pos),
+            PathError::GoalBlocked(pos) => write!(f, "goal {:?} is not traversable", pos),
+            PathError::NoPath => write!(f, "no path exists between start and goal"),
+            PathError::ExceedsMaxDistance => {
+                write!(f, "no path exists within the maximum distance")
+            }
+            PathError::Cancelled => write!(f, "the search was cancelled"),
+        }
+    }
+}
+
+impl<C: fmt::Debug> Error for PathError<C> {}
+
+impl<C> PathError<C> {
+    ///Converts the positions held by the error with `f`, leaving other errors unchanged.
+    pub fn map_coords<D, F: Fn(C) -> D>(self, f: F) -> PathError<D> {
+        match self {
+            PathError::StartOutOfBounds(pos) => PathError::StartOutOfBounds(f(pos)),
+            PathError::GoalOutOfBounds(pos) => PathError::GoalOutOfBounds(f(pos)),
+            PathError::OutsideMap(pos) => PathError::OutsideMap(pos),
+            PathError::StartBlocked(pos) => PathError::StartBlocked(f(pos)),
+            PathError::GoalBlocked(pos) => PathError::GoalBlocked(f(pos)),
+            PathError::NoPath => PathError::NoPath,
+            PathError::ExceedsMaxDistance => PathError::ExceedsMaxDistance,
+            PathError::Cancelled => PathError::Cancelled,
+        }
+    }
+}
+
+///Describes why a map could not be built from a level editor's file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MapLoadError {
+    ///The file could not be read or parsed, for the reason given.
+    Parse(String),
+    ///No layer with the given name was found.
+    MissingLayer(String),
+    ///The layer with the given name does not hold a grid of tiles.
+    NotTileLayer(String),
+    ///The level has no fixed size, so cannot be turned into a map.
+    Unbounded,
+}
+
+impl fmt::Display for MapLoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MapLoadError::Parse(reason) => write!(f, "could not load level: {}", reason),
+            MapLoadError::MissingLayer(name) => write!(f, "no layer named {:?}", name),
+            MapLoadError::NotTileLayer(name) => write!(f, "layer {:?} is not a tile layer", name),
+            MapLoadError::Unbounded => write!(f, "the level has no fixed size"),
+        }
+    }
+}
+
+impl Error for MapLoadError {}
+
+///Describes why a [`MapDiff`](crate::MapDiff) could not be made or applied.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum MapDiffError {
+    ///The map is not the size the diff was made for, given as width and height.
+    SizeMismatch {
+        ///The size the diff expects.
+        expected: (usize, usize),
+        ///The size of the map.
+        found: (usize, usize),
+    },
+    ///A tile does not hold what the diff expects it to before the change.
+    Conflict {
+        ///The tile that differs.
+        position: Coords2D,
+        ///The tile character the diff expects.
+        expected: char,
+        ///The tile character on the map.
+        found: char,
+    },
+}
+
+impl fmt::Display for MapDiffError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MapDiffError::SizeMismatch { expected, found } => write!(
+                f,
+                "expected a {}x{} map but found {}x{}",
+                expected.0, expected.1, found.0, found.1
+            ),
+            MapDiffError::Conflict {
+                position,
+                expected,
+                found,
+            } => write!(
+                f,
+                "expected {:?} at {:?} but found {:?}",
+                expected, position, found
+            ),
+        }
+    }
+}
+
+impl Error for MapDiffError {}
+
+///Describes why routes or search recordings could not be decoded from their saved form.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RouteDecodeError {
+    ///The bytes are not a valid message, for the reason given.
+    Malformed(String),
+    ///The message is well formed but does not describe a valid route or error, for the reason given.
+    Invalid(&'static str),
+}
+
+impl fmt::Display for RouteDecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RouteDecodeError::Malformed(reason) => {
+                write!(f, "could not decode message: {}", reason)
+            }
+            RouteDecodeError::Invalid(reason) => write!(f, "invalid route message: {}", reason),
+        }
+    }
+}
+
+impl Error for RouteDecodeError {}
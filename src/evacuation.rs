@@ -0,0 +1,151 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::grid::Coords2D;
+use crate::grid::Map2D;
+use crate::grid::MovingAiMap;
+
+use crate::flood::{flood, Flood};
+use crate::utils::{compose, distance, index};
+use crate::{PathError, Route, SearchOptions};
+
+///Assigns each of `agents` to one of `exits` and finds its route there, searching backwards from the exits
+///once rather than once per agent. Returns, for each agent in order, the index of its exit and the route to it,
+///or `None` if the agent is out of bounds, blocked or cannot reach any exit.
+///Exits that are out of bounds or blocked are ignored.
+///
+///With a `balance` of zero every agent takes its nearest exit, found with a single search from all exits at once.
+///A positive `balance` spreads agents across exits instead: each agent already heading to an exit makes it
+///count as `balance` further away for the agents assigned after it, so crowds split between exits rather than
+///queueing at the nearest. Agents nearest an exit are assigned first. Balancing takes one search per exit,
+///however many agents there are.
+///
+///`options` controls the maximum distance, beyond which agents are left without an exit,
+///cancellation and the steps listed in each route.
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+/// use blitz_path::SearchOptions;
+///
+/// let map = movingai::parser::parse_map_file(Path::new("./tests/map/maze512-32-9.map")).expect("Could not load map.");
+///
+/// let agents = [(2, 1), (3, 1), (4, 1)];
+/// let exits = [(1, 5), (5, 1)];
+///
+/// //Everyone is nearest the second exit
+/// let nearest = blitz_path::evacuation_paths(&map, &agents, &exits, 0.0, &SearchOptions::new()).unwrap();
+/// assert!(nearest.iter().flatten().all(|(exit, _)| *exit == 1));
+/// let (_, route) = nearest[1].as_ref().unwrap();
+/// assert_eq!(route.steps(), [(5, 1), (4, 1), (3, 1)]);
+///
+/// //With balancing one agent heads for the other exit
+/// let balanced = blitz_path::evacuation_paths(&map, &agents, &exits, 5.0, &SearchOptions::new()).unwrap();
+/// let first = balanced.iter().flatten().filter(|(exit, _)| *exit == 0).count();
+/// assert_eq!(first, 1);
+/// ```
+pub fn evacuation_paths(
+    map: &MovingAiMap,
+    agents: &[Coords2D],
+    exits: &[Coords2D],
+    balance: f64,
+    options: &SearchOptions,
+) -> Result<Vec<Option<(usize, Route)>>, PathError> {
+    let mut iteration = 0;
+    let mut search = |sources: &[(Coords2D, f64)]| {
+        let mut cancelled = false;
+        let reached = flood(
+            map,
+            sources,
+            options.max_distance.unwrap_or(f64::INFINITY),
+            //Routes are followed back from the exits, so each move must also be possible the other way
+            |from, to| {
+                map.is_traversable_from(to, from)
+                    .then(|| distance(from, to))
+            },
+            |_, _| {
+                cancelled = options.should_cancel(iteration);
+                iteration += 1;
+                cancelled
+            },
+        );
+        if cancelled {
+            Err(PathError::Cancelled)
+        } else {
+            Ok(reached)
+        }
+    };
+    let cost = |reached: &Flood, agent: Coords2D| {
+        if map.is_out_of_bound(agent) {
+            f64::INFINITY
+        } else {
+            reached.costs[index(map, agent)]
+        }
+    };
+
+    if balance <= 0.0 {
+        let sources: Vec<(Coords2D, f64)> = exits.iter().map(|exit| (*exit, 0.0)).collect();
+        let reached = search(&sources)?;
+        return Ok(agents
+            .iter()
+            .map(|agent| {
+                if !cost(&reached, *agent).is_finite() {
+                    return None;
+                }
+                let route = reached.route_to(map, *agent)?;
+                let exit = route.steps().last().copied()?;
+                let exit = exits.iter().position(|position| *position == exit)?;
+                Some((exit, evacuation_route(route, options)))
+            })
+            .collect());
+    }
+
+    let floods = exits
+        .iter()
+        .map(|exit| search(&[(*exit, 0.0)]))
+        .collect::<Result<Vec<Flood>, PathError>>()?;
+    let nearest = |agent: Coords2D| {
+        floods
+            .iter()
+            .map(|reached| cost(reached, agent))
+            .fold(f64::INFINITY, f64::min)
+    };
+
+    //Assign the agents nearest an exit first, each to the exit with the lowest cost including its load
+    let mut order: Vec<usize> = (0..agents.len())
+        .filter(|agent| nearest(agents[*agent]).is_finite())
+        .collect();
+    order.sort_by(|a, b| nearest(agents[*a]).total_cmp(&nearest(agents[*b])));
+    let mut loads = vec![0; exits.len()];
+    let mut assigned = vec![None; agents.len()];
+    for agent in order {
+        let mut best: Option<(usize, f64)> = None;
+        for (exit, reached) in floods.iter().enumerate() {
+            let total = cost(reached, agents[agent]) + balance * loads[exit] as f64;
+            if total.is_finite() && best.is_none_or(|(_, best)| total < best) {
+                best = Some((exit, total));
+            }
+        }
+        if let Some((exit, _)) = best {
+            loads[exit] += 1;
+            assigned[agent] = Some(exit);
+        }
+    }
+
+    Ok(assigned
+        .iter()
+        .zip(agents)
+        .map(|(exit, agent)| {
+            let exit = (*exit)?;
+            let route = floods[exit].route_to(map, *agent)?;
+            Some((exit, evacuation_route(route, options)))
+        })
+        .collect())
+}
+
+//Turn a route found backwards from an exit into one leading the agent to it
+fn evacuation_route(route: Route, options: &SearchOptions) -> Route {
+    let mut steps = route.steps();
+    steps.reverse();
+    Route::from((route.distance(), compose(steps, options)))
+}
@@ -0,0 +1,94 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::grid::Coords2D;
+
+use crate::{Route, SignedCoords};
+
+//Step coordinates that can be written out as points
+pub trait ExportCoords: Copy {
+    fn point(self) -> (f64, f64);
+}
+
+impl ExportCoords for Coords2D {
+    fn point(self) -> (f64, f64) {
+        (self.0 as f64, self.1 as f64)
+    }
+}
+
+impl ExportCoords for SignedCoords {
+    fn point(self) -> (f64, f64) {
+        (self.0 as f64, self.1 as f64)
+    }
+}
+
+impl<C: ExportCoords> Route<C> {
+    ///Returns the route as a JSON object holding its distance and its steps as [x, y] pairs,
+    ///in the same order as [`steps`](Route::steps).
+    /// # Examples
+    ///
+    /// ```
+    /// use blitz_path::Route;
+    ///
+    /// let route: Route = Route::from((2.0, vec![(3, 1), (2, 1), (1, 1)]));
+    /// assert_eq!(route.to_json(), r#"{"distance":2,"steps":[[3,1],[2,1],[1,1]]}"#);
+    /// ```
+    pub fn to_json(&self) -> String {
+        json(
+            self.distance(),
+            self.steps().iter().map(|step| step.point()),
+        )
+    }
+
+    ///Returns the route as a GeoJSON Feature holding a LineString running from the start to the goal,
+    ///with the route's distance as a property. Coordinates are tile coordinates,
+    ///in world space for a route from an [`OffsetMap`](crate::OffsetMap).
+    /// # Examples
+    ///
+    /// ```
+    /// use blitz_path::Route;
+    ///
+    /// let route: Route = Route::from((1.0, vec![(2, 1), (1, 1)]));
+    /// assert_eq!(
+    ///     route.to_geojson(),
+    ///     r#"{"type":"Feature","geometry":{"type":"LineString","coordinates":[[1,1],[2,1]]},"properties":{"distance":1}}"#
+    /// );
+    /// ```
+    pub fn to_geojson(&self) -> String {
+        self.to_geojson_scaled(1.0, (0.0, 0.0))
+    }
+
+    ///Returns the route as a GeoJSON Feature like [`to_geojson`](Route::to_geojson),
+    ///with each tile placed at `origin` plus its coordinates times `cell_size`.
+    ///Add half a cell to `origin` to place points at the centre of each tile rather than its corner.
+    ///The distance property is scaled to world units too.
+    pub fn to_geojson_scaled(&self, cell_size: f64, origin: (f64, f64)) -> String {
+        geojson(
+            self.distance() * cell_size,
+            self.steps().iter().map(|step| {
+                let (x, y) = step.point();
+                (origin.0 + x * cell_size, origin.1 + y * cell_size)
+            }),
+        )
+    }
+}
+
+//Write points as a JSON array of [x, y] pairs
+fn points<I: Iterator<Item = (f64, f64)>>(points: I) -> String {
+    let points: Vec<String> = points.map(|(x, y)| format!("[{},{}]", x, y)).collect();
+    format!("[{}]", points.join(","))
+}
+
+fn json<I: Iterator<Item = (f64, f64)>>(distance: f64, steps: I) -> String {
+    format!(r#"{{"distance":{},"steps":{}}}"#, distance, points(steps))
+}
+
+//Steps are listed goal first, but a LineString should run in the direction of travel
+fn geojson<I: DoubleEndedIterator<Item = (f64, f64)>>(distance: f64, steps: I) -> String {
+    format!(
+        r#"{{"type":"Feature","geometry":{{"type":"LineString","coordinates":{}}},"properties":{{"distance":{}}}}}"#,
+        points(steps.rev()),
+        distance
+    )
+}
@@ -0,0 +1,86 @@
+use alloc::vec::Vec;
+
+use crate::grid::Coords2D;
+use crate::grid::MovingAiMap;
+
+use crate::flood::flood;
+use crate::utils::{compose, distance, index, validate};
+use crate::{PathError, Route, SearchOptions};
+
+///Finds the route from `start` that ends as far as possible from every one of `threats`, travelling no further than `budget`.
+///Distance from the threats is measured along paths rather than in a straight line, so the route will not
+///stop behind a thin wall the threat could step around. Of equally safe tiles the cheapest to reach is chosen.
+///If nowhere in range is safer than `start` the route only contains `start`.
+///Threats that are out of bounds or blocked are ignored, and tiles no threat can reach count as the safest of all.
+///
+///`options` controls cancellation and the steps listed in the route.
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+/// use blitz_path::SearchOptions;
+///
+/// let map = movingai::parser::parse_map_file(Path::new("./tests/map/maze512-32-9.map")).expect("Could not load map.");
+///
+/// let path = blitz_path::flee_path(&map, (3, 1), &[(1, 1)], 2.0, &SearchOptions::new()).unwrap();
+/// assert_eq!(path.steps().first(), Some(&(5, 1)));
+/// assert_eq!(path.distance(), 2.0);
+/// ```
+pub fn flee_path(
+    map: &MovingAiMap,
+    start: Coords2D,
+    threats: &[Coords2D],
+    budget: f64,
+    options: &SearchOptions,
+) -> Result<Route, PathError> {
+    validate(map, start, start)?;
+
+    let mut cancelled = false;
+    let mut iteration = 0;
+
+    //How far every tile is from the nearest threat
+    let sources: Vec<(Coords2D, f64)> = threats.iter().map(|threat| (*threat, 0.0)).collect();
+    let danger = flood(
+        map,
+        &sources,
+        f64::INFINITY,
+        |from, to| Some(distance(from, to)),
+        |_, _| {
+            cancelled = options.should_cancel(iteration);
+            iteration += 1;
+            cancelled
+        },
+    );
+    if cancelled {
+        return Err(PathError::Cancelled);
+    }
+
+    //Keep the first tile settled at the best distance, which is the cheapest to reach
+    let mut best = (danger.costs[index(map, start)], start);
+    let reached = flood(
+        map,
+        &[(start, 0.0)],
+        budget,
+        |from, to| Some(distance(from, to)),
+        |position, _| {
+            if options.should_cancel(iteration) {
+                cancelled = true;
+                return true;
+            }
+            iteration += 1;
+
+            let safety = danger.costs[index(map, position)];
+            if safety > best.0 {
+                best = (safety, position);
+            }
+            false
+        },
+    );
+
+    if cancelled {
+        return Err(PathError::Cancelled);
+    }
+    let route = reached.route_to(map, best.1).ok_or(PathError::NoPath)?;
+    let steps = compose(route.steps(), options);
+    Ok(Route::from((route.distance(), steps)))
+}
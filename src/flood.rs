@@ -0,0 +1,107 @@
+use alloc::collections::BinaryHeap;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::grid::Coords2D;
+use crate::grid::Map2D;
+use crate::grid::MovingAiMap;
+
+use crate::node::Node;
+use crate::utils::{index, neighbours};
+use crate::Route;
+
+//Result of flooding outwards from one or more sources, indexed in row-major order
+pub struct Flood {
+    //Cheapest cost from any source, infinite if unreached
+    pub costs: Vec<f64>,
+    //Tile each tile was reached from, equal to itself for sources
+    pub parents: Vec<Coords2D>,
+    //Whether any tile was left unreached because it cost more than the limit
+    pub pruned: bool,
+}
+
+impl Flood {
+    //Follow parents back from a reached tile, giving a route with the tile first and its source last
+    pub fn route_to(&self, map: &MovingAiMap, tile: Coords2D) -> Option<Route> {
+        let cost = self.costs[index(map, tile)];
+        if !cost.is_finite() {
+            return None;
+        }
+
+        let mut steps = vec![tile];
+        let mut current = tile;
+        while self.parents[index(map, current)] != current {
+            current = self.parents[index(map, current)];
+            steps.push(current);
+        }
+
+        Some(Route::from((cost, steps)))
+    }
+}
+
+//Multi-source Dijkstra over traversable tiles, stopping at tiles costing more than max_cost.
+//Each source starts at its own initial cost, and cost returns None for forbidden moves.
+//settle is called once for each tile as its final cost becomes known, and stops the flood by returning true.
+pub fn flood<C, S>(
+    map: &MovingAiMap,
+    sources: &[(Coords2D, f64)],
+    max_cost: f64,
+    cost: C,
+    mut settle: S,
+) -> Flood
+where
+    C: Fn(Coords2D, Coords2D) -> Option<f64>,
+    S: FnMut(Coords2D, f64) -> bool,
+{
+    let size = map.width() * map.height();
+    let mut flood = Flood {
+        costs: vec![f64::INFINITY; size],
+        parents: vec![Coords2D::from((0, 0)); size],
+        pruned: false,
+    };
+    let mut open = BinaryHeap::new();
+
+    for (position, initial) in sources {
+        if !map.is_traversable(*position) {
+            continue;
+        }
+        if *initial > max_cost {
+            flood.pruned = true;
+            continue;
+        }
+        let position_index = index(map, *position);
+        if *initial < flood.costs[position_index] {
+            flood.costs[position_index] = *initial;
+            flood.parents[position_index] = *position;
+            open.push(Node::new(*initial, 0.0, *position, *position));
+        }
+    }
+
+    while let Some(node_current) = open.pop() {
+        //Skip stale entries that have since been reached more cheaply
+        if node_current.g > flood.costs[index(map, node_current.position)] {
+            continue;
+        }
+        if settle(node_current.position, node_current.g) {
+            break;
+        }
+
+        for successor in neighbours(map, node_current.position) {
+            let step = match cost(node_current.position, successor) {
+                Some(step) => step,
+                None => continue,
+            };
+            let total = node_current.g + step;
+            let successor_index = index(map, successor);
+            if total > max_cost {
+                flood.pruned = true;
+            } else if total < flood.costs[successor_index] {
+                flood.costs[successor_index] = total;
+                flood.parents[successor_index] = node_current.position;
+                open.push(Node::new(total, 0.0, successor, node_current.position));
+            }
+        }
+    }
+
+    flood
+}
@@ -0,0 +1,168 @@
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::grid::Coords2D;
+use crate::grid::Map2D;
+use crate::grid::MovingAiMap;
+
+use crate::los::in_sight;
+use crate::memory::vec_size;
+use crate::utils::distance;
+use crate::{MapDiff, MemoryFootprint};
+
+//What unobserved tiles are assumed to hold
+const UNKNOWN: char = '.';
+
+///What an agent exploring unknown terrain believes a map to be, for planning under the freespace assumption.
+///
+///Tiles that have not been observed are assumed to be open ground, so routes planned on [`map`](FogOfWar::map)
+///head straight through unexplored areas and are never longer than the true shortest route. As the agent
+///observes tiles with [`sense`](FogOfWar::sense) or [`reveal`](FogOfWar::reveal), the believed map is updated
+///and the changes are returned as a [`MapDiff`], which tells an [`ActivePath`](crate::ActivePath) planned on the
+///believed map whether its route needs an [`update`](crate::ActivePath::update). Following the updated route
+///and sensing again reaches the goal whenever the true map allows it.
+///
+///The believed map is rebuilt whenever an observation changes it, which takes time proportional to its size.
+///Like every map built by this crate it is octile.
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+/// use blitz_path::grid::Map2D;
+/// use blitz_path::{ActivePath, Algorithm, FogOfWar, SearchOptions};
+///
+/// let world = movingai::parser::parse_map_file(Path::new("./tests/map/maze512-32-9.map")).expect("Could not load map.");
+/// let mut fog = FogOfWar::new(world.width(), world.height());
+///
+/// let (mut position, goal) = ((1, 1), (30, 30));
+/// fog.sense(&world, position, 10);
+/// let mut path = ActivePath::new(fog.map(), position, goal, Algorithm::AStar, &SearchOptions::new()).unwrap();
+///
+/// while position != goal {
+///     //Take one step, look around, and keep the route clear of anything newly seen
+///     position = path.route().steps()[path.route().steps().len() - 2];
+///     fog.sense(&world, position, 10);
+///     path.update(fog.map(), position).unwrap();
+/// }
+/// assert!(fog.is_known((30, 30)));
+/// ```
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FogOfWar {
+    width: usize,
+    height: usize,
+    //Tile believed to be at every position in row-major order
+    tiles: Vec<char>,
+    known: Vec<bool>,
+    map: MovingAiMap,
+}
+
+impl FogOfWar {
+    ///Creates a map of the given size where nothing has been observed, so every tile is assumed open.
+    pub fn new(width: usize, height: usize) -> FogOfWar {
+        let tiles = vec![UNKNOWN; width * height];
+        FogOfWar {
+            width,
+            height,
+            map: believed(width, height, &tiles),
+            tiles,
+            known: vec![false; width * height],
+        }
+    }
+
+    ///Returns the map as it is believed to be, with every unobserved tile open, for planning routes on.
+    pub fn map(&self) -> &MovingAiMap {
+        &self.map
+    }
+
+    ///Returns true if `tile` has been observed.
+    pub fn is_known(&self, tile: Coords2D) -> bool {
+        self.index(tile).is_some_and(|index| self.known[index])
+    }
+
+    ///Returns the number of tiles that have been observed.
+    pub fn known_count(&self) -> usize {
+        self.known.iter().filter(|known| **known).count()
+    }
+
+    ///Records that each tile in `observed` holds the given MovingAI tile character.
+    ///Tiles outside the map are ignored, and a tile observed again takes its latest value.
+    ///Returns the changes this made to the believed map, which are empty if nothing new was learned.
+    pub fn reveal(&mut self, observed: &[(Coords2D, char)]) -> MapDiff {
+        let diff = MapDiff::new(&self.map, observed);
+        for (tile, value) in observed {
+            if let Some(index) = self.index(*tile) {
+                self.known[index] = true;
+                self.tiles[index] = *value;
+            }
+        }
+        if !diff.is_empty() {
+            self.map = believed(self.width, self.height, &self.tiles);
+        }
+        diff
+    }
+
+    ///Observes each of `tiles` on `world`, the true map, which must be the same size.
+    ///Returns the changes this made to the believed map.
+    pub fn observe<I: IntoIterator<Item = Coords2D>>(
+        &mut self,
+        world: &MovingAiMap,
+        tiles: I,
+    ) -> MapDiff {
+        let observed: Vec<(Coords2D, char)> = tiles
+            .into_iter()
+            .filter(|tile| !world.is_out_of_bound(*tile))
+            .map(|tile| (tile, *world.get(tile)))
+            .collect();
+        self.reveal(&observed)
+    }
+
+    ///Observes every tile of `world` within `radius` of `position` that can be seen from it,
+    ///with walls blocking sight as in [`line_of_sight`](crate::line_of_sight) but the walls themselves seen.
+    ///Returns the changes this made to the believed map.
+    pub fn sense(&mut self, world: &MovingAiMap, position: Coords2D, radius: usize) -> MapDiff {
+        let (left, top) = (
+            position.0.saturating_sub(radius),
+            position.1.saturating_sub(radius),
+        );
+        let (right, bottom) = (
+            (position.0 + radius).min(self.width.saturating_sub(1)),
+            (position.1 + radius).min(self.height.saturating_sub(1)),
+        );
+        let visible: Vec<Coords2D> = (top..=bottom)
+            .flat_map(|y| (left..=right).map(move |x| (x, y)))
+            .filter(|tile| distance(position, *tile) <= radius as f64)
+            .filter(|tile| in_sight(world, position, *tile))
+            .collect();
+        self.observe(world, visible)
+    }
+
+    ///Returns the width of the map.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    ///Returns the height of the map.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    fn index(&self, tile: Coords2D) -> Option<usize> {
+        if tile.0 >= self.width || tile.1 >= self.height {
+            return None;
+        }
+        Some(tile.1 * self.width + tile.0)
+    }
+}
+
+//Builds the believed map from its tiles
+fn believed(width: usize, height: usize, tiles: &[char]) -> MovingAiMap {
+    MovingAiMap::new(String::from("octile"), height, width, tiles.to_vec())
+}
+
+impl MemoryFootprint for FogOfWar {
+    fn heap_size(&self) -> usize {
+        vec_size(&self.tiles) + vec_size(&self.known) + self.map.heap_size()
+    }
+}
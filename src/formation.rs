@@ -0,0 +1,141 @@
+use alloc::vec::Vec;
+
+use crate::grid::Coords2D;
+use crate::grid::Map2D;
+use crate::grid::MovingAiMap;
+
+use crate::memory::vec_size;
+use crate::utils::{distance, validate};
+use crate::{a_star_path_weighted, MemoryFootprint, PathError, Route, SearchOptions, SignedCoords};
+
+///The shape of a group of agents that moves as one, such as a squad in a 3x2 block.
+///Each member keeps a fixed offset from the formation's anchor, and a single route is planned
+///for the anchor that leaves room for every member, rather than searching once per member.
+///
+///The anchor is usually a member itself, such as the leader, and must be traversable.
+///Formations keep their shape and do not turn to face the way they move.
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+/// use blitz_path::{Formation, SearchOptions};
+///
+/// let map = movingai::parser::parse_map_file(Path::new("./tests/map/maze512-32-9.map")).expect("Could not load map.");
+///
+/// let squad = Formation::grid(3, 2);
+/// assert!(squad.fits(&map, (1, 1)));
+/// assert!(!squad.fits(&map, (0, 0)));
+///
+/// let route = squad.path(&map, (1, 1), (5, 5), &SearchOptions::new()).unwrap();
+/// let members = squad.member_routes(&route).unwrap();
+/// assert_eq!(members[5].steps()[0], (7, 6));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Formation {
+    offsets: Vec<SignedCoords>,
+}
+
+impl Formation {
+    ///Creates a formation with a member at each of `offsets` from the anchor.
+    pub fn new(offsets: Vec<SignedCoords>) -> Formation {
+        Formation { offsets }
+    }
+
+    ///Creates a block `width` members wide and `height` deep, anchored on its top left member.
+    ///Members are listed row by row.
+    pub fn grid(width: usize, height: usize) -> Formation {
+        let mut offsets = Vec::with_capacity(width * height);
+        for y in 0..height {
+            for x in 0..width {
+                offsets.push((x as i64, y as i64));
+            }
+        }
+        Formation { offsets }
+    }
+
+    ///Returns the offset of every member from the anchor.
+    pub fn offsets(&self) -> &[SignedCoords] {
+        &self.offsets
+    }
+
+    ///Returns the tile of every member when the anchor is at `anchor`,
+    ///or `None` if any would be off the top or left edge of the map.
+    pub fn members_at(&self, anchor: Coords2D) -> Option<Vec<Coords2D>> {
+        self.offsets
+            .iter()
+            .map(|offset| {
+                let x = anchor.0.checked_add_signed(offset.0 as isize)?;
+                let y = anchor.1.checked_add_signed(offset.1 as isize)?;
+                Some((x, y))
+            })
+            .collect()
+    }
+
+    ///Returns true if every member has a traversable tile when the anchor is at `anchor`.
+    pub fn fits(&self, map: &MovingAiMap, anchor: Coords2D) -> bool {
+        self.members_at(anchor)
+            .is_some_and(|members| members.iter().all(|member| map.is_traversable(*member)))
+    }
+
+    ///Finds a route for the anchor from `start` to `goal` along which every member can make each step,
+    ///so the whole formation squeezes through gaps only where it fits, and never cuts corners.
+    ///Steps cost their length, as for a single agent.
+    ///
+    ///Returns `PathError::StartBlocked` or `PathError::GoalBlocked` if the formation does not fit
+    ///at either end. Positions are never snapped, whatever the `snap_radius` of `options`.
+    pub fn path(
+        &self,
+        map: &MovingAiMap,
+        start: Coords2D,
+        goal: Coords2D,
+        options: &SearchOptions,
+    ) -> Result<Route, PathError> {
+        validate(map, start, goal)?;
+        if !self.fits(map, start) {
+            return Err(PathError::StartBlocked(start));
+        }
+        if !self.fits(map, goal) {
+            return Err(PathError::GoalBlocked(goal));
+        }
+
+        let cost = |from: Coords2D, to: Coords2D| {
+            let (from_members, to_members) = (self.members_at(from)?, self.members_at(to)?);
+            if from_members
+                .iter()
+                .zip(&to_members)
+                .all(|(from, to)| map.is_traversable_from(*from, *to))
+            {
+                Some(distance(from, to))
+            } else {
+                None
+            }
+        };
+        a_star_path_weighted(map, start, goal, options, cost)
+    }
+
+    ///Returns the route each member follows when the anchor follows `route`, in the order of the offsets.
+    ///Every member's route has the same distance as the anchor's. Returns `None` if any member
+    ///would be off the top or left edge of the map, which cannot happen for routes from [`path`](Formation::path).
+    pub fn member_routes(&self, route: &Route) -> Option<Vec<Route>> {
+        let anchors: Vec<Vec<Coords2D>> = route
+            .steps()
+            .into_iter()
+            .map(|anchor| self.members_at(anchor))
+            .collect::<Option<_>>()?;
+        Some(
+            (0..self.offsets.len())
+                .map(|member| {
+                    let steps = anchors.iter().map(|members| members[member]).collect();
+                    Route::from((route.distance(), steps))
+                })
+                .collect(),
+        )
+    }
+}
+
+impl MemoryFootprint for Formation {
+    fn heap_size(&self) -> usize {
+        vec_size(&self.offsets)
+    }
+}
@@ -0,0 +1,158 @@
+//Bindings for using the crate from GDScript as a Godot GDExtension
+
+use core::convert::TryFrom;
+
+use crate::grid::{Coords2D, Map2D, MovingAiMap};
+use godot::classes::TileMapLayer;
+use godot::prelude::*;
+
+use crate::Route;
+
+struct BlitzPathExtension;
+
+#[gdextension]
+unsafe impl ExtensionLibrary for BlitzPathExtension {}
+
+///A map that can be searched from GDScript, registered with Godot as `BlitzMap`.
+///Cells are addressed with the same coordinates as the `TileMapLayer` the map was built from.
+#[derive(GodotClass)]
+#[class(no_init, base = RefCounted, rename = BlitzMap)]
+pub struct GodotMap {
+    map: MovingAiMap,
+    //Cell of the tile map layer at the top left corner of the map
+    origin: Vector2i,
+}
+
+#[godot_api]
+impl GodotMap {
+    ///Builds a map covering the used cells of a tile map layer, such as a layer of walls.
+    ///A cell is blocked if it holds a tile, or if `solid_data` is not empty, only if the tile's custom data layer of that name is true.
+    ///Returns null if the layer covers more cells than can be addressed.
+    #[func]
+    fn from_tile_map_layer(layer: Gd<TileMapLayer>, solid_data: GString) -> Option<Gd<GodotMap>> {
+        let used = layer.get_used_rect();
+        let (width, height) = (used.size.x.max(0) as usize, used.size.y.max(0) as usize);
+        let count = match width.checked_mul(height) {
+            Some(count) => count,
+            None => {
+                godot_error!("a {}x{} map is too large", width, height);
+                return None;
+            }
+        };
+
+        let mut tiles = Vec::with_capacity(count);
+        for y in 0..used.size.y {
+            for x in 0..used.size.x {
+                let cell = used.position + Vector2i::new(x, y);
+                let solid = if solid_data.is_empty() {
+                    layer.get_cell_source_id(cell) != -1
+                } else {
+                    layer
+                        .get_cell_tile_data(cell)
+                        .is_some_and(|data| data.get_custom_data(&solid_data).booleanize())
+                };
+                tiles.push(if solid { '@' } else { '.' });
+            }
+        }
+
+        Some(Gd::from_object(GodotMap {
+            map: MovingAiMap::new(String::from("octile"), height, width, tiles),
+            origin: used.position,
+        }))
+    }
+
+    ///Builds a map from one byte per cell in row-major order, with zero for blocked cells and anything else for open ones.
+    ///Returns null if the number of bytes does not match the size.
+    #[func]
+    fn from_cells(width: i32, height: i32, cells: PackedByteArray) -> Option<Gd<GodotMap>> {
+        let (width, height) = (usize::try_from(width).ok()?, usize::try_from(height).ok()?);
+        let count = match width.checked_mul(height) {
+            Some(count) => count,
+            None => {
+                godot_error!("a {}x{} map is too large", width, height);
+                return None;
+            }
+        };
+        if cells.len() != count {
+            godot_error!(
+                "expected {} cells for a {}x{} map but got {}",
+                count,
+                width,
+                height,
+                cells.len()
+            );
+            return None;
+        }
+
+        let tiles = cells
+            .as_slice()
+            .iter()
+            .map(|cell| if *cell == 0 { '@' } else { '.' })
+            .collect();
+        Some(Gd::from_object(GodotMap {
+            map: MovingAiMap::new(String::from("octile"), height, width, tiles),
+            origin: Vector2i::ZERO,
+        }))
+    }
+
+    ///Returns the width of the map in cells.
+    #[func]
+    fn get_width(&self) -> i32 {
+        self.map.width() as i32
+    }
+
+    ///Returns the height of the map in cells.
+    #[func]
+    fn get_height(&self) -> i32 {
+        self.map.height() as i32
+    }
+
+    ///Returns whether a cell is outside the map or cannot be traversed.
+    #[func]
+    fn is_cell_solid(&self, cell: Vector2i) -> bool {
+        self.to_coords(cell)
+            .is_none_or(|coords| !self.map.is_traversable(coords))
+    }
+
+    ///Finds a route using the JPS algorithm, returned as the cells along it from `from` to `to`.
+    ///Returns an empty array if there is no route.
+    #[func]
+    fn find_path(&self, from: Vector2i, to: Vector2i) -> PackedVector2Array {
+        self.search(from, to)
+            .map(|route| {
+                route
+                    .steps()
+                    .iter()
+                    .rev()
+                    .map(|(x, y)| {
+                        Vector2::new(
+                            (*x as i32 + self.origin.x) as f32,
+                            (*y as i32 + self.origin.y) as f32,
+                        )
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    ///Finds the length of the shortest route using the JPS algorithm, or -1 if there is no route.
+    #[func]
+    fn get_path_distance(&self, from: Vector2i, to: Vector2i) -> f64 {
+        self.search(from, to).map_or(-1.0, |route| route.distance())
+    }
+}
+
+impl GodotMap {
+    //Cells outside the map, including those left of or above it, have no coordinates
+    fn to_coords(&self, cell: Vector2i) -> Option<Coords2D> {
+        let x = usize::try_from(cell.x - self.origin.x).ok()?;
+        let y = usize::try_from(cell.y - self.origin.y).ok()?;
+        Some((x, y))
+    }
+
+    fn search(&self, from: Vector2i, to: Vector2i) -> Option<Route> {
+        let start = self.to_coords(from)?;
+        let goal = self.to_coords(to)?;
+        crate::jps_path(&self.map, start, goal).ok()
+    }
+}
@@ -0,0 +1,220 @@
+//!The map types every search works on.
+//!
+//!With the default `movingai` feature these are the types of the [movingai](https://docs.rs/movingai) crate,
+//!so maps and scenarios can be loaded with its parser. Without it they are a self-contained grid with the same API,
+//!following the same MovingAI movement rules, for users who build their maps in code and do not need the parser.
+//! # Examples
+//!
+//! ```
+//! use blitz_path::grid::{Map2D, MovingAiMap};
+//!
+//! let tiles = "....@.....".chars().collect();
+//! let map = MovingAiMap::new(String::from("octile"), 2, 5, tiles);
+//! assert!(!map.is_traversable((4, 0)));
+//!
+//! let path = blitz_path::a_star_path(&map, (0, 0), (4, 1)).unwrap();
+//! assert_eq!(path.steps().first(), Some(&(4, 1)));
+//! ```
+
+#[cfg(feature = "movingai")]
+pub use movingai::{Coords2D, CoordsIter, Map2D, MovingAiMap};
+
+#[cfg(not(feature = "movingai"))]
+pub use self::standalone::{Coords2D, CoordsIter, Map2D, MovingAiMap};
+
+#[cfg(not(feature = "movingai"))]
+mod standalone {
+    use alloc::boxed::Box;
+    use alloc::string::String;
+    use alloc::vec::Vec;
+    use core::ops::Index;
+
+    ///Coordinates in (x, y) order.
+    pub type Coords2D = (usize, usize);
+
+    ///Operations common to 2D maps.
+    pub trait Map2D<T> {
+        ///Returns the height of the map.
+        fn height(&self) -> usize;
+
+        ///Returns the width of the map.
+        fn width(&self) -> usize;
+
+        ///Returns the tile at `coords`, which must be inside the map.
+        fn get(&self, coords: Coords2D) -> &T;
+
+        ///Returns true if `coords` lies outside the map.
+        fn is_out_of_bound(&self, coords: Coords2D) -> bool;
+
+        ///Returns true if a tile can be traversed in some situation. Only trees (`T`), obstacles (`@` and `O`),
+        ///unrecognised tiles and tiles outside the map can never be traversed.
+        fn is_traversable(&self, tile: Coords2D) -> bool;
+
+        ///Returns true if `to` can be entered from the neighbouring tile `from`. Water (`W`) can only be entered
+        ///from water and swamp (`S`) only from open ground or swamp, and on octile maps diagonal steps
+        ///may not cut corners.
+        fn is_traversable_from(&self, from: Coords2D, to: Coords2D) -> bool;
+
+        ///Returns every coordinate of the map in row-major order.
+        fn coords(&self) -> CoordsIter;
+
+        ///Returns the number of tiles that can be traversed in some situation.
+        fn free_states(&self) -> usize;
+
+        ///Returns the neighbours of a tile that can be entered from it.
+        fn neighbors(&self, tile: Coords2D) -> Vec<Coords2D>;
+    }
+
+    ///A grid of MovingAI tile characters in row-major order.
+    ///Maps of type `octile` allow diagonal steps, and any other type only allows straight ones.
+    #[derive(Debug)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub struct MovingAiMap {
+        map_type: String,
+        height: usize,
+        width: usize,
+        map: Box<[char]>,
+    }
+
+    impl MovingAiMap {
+        ///Creates a map from its type, usually `octile`, its size and its tiles in row-major order.
+        ///
+        ///# Panics
+        ///
+        ///Panics if the number of tiles is not `height * width`.
+        pub fn new(map_type: String, height: usize, width: usize, map: Vec<char>) -> MovingAiMap {
+            MovingAiMap::new_from_slice(map_type, height, width, map.into_boxed_slice())
+        }
+
+        ///Creates a map from its type, size and a boxed slice of its tiles in row-major order.
+        ///
+        ///# Panics
+        ///
+        ///Panics if the number of tiles is not `height * width`.
+        pub fn new_from_slice(
+            map_type: String,
+            height: usize,
+            width: usize,
+            map: Box<[char]>,
+        ) -> MovingAiMap {
+            assert_eq!(map.len(), height * width);
+            MovingAiMap {
+                map_type,
+                height,
+                width,
+                map,
+            }
+        }
+
+        //Whether two tiles are neighbours under the movement allowed by the map type
+        fn coordinates_connect(&self, a: Coords2D, b: Coords2D) -> bool {
+            let (dx, dy) = (a.0.abs_diff(b.0), a.1.abs_diff(b.1));
+            if self.map_type == "octile" {
+                dx <= 1 && dy <= 1
+            } else {
+                dx + dy == 1
+            }
+        }
+    }
+
+    ///Iterates over the coordinates of a map in row-major order.
+    pub struct CoordsIter {
+        ///The map width.
+        pub width: usize,
+        ///The map height.
+        pub height: usize,
+        ///The x coordinate of the next step.
+        pub curr_x: usize,
+        ///The y coordinate of the next step.
+        pub curr_y: usize,
+    }
+
+    impl Iterator for CoordsIter {
+        type Item = Coords2D;
+
+        fn next(&mut self) -> Option<Coords2D> {
+            if self.curr_y >= self.height || self.width == 0 {
+                return None;
+            }
+            let current = (self.curr_x, self.curr_y);
+            self.curr_x += 1;
+            if self.curr_x >= self.width {
+                self.curr_x = 0;
+                self.curr_y += 1;
+            }
+            Some(current)
+        }
+    }
+
+    impl Map2D<char> for MovingAiMap {
+        fn height(&self) -> usize {
+            self.height
+        }
+
+        fn width(&self) -> usize {
+            self.width
+        }
+
+        fn get(&self, coords: Coords2D) -> &char {
+            &self.map[coords.1 * self.width + coords.0]
+        }
+
+        fn is_out_of_bound(&self, coords: Coords2D) -> bool {
+            coords.0 >= self.width || coords.1 >= self.height
+        }
+
+        fn is_traversable(&self, tile: Coords2D) -> bool {
+            !self.is_out_of_bound(tile) && matches!(self.get(tile), '.' | 'G' | 'S' | 'W')
+        }
+
+        fn is_traversable_from(&self, from: Coords2D, to: Coords2D) -> bool {
+            if self.is_out_of_bound(from)
+                || self.is_out_of_bound(to)
+                || !self.coordinates_connect(to, from)
+            {
+                return false;
+            }
+
+            let diagonal = from.0 != to.0 && from.1 != to.1;
+            if diagonal && self.map_type == "octile" {
+                //A diagonal step is only allowed if both straight routes around it are
+                let corners = [(from.0, to.1), (to.0, from.1)];
+                return corners.iter().all(|corner| {
+                    self.is_traversable_from(from, *corner) && self.is_traversable_from(*corner, to)
+                });
+            }
+
+            matches!(
+                (*self.get(to), *self.get(from)),
+                ('.', _) | ('G', _) | ('S', '.') | ('S', 'S') | ('W', 'W')
+            )
+        }
+
+        fn coords(&self) -> CoordsIter {
+            CoordsIter {
+                width: self.width,
+                height: self.height,
+                curr_x: 0,
+                curr_y: 0,
+            }
+        }
+
+        fn free_states(&self) -> usize {
+            self.coords()
+                .filter(|tile| self.is_traversable(*tile))
+                .count()
+        }
+
+        fn neighbors(&self, tile: Coords2D) -> Vec<Coords2D> {
+            crate::utils::neighbours(self, tile).collect()
+        }
+    }
+
+    impl Index<Coords2D> for MovingAiMap {
+        type Output = char;
+
+        fn index(&self, coords: Coords2D) -> &char {
+            self.get(coords)
+        }
+    }
+}
@@ -0,0 +1,55 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::grid::Map2D;
+use crate::grid::MovingAiMap;
+
+///Returns a copy of `map` with every obstacle grown by `radius` tiles, blocking every tile whose centre is within
+///`radius` of the centre of a blocked tile, with the edge of the map counting as one. Searching the inflated map
+///with the plain path functions gives routes a circular agent of that radius can follow, a cheap alternative to
+///clearance-aware searches such as [`ClearanceMap::wall_penalty`](crate::ClearanceMap::wall_penalty).
+///
+///Newly blocked tiles become `'@'` and the rest keep their tile characters. The result is octile,
+///like every map built by this crate.
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+/// use movingai::Map2D;
+///
+/// let map = movingai::parser::parse_map_file(Path::new("./tests/map/maze512-32-9.map")).expect("Could not load map.");
+///
+/// let inflated = blitz_path::inflate(&map, 2);
+/// assert!(!inflated.is_traversable((2, 2)));
+/// assert!(inflated.is_traversable((3, 3)));
+/// ```
+pub fn inflate(map: &MovingAiMap, radius: usize) -> MovingAiMap {
+    let (width, height) = (map.width(), map.height());
+    let mut tiles: Vec<char> = map.coords().map(|tile| *map.get(tile)).collect();
+
+    //Offsets within the radius, found once and stamped around every obstacle
+    let reach = radius as isize;
+    let disc: Vec<(isize, isize)> = (-reach..=reach)
+        .flat_map(|dy| (-reach..=reach).map(move |dx| (dx, dy)))
+        .filter(|(dx, dy)| (dx * dx + dy * dy) as usize <= radius * radius)
+        .collect();
+
+    for (x, y) in map.coords() {
+        if map.is_traversable((x, y)) {
+            //Near enough to the edge for the tiles beyond it to count
+            if x < radius || y < radius || width - 1 - x < radius || height - 1 - y < radius {
+                tiles[y * width + x] = '@';
+            }
+            continue;
+        }
+        for (dx, dy) in &disc {
+            if let (Some(nx), Some(ny)) = (x.checked_add_signed(*dx), y.checked_add_signed(*dy)) {
+                if nx < width && ny < height {
+                    tiles[ny * width + nx] = '@';
+                }
+            }
+        }
+    }
+
+    MovingAiMap::new(String::from("octile"), height, width, tiles)
+}
@@ -0,0 +1,178 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::grid::Coords2D;
+use crate::grid::Map2D;
+use crate::grid::MovingAiMap;
+
+use crate::flood::flood;
+use crate::memory::vec_size;
+use crate::utils::distance;
+use crate::{MemoryFootprint, Route};
+
+///A point spreading influence across the map, such as a unit projecting threat.
+///Negative strengths can be used for opposing influence, like friendly and enemy control.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct InfluenceSource {
+    ///The tile the influence spreads from.
+    pub position: Coords2D,
+    ///The influence at the source tile itself.
+    pub strength: f64,
+}
+
+///How influence weakens with the distance travelled from its source.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Decay {
+    ///Falls by this fraction of the source's strength per tile travelled, reaching zero at 1 / rate tiles.
+    Linear(f64),
+    ///Is multiplied by this factor, between 0 and 1, per tile travelled.
+    Exponential(f64),
+}
+
+impl Decay {
+    //Fraction of a source's strength remaining after travelling distance
+    fn falloff(&self, distance: f64) -> f64 {
+        match *self {
+            Decay::Linear(rate) => (1.0 - rate * distance).max(0.0),
+            Decay::Exponential(factor) => libm::pow(factor, distance),
+        }
+    }
+
+    //Distance beyond which a source contributes less than cutoff, as a fraction of its strength
+    fn range(&self, cutoff: f64) -> f64 {
+        match *self {
+            Decay::Linear(rate) if rate > 0.0 => (1.0 - cutoff) / rate,
+            Decay::Exponential(factor) if factor > 0.0 && factor < 1.0 => {
+                libm::log(cutoff) / libm::log(factor)
+            }
+            _ => f64::INFINITY,
+        }
+    }
+}
+
+///Values spread from weighted sources across the traversable tiles of a map, for tactical AI such as threat maps.
+///Influence travels around obstacles rather than through them, weakening with the distance walked,
+///and the contributions of every source are summed.
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+/// use blitz_path::{Decay, InfluenceMap, InfluenceSource};
+///
+/// let map = movingai::parser::parse_map_file(Path::new("./tests/map/maze512-32-9.map")).expect("Could not load map.");
+///
+/// let sources = [InfluenceSource { position: (1, 1), strength: 10.0 }];
+/// let influence = InfluenceMap::new(&map, &sources, Decay::Linear(0.1), 0.01);
+///
+/// assert_eq!(influence.get((1, 1)), 10.0);
+/// assert_eq!(influence.get((2, 1)), 9.0);
+/// assert_eq!(influence.get((0, 0)), 0.0);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct InfluenceMap {
+    width: usize,
+    height: usize,
+    values: Vec<f64>,
+}
+
+impl InfluenceMap {
+    ///Spreads influence from every source across `map`.
+    ///A source stops spreading once it contributes less than `cutoff` times its strength.
+    pub fn new(
+        map: &MovingAiMap,
+        sources: &[InfluenceSource],
+        decay: Decay,
+        cutoff: f64,
+    ) -> InfluenceMap {
+        let mut influence = InfluenceMap {
+            width: map.width(),
+            height: map.height(),
+            values: vec![0.0; map.width() * map.height()],
+        };
+
+        let range = decay.range(cutoff);
+        for source in sources {
+            let reached = flood(
+                map,
+                &[(source.position, 0.0)],
+                range,
+                |from, to| Some(distance(from, to)),
+                |_, _| false,
+            );
+            for (value, cost) in influence.values.iter_mut().zip(&reached.costs) {
+                if cost.is_finite() {
+                    *value += source.strength * decay.falloff(*cost);
+                }
+            }
+        }
+
+        influence
+    }
+
+    ///Returns the total influence at a tile, or 0.0 if it is outside the map.
+    pub fn get(&self, position: Coords2D) -> f64 {
+        if position.0 >= self.width || position.1 >= self.height {
+            return 0.0;
+        }
+        self.values[position.1 * self.width + position.0]
+    }
+
+    ///Returns a step cost for the weighted searches that trades extra distance for less risk,
+    ///treating positive influence as risk, such as the threat of enemy towers.
+    ///Entering a tile costs the distance travelled times one plus `weight` times its influence,
+    ///so the risk of a route builds up with the time spent in danger. Negative influence counts as no risk.
+    ///No step ever costs less than the distance it covers, so the cost can be used with
+    ///[`a_star_path_weighted`](crate::a_star_path_weighted).
+    /// # Examples
+    ///
+    /// ```
+    /// use std::path::Path;
+    /// use blitz_path::{Decay, InfluenceMap, InfluenceSource, SearchOptions};
+    ///
+    /// let map = movingai::parser::parse_map_file(Path::new("./tests/map/maze512-32-9.map")).expect("Could not load map.");
+    ///
+    /// let towers = [InfluenceSource { position: (5, 3), strength: 5.0 }];
+    /// let threat = InfluenceMap::new(&map, &towers, Decay::Linear(0.25), 0.01);
+    ///
+    /// let direct = blitz_path::a_star_path(&map, (1, 3), (9, 3)).unwrap();
+    /// let cautious = blitz_path::a_star_path_weighted(&map, (1, 3), (9, 3), &SearchOptions::new(), threat.risk_cost(1.0)).unwrap();
+    /// assert!(threat.route_risk(&cautious) < threat.route_risk(&direct));
+    /// ```
+    pub fn risk_cost(&self, weight: f64) -> impl Fn(Coords2D, Coords2D) -> Option<f64> + '_ {
+        move |from, to| Some(distance(from, to) * (1.0 + weight * self.get(to).max(0.0)))
+    }
+
+    ///Returns the risk built up along a route, as the sum over its steps of the positive influence
+    ///of each tile entered times the distance travelled to it. Routes should list every tile.
+    pub fn route_risk(&self, route: &Route) -> f64 {
+        route
+            .steps()
+            .windows(2)
+            .map(|pair| distance(pair[0], pair[1]) * self.get(pair[0]).max(0.0))
+            .sum()
+    }
+
+    ///Returns the influence of every tile in row-major order.
+    pub fn values(&self) -> &[f64] {
+        &self.values
+    }
+
+    ///Returns the width of the underlying map.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    ///Returns the height of the underlying map.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+}
+
+impl MemoryFootprint for InfluenceMap {
+    fn heap_size(&self) -> usize {
+        vec_size(&self.values)
+    }
+}
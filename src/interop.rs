@@ -0,0 +1,109 @@
+//Adapters between this crate's maps and routes and the pathfinding crate
+
+use core::cmp::Ordering;
+use core::ops::Add;
+
+use crate::grid::{Coords2D, Map2D, MovingAiMap};
+use pathfinding::grid::Grid;
+use pathfinding::num_traits::Zero;
+
+use crate::utils::{distance, neighbours};
+use crate::Route;
+
+///A step cost usable with the pathfinding crate, which needs totally ordered costs.
+///Ordered with `f64::total_cmp`.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct PathCost(pub f64);
+
+impl PartialEq for PathCost {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for PathCost {}
+
+impl PartialOrd for PathCost {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for PathCost {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+impl Add for PathCost {
+    type Output = PathCost;
+
+    fn add(self, other: PathCost) -> PathCost {
+        PathCost(self.0 + other.0)
+    }
+}
+
+impl Zero for PathCost {
+    fn zero() -> Self {
+        PathCost(0.0)
+    }
+
+    fn is_zero(&self) -> bool {
+        self.0 == 0.0
+    }
+}
+
+///Returns a successor function for the pathfinding crate's searches, giving the same moves and costs
+///as the searches in this crate.
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+/// use blitz_path::interop::{heuristic, successors};
+///
+/// let map = movingai::parser::parse_map_file(Path::new("./tests/map/maze512-32-9.map")).expect("Could not load map.");
+/// let goal = (5, 5);
+///
+/// let (steps, cost) = pathfinding::directed::astar::astar(&(1, 1), successors(&map), heuristic(goal), |tile| *tile == goal).unwrap();
+/// assert_eq!(cost.0, blitz_path::a_star_path(&map, (1, 1), goal).unwrap().distance());
+/// ```
+pub fn successors(map: &MovingAiMap) -> impl Fn(&Coords2D) -> Vec<(Coords2D, PathCost)> + '_ {
+    move |tile| {
+        neighbours(map, *tile)
+            .map(|next| (next, PathCost(distance(*tile, next))))
+            .collect()
+    }
+}
+
+///Returns the straight line distance to `goal`, as a heuristic for the pathfinding crate's searches.
+pub fn heuristic(goal: Coords2D) -> impl Fn(&Coords2D) -> PathCost {
+    move |tile| PathCost(distance(*tile, goal))
+}
+
+///Converts a path found by the pathfinding crate, which runs from start to goal, into a Route.
+pub fn route_from_path(path: Vec<Coords2D>, cost: PathCost) -> Route {
+    let mut steps = path;
+    steps.reverse();
+    Route::from((cost.0, steps))
+}
+
+///Converts a map into a pathfinding crate Grid, with a vertex for every traversable tile and diagonal moves enabled.
+///Grids allow diagonal moves past the corners of obstacles, which maps do not,
+///so prefer [`successors`] when routes need to match the searches in this crate.
+pub fn grid_from_map(map: &MovingAiMap) -> Grid {
+    let mut grid = Grid::new(map.width(), map.height());
+    grid.enable_diagonal_mode();
+    for tile in map.coords().filter(|tile| map.is_traversable(*tile)) {
+        grid.add_vertex(tile);
+    }
+    grid
+}
+
+///Converts a pathfinding crate Grid into a map, with vertices as open tiles and everything else blocked.
+pub fn map_from_grid(grid: &Grid) -> MovingAiMap {
+    let tiles = (0..grid.height)
+        .flat_map(|y| (0..grid.width).map(move |x| (x, y)))
+        .map(|tile| if grid.has_vertex(tile) { '.' } else { '@' })
+        .collect();
+    MovingAiMap::new(String::from("octile"), grid.height, grid.width, tiles)
+}
@@ -0,0 +1,88 @@
+use alloc::collections::BTreeSet;
+use alloc::vec::Vec;
+
+use crate::grid::Coords2D;
+
+use crate::Route;
+
+///A place where two routes meet, found by [`Route::conflicts`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RouteConflict {
+    ///Both routes pass through this tile.
+    Cell(Coords2D),
+    ///Both routes cut across the same square of four tiles along opposite diagonals,
+    ///crossing at its centre without sharing a tile. Gives the step of each route, start first.
+    Crossing {
+        ///The step of the route the conflicts were asked for.
+        first: (Coords2D, Coords2D),
+        ///The step of the other route.
+        second: (Coords2D, Coords2D),
+    },
+}
+
+impl Route {
+    ///Returns every place this route meets `other`, in the order this route reaches them,
+    ///for quick conflict checks between planned routes without building a
+    ///[`ReservationTable`](crate::ReservationTable). Timing is ignored, so routes that pass through
+    ///the same tile at different times still conflict. Each shared tile is reported once,
+    ///and tiles between steps that are not neighbours, such as jump points, are filled in.
+    /// # Examples
+    ///
+    /// ```
+    /// use blitz_path::{Route, RouteConflict};
+    ///
+    /// let east: Route = Route::from((4.0, vec![(4, 2), (3, 2), (2, 2), (1, 2), (0, 2)]));
+    /// let south: Route = Route::from((4.0, vec![(2, 4), (2, 0)]));
+    /// assert_eq!(east.conflicts(&south), vec![RouteConflict::Cell((2, 2))]);
+    ///
+    /// let down: Route = Route::from((2f64.sqrt(), vec![(1, 1), (0, 0)]));
+    /// let up: Route = Route::from((2f64.sqrt(), vec![(0, 1), (1, 0)]));
+    /// assert!(down.intersects(&up));
+    /// ```
+    pub fn conflicts(&self, other: &Route) -> Vec<RouteConflict> {
+        let ours = self.cells();
+        let theirs = other.cells();
+        let shared: BTreeSet<Coords2D> = theirs.iter().copied().collect();
+        let diagonals: BTreeSet<(Coords2D, Coords2D)> = theirs
+            .windows(2)
+            .filter(|pair| is_diagonal(pair[0], pair[1]))
+            .map(|pair| (pair[0], pair[1]))
+            .collect();
+
+        let mut reported = BTreeSet::new();
+        let mut conflicts = Vec::new();
+        for (i, tile) in ours.iter().enumerate() {
+            if shared.contains(tile) && reported.insert(*tile) {
+                conflicts.push(RouteConflict::Cell(*tile));
+            }
+            let next = match ours.get(i + 1) {
+                Some(next) => *next,
+                None => continue,
+            };
+            if !is_diagonal(*tile, next) {
+                continue;
+            }
+            //The other diagonal of the square, taken either way round
+            let crossing = ((next.0, tile.1), (tile.0, next.1));
+            for second in [crossing, (crossing.1, crossing.0)] {
+                if diagonals.contains(&second) {
+                    conflicts.push(RouteConflict::Crossing {
+                        first: (*tile, next),
+                        second,
+                    });
+                }
+            }
+        }
+        conflicts
+    }
+
+    ///Returns true if this route meets `other` anywhere, as described in [`conflicts`](Route::conflicts).
+    pub fn intersects(&self, other: &Route) -> bool {
+        !self.conflicts(other).is_empty()
+    }
+}
+
+fn is_diagonal(from: Coords2D, to: Coords2D) -> bool {
+    from.0.abs_diff(to.0) == 1 && from.1.abs_diff(to.1) == 1
+}
@@ -1,12 +1,16 @@
-use std::collections::BinaryHeap;
+use alloc::vec::Vec;
 
-use movingai::Coords2D;
-use movingai::Map2D;
-use movingai::MovingAiMap;
+use crate::grid::Coords2D;
+use crate::grid::Map2D;
+use crate::grid::MovingAiMap;
 
 use crate::node::Node;
-use crate::utils::{direction, distance, rewind};
-use crate::Route;
+use crate::pruning::{DefaultPruning, PruningRule};
+use crate::stats::{timed, track_memory};
+use crate::steps::Step;
+use crate::trace::instrument;
+use crate::utils::{compose, direction, distance, offset, prepare, rewind};
+use crate::{PathError, Route, SearchContext, SearchEvent, SearchObserver, SearchOptions};
 
 #[derive(Copy, Clone)]
 enum Direction {
@@ -17,6 +21,7 @@ enum Direction {
 
 ///Creates a new route using the JPS algorithm.
 ///Returns a Route struct containing the distance to the goal and number of steps needed to get there.
+///Returns a PathError if either point is out of bounds or blocked, or if no route exists.
 /// # Examples
 ///
 /// ```
@@ -30,78 +35,331 @@ enum Direction {
 ///
 /// // using as f32 as scene.optimal_length is stored as f64,
 /// // but only seems to have precision to f32
-/// if let Some(path) = path {
+/// if let Ok(path) = path {
 ///     assert_eq!(scene.optimal_length as f32, path.distance() as f32);  
 /// }
 /// ```
+pub fn jps_path(map: &MovingAiMap, start: Coords2D, goal: Coords2D) -> Result<Route, PathError> {
+    jps_path_with(map, start, goal, &SearchOptions::default())
+}
 
-pub fn jps_path(map: &MovingAiMap, start: Coords2D, goal: Coords2D) -> Option<Route> {
-    //Initialize open and closed lists
-    let mut open = BinaryHeap::new();
-    let mut closed = Vec::<Node>::new();
-
-    //Push start node to open list
-    let start_node = Node::new(0.0, distance(start, goal), start, start);
-    if start == goal {
-        open.push(start_node);
-    } else {
-        //Add start's neighbours to open list - modified as seems to be error in neighbours function
-        let prev_x = start_node.position.0 - 1;
-        let next_x = start_node.position.0 + 1;
-        let prev_y = start_node.position.1 - 1;
-        let next_y = start_node.position.1 + 1;
-        for x in prev_x..=next_x {
-            for y in prev_y..=next_y {
-                let coords = Coords2D::from((x, y));
-                let node = Node::from_parent(&start_node, coords, goal);
-                open.push(node);
-            }
-        }
+///Creates a new route using the JPS algorithm, configured by `options`.
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+/// use blitz_path::SearchOptions;
+///
+/// let map = movingai::parser::parse_map_file(Path::new("./tests/map/maze512-32-9.map")).expect("Could not load map.");
+///
+/// // (0, 0) is a wall, so the route starts from the nearest open tile instead
+/// let options = SearchOptions::new().snap_radius(2);
+/// let path = blitz_path::jps_path_with(&map, (0, 0), (5, 5), &options).unwrap();
+/// assert_eq!(path.steps().last(), Some(&(1, 1)));
+/// ```
+pub fn jps_path_with(
+    map: &MovingAiMap,
+    start: Coords2D,
+    goal: Coords2D,
+    options: &SearchOptions,
+) -> Result<Route, PathError> {
+    jps_path_observed(map, start, goal, options, &mut ())
+}
+
+///Creates a new route using the JPS algorithm, reporting each step of the search to `observer`.
+///See [`SearchObserver`](crate::SearchObserver) for details of the events raised.
+pub fn jps_path_observed<O: SearchObserver>(
+    map: &MovingAiMap,
+    start: Coords2D,
+    goal: Coords2D,
+    options: &SearchOptions,
+    observer: &mut O,
+) -> Result<Route, PathError> {
+    search_with_rule(
+        &mut SearchContext::new(),
+        map,
+        start,
+        goal,
+        options,
+        observer,
+    )
+}
+
+///Finds the length of the shortest route using the JPS algorithm, without building the route itself.
+///Cheaper than [`jps_path_with`] when only the distance matters, such as when scoring many candidate moves.
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+/// use blitz_path::SearchOptions;
+///
+/// let map = movingai::parser::parse_map_file(Path::new("./tests/map/maze512-32-9.map")).expect("Could not load map.");
+/// let scenes = movingai::parser::parse_scen_file(Path::new("./tests/map/maze512-32-9.map.scen")).expect("Could not load scenario.");
+/// let scene = &scenes[0];
+///
+/// let distance = blitz_path::jps_distance(&map, scene.start_pos, scene.goal_pos, &SearchOptions::new()).unwrap();
+/// assert_eq!(scene.optimal_length as f32, distance as f32);
+/// ```
+pub fn jps_distance(
+    map: &MovingAiMap,
+    start: Coords2D,
+    goal: Coords2D,
+    options: &SearchOptions,
+) -> Result<f64, PathError> {
+    distance_with_rule(&mut SearchContext::new(), map, start, goal, options)
+}
+
+//Runs the search with the configured pruning rule, keeping the default rule statically dispatched
+pub(crate) fn search_with_rule<O: SearchObserver>(
+    context: &mut SearchContext,
+    map: &MovingAiMap,
+    start: Coords2D,
+    goal: Coords2D,
+    options: &SearchOptions,
+    observer: &mut O,
+) -> Result<Route, PathError> {
+    let result = instrument("jps", start, goal, observer, |observer| {
+        let node_goal = explore_with_rule(context, map, start, goal, options, observer)?;
+        Ok(route(context, &node_goal, options))
+    });
+    #[cfg(feature = "verify")]
+    crate::verify::assert_optimal(
+        map,
+        start,
+        goal,
+        options,
+        result.as_ref().map(Route::distance).map_err(|error| *error),
+    );
+    result
+}
+
+//Unwind, looking in the open list too as jump points may not have been expanded yet
+pub(crate) fn route(context: &SearchContext, node_goal: &Node, options: &SearchOptions) -> Route {
+    let find = |position| {
+        context
+            .closed
+            .iter()
+            .chain(context.open.iter())
+            .find(|x: &&Node| x.position == position)
+            .copied()
+    };
+    //Every parent of a node was expanded before it, so is always found
+    let path = rewind(node_goal, find).expect("parents are expanded before their children");
+    let path = compose(path, options);
+    Route::from((node_goal.g, path))
+}
 
-        closed.push(start_node);
+//Runs the search with the configured pruning rule, returning only the length of the route
+pub(crate) fn distance_with_rule(
+    context: &mut SearchContext,
+    map: &MovingAiMap,
+    start: Coords2D,
+    goal: Coords2D,
+    options: &SearchOptions,
+) -> Result<f64, PathError> {
+    let result = instrument("jps", start, goal, &mut (), |observer| {
+        explore_with_rule(context, map, start, goal, options, observer).map(|node| node.g)
+    });
+    #[cfg(feature = "verify")]
+    crate::verify::assert_optimal(map, start, goal, options, result);
+    result
+}
+
+pub(crate) fn explore_with_rule<O: SearchObserver>(
+    context: &mut SearchContext,
+    map: &MovingAiMap,
+    start: Coords2D,
+    goal: Coords2D,
+    options: &SearchOptions,
+    observer: &mut O,
+) -> Result<Node, PathError> {
+    match &options.pruning {
+        Some(rule) => explore(context, map, start, goal, options, rule.as_ref(), observer),
+        None => explore(
+            context,
+            map,
+            start,
+            goal,
+            options,
+            &DefaultPruning,
+            observer,
+        ),
     }
+}
 
-    //Examine the nodes
-    while let Some(node_current) = open.pop() {
-        //If this is the target node return the distance to get there
-        if node_current.position == goal {
-            //Push all remaining to closed
-            for node in open {
-                closed.push(node);
+//Runs the search until the goal is reached, returning its node and leaving the search state in the context.
+//The time taken is recorded in the context's stats.
+fn explore<P: PruningRule + ?Sized, O: SearchObserver>(
+    context: &mut SearchContext,
+    map: &MovingAiMap,
+    start: Coords2D,
+    goal: Coords2D,
+    options: &SearchOptions,
+    rule: &P,
+    observer: &mut O,
+) -> Result<Node, PathError> {
+    let (result, elapsed) = timed(options.measure_time, || {
+        let mut search = JumpSearch::start(context, map, start, goal, options, rule, observer)?;
+        loop {
+            if let Step::Finished(result) = search.step(context, observer) {
+                return result;
             }
+        }
+    });
+    context.stats.elapsed = elapsed;
+    track_memory(context);
+    result
+}
+
+//A jump point search in progress, advanced one expansion at a time.
+//The open and closed lists live in the context, so they can be inspected between steps.
+pub(crate) struct JumpSearch<'a, P: ?Sized> {
+    map: &'a MovingAiMap,
+    goal: Coords2D,
+    options: &'a SearchOptions,
+    rule: &'a P,
+    pruned: bool,
+    iteration: usize,
+}
 
-            //Unwind
-            let path = rewind(&node_current, &closed);
-            let route = Route::from((node_current.g, path));
-            return Some(route);
+impl<'a, P: PruningRule + ?Sized> JumpSearch<'a, P> {
+    //Clear the context and push the start node's neighbours, ready for the first step
+    pub(crate) fn start<O: SearchObserver>(
+        context: &mut SearchContext,
+        map: &'a MovingAiMap,
+        start: Coords2D,
+        goal: Coords2D,
+        options: &'a SearchOptions,
+        rule: &'a P,
+        observer: &mut O,
+    ) -> Result<JumpSearch<'a, P>, PathError> {
+        //Initialize open and closed lists
+        context.reset(options.capacity(map));
+        let SearchContext {
+            open,
+            closed,
+            stats,
+        } = context;
+        let (start, goal) = prepare(map, start, goal, options)?;
+
+        //Even a straight line would be too long
+        if options.exceeds_max_distance(distance(start, goal)) {
+            return Err(PathError::ExceedsMaxDistance);
         }
 
-        //Check if node is on closed list and continue if is
-        if closed.contains(&node_current) {
-            continue;
+        //Push start node to open list, alone if it is the goal so the route is just the start
+        let start_node = Node::new(0.0, distance(start, goal), start, start);
+        if start == goal {
+            observer.observe(SearchEvent::Discovered(start_node.info()));
+            open.push(start_node);
+            stats.generate(open.len());
+        } else {
+            //Add start's neighbours to open list, skipping any that can't be stepped onto from the start
+            for dx in -1..=1 {
+                for dy in -1..=1 {
+                    let coords = match offset(start_node.position, dx, dy) {
+                        Some(coords) if map.is_traversable_from(start, coords) => coords,
+                        _ => continue,
+                    };
+                    let node = Node::from_parent(&start_node, coords, goal);
+                    observer.observe(SearchEvent::Discovered(node.info()));
+                    open.push(node);
+                    stats.generate(open.len());
+                }
+            }
+
+            closed.push(start_node);
         }
 
-        //Calculate direction
-        let direction = direction(node_current.position, node_current.parent);
+        Ok(JumpSearch {
+            map,
+            goal,
+            options,
+            rule,
+            pruned: false,
+            iteration: 0,
+        })
+    }
+
+    //Examine nodes until one is expanded, counting the work done in the context's stats
+    pub(crate) fn step<O: SearchObserver>(
+        &mut self,
+        context: &mut SearchContext,
+        observer: &mut O,
+    ) -> Step {
+        let SearchContext {
+            open,
+            closed,
+            stats,
+        } = context;
+        let (map, goal, options) = (self.map, self.goal, self.options);
+
+        while let Some(node_current) = open.pop() {
+            //Stop if asked to by another thread
+            if options.should_cancel(self.iteration) {
+                return Step::Finished(Err(PathError::Cancelled));
+            }
+            self.iteration += 1;
 
-        if let Some(nodes) = check_jump(&node_current, map, (direction.0, direction.1), goal) {
-            for node in nodes {
-                open.push(node);
+            //If this is the target node return it
+            if node_current.position == goal {
+                return Step::Finished(Ok(node_current));
             }
+
+            //Check if node is on closed list and continue if is
+            if closed
+                .iter()
+                .any(|node| node.position == node_current.position)
+            {
+                continue;
+            }
+
+            observer.observe(SearchEvent::Expanded(node_current.info()));
+            stats.expanded += 1;
+
+            //Calculate direction
+            let direction = direction(node_current.position, node_current.parent);
+
+            if let Some(nodes) = check_jump(
+                &node_current,
+                map,
+                self.rule,
+                (direction.0, direction.1),
+                goal,
+                &mut stats.jump_scans,
+            ) {
+                for node in nodes {
+                    //Skip nodes that can only lead to routes longer than allowed
+                    if options.exceeds_max_distance(node.f) {
+                        self.pruned = true;
+                        continue;
+                    }
+                    observer.observe(SearchEvent::Discovered(node.info()));
+                    open.push(node);
+                    stats.generate(open.len());
+                }
+            }
+
+            //Push current node to closed list
+            closed.push(node_current);
+            return Step::Expanded(node_current);
         }
 
-        //Push current node to closed list
-        closed.push(node_current);
+        if self.pruned {
+            Step::Finished(Err(PathError::ExceedsMaxDistance))
+        } else {
+            Step::Finished(Err(PathError::NoPath))
+        }
     }
-
-    None
 }
 
-fn check_jump(
+fn check_jump<P: PruningRule + ?Sized>(
     parent: &Node,
     map: &MovingAiMap,
+    rule: &P,
     direction: (i32, i32),
     goal: Coords2D,
+    scans: &mut usize,
 ) -> Option<Vec<Node>> {
     //println!("Checking: {:?}", parent.position);
     //Expand depending on direction
@@ -118,95 +376,21 @@ fn check_jump(
         Direction::Vertical(direction.1)
     };
 
-    if let Some(nodes) = expand(map, &parent, dir, goal) {
-        Some(nodes)
-    } else {
-        None
-    }
-}
-
-fn forced_horizontal(
-    map: &MovingAiMap,
-    check_node: &Node,
-    direction: i32,
-    goal: Coords2D,
-) -> Option<Vec<Node>> {
-    let next_x = (check_node.position.0 as i32 + direction) as usize;
-    let up_y = (check_node.position.1 as i32 - 1) as usize;
-    let down_y = (check_node.position.1 as i32 + 1) as usize;
-
-    let mut nodes = Vec::new();
-
-    //Check if blocked up
-    if (!map.is_traversable(Coords2D::from((check_node.position.0, up_y))))
-        && (map.is_traversable(Coords2D::from((next_x, up_y))))
-    {
-        let jump_point = Coords2D::from((next_x, up_y));
-        let jump_node = Node::from_parent(&check_node, jump_point, goal);
-        nodes.push(jump_node);
-    }
-
-    //Check if blocked down
-    if (!map.is_traversable(Coords2D::from((check_node.position.0, down_y))))
-        && (map.is_traversable(Coords2D::from((next_x, down_y))))
-    {
-        let jump_point = Coords2D::from((next_x, down_y));
-        let jump_node = Node::from_parent(&check_node, jump_point, goal);
-        nodes.push(jump_node);
-    }
-
-    if !nodes.is_empty() {
-        Some(nodes)
-    } else {
-        None
-    }
-}
-
-fn forced_vertical(
-    map: &MovingAiMap,
-    check_node: &Node,
-    direction: i32,
-    goal: Coords2D,
-) -> Option<Vec<Node>> {
-    let next_y = (check_node.position.1 as i32 + direction) as usize;
-    let left_x = (check_node.position.0 as i32 - 1) as usize;
-    let right_x = (check_node.position.0 as i32 + 1) as usize;
-
-    let mut nodes = Vec::new();
-
-    //Check if blocked left
-    if (!map.is_traversable(Coords2D::from((left_x, check_node.position.1))))
-        && (map.is_traversable(Coords2D::from((left_x, next_y))))
-    {
-        let jump_point = Coords2D::from((left_x, next_y));
-        let jump_node = Node::from_parent(&check_node, jump_point, goal);
-        nodes.push(jump_node);
-    }
-
-    //Check if blocked right
-    if (!map.is_traversable(Coords2D::from((right_x, check_node.position.1))))
-        && (map.is_traversable(Coords2D::from((right_x, next_y))))
-    {
-        let jump_point = Coords2D::from((right_x, next_y));
-        let jump_node = Node::from_parent(&check_node, jump_point, goal);
-        nodes.push(jump_node);
-    }
-
-    if !nodes.is_empty() {
-        Some(nodes)
-    } else {
-        None
-    }
+    expand(map, rule, parent, dir, goal, scans)
 }
 
-fn expand(
+fn expand<P: PruningRule + ?Sized>(
     map: &MovingAiMap,
+    rule: &P,
     start_node: &Node,
     direction: Direction,
     goal: Coords2D,
+    scans: &mut usize,
 ) -> Option<Vec<Node>> {
+    *scans += 1;
     let mut current = *start_node;
     let mut nodes = Vec::new();
+    let mut forced = Vec::new();
     loop {
         //Check if goal
         if current.position == goal {
@@ -215,57 +399,58 @@ fn expand(
             return Some(nodes);
         }
 
-        //Check blocked
-        if !map.is_traversable(current.position) {
-            return None;
-        }
-
         //Otherwise Expand depending on direction
         let dir;
         match direction {
             Direction::Vertical(vert) => {
                 dir = (0, vert);
                 //Check for forced neighbours
-                if let Some(mut vert_nodes) = forced_vertical(map, &current, vert, goal) {
-                    nodes.append(&mut vert_nodes);
-                }
+                rule.forced_vertical(map, current.position, vert, &mut forced);
             }
             Direction::Horizontal(hor) => {
                 dir = (hor, 0);
                 //Check for forced neighbours
-                if let Some(mut hor_nodes) = forced_horizontal(map, &current, hor, goal) {
-                    nodes.append(&mut hor_nodes);
-                }
+                rule.forced_horizontal(map, current.position, hor, &mut forced);
             }
             Direction::Diagonal(hor, vert) => {
                 dir = (hor, vert);
                 //Expand horizontally
-                if let Some(mut hor_nodes) = expand(map, &current, Direction::Horizontal(hor), goal)
+                if let Some(mut hor_nodes) =
+                    expand(map, rule, &current, Direction::Horizontal(hor), goal, scans)
                 {
                     nodes.append(&mut hor_nodes);
                 }
                 //Expand vertically
-                if let Some(mut vert_nodes) = expand(map, &current, Direction::Vertical(vert), goal)
+                if let Some(mut vert_nodes) =
+                    expand(map, rule, &current, Direction::Vertical(vert), goal, scans)
                 {
                     nodes.append(&mut vert_nodes);
                 }
             }
         }
 
-        let next_x = (current.position.0 as i32 + dir.0) as usize;
-        let next_y = (current.position.1 as i32 + dir.1) as usize;
-        let next_position = Coords2D::from((next_x, next_y));
+        //Forced neighbours past a corner can't be stepped onto from here
+        for jump_point in forced.drain(..) {
+            if map.is_traversable_from(current.position, jump_point) {
+                nodes.push(Node::from_parent(&current, jump_point, goal));
+            }
+        }
+
+        //Leaving the map, stepping onto a blocked tile or cutting a corner ends the scan
+        let next_position = offset(current.position, dir.0, dir.1)
+            .filter(|next| map.is_traversable_from(current.position, *next));
 
         //If forced neighbours found return them along with this node and next on to continue checking in this direction
         if !nodes.is_empty() {
-            let next_node = Node::from_parent(&current, next_position, goal);
             nodes.push(current);
-            nodes.push(next_node);
+            if let Some(next_position) = next_position {
+                nodes.push(Node::from_parent(&current, next_position, goal));
+            }
 
             return Some(nodes);
         }
 
         //Else move onto next tile
-        current = Node::from_parent(start_node, next_position, goal);
+        current = Node::from_parent(start_node, next_position?, goal);
     }
 }
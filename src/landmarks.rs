@@ -0,0 +1,206 @@
+use alloc::vec::Vec;
+
+use crate::grid::Coords2D;
+use crate::grid::Map2D;
+use crate::grid::MovingAiMap;
+
+use crate::flood::flood;
+use crate::memory::vec_size;
+use crate::utils::{distance, index};
+use crate::{MemoryFootprint, PathError, Route, SearchContext, SearchOptions};
+
+///Distances from a handful of landmark tiles to every tile of a map, giving the ALT heuristic for
+///[`a_star_path_landmarks`]. By the triangle inequality a route from `a` to `b` is at least as long as
+///how much further `b` is than `a` from any landmark, which is far tighter than the straight line
+///distance when large obstacles stand in the way, so searches expand far fewer nodes.
+///Only distances from the landmarks are kept, as some moves can't be made in reverse, such as leaving
+///ground for swamp, so the distance back to a landmark may be longer.
+///
+///The heuristic is only admissible for the map it was built on, or a copy with more tiles blocked
+///rather than opened, so rebuild it after walls are removed. Search options that only make steps
+///dearer, such as congestion and cost stacks, keep it admissible.
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+/// use blitz_path::{Landmarks, SearchOptions};
+///
+/// let map = movingai::parser::parse_map_file(Path::new("./tests/map/maze512-32-9.map")).expect("Could not load map.");
+///
+/// let landmarks = Landmarks::new(&map, &[(1, 1)]);
+/// assert_eq!(landmarks.heuristic((1, 1), (5, 1)), 4.0);
+///
+/// let path = blitz_path::a_star_path_landmarks(&map, (1, 1), (5, 5), &landmarks, &SearchOptions::new()).unwrap();
+/// let expected = blitz_path::a_star_path(&map, (1, 1), (5, 5)).unwrap();
+/// assert_eq!(path.distance(), expected.distance());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Landmarks {
+    width: usize,
+    height: usize,
+    landmarks: Vec<Coords2D>,
+    //Distance from each landmark to every tile in row-major order, infinite if unreachable
+    distances: Vec<Vec<f64>>,
+}
+
+impl Landmarks {
+    ///Measures the distance from each of `landmarks` to every tile of `map`.
+    ///Landmarks that are blocked or outside the map are left out.
+    pub fn new(map: &MovingAiMap, landmarks: &[Coords2D]) -> Landmarks {
+        let mut result = Landmarks {
+            width: map.width(),
+            height: map.height(),
+            landmarks: Vec::new(),
+            distances: Vec::new(),
+        };
+        for landmark in landmarks {
+            result.add(map, *landmark);
+        }
+        result
+    }
+
+    ///Picks `count` landmarks spread across `map`, each as far as possible from those already picked,
+    ///which places them around the edges of the map where they give the tightest estimates.
+    ///Tiles that none of the landmarks so far can reach are picked first, so every region gets one.
+    pub fn spread(map: &MovingAiMap, count: usize) -> Landmarks {
+        let mut result = Landmarks::new(map, &[]);
+        //Nearest landmark to every tile, starting from the first open tile rather than a landmark
+        let mut nearest = match map.coords().find(|tile| map.is_traversable(*tile)) {
+            Some(first) => measure(map, first),
+            None => return result,
+        };
+
+        while result.landmarks.len() < count {
+            let mut best: Option<(Coords2D, f64)> = None;
+            for tile in map.coords() {
+                let cost = nearest[index(map, tile)];
+                if map.is_traversable(tile)
+                    && !result.landmarks.contains(&tile)
+                    && best.is_none_or(|(_, best)| cost > best)
+                {
+                    best = Some((tile, cost));
+                }
+            }
+            let landmark = match best {
+                Some((landmark, _)) => landmark,
+                None => break,
+            };
+
+            result.add(map, landmark);
+            let distances = result.distances.last().expect("landmark was just added");
+            //The first landmark replaces the starting tile rather than joining it
+            if result.landmarks.len() == 1 {
+                nearest.clone_from(distances);
+            } else {
+                for (nearest, cost) in nearest.iter_mut().zip(distances) {
+                    *nearest = nearest.min(*cost);
+                }
+            }
+        }
+
+        result
+    }
+
+    ///Returns a lower bound on the length of any route from `from` to `to`, the largest of the straight line
+    ///distance and how much further `to` is than `from` from each landmark.
+    pub fn heuristic(&self, from: Coords2D, to: Coords2D) -> f64 {
+        let straight = distance(from, to);
+        let (from, to) = match (self.index(from), self.index(to)) {
+            (Some(from), Some(to)) => (from, to),
+            _ => return straight,
+        };
+
+        self.distances
+            .iter()
+            .map(|distances| (distances[from], distances[to]))
+            //A landmark that cannot reach both tiles says nothing about the route between them
+            .filter(|(from, to)| from.is_finite() && to.is_finite())
+            .map(|(from, to)| to - from)
+            .fold(straight, f64::max)
+    }
+
+    ///Returns the landmarks, in the order they were added.
+    pub fn landmarks(&self) -> &[Coords2D] {
+        &self.landmarks
+    }
+
+    ///Returns the distance from the landmark at `landmark` in [`Landmarks::landmarks`] to `tile`,
+    ///or `None` if either does not exist or the tile cannot be reached.
+    pub fn distance(&self, landmark: usize, tile: Coords2D) -> Option<f64> {
+        let cost = self.distances.get(landmark)?[self.index(tile)?];
+        if cost.is_finite() {
+            Some(cost)
+        } else {
+            None
+        }
+    }
+
+    ///Returns the number of landmarks.
+    pub fn len(&self) -> usize {
+        self.landmarks.len()
+    }
+
+    ///Returns true if there are no landmarks, leaving only the straight line distance as a heuristic.
+    pub fn is_empty(&self) -> bool {
+        self.landmarks.is_empty()
+    }
+
+    ///Returns the width of the underlying map.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    ///Returns the height of the underlying map.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    fn add(&mut self, map: &MovingAiMap, landmark: Coords2D) {
+        if map.is_out_of_bound(landmark) || !map.is_traversable(landmark) {
+            return;
+        }
+        self.landmarks.push(landmark);
+        self.distances.push(measure(map, landmark));
+    }
+
+    fn index(&self, tile: Coords2D) -> Option<usize> {
+        if tile.0 >= self.width || tile.1 >= self.height {
+            return None;
+        }
+        Some(tile.1 * self.width + tile.0)
+    }
+}
+
+//Distance from a tile to every other tile in row-major order, infinite if unreachable
+fn measure(map: &MovingAiMap, source: Coords2D) -> Vec<f64> {
+    flood(
+        map,
+        &[(source, 0.0)],
+        f64::INFINITY,
+        |from, to| Some(distance(from, to)),
+        |_, _| false,
+    )
+    .costs
+}
+
+///Creates a new route using the A* algorithm guided by the ALT heuristic of `landmarks`,
+///finding the same distance as [`a_star_path`](crate::a_star_path) while expanding fewer nodes
+///on maps where obstacles make the straight line a poor estimate.
+pub fn a_star_path_landmarks(
+    map: &MovingAiMap,
+    start: Coords2D,
+    goal: Coords2D,
+    landmarks: &Landmarks,
+    options: &SearchOptions,
+) -> Result<Route, PathError> {
+    SearchContext::new().a_star_path_landmarks(map, start, goal, landmarks, options)
+}
+
+impl MemoryFootprint for Landmarks {
+    fn heap_size(&self) -> usize {
+        vec_size(&self.landmarks)
+            + vec_size(&self.distances)
+            + self.distances.iter().map(vec_size).sum::<usize>()
+    }
+}
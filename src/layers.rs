@@ -0,0 +1,144 @@
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::grid::Coords2D;
+
+use crate::utils::distance;
+
+//The cost a layer adds to a step, or None if the layer forbids it
+type LayerCost<'a> = Box<dyn Fn(Coords2D, Coords2D) -> Option<f64> + Send + Sync + 'a>;
+
+struct Layer<'a> {
+    name: String,
+    weight: f64,
+    cost: LayerCost<'a>,
+}
+
+///Named layers of step costs, such as terrain, dynamic hazards, congestion and game-specific rules,
+///each scaled by its own weight and added together when a step is taken, so layers can be changed,
+///reweighted or switched off without merging them into a single grid by hand.
+///
+///Each layer gives the extra cost of a step, or `None` to forbid it, and is called with the tile being left
+///and the tile being entered. A layer with a weight of zero is switched off, and neither adds to nor forbids steps.
+///Use the stack with the weighted searches through [`step_cost`](CostStack::step_cost), or with every A* and Dijkstra
+///search through [`SearchOptions::cost_stack`](crate::SearchOptions::cost_stack).
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+/// use blitz_path::{CostStack, SearchOptions};
+///
+/// let map = movingai::parser::parse_map_file(Path::new("./tests/map/maze512-32-9.map")).expect("Could not load map.");
+///
+/// let mut stack = CostStack::new();
+/// // Mud along row 1, and a fire that cannot be walked through
+/// stack.push("mud", 2.0, |_, to: (usize, usize)| Some(if to.1 == 1 { 1.0 } else { 0.0 }));
+/// stack.push("fire", 1.0, |_, to| if to == (3, 2) { None } else { Some(0.0) });
+///
+/// let path = blitz_path::a_star_path_weighted(&map, (1, 1), (5, 1), &SearchOptions::new(), stack.step_cost()).unwrap();
+/// assert!(path.distance() < 4.0 + 2.0 * 4.0);
+///
+/// // Once the mud dries, the straight route is cheapest again
+/// stack.set_weight("mud", 0.0);
+/// let path = blitz_path::a_star_path_weighted(&map, (1, 1), (5, 1), &SearchOptions::new(), stack.step_cost()).unwrap();
+/// assert_eq!(path.distance(), 4.0);
+/// ```
+#[derive(Default)]
+pub struct CostStack<'a> {
+    layers: Vec<Layer<'a>>,
+}
+
+impl<'a> CostStack<'a> {
+    ///Creates a stack with no layers, where every step costs only the distance it covers.
+    pub fn new() -> CostStack<'a> {
+        CostStack { layers: Vec::new() }
+    }
+
+    ///Adds a layer on top of the stack, or replaces the layer with the same name in place.
+    pub fn push<C>(&mut self, name: &str, weight: f64, cost: C)
+    where
+        C: Fn(Coords2D, Coords2D) -> Option<f64> + Send + Sync + 'a,
+    {
+        let layer = Layer {
+            name: String::from(name),
+            weight,
+            cost: Box::new(cost),
+        };
+        match self.layers.iter_mut().find(|layer| layer.name == name) {
+            Some(existing) => *existing = layer,
+            None => self.layers.push(layer),
+        }
+    }
+
+    ///Removes the layer with the given name, returning whether there was one.
+    pub fn remove(&mut self, name: &str) -> bool {
+        let before = self.layers.len();
+        self.layers.retain(|layer| layer.name != name);
+        self.layers.len() != before
+    }
+
+    ///Sets the weight of the layer with the given name, returning whether there was one.
+    pub fn set_weight(&mut self, name: &str, weight: f64) -> bool {
+        match self.layers.iter_mut().find(|layer| layer.name == name) {
+            Some(layer) => {
+                layer.weight = weight;
+                true
+            }
+            None => false,
+        }
+    }
+
+    ///Returns the weight of the layer with the given name.
+    pub fn weight(&self, name: &str) -> Option<f64> {
+        self.layers
+            .iter()
+            .find(|layer| layer.name == name)
+            .map(|layer| layer.weight)
+    }
+
+    ///Returns the names of the layers, from the bottom of the stack to the top.
+    pub fn names(&self) -> Vec<&str> {
+        self.layers
+            .iter()
+            .map(|layer| layer.name.as_str())
+            .collect()
+    }
+
+    ///Returns the number of layers.
+    pub fn len(&self) -> usize {
+        self.layers.len()
+    }
+
+    ///Returns true if the stack has no layers.
+    pub fn is_empty(&self) -> bool {
+        self.layers.is_empty()
+    }
+
+    ///Returns the weighted sum of every switched on layer's cost for a step,
+    ///or `None` if any of them forbids it.
+    pub fn penalty(&self, from: Coords2D, to: Coords2D) -> Option<f64> {
+        self.layers
+            .iter()
+            .filter(|layer| layer.weight != 0.0)
+            .try_fold(0.0, |total, layer| {
+                Some(total + layer.weight * (layer.cost)(from, to)?)
+            })
+    }
+
+    ///Returns a step cost for the weighted searches, giving the distance of each step plus its [`penalty`](CostStack::penalty).
+    ///As long as no layer's weighted cost is negative, no step ever costs less than the distance it covers,
+    ///so the cost can be used with [`a_star_path_weighted`](crate::a_star_path_weighted).
+    pub fn step_cost(&self) -> impl Fn(Coords2D, Coords2D) -> Option<f64> + '_ {
+        move |from, to| Some(distance(from, to) + self.penalty(from, to)?)
+    }
+}
+
+impl fmt::Debug for CostStack<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map()
+            .entries(self.layers.iter().map(|layer| (&layer.name, layer.weight)))
+            .finish()
+    }
+}
@@ -0,0 +1,105 @@
+//Building maps from levels made in the LDtk editor
+
+use std::fs;
+use std::path::Path;
+
+use crate::grid::MovingAiMap;
+use serde::Deserialize;
+
+use crate::MapLoadError;
+
+//Only the parts of an LDtk project needed to read IntGrid layers
+#[derive(Deserialize)]
+struct Project {
+    levels: Vec<Level>,
+}
+
+#[derive(Deserialize)]
+struct Level {
+    identifier: String,
+    #[serde(rename = "layerInstances")]
+    layer_instances: Option<Vec<Layer>>,
+}
+
+#[derive(Deserialize)]
+struct Layer {
+    #[serde(rename = "__identifier")]
+    identifier: String,
+    #[serde(rename = "__type")]
+    layer_type: String,
+    #[serde(rename = "__cWid")]
+    width: usize,
+    #[serde(rename = "__cHei")]
+    height: usize,
+    #[serde(rename = "intGridCsv")]
+    int_grid: Vec<i64>,
+}
+
+///Loads a map from an IntGrid layer of a level in an LDtk .ldtk project file.
+///Each cell of the layer becomes one tile, blocked wherever `is_obstacle` returns true for the cell's value.
+///Empty cells have the value 0.
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+///
+/// let map = blitz_path::map_from_ldtk(Path::new("./tests/map/level.ldtk"), "Level_0", "Collisions", |value| value == 1).unwrap();
+/// assert!(blitz_path::is_reachable(&map, (1, 1), (3, 2)));
+/// ```
+pub fn map_from_ldtk<F: Fn(i64) -> bool>(
+    path: &Path,
+    level: &str,
+    layer: &str,
+    is_obstacle: F,
+) -> Result<MovingAiMap, MapLoadError> {
+    let project =
+        fs::read_to_string(path).map_err(|error| MapLoadError::Parse(error.to_string()))?;
+    map_from_ldtk_str(&project, level, layer, is_obstacle)
+}
+
+///Builds a map from an IntGrid layer of a level, like [`map_from_ldtk`], from the contents of a project file.
+pub fn map_from_ldtk_str<F: Fn(i64) -> bool>(
+    project: &str,
+    level: &str,
+    layer: &str,
+    is_obstacle: F,
+) -> Result<MovingAiMap, MapLoadError> {
+    let project: Project =
+        serde_json::from_str(project).map_err(|error| MapLoadError::Parse(error.to_string()))?;
+
+    let level_data = project
+        .levels
+        .into_iter()
+        .find(|found| found.identifier == level)
+        .ok_or_else(|| MapLoadError::Parse(format!("no level named {:?}", level)))?;
+    let layers = level_data.layer_instances.ok_or_else(|| {
+        MapLoadError::Parse(format!("level {:?} is saved in a separate file", level))
+    })?;
+    let layer_data = layers
+        .into_iter()
+        .find(|found| found.identifier == layer)
+        .ok_or_else(|| MapLoadError::MissingLayer(String::from(layer)))?;
+    if layer_data.layer_type != "IntGrid" {
+        return Err(MapLoadError::NotTileLayer(String::from(layer)));
+    }
+    if layer_data.int_grid.len() != layer_data.width * layer_data.height {
+        return Err(MapLoadError::Parse(format!(
+            "layer {:?} has {} cells but should have {}",
+            layer,
+            layer_data.int_grid.len(),
+            layer_data.width * layer_data.height
+        )));
+    }
+
+    let tiles = layer_data
+        .int_grid
+        .iter()
+        .map(|value| if is_obstacle(*value) { '@' } else { '.' })
+        .collect();
+    Ok(MovingAiMap::new(
+        String::from("octile"),
+        layer_data.height,
+        layer_data.width,
+        tiles,
+    ))
+}
@@ -2,14 +2,244 @@
 //!
 //! `blitz-path` contains (hopefully) lightning-quick implementations of various pathfinding algorithms. Currently in a very wip state. It relies on the [movingai-rust](https://github.com/THeK3nger/movingai-rust) crate for map implementation and testing / benchmarks.
 //!
-//! It currently provides implementations of the A* and JPS pathfinding algorithms.
+//! It currently provides implementations of the A*, JPS and Dijkstra pathfinding algorithms, each of which can
+//! also be chosen at runtime through [`Algorithm`] and [`find_path`].
+//! Space-time A* plans routes for several agents in turn, each avoiding the routes planned before it,
+//! and [`prioritized_planning`] reorders the agents when one is left without a route.
+//! A* and Dijkstra also accept a closure giving the cost of each step, for maps where movement costs vary.
+//! The nodes and helpers the searches are built from are exposed in [`primitives`], for writing other search variants.
+//!
+//! The default `movingai` feature uses the map types of the movingai crate, so maps and scenarios can be loaded
+//! with its parser. Disabling it swaps them for the self-contained grid in [`grid`], which follows the same rules,
+//! for users who build maps in code. The scenario suite, the benchmark set runner and the Python module need the feature.
+//! Enabling the `tracing` feature emits spans and events for each search through the
+//! [tracing](https://docs.rs/tracing) crate, including the number of nodes expanded.
+//! Enabling the `tiled` feature allows loading maps from [Tiled](https://www.mapeditor.org) .tmx files.
+//! Enabling the `tokio` feature adds async versions of the searches, which run on tokio's blocking thread pool
+//! and are cancelled when their [`PathFuture`] is dropped.
+//! Enabling the `wasm-bindgen` feature exports [`WasmMap`] to JavaScript for use in the browser.
+//! Enabling the `bevy` feature adds [`PathfindingPlugin`], which solves [`PathRequest`] components attached to entities.
+//! Enabling the `godot` feature builds a Godot GDExtension exposing [`GodotMap`] to GDScript as `BlitzMap`.
+//! Enabling the `ldtk` feature allows loading maps from the IntGrid layers of [LDtk](https://ldtk.io) projects.
+//! Enabling the `memory-stats` feature records the memory each search held in [`SearchStats`]. Sizes of maps and
+//! preprocessed data are always available through [`MemoryFootprint`].
+//! Enabling the `ndarray` feature allows building maps and step costs from [ndarray](https://docs.rs/ndarray) arrays.
+//! Enabling the `pathfinding` feature adds the [`interop`] module, for searching maps with the
+//! [pathfinding](https://docs.rs/pathfinding) crate and converting between its grids and maps.
+//! Enabling the `protobuf` feature adds a compact protocol buffer encoding for routes and batches of search results,
+//! with the schema in `proto/route.proto`.
+//! Enabling the `pyo3` feature builds a Python module exposing maps, searches and routes.
+//! Enabling the `rerun` feature allows logging maps, routes and the progress of searches to the [rerun](https://rerun.io) viewer.
+//! Enabling the `serde` feature makes maps, routes and precomputed grids serializable, and adds the [`versioned`]
+//! envelope for data that must keep loading across releases.
+//! Enabling the `verify` feature checks every JPS result against Dijkstra's algorithm, panicking with the
+//! offending scenario if they disagree. See [`verify_jps`].
+//!
+//! All public types are `Send + Sync`. Maps and options are only ever read during a search, so one map
+//! can be wrapped in an `Arc` and queried from many threads at once. Mutable scratch state lives in
+//! [`SearchContext`], which each thread should own.
+//!
+//! Searches are deterministic: identical inputs always produce bitwise-identical routes, on any platform.
+//! Ties between equally promising nodes are broken on distance to goal and then position, and no
+//! search state depends on hashing or allocation order, so results are safe to use in lockstep simulations.
+//!
+//! The crate supports `no_std` targets with an allocator. Disabling the default `std` feature leaves the searches,
+//! which need only `core` and `alloc`, with floating point functions taken from [libm](https://docs.rs/libm).
+//! Loading maps from files, streaming searches to another thread and timing searches need `std`, as do the
+//! movingai parser and the integrations with other crates, whose features enable it.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
 
+mod active;
+mod algorithm;
+mod anisotropy;
+#[cfg(feature = "ndarray")]
+mod arrays;
 mod astar;
+mod avoidance;
+#[cfg(feature = "bevy")]
+mod bevy;
+mod cancel;
+mod clearance;
+mod components;
+mod compress;
+mod congestion;
+mod context;
+mod corridor;
+mod coverage;
+mod crop;
+mod crowd;
+mod desire;
+mod diff;
+mod dijkstra;
+mod directional;
+mod dump;
+mod elevation;
+mod error;
+mod evacuation;
+mod export;
+mod flee;
+mod flood;
+mod fog;
+mod formation;
+#[cfg(feature = "godot")]
+mod godot;
+pub mod grid;
+mod inflate;
+mod influence;
+#[cfg(feature = "pathfinding")]
+pub mod interop;
+mod intersection;
 mod jps;
+mod landmarks;
+mod layers;
+#[cfg(feature = "ldtk")]
+mod ldtk;
+mod los;
+mod memory;
 mod node;
+mod observer;
+mod occupancy;
+mod offset;
+mod options;
+mod pareto;
+mod payload;
+pub mod primitives;
+mod prioritized;
+#[cfg(feature = "protobuf")]
+mod protobuf;
+mod pruning;
+#[cfg(feature = "pyo3")]
+mod python;
+mod reachability;
+mod realtime;
+#[cfg(feature = "rerun")]
+mod recording;
+mod regions;
+mod replay;
+mod reservation;
+mod resource;
 mod route;
+mod shape;
+mod similarity;
+mod spacetime;
+mod spline;
+mod stats;
+mod stealth;
+mod steps;
+#[cfg(feature = "std")]
+mod stream;
+#[cfg(feature = "movingai")]
+mod suite;
+mod targets;
+#[cfg(feature = "tokio")]
+mod tasks;
+#[cfg(feature = "tiled")]
+mod tmx;
+mod trace;
+mod transform;
+mod turning;
 mod utils;
+mod verify;
+#[cfg(feature = "serde")]
+pub mod versioned;
+mod voronoi;
+#[cfg(feature = "wasm-bindgen")]
+mod wasm;
+mod waypoints;
 
-pub use astar::a_star_path;
-pub use jps::jps_path;
+pub use active::{ActivePath, PathUpdate};
+pub use algorithm::{find_path, Algorithm};
+pub use anisotropy::{a_star_path_anisotropic, Anisotropy};
+#[cfg(feature = "ndarray")]
+pub use arrays::{array_cost, map_from_costs, map_from_occupancy, occupancy_from_map};
+pub use astar::{
+    a_star_distance, a_star_path, a_star_path_observed, a_star_path_weighted, a_star_path_with,
+};
+pub use avoidance::local_detour;
+#[cfg(feature = "bevy")]
+pub use bevy::{PathGrid, PathRequest, PathResult, PathfindingPlugin};
+pub use cancel::CancelToken;
+pub use clearance::ClearanceMap;
+pub use components::{components, Components};
+pub use congestion::CongestionMap;
+pub use context::SearchContext;
+pub use corridor::optimal_corridor;
+pub use coverage::coverage_path;
+pub use crop::{crop, CroppedMap};
+pub use crowd::{Crowd, CrowdAgent, FlowField};
+pub use desire::DesireMap;
+pub use diff::{CellChange, MapDiff};
+pub use dijkstra::{dijkstra_path, dijkstra_path_weighted};
+pub use directional::{Direction, DirectionalCosts};
+pub use dump::HeatmapValue;
+pub use elevation::Heightmap;
+pub use error::{MapDiffError, MapLoadError, PathError, RouteDecodeError};
+pub use evacuation::evacuation_paths;
+pub use flee::flee_path;
+pub use fog::FogOfWar;
+pub use formation::Formation;
+#[cfg(feature = "godot")]
+pub use godot::GodotMap;
+pub use inflate::inflate;
+pub use influence::{Decay, InfluenceMap, InfluenceSource};
+pub use intersection::RouteConflict;
+pub use jps::{jps_distance, jps_path, jps_path_observed, jps_path_with};
+pub use landmarks::{a_star_path_landmarks, Landmarks};
+pub use layers::CostStack;
+#[cfg(feature = "ldtk")]
+pub use ldtk::{map_from_ldtk, map_from_ldtk_str};
+pub use los::{line_of_sight, line_of_sight_tiles};
+pub use memory::MemoryFootprint;
+pub use observer::{NodeInfo, SearchEvent, SearchObserver};
+pub use occupancy::{OccupancyGrid, Pose2D};
+pub use offset::{OffsetMap, SignedCoords};
+pub use options::{RouteSteps, SearchOptions};
+pub use pareto::{pareto_paths, ParetoFrontier};
+pub use payload::PayloadLayer;
+pub use prioritized::{prioritized_planning, PrioritizedPlan};
+#[cfg(feature = "protobuf")]
+pub use protobuf::{decode_route_batch, encode_route_batch, BatchEntry};
+pub use pruning::{DefaultPruning, PruningRule};
+#[cfg(feature = "pyo3")]
+pub use python::{PyMap, PyRoute};
+pub use reachability::{is_reachable, reachable_within};
+pub use realtime::RealTimeAgent;
+#[cfg(feature = "rerun")]
+pub use recording::{log_map_to_rerun, log_route_to_rerun, RerunObserver};
+pub use regions::{weighted_region_path, WeightedRegions};
+pub use replay::SearchRecording;
+pub use reservation::ReservationTable;
+pub use resource::resource_path;
 pub use route::Route;
+pub use spacetime::{
+    space_time_a_star, space_time_a_star_reserved, time_expanded_search, TimedRoute,
+};
+pub use spline::{smooth_route, smooth_route_within, CurveKind, RouteCurve};
+pub use stats::SearchStats;
+pub use stealth::{stealth_path, VisibilityMap, Watcher};
+pub use steps::{SearchSnapshot, SearchSteps};
+#[cfg(feature = "std")]
+pub use stream::{a_star_path_streamed, jps_path_streamed, StreamEvent};
+#[cfg(feature = "movingai")]
+pub use suite::{
+    run_benchmark_set, run_benchmark_set_with, run_scen_suite, BenchmarkReport, MapReport,
+    ScenarioResult, SuiteReport,
+};
+pub use targets::{nearest_target_path, nearest_targets_paths};
+#[cfg(feature = "tokio")]
+pub use tasks::{a_star_path_async, jps_path_async, spawn_search, PathFuture};
+#[cfg(feature = "tiled")]
+pub use tmx::{map_from_tiled, TiledObstacles};
+pub use transform::MapTransform;
+pub use turning::a_star_path_turning;
+pub use utils::nearest_traversable;
+pub use verify::{verify_jps, Discrepancy};
+#[cfg(feature = "serde")]
+pub use versioned::{Stable, Versioned, FORMAT_VERSION};
+pub use voronoi::VoronoiRegions;
+#[cfg(feature = "wasm-bindgen")]
+pub use wasm::WasmMap;
+pub use waypoints::{route_through, visit_all};
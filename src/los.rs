@@ -0,0 +1,99 @@
+use alloc::vec::Vec;
+
+use crate::grid::Coords2D;
+use crate::grid::Map2D;
+use crate::grid::MovingAiMap;
+
+///Returns true if a straight line between the centres of `a` and `b` crosses only traversable tiles.
+///Every tile the line touches is checked (a supercover line), and where the line passes exactly
+///through a corner both tiles beside it must be traversable, matching the rule that moves may not cut corners.
+///Returns false if either point is out of bounds or blocked.
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+///
+/// let map = movingai::parser::parse_map_file(Path::new("./tests/map/maze512-32-9.map")).expect("Could not load map.");
+///
+/// assert!(blitz_path::line_of_sight(&map, (1, 1), (20, 1)));
+/// assert!(blitz_path::line_of_sight(&map, (1, 1), (5, 3)));
+/// assert!(!blitz_path::line_of_sight(&map, (1, 1), (0, 5)));
+/// ```
+pub fn line_of_sight(map: &MovingAiMap, a: Coords2D, b: Coords2D) -> bool {
+    line_tiles(map, a, b, |_| {})
+}
+
+///Returns every tile touched by the straight line between the centres of `a` and `b`, in order from `a`,
+///or None if any of them is not traversable. The tiles on both sides of an exact corner crossing are
+///included, so consecutive tiles are not always neighbours.
+pub fn line_of_sight_tiles(map: &MovingAiMap, a: Coords2D, b: Coords2D) -> Option<Vec<Coords2D>> {
+    let mut tiles = Vec::new();
+    if line_tiles(map, a, b, |tile| tiles.push(tile)) {
+        Some(tiles)
+    } else {
+        None
+    }
+}
+
+//Returns true if nothing blocks the line from a to b before it reaches b, which may itself be blocked,
+//so walls facing an observer can be seen as well as the open ground in front of them
+pub(crate) fn in_sight(map: &MovingAiMap, a: Coords2D, b: Coords2D) -> bool {
+    !map.is_out_of_bound(b) && walk(a, b, |tile| tile == b || map.is_traversable(tile), |_| {})
+}
+
+//Walk the supercover line from a to b on map, passing each tile to visit and stopping at the first blocked one
+fn line_tiles<F: FnMut(Coords2D)>(map: &MovingAiMap, a: Coords2D, b: Coords2D, visit: F) -> bool {
+    walk(a, b, |tile| map.is_traversable(tile), visit)
+}
+
+//Walk the supercover line from a to b, passing each tile to visit and stopping at the first one that is not open
+fn walk<O: Fn(Coords2D) -> bool, F: FnMut(Coords2D)>(
+    a: Coords2D,
+    b: Coords2D,
+    open: O,
+    mut visit: F,
+) -> bool {
+    let traversable =
+        |x: i64, y: i64| x >= 0 && y >= 0 && open(Coords2D::from((x as usize, y as usize)));
+
+    let (mut x, mut y) = (a.0 as i64, a.1 as i64);
+    let (dx, dy) = (b.0 as i64 - x, b.1 as i64 - y);
+    let (nx, ny) = (dx.abs(), dy.abs());
+    let (sx, sy) = (dx.signum(), dy.signum());
+
+    if !traversable(x, y) {
+        return false;
+    }
+    visit(Coords2D::from((x as usize, y as usize)));
+
+    let (mut ix, mut iy) = (0, 0);
+    while ix < nx || iy < ny {
+        //Compare where the line crosses the next vertical and horizontal tile edges
+        let decision = (1 + 2 * ix) * ny - (1 + 2 * iy) * nx;
+        if decision == 0 {
+            //Exactly through a corner, so both tiles beside it are touched
+            if !traversable(x + sx, y) || !traversable(x, y + sy) {
+                return false;
+            }
+            visit(Coords2D::from(((x + sx) as usize, y as usize)));
+            visit(Coords2D::from((x as usize, (y + sy) as usize)));
+            x += sx;
+            y += sy;
+            ix += 1;
+            iy += 1;
+        } else if decision < 0 {
+            x += sx;
+            ix += 1;
+        } else {
+            y += sy;
+            iy += 1;
+        }
+
+        if !traversable(x, y) {
+            return false;
+        }
+        visit(Coords2D::from((x as usize, y as usize)));
+    }
+
+    true
+}
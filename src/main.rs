@@ -0,0 +1,218 @@
+//Solves every scenario in a MovingAI .scen file, reporting costs, timings and mismatches against the recorded optima.
+//The bench mode instead times every algorithm over a directory of maps, writing CSV for comparisons.
+
+use std::env;
+use std::path::Path;
+use std::process;
+#[cfg(feature = "pathfinding")]
+use std::time::Instant;
+
+use movingai::parser::{parse_map_file, parse_scen_file};
+use movingai::{MovingAiMap, SceneRecord};
+
+#[cfg(feature = "pathfinding")]
+use blitz_path::PathError;
+use blitz_path::{run_benchmark_set_with, run_scen_suite, Algorithm, SuiteReport};
+
+const USAGE: &str =
+    "usage: blitz-path <map file> <scen file> [--algorithm jps|astar|dijkstra] [--limit N]
+       blitz-path bench <directory> [--algorithm NAME]... [--limit N]";
+
+//Algorithms timed by the bench mode when none are chosen
+#[cfg(not(feature = "pathfinding"))]
+const BENCH_ALGORITHMS: &[&str] = &["jps", "astar", "dijkstra"];
+#[cfg(feature = "pathfinding")]
+const BENCH_ALGORITHMS: &[&str] = &["jps", "astar", "dijkstra", "pathfinding-astar"];
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    if args.first().map(String::as_str) == Some("bench") {
+        bench(args.into_iter().skip(1));
+        return;
+    }
+
+    let mut files = Vec::new();
+    let mut algorithm = String::from("jps");
+    let mut limit = usize::MAX;
+
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--algorithm" => algorithm = args.next().unwrap_or_else(|| fail("missing algorithm")),
+            "--limit" => {
+                limit = args
+                    .next()
+                    .and_then(|limit| limit.parse().ok())
+                    .unwrap_or_else(|| fail("--limit needs a number"))
+            }
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                return;
+            }
+            _ => files.push(arg),
+        }
+    }
+    if files.len() != 2 {
+        fail("expected a map file and a scen file");
+    }
+
+    let map = parse_map_file(Path::new(&files[0]))
+        .unwrap_or_else(|error| fail(&format!("could not load {}: {}", files[0], error)));
+    let scenes = parse_scen_file(Path::new(&files[1]))
+        .unwrap_or_else(|error| fail(&format!("could not load {}: {}", files[1], error)));
+    let algorithm = match algorithm.as_str() {
+        "jps" => Algorithm::Jps,
+        "astar" => Algorithm::AStar,
+        "dijkstra" => Algorithm::Dijkstra,
+        _ => fail(&format!("unknown algorithm {}", algorithm)),
+    };
+
+    let report = run_scen_suite(&map, &scenes[..limit.min(scenes.len())], algorithm);
+    println!("scenario\tstart\tgoal\texpected\tcost\tmicros\tstatus");
+    for result in &report.results {
+        let (cost, status) = match result.found {
+            Ok(distance) if result.passed() => (format!("{:.8}", distance), String::from("ok")),
+            Ok(distance) => (format!("{:.8}", distance), String::from("MISMATCH")),
+            Err(error) => (String::from("-"), format!("ERROR {}", error)),
+        };
+        println!(
+            "{}\t{:?}\t{:?}\t{:.8}\t{}\t{}\t{}",
+            result.index,
+            result.start,
+            result.goal,
+            result.expected,
+            cost,
+            result.elapsed.as_micros(),
+            status
+        );
+    }
+
+    let mismatches = report.failures().count();
+    println!(
+        "{} scenarios, {} mismatches, {} micros total",
+        report.results.len(),
+        mismatches,
+        report.total_time().as_micros()
+    );
+    if mismatches > 0 {
+        process::exit(1);
+    }
+}
+
+//Times each algorithm on every scenario of every map in a directory that has a matching .map.scen file,
+//printing CSV rows with the number of nodes each search expanded
+fn bench<I: Iterator<Item = String>>(mut args: I) {
+    let mut directory = None;
+    let mut algorithms = Vec::new();
+    let mut limit = None;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--algorithm" => {
+                let algorithm = args.next().unwrap_or_else(|| fail("missing algorithm"));
+                if !BENCH_ALGORITHMS.contains(&algorithm.as_str()) {
+                    fail(&format!("unknown algorithm {}", algorithm));
+                }
+                algorithms.push(algorithm);
+            }
+            "--limit" => {
+                limit = Some(
+                    args.next()
+                        .and_then(|limit| limit.parse().ok())
+                        .unwrap_or_else(|| fail("--limit needs a number")),
+                )
+            }
+            _ if directory.is_none() => directory = Some(arg),
+            _ => fail(&format!("unexpected argument {}", arg)),
+        }
+    }
+    let directory = directory.unwrap_or_else(|| fail("expected a directory of maps"));
+    if algorithms.is_empty() {
+        algorithms = BENCH_ALGORITHMS
+            .iter()
+            .map(|name| String::from(*name))
+            .collect();
+    }
+
+    println!("map,scenario,algorithm,expected,cost,micros,expanded");
+    for algorithm in &algorithms {
+        let report = run_benchmark_set_with(&directory, limit, |map, scenes| {
+            run_suite(algorithm, map, scenes)
+        })
+        .unwrap_or_else(|error| fail(&format!("could not run {}: {}", directory, error)));
+
+        for map in &report.maps {
+            for result in &map.report.results {
+                let cost = result
+                    .found
+                    .map_or(String::new(), |distance| format!("{:.8}", distance));
+                println!(
+                    "{},{},{},{:.8},{},{},{}",
+                    map.name,
+                    result.index,
+                    algorithm,
+                    result.expected,
+                    cost,
+                    result.elapsed.as_micros(),
+                    result.expanded
+                );
+            }
+        }
+    }
+}
+
+//Runs every scenario of a map with the algorithm named
+fn run_suite(algorithm: &str, map: &MovingAiMap, scenes: &[SceneRecord]) -> SuiteReport {
+    match algorithm {
+        "jps" => run_scen_suite(map, scenes, Algorithm::Jps),
+        "astar" => run_scen_suite(map, scenes, Algorithm::AStar),
+        "dijkstra" => run_scen_suite(map, scenes, Algorithm::Dijkstra),
+        #[cfg(feature = "pathfinding")]
+        "pathfinding-astar" => pathfinding_suite(map, scenes),
+        _ => unreachable!("algorithms are checked when parsing arguments"),
+    }
+}
+
+//Times the pathfinding crate's A* on every scenario, counting the nodes it expands
+#[cfg(feature = "pathfinding")]
+fn pathfinding_suite(map: &MovingAiMap, scenes: &[SceneRecord]) -> SuiteReport {
+    use blitz_path::{interop, ScenarioResult};
+
+    let successors = interop::successors(map);
+    let results = scenes
+        .iter()
+        .enumerate()
+        .map(|(index, scene)| {
+            let (start, goal) = (scene.start_pos, scene.goal_pos);
+            let mut expanded = 0;
+            let timer = Instant::now();
+            let result = pathfinding::directed::astar::astar(
+                &start,
+                |position| {
+                    expanded += 1;
+                    successors(position)
+                },
+                interop::heuristic(goal),
+                |position| *position == goal,
+            );
+            let elapsed = timer.elapsed();
+            ScenarioResult {
+                index,
+                start,
+                goal,
+                expected: scene.optimal_length,
+                found: result
+                    .map(|(path, cost)| interop::route_from_path(path, cost).distance())
+                    .ok_or(PathError::NoPath),
+                elapsed,
+                expanded,
+            }
+        })
+        .collect();
+    SuiteReport { results }
+}
+
+//Print an error and the usage, then exit
+fn fail(message: &str) -> ! {
+    eprintln!("error: {}\n{}", message, USAGE);
+    process::exit(2);
+}
@@ -0,0 +1,74 @@
+use alloc::collections::{BTreeMap, BinaryHeap};
+use alloc::vec::Vec;
+use core::mem::{size_of, size_of_val};
+
+use crate::grid::{Map2D, MovingAiMap};
+use crate::node::Node;
+use crate::SearchContext;
+
+///Memory held by maps, planners and preprocessed data, for budgeting navigation data per level.
+///
+///Sizes are estimates in bytes: the value itself plus the buffers it owns, counted at their full capacity.
+///Ordered maps are counted by the size of their entries, ignoring the bookkeeping of their nodes,
+///so types built on them may hold somewhat more than reported.
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+/// use blitz_path::{components, ClearanceMap, MemoryFootprint};
+///
+/// let map = movingai::parser::parse_map_file(Path::new("./tests/map/maze512-32-9.map")).expect("Could not load map.");
+///
+/// //One label per tile, and one clearance value per tile
+/// assert!(components(&map).memory_footprint() >= 512 * 512 * 4);
+/// assert!(ClearanceMap::new(&map).memory_footprint() >= 512 * 512 * 8);
+/// ```
+pub trait MemoryFootprint {
+    ///Returns the bytes held on the heap by buffers this value owns.
+    fn heap_size(&self) -> usize;
+
+    ///Returns the bytes held by this value, including the buffers it owns.
+    fn memory_footprint(&self) -> usize {
+        size_of_val(self) + self.heap_size()
+    }
+}
+
+impl MemoryFootprint for MovingAiMap {
+    //One character per tile, as movingai does not expose its buffers
+    fn heap_size(&self) -> usize {
+        self.width() * self.height() * size_of::<char>()
+    }
+}
+
+impl MemoryFootprint for SearchContext {
+    fn heap_size(&self) -> usize {
+        heap_size(&self.open) + vec_size(&self.closed)
+    }
+}
+
+impl<T: MemoryFootprint> MemoryFootprint for Option<T> {
+    fn heap_size(&self) -> usize {
+        self.as_ref().map_or(0, T::heap_size)
+    }
+}
+
+impl<T: MemoryFootprint> MemoryFootprint for Vec<T> {
+    fn heap_size(&self) -> usize {
+        vec_size(self) + self.iter().map(T::heap_size).sum::<usize>()
+    }
+}
+
+//Bytes held by the buffer of a vector of values that own nothing themselves
+pub(crate) fn vec_size<T>(values: &Vec<T>) -> usize {
+    values.capacity() * size_of::<T>()
+}
+
+//Bytes held by the buffer of a heap of nodes
+fn heap_size(nodes: &BinaryHeap<Node>) -> usize {
+    nodes.capacity() * size_of::<Node>()
+}
+
+//Bytes held by the entries of an ordered map of values that own nothing themselves
+pub(crate) fn map_size<K, V>(entries: &BTreeMap<K, V>) -> usize {
+    entries.len() * size_of::<(K, V)>()
+}
@@ -1,18 +1,29 @@
-use movingai::Coords2D;
-use std::cmp::Ordering;
+use crate::grid::Coords2D;
+use core::cmp::Ordering;
 
 use crate::utils::distance;
+use crate::NodeInfo;
 
+///A tile reached by a search, as kept in the open and closed lists.
+///Ordered so that a `BinaryHeap` of nodes pops the lowest `f` first, breaking ties on lower `h` and then position.
+///Nodes compare equal only when their order is equal, sharing `f`, `h` and position, so search closed lists by
+///comparing positions rather than with `contains`.
 #[derive(Debug, Copy, Clone)]
 pub struct Node {
+    ///The estimated total cost of a route through this node, `g + h`.
     pub f: f64,
+    ///The cost of the cheapest route found from the start to this node.
     pub g: f64,
+    ///The estimated cost from this node to the goal.
     pub h: f64,
+    ///The tile this node is on.
     pub position: Coords2D,
+    ///The tile this node was reached from, equal to `position` for the start.
     pub parent: Coords2D,
 }
 
 impl Node {
+    ///Creates a node from its cost so far and estimate to the goal.
     pub fn new(g: f64, h: f64, position: Coords2D, parent: Coords2D) -> Node {
         Node {
             f: g + h,
@@ -23,6 +34,8 @@ impl Node {
         }
     }
 
+    ///Creates the node for stepping from `parent` to `position`, costing the straight line distance
+    ///and estimating the rest with the straight line distance to `goal`.
     pub fn from_parent(parent: &Node, position: Coords2D, goal: Coords2D) -> Node {
         //Calculate distances
         let distance_to_goal = distance(position, goal);
@@ -34,10 +47,20 @@ impl Node {
             f: total_distance + distance_to_goal,
             g: total_distance,
             h: distance_to_goal,
-            position: position,
+            position,
             parent: parent.position,
         }
     }
+
+    ///Returns the node as reported to a [`SearchObserver`](crate::SearchObserver).
+    pub fn info(&self) -> NodeInfo {
+        NodeInfo {
+            position: self.position,
+            g: self.g,
+            h: self.h,
+            parent: self.parent,
+        }
+    }
 }
 
 impl PartialOrd for Node {
@@ -49,16 +72,19 @@ impl PartialOrd for Node {
 impl Ord for Node {
     fn cmp(&self, other: &Node) -> Ordering {
         // This is reversed on purpose to make the max-heap into min-heap.
-        other.f.partial_cmp(&self.f).unwrap()
+        // Ties are broken on h and then position so the order never depends on insertion order.
+        other
+            .f
+            .total_cmp(&self.f)
+            .then_with(|| other.h.total_cmp(&self.h))
+            .then_with(|| other.position.cmp(&self.position))
     }
 }
 
 impl PartialEq for Node {
     fn eq(&self, other: &Node) -> bool {
-        self.position == other.position
+        self.cmp(other) == Ordering::Equal
     }
 }
 
-impl Eq for Node {
-    // add code here
-}
+impl Eq for Node {}
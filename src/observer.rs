@@ -0,0 +1,62 @@
+use crate::grid::Coords2D;
+
+///A snapshot of a single node at the moment an event was raised.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct NodeInfo {
+    ///The tile this node represents.
+    pub position: Coords2D,
+    ///The cost of the best known route from the start to this node.
+    pub g: f64,
+    ///The estimated cost from this node to the goal.
+    pub h: f64,
+    ///The tile this node was reached from. Equal to position for the start node.
+    pub parent: Coords2D,
+}
+
+///Something that happened during a search.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum SearchEvent {
+    ///A node was taken from the open list and its successors generated.
+    Expanded(NodeInfo),
+    ///A node was added to the open list. For JPS these are the jump points found by scanning.
+    Discovered(NodeInfo),
+}
+
+///Receives events as a search progresses, for visualisation or teaching tools.
+///Searches are generic over the observer, so the no-op `()` observer used by the plain
+///path functions compiles away entirely.
+///
+///Any `FnMut(SearchEvent)` closure can be used as an observer.
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+/// use blitz_path::{SearchEvent, SearchOptions};
+///
+/// let map = movingai::parser::parse_map_file(Path::new("./tests/map/maze512-32-9.map")).expect("Could not load map.");
+///
+/// let mut expanded = 0;
+/// let mut count = |event: SearchEvent| {
+///     if let SearchEvent::Expanded(_) = event {
+///         expanded += 1;
+///     }
+/// };
+/// blitz_path::jps_path_observed(&map, (1, 1), (5, 5), &SearchOptions::new(), &mut count).unwrap();
+/// assert!(expanded > 0);
+/// ```
+pub trait SearchObserver {
+    ///Called for every event raised by the search.
+    fn observe(&mut self, event: SearchEvent);
+}
+
+impl SearchObserver for () {
+    #[inline(always)]
+    fn observe(&mut self, _event: SearchEvent) {}
+}
+
+impl<F: FnMut(SearchEvent)> SearchObserver for F {
+    #[inline]
+    fn observe(&mut self, event: SearchEvent) {
+        self(event)
+    }
+}
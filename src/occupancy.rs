@@ -0,0 +1,126 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::grid::Coords2D;
+use crate::grid::MovingAiMap;
+
+use crate::memory::vec_size;
+use crate::{MemoryFootprint, Route};
+
+///A position and heading in a metric world frame, with yaw in radians anticlockwise from the x axis.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Pose2D {
+    ///Position along the world frame's x axis, in metres.
+    pub x: f64,
+    ///Position along the world frame's y axis, in metres.
+    pub y: f64,
+    ///Heading in radians, anticlockwise from the world frame's x axis.
+    pub yaw: f64,
+}
+
+///An occupancy grid laid out like the ROS `nav_msgs/OccupancyGrid` message, for use in robot navigation.
+///Cell (0, 0) is the first in `data` and sits at `origin`, with x increasing along each row and y from row to row.
+/// # Examples
+///
+/// ```
+/// use blitz_path::{OccupancyGrid, Pose2D};
+///
+/// let grid = OccupancyGrid {
+///     width: 3,
+///     height: 2,
+///     resolution: 0.5,
+///     origin: Pose2D { x: -1.0, y: -1.0, yaw: 0.0 },
+///     data: vec![0, 100, 0, 0, -1, 0],
+/// };
+/// let map = grid.to_map(65, true);
+///
+/// assert_eq!(grid.world_to_cell(-0.75, -0.25), Some((0, 1)));
+/// assert_eq!(grid.cell_to_world((2, 0)), (0.25, -0.75));
+/// assert!(!blitz_path::is_reachable(&map, (0, 0), (2, 0)));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OccupancyGrid {
+    ///Number of cells along the x axis.
+    pub width: usize,
+    ///Number of cells along the y axis.
+    pub height: usize,
+    ///Size of each cell in metres.
+    pub resolution: f64,
+    ///The world pose of the outer corner of cell (0, 0).
+    pub origin: Pose2D,
+    ///Occupancy probability of each cell in row-major order, from 0 to 100, or -1 if unknown.
+    pub data: Vec<i8>,
+}
+
+impl OccupancyGrid {
+    ///Converts the grid into a map, blocking cells with a probability of at least `threshold`.
+    ///Unknown cells, including any missing from the end of `data`, are blocked if `unknown_blocked` is set.
+    pub fn to_map(&self, threshold: i8, unknown_blocked: bool) -> MovingAiMap {
+        let tiles = (0..self.width * self.height)
+            .map(|index| {
+                let blocked = match self.data.get(index) {
+                    Some(value) if *value >= 0 => *value >= threshold,
+                    _ => unknown_blocked,
+                };
+                if blocked {
+                    '@'
+                } else {
+                    '.'
+                }
+            })
+            .collect();
+        MovingAiMap::new(String::from("octile"), self.height, self.width, tiles)
+    }
+
+    ///Returns the cell containing a world position, or None if it lies outside the grid.
+    pub fn world_to_cell(&self, x: f64, y: f64) -> Option<Coords2D> {
+        let (sin, cos) = libm::sincos(self.origin.yaw);
+        let (dx, dy) = (x - self.origin.x, y - self.origin.y);
+        let column = libm::floor((dx * cos + dy * sin) / self.resolution);
+        let row = libm::floor((dy * cos - dx * sin) / self.resolution);
+        if column < 0.0 || row < 0.0 || column >= self.width as f64 || row >= self.height as f64 {
+            return None;
+        }
+
+        Some((column as usize, row as usize))
+    }
+
+    ///Returns the world position of the centre of a cell.
+    pub fn cell_to_world(&self, cell: Coords2D) -> (f64, f64) {
+        let (sin, cos) = libm::sincos(self.origin.yaw);
+        let local_x = (cell.0 as f64 + 0.5) * self.resolution;
+        let local_y = (cell.1 as f64 + 0.5) * self.resolution;
+        (
+            self.origin.x + local_x * cos - local_y * sin,
+            self.origin.y + local_x * sin + local_y * cos,
+        )
+    }
+
+    ///Converts a route found on this grid's map into poses at the centre of each cell, ordered from start to goal.
+    ///Each pose faces the next one along the route, and the final pose keeps the heading it arrived with.
+    pub fn route_to_poses(&self, route: &Route) -> Vec<Pose2D> {
+        let cells: Vec<Coords2D> = route.steps().iter().rev().copied().collect();
+        let mut poses: Vec<Pose2D> = Vec::with_capacity(cells.len());
+        let mut yaw = self.origin.yaw;
+
+        for (index, cell) in cells.iter().enumerate() {
+            if let Some(next) = cells.get(index + 1) {
+                let dx = next.0 as f64 - cell.0 as f64;
+                let dy = next.1 as f64 - cell.1 as f64;
+                yaw = self.origin.yaw + libm::atan2(dy, dx);
+            }
+            let (x, y) = self.cell_to_world(*cell);
+            poses.push(Pose2D { x, y, yaw });
+        }
+
+        poses
+    }
+}
+
+impl MemoryFootprint for OccupancyGrid {
+    fn heap_size(&self) -> usize {
+        vec_size(&self.data)
+    }
+}
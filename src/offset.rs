@@ -0,0 +1,120 @@
+use core::convert::TryFrom;
+
+use crate::grid::Coords2D;
+use crate::grid::Map2D;
+use crate::grid::MovingAiMap;
+
+use crate::{a_star_path_with, jps_path_with, PathError, Route, SearchOptions};
+
+///Signed world coordinates in the (x, y) format.
+pub type SignedCoords = (i64, i64);
+
+///A map placed in a signed world coordinate space, with its top left tile at `origin`.
+///Lets maps covering negative or offset coordinates, such as chunks of a larger world,
+///be searched directly in world coordinates.
+///
+///Errors report positions in world coordinates, like the routes found.
+///The whole map must fit in world space, so an origin that would put any tile past `i64::MAX` is refused.
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+/// use blitz_path::{OffsetMap, SearchOptions};
+///
+/// let map = movingai::parser::parse_map_file(Path::new("./tests/map/maze512-32-9.map")).expect("Could not load map.");
+///
+/// // Tile (0, 0) of the map sits at (-256, -256) in the world
+/// let world = OffsetMap::new(&map, (-256, -256)).unwrap();
+/// let path = world.jps_path((-255, -255), (-251, -251), &SearchOptions::new()).unwrap();
+///
+/// assert_eq!(path.steps()[0], (-251, -251));
+/// assert_eq!(path.steps().last(), Some(&(-255, -255)));
+/// ```
+#[derive(Debug, Copy, Clone)]
+pub struct OffsetMap<'a> {
+    map: &'a MovingAiMap,
+    origin: SignedCoords,
+}
+
+impl<'a> OffsetMap<'a> {
+    ///Places `map` in world space with its tile (0, 0) at `origin`.
+    ///Returns None if the map's far corner would lie past `i64::MAX` in world coordinates.
+    pub fn new(map: &'a MovingAiMap, origin: SignedCoords) -> Option<OffsetMap<'a>> {
+        let width = i64::try_from(map.width()).ok()?;
+        let height = i64::try_from(map.height()).ok()?;
+        origin.0.checked_add(width)?;
+        origin.1.checked_add(height)?;
+        Some(OffsetMap { map, origin })
+    }
+
+    ///Returns the underlying map.
+    pub fn map(&self) -> &'a MovingAiMap {
+        self.map
+    }
+
+    ///Returns the world position of the map's tile (0, 0).
+    pub fn origin(&self) -> SignedCoords {
+        self.origin
+    }
+
+    ///Converts a world position into map coordinates, or None if it lies outside the map.
+    pub fn to_local(&self, world: SignedCoords) -> Option<Coords2D> {
+        let x = world.0.checked_sub(self.origin.0)?;
+        let y = world.1.checked_sub(self.origin.1)?;
+        if x < 0 || y < 0 {
+            return None;
+        }
+
+        let local = Coords2D::from((x as usize, y as usize));
+        if self.map.is_out_of_bound(local) {
+            None
+        } else {
+            Some(local)
+        }
+    }
+
+    ///Converts map coordinates into a world position.
+    ///`local` must lie within the map, as only its tiles are sure to fit in world space.
+    pub fn to_world(&self, local: Coords2D) -> SignedCoords {
+        (
+            self.origin.0 + local.0 as i64,
+            self.origin.1 + local.1 as i64,
+        )
+    }
+
+    ///Creates a new route between two world positions using the A* algorithm.
+    pub fn a_star_path(
+        &self,
+        start: SignedCoords,
+        goal: SignedCoords,
+        options: &SearchOptions,
+    ) -> Result<Route<SignedCoords>, PathError<SignedCoords>> {
+        let (local_start, local_goal) = self.endpoints(start, goal)?;
+        let route = a_star_path_with(self.map, local_start, local_goal, options)
+            .map_err(|error| error.map_coords(|tile| self.to_world(tile)))?;
+        Ok(route.map_steps(|step| self.to_world(step)))
+    }
+
+    ///Creates a new route between two world positions using the JPS algorithm.
+    pub fn jps_path(
+        &self,
+        start: SignedCoords,
+        goal: SignedCoords,
+        options: &SearchOptions,
+    ) -> Result<Route<SignedCoords>, PathError<SignedCoords>> {
+        let (local_start, local_goal) = self.endpoints(start, goal)?;
+        let route = jps_path_with(self.map, local_start, local_goal, options)
+            .map_err(|error| error.map_coords(|tile| self.to_world(tile)))?;
+        Ok(route.map_steps(|step| self.to_world(step)))
+    }
+
+    fn endpoints(
+        &self,
+        start: SignedCoords,
+        goal: SignedCoords,
+    ) -> Result<(Coords2D, Coords2D), PathError<SignedCoords>> {
+        let local_start = self.to_local(start).ok_or(PathError::OutsideMap(start))?;
+        let local_goal = self.to_local(goal).ok_or(PathError::OutsideMap(goal))?;
+        Ok((local_start, local_goal))
+    }
+}
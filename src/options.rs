@@ -0,0 +1,200 @@
+use alloc::sync::Arc;
+
+use crate::grid::Coords2D;
+use crate::grid::Map2D;
+use crate::grid::MovingAiMap;
+
+use crate::{CancelToken, Components, CongestionMap, CostStack, PruningRule};
+
+//Number of loop iterations between checks of the cancel token
+const CANCEL_POLL_INTERVAL: usize = 64;
+
+//Nodes reserved per tile of the map's width and height when no capacity hint is given,
+//enough for searches across a modest part of the map without reserving room for all of it
+const CAPACITY_PER_SIDE: usize = 2;
+
+///Which tiles of the route are listed in its steps.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RouteSteps {
+    ///Every tile the route passes through.
+    #[default]
+    Cells,
+    ///Only the start, the goal and the tiles where the route changes direction.
+    ///For JPS these are the jump points the route passes through.
+    JumpPoints,
+}
+
+///Optional settings controlling how a search is carried out.
+///The default options reproduce the behaviour of the plain path functions.
+/// # Examples
+///
+/// ```
+/// use blitz_path::SearchOptions;
+///
+/// let options = SearchOptions::new().snap_radius(3);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct SearchOptions {
+    pub(crate) snap_radius: Option<usize>,
+    pub(crate) max_distance: Option<f64>,
+    pub(crate) cancel_token: Option<CancelToken>,
+    pub(crate) pruning: Option<Arc<dyn PruningRule>>,
+    pub(crate) exclude_start: bool,
+    pub(crate) route_steps: RouteSteps,
+    pub(crate) repeat_goal: bool,
+    pub(crate) components: Option<Arc<Components>>,
+    pub(crate) time_horizon: Option<usize>,
+    pub(crate) congestion: Option<Arc<CongestionMap>>,
+    pub(crate) cost_stack: Option<Arc<CostStack<'static>>>,
+    pub(crate) avoid_swamps: bool,
+    pub(crate) capacity_hint: Option<usize>,
+    pub(crate) measure_time: bool,
+}
+
+impl SearchOptions {
+    ///Creates a new set of options with every setting at its default.
+    pub fn new() -> SearchOptions {
+        SearchOptions::default()
+    }
+
+    ///Moves a blocked or out of bounds start or goal to the nearest traversable tile
+    ///no further than `radius` tiles away before searching.
+    pub fn snap_radius(mut self, radius: usize) -> SearchOptions {
+        self.snap_radius = Some(radius);
+        self
+    }
+
+    ///Fails the search if the shortest route is longer than `distance`.
+    ///Nodes that cannot lead to a short enough route are pruned, so hopeless searches finish early.
+    pub fn max_distance(mut self, distance: f64) -> SearchOptions {
+        self.max_distance = Some(distance);
+        self
+    }
+
+    ///Polls `token` while searching, stopping with `PathError::Cancelled` once it is cancelled.
+    pub fn cancel_token(mut self, token: CancelToken) -> SearchOptions {
+        self.cancel_token = Some(token);
+        self
+    }
+
+    ///Replaces the forced neighbour rules used by JPS. Has no effect on other algorithms.
+    pub fn jps_pruning<P: PruningRule + 'static>(mut self, rule: P) -> SearchOptions {
+        self.pruning = Some(Arc::new(rule));
+        self
+    }
+
+    ///Sets whether the start tile is listed as the final step of the route. Defaults to true.
+    pub fn include_start(mut self, include: bool) -> SearchOptions {
+        self.exclude_start = !include;
+        self
+    }
+
+    ///Sets whether every tile or only the turning points of the route are listed. Defaults to every tile.
+    pub fn route_steps(mut self, steps: RouteSteps) -> SearchOptions {
+        self.route_steps = steps;
+        self
+    }
+
+    ///Sets whether the goal is listed twice at the front of the route, for consumers that
+    ///expect an explicit arrival step. Defaults to false.
+    pub fn repeat_goal(mut self, repeat: bool) -> SearchOptions {
+        self.repeat_goal = repeat;
+        self
+    }
+
+    ///Checks `components` before searching and fails immediately with `PathError::NoPath` if the start
+    ///and goal are in different regions, rather than exploring the whole of the start's region.
+    ///Tiles in the same region are still searched, as one-way moves may leave no route between them.
+    ///The components must have been built from the map being searched.
+    pub fn components(mut self, components: Arc<Components>) -> SearchOptions {
+        self.components = Some(components);
+        self
+    }
+
+    ///Limits space-time searches to this many timesteps after their start time, failing with
+    ///`PathError::NoPath` if the goal cannot be reached and held by then.
+    ///Defaults to four times the width plus height of the map, counted from the last reservation
+    ///the search has to wait for.
+    pub fn time_horizon(mut self, timesteps: usize) -> SearchOptions {
+        self.time_horizon = Some(timesteps);
+        self
+    }
+
+    ///Makes A* and Dijkstra searches avoid busy tiles, with each step costing its length
+    ///times one plus the congestion of the tile entered. Routes report this higher cost as their distance.
+    ///The congestion map must have been built for the map being searched.
+    pub fn congestion(mut self, congestion: Arc<CongestionMap>) -> SearchOptions {
+        self.congestion = Some(congestion);
+        self
+    }
+
+    ///Makes A* and Dijkstra searches add the [`penalty`](CostStack::penalty) of `stack` to the cost of every step,
+    ///and skip the steps it forbids. Routes report this higher cost as their distance.
+    ///The stack is shared between searches, so change its layers before wrapping it, or through [`Arc::get_mut`].
+    pub fn cost_stack(mut self, stack: Arc<CostStack<'static>>) -> SearchOptions {
+        self.cost_stack = Some(stack);
+        self
+    }
+
+    ///Makes A* and Dijkstra searches follow the MovingAI benchmark convention for swamp tiles (`'S'`),
+    ///which routes may cross but only use when no route keeps out of them, so results can be compared
+    ///with the optimal lengths of benchmark sets that contain swamps. The start and goal may be swamps.
+    ///If every route crosses a swamp the search runs again through them, taking up to twice as long. Defaults to false.
+    pub fn avoid_swamps(mut self, avoid: bool) -> SearchOptions {
+        self.avoid_swamps = avoid;
+        self
+    }
+
+    ///Sets whether A* and JPS searches time themselves, recording the time taken in
+    ///[`SearchStats::elapsed`](crate::SearchStats::elapsed). Reading the clock is left out unless asked for,
+    ///so `elapsed` stays zero by default. Defaults to false.
+    pub fn measure_time(mut self, measure: bool) -> SearchOptions {
+        self.measure_time = measure;
+        self
+    }
+
+    ///Reserves room for `nodes` nodes in the open and closed lists before searching, so searches expanding
+    ///up to that many nodes never reallocate them. Contexts keep their room between searches, so a
+    ///[`SearchContext`](crate::SearchContext) only pays for the first search needing it.
+    ///Defaults to twice the sum of the width and height of the map, or its area if that is smaller.
+    pub fn capacity_hint(mut self, nodes: usize) -> SearchOptions {
+        self.capacity_hint = Some(nodes);
+        self
+    }
+
+    ///Returns the cost of a `step` from `from` into `tile` after adding any congestion and cost layers,
+    ///or `None` if a layer forbids it. Custom searches should pass every step cost through this.
+    pub fn step_cost(&self, from: Coords2D, tile: Coords2D, step: f64) -> Option<f64> {
+        let step = match &self.congestion {
+            Some(congestion) => step * (1.0 + congestion.get(tile)),
+            None => step,
+        };
+        match &self.cost_stack {
+            Some(stack) => Some(step + stack.penalty(from, tile)?),
+            None => Some(step),
+        }
+    }
+
+    ///Returns true if a search on its `iteration`th expansion should stop because its cancel token was cancelled.
+    ///The token is only checked every few iterations, so this is cheap to call on every expansion.
+    pub fn should_cancel(&self, iteration: usize) -> bool {
+        iteration.is_multiple_of(CANCEL_POLL_INTERVAL)
+            && self
+                .cancel_token
+                .as_ref()
+                .is_some_and(|token| token.is_cancelled())
+    }
+
+    ///Returns true if a node whose estimated total cost is `f` can be discarded for exceeding the maximum distance.
+    pub fn exceeds_max_distance(&self, f: f64) -> bool {
+        self.max_distance.is_some_and(|max| f > max)
+    }
+
+    //Number of nodes to reserve room for before searching map
+    pub(crate) fn capacity(&self, map: &MovingAiMap) -> usize {
+        let (width, height) = (map.width(), map.height());
+        self.capacity_hint
+            .unwrap_or((CAPACITY_PER_SIDE * (width + height)).min(width * height))
+    }
+}
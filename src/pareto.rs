@@ -0,0 +1,241 @@
+use alloc::collections::BinaryHeap;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+
+use crate::grid::Coords2D;
+use crate::grid::Map2D;
+use crate::grid::MovingAiMap;
+
+use crate::utils::{compose, distance, index, neighbours, prepare};
+use crate::{PathError, Route, SearchOptions};
+
+//A way of reaching a tile, with the total of each objective on arrival
+#[derive(Debug, Copy, Clone)]
+struct Label {
+    position: Coords2D,
+    distance: f64,
+    second: f64,
+    parent: Option<usize>,
+}
+
+//An entry in the open list, ordered on estimated distance and then the second objective
+#[derive(Debug, Copy, Clone)]
+struct Entry {
+    f: f64,
+    second: f64,
+    position: Coords2D,
+    label: usize,
+}
+
+impl PartialOrd for Entry {
+    fn partial_cmp(&self, other: &Entry) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Entry {
+    fn cmp(&self, other: &Entry) -> Ordering {
+        //Reversed to make the max-heap a min-heap
+        other
+            .f
+            .total_cmp(&self.f)
+            .then_with(|| other.second.total_cmp(&self.second))
+            .then_with(|| other.position.cmp(&self.position))
+            .then_with(|| other.label.cmp(&self.label))
+    }
+}
+
+impl PartialEq for Entry {
+    fn eq(&self, other: &Entry) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Entry {}
+
+///The routes found by [`pareto_paths`], none of which is both longer and worse on the second objective
+///than another. Ordered from the shortest route to the one scoring best on the second objective.
+pub struct ParetoFrontier {
+    routes: Vec<(Route, f64)>,
+}
+
+impl ParetoFrontier {
+    ///Returns every route with its total on the second objective, shortest first.
+    pub fn routes(&self) -> &[(Route, f64)] {
+        &self.routes
+    }
+
+    ///Returns the number of routes on the frontier.
+    pub fn len(&self) -> usize {
+        self.routes.len()
+    }
+
+    ///Returns true if the frontier holds no routes, which never happens for a frontier returned by a search.
+    pub fn is_empty(&self) -> bool {
+        self.routes.is_empty()
+    }
+
+    ///Returns the shortest route, breaking ties on the second objective.
+    pub fn shortest(&self) -> Option<&(Route, f64)> {
+        self.routes.first()
+    }
+
+    ///Returns the route scoring best on the second objective, breaking ties on distance.
+    pub fn best_second(&self) -> Option<&(Route, f64)> {
+        self.routes.last()
+    }
+
+    ///Returns the route with the lowest distance plus `weight` times its second objective.
+    ///Ties go to the shorter route.
+    pub fn weighted(&self, weight: f64) -> Option<&(Route, f64)> {
+        self.routes.iter().min_by(|a, b| {
+            (a.0.distance() + weight * a.1).total_cmp(&(b.0.distance() + weight * b.1))
+        })
+    }
+}
+
+///Finds every route from `start` to `goal` that is not beaten on both distance and a second objective,
+///such as danger, by another route, so the trade off can be chosen after searching rather than guessed up front.
+///`second` gives the second objective of each step, must never be negative, and returns None if the move is not allowed.
+///Routes with equal totals on both objectives are only returned once.
+///
+///The search keeps every way of reaching a tile that is not beaten on both objectives, so it explores
+///far more than a single objective search, growing with the size of the frontier.
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+/// use blitz_path::SearchOptions;
+///
+/// let map = movingai::parser::parse_map_file(Path::new("./tests/map/maze512-32-9.map")).expect("Could not load map.");
+///
+/// // Row 1 is dangerous, and the further south the safer
+/// let danger = |_from: (usize, usize), to: (usize, usize)| Some(if to.1 == 1 { 5.0 } else { 0.0 });
+/// let frontier = blitz_path::pareto_paths(&map, (1, 1), (6, 1), danger, &SearchOptions::new()).unwrap();
+///
+/// let (shortest, risk) = frontier.shortest().unwrap();
+/// assert_eq!((shortest.distance(), *risk), (5.0, 25.0));
+/// let (safest, risk) = frontier.best_second().unwrap();
+/// assert_eq!(*risk, 5.0);
+/// assert!(safest.distance() > 5.0);
+/// ```
+pub fn pareto_paths<C>(
+    map: &MovingAiMap,
+    start: Coords2D,
+    goal: Coords2D,
+    second: C,
+    options: &SearchOptions,
+) -> Result<ParetoFrontier, PathError>
+where
+    C: Fn(Coords2D, Coords2D) -> Option<f64>,
+{
+    let (start, goal) = prepare(map, start, goal, options)?;
+    if options.exceeds_max_distance(distance(start, goal)) {
+        return Err(PathError::ExceedsMaxDistance);
+    }
+
+    let mut labels = vec![Label {
+        position: start,
+        distance: 0.0,
+        second: 0.0,
+        parent: None,
+    }];
+    //Labels already expanded at each tile, and those that reached the goal
+    let mut settled: Vec<Vec<usize>> = vec![Vec::new(); map.width() * map.height()];
+    let mut solutions: Vec<usize> = Vec::new();
+    //Whether some label in the list is at least as good on both objectives
+    let dominated = |labels: &[Label], others: &[usize], distance: f64, second: f64| {
+        others
+            .iter()
+            .any(|other| labels[*other].distance <= distance && labels[*other].second <= second)
+    };
+
+    let mut open = BinaryHeap::new();
+    open.push(Entry {
+        f: distance(start, goal),
+        second: 0.0,
+        position: start,
+        label: 0,
+    });
+
+    let mut pruned = false;
+    let mut iteration = 0;
+    while let Some(entry) = open.pop() {
+        let label = labels[entry.label];
+        let tile_index = index(map, label.position);
+        if dominated(&labels, &settled[tile_index], label.distance, label.second)
+            || dominated(&labels, &solutions, entry.f, label.second)
+        {
+            continue;
+        }
+        if options.should_cancel(iteration) {
+            return Err(PathError::Cancelled);
+        }
+        iteration += 1;
+        settled[tile_index].push(entry.label);
+
+        if label.position == goal {
+            solutions.push(entry.label);
+            continue;
+        }
+
+        for next in neighbours(map, label.position) {
+            let step = match second(label.position, next) {
+                Some(step) => step,
+                None => continue,
+            };
+            let travelled = label.distance + distance(label.position, next);
+            let total = label.second + step;
+            let f = travelled + distance(next, goal);
+            if options.exceeds_max_distance(f) {
+                pruned = true;
+                continue;
+            }
+            if dominated(&labels, &settled[index(map, next)], travelled, total)
+                || dominated(&labels, &solutions, f, total)
+            {
+                continue;
+            }
+
+            labels.push(Label {
+                position: next,
+                distance: travelled,
+                second: total,
+                parent: Some(entry.label),
+            });
+            open.push(Entry {
+                f,
+                second: total,
+                position: next,
+                label: labels.len() - 1,
+            });
+        }
+    }
+
+    if solutions.is_empty() {
+        return Err(if pruned {
+            PathError::ExceedsMaxDistance
+        } else {
+            PathError::NoPath
+        });
+    }
+
+    let routes = solutions
+        .into_iter()
+        .map(|solution| {
+            let label = labels[solution];
+            let mut path = vec![label.position];
+            let mut parent = label.parent;
+            while let Some(current) = parent {
+                path.push(labels[current].position);
+                parent = labels[current].parent;
+            }
+            (
+                Route::from((label.distance, compose(path, options))),
+                label.second,
+            )
+        })
+        .collect();
+    Ok(ParetoFrontier { routes })
+}
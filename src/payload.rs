@@ -0,0 +1,124 @@
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::vec::Vec;
+use core::iter::FromIterator;
+
+use crate::grid::Coords2D;
+
+use crate::memory::map_size;
+use crate::{MemoryFootprint, Route};
+
+///User data attached to tiles, such as pickups, triggers or spawn points, for asking what a route passes over
+///with [`Route::collect_payloads`]. Each tile holds at most one payload; use a `Vec` as the payload to keep
+///several on a tile. Only tiles holding a payload take up memory, so a sparse layer over a large map is cheap.
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+/// use blitz_path::PayloadLayer;
+///
+/// let map = movingai::parser::parse_map_file(Path::new("./tests/map/maze512-32-9.map")).expect("Could not load map.");
+///
+/// let mut pickups = PayloadLayer::new();
+/// pickups.insert((3, 1), "medkit");
+/// pickups.insert((1, 3), "ammo");
+///
+/// let path = blitz_path::a_star_path(&map, (1, 1), (5, 1)).unwrap();
+/// assert_eq!(path.collect_payloads(&pickups), [((3, 1), &"medkit")]);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PayloadLayer<T> {
+    payloads: BTreeMap<Coords2D, T>,
+}
+
+impl<T> PayloadLayer<T> {
+    ///Creates a layer with no payloads.
+    pub fn new() -> PayloadLayer<T> {
+        PayloadLayer {
+            payloads: BTreeMap::new(),
+        }
+    }
+
+    ///Attaches `payload` to `tile`, returning the payload it replaces if there was one.
+    pub fn insert(&mut self, tile: Coords2D, payload: T) -> Option<T> {
+        self.payloads.insert(tile, payload)
+    }
+
+    ///Removes the payload of `tile`, returning it if there was one.
+    pub fn remove(&mut self, tile: Coords2D) -> Option<T> {
+        self.payloads.remove(&tile)
+    }
+
+    ///Returns the payload of `tile`, if any.
+    pub fn get(&self, tile: Coords2D) -> Option<&T> {
+        self.payloads.get(&tile)
+    }
+
+    ///Returns the payload of `tile` for changing in place, if any.
+    pub fn get_mut(&mut self, tile: Coords2D) -> Option<&mut T> {
+        self.payloads.get_mut(&tile)
+    }
+
+    ///Returns every tile holding a payload and its payload, in order of their coordinates.
+    pub fn iter(&self) -> impl Iterator<Item = (Coords2D, &T)> {
+        self.payloads.iter().map(|(tile, payload)| (*tile, payload))
+    }
+
+    ///Returns the number of tiles holding a payload.
+    pub fn len(&self) -> usize {
+        self.payloads.len()
+    }
+
+    ///Returns true if no tile holds a payload.
+    pub fn is_empty(&self) -> bool {
+        self.payloads.is_empty()
+    }
+
+    ///Removes every payload.
+    pub fn clear(&mut self) {
+        self.payloads.clear();
+    }
+}
+
+impl<T> Default for PayloadLayer<T> {
+    fn default() -> PayloadLayer<T> {
+        PayloadLayer::new()
+    }
+}
+
+impl<T> FromIterator<(Coords2D, T)> for PayloadLayer<T> {
+    fn from_iter<I: IntoIterator<Item = (Coords2D, T)>>(payloads: I) -> PayloadLayer<T> {
+        PayloadLayer {
+            payloads: payloads.into_iter().collect(),
+        }
+    }
+}
+
+impl Route {
+    ///Returns the payload of every tile the route passes over that holds one in `layer`, with its tile,
+    ///in the order they are reached from the start. Tiles between distant steps count as passed over,
+    ///as for a route built with [`RouteSteps::Cells`](crate::RouteSteps::Cells), and a tile passed over
+    ///more than once is listed only the first time.
+    pub fn collect_payloads<'a, T>(&self, layer: &'a PayloadLayer<T>) -> Vec<(Coords2D, &'a T)> {
+        let mut collected = Vec::new();
+        if layer.is_empty() {
+            return collected;
+        }
+        let mut seen = BTreeSet::new();
+        for tile in self.cells() {
+            if let Some(payload) = layer.get(tile) {
+                if seen.insert(tile) {
+                    collected.push((tile, payload));
+                }
+            }
+        }
+        collected
+    }
+}
+
+//Payloads are counted by their own size, ignoring any buffers they own
+impl<T> MemoryFootprint for PayloadLayer<T> {
+    fn heap_size(&self) -> usize {
+        map_size(&self.payloads)
+    }
+}
@@ -0,0 +1,48 @@
+//!Building blocks the searches in this crate are made from, for implementing other search variants on top of them
+//!without copying their internals. Custom searches that call [`prepare`], pass step costs through
+//![`SearchOptions::step_cost`](crate::SearchOptions::step_cost), check
+//![`SearchOptions::should_cancel`](crate::SearchOptions::should_cancel) and
+//![`SearchOptions::exceeds_max_distance`](crate::SearchOptions::exceeds_max_distance), and finish with [`compose`]
+//!honour the same [`SearchOptions`](crate::SearchOptions) as the built in ones.
+//! # Examples
+//!
+//! A plain A* search, finding a route as short as [`a_star_path`](crate::a_star_path):
+//!
+//! ```
+//! use std::collections::BinaryHeap;
+//! use std::path::Path;
+//! use blitz_path::primitives::{compose, distance, neighbours, prepare, rewind, Node};
+//! use blitz_path::{PathError, Route, SearchOptions};
+//!
+//! let map = movingai::parser::parse_map_file(Path::new("./tests/map/maze512-32-9.map")).expect("Could not load map.");
+//! let options = SearchOptions::new();
+//!
+//! let search = |start, goal| -> Result<Route, PathError> {
+//!     let (start, goal) = prepare(&map, start, goal, &options)?;
+//!     let mut open = BinaryHeap::from([Node::new(0.0, distance(start, goal), start, start)]);
+//!     let mut closed: Vec<Node> = Vec::new();
+//!     while let Some(node) = open.pop() {
+//!         if closed.iter().any(|n| n.position == node.position) {
+//!             continue;
+//!         }
+//!         if node.position == goal {
+//!             let path = rewind(&node, |parent| closed.iter().find(|n| n.position == parent).copied()).unwrap();
+//!             return Ok(Route::from((node.g, compose(path, &options))));
+//!         }
+//!         for next in neighbours(&map, node.position) {
+//!             open.push(Node::from_parent(&node, next, goal));
+//!         }
+//!         closed.push(node);
+//!     }
+//!     Err(PathError::NoPath)
+//! };
+//!
+//! let route = search((1, 1), (9, 3)).unwrap();
+//! let expected = blitz_path::a_star_path(&map, (1, 1), (9, 3)).unwrap();
+//! assert_eq!(route.distance(), expected.distance());
+//! ```
+
+pub use crate::node::Node;
+pub use crate::utils::{
+    compose, direction, distance, index, neighbours, offset, prepare, rewind, validate,
+};
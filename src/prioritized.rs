@@ -0,0 +1,110 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::grid::Coords2D;
+use crate::grid::MovingAiMap;
+
+use crate::memory::vec_size;
+use crate::{
+    space_time_a_star_reserved, MemoryFootprint, PathError, ReservationTable, SearchOptions,
+    TimedRoute,
+};
+
+///Routes for a group of agents found by [`prioritized_planning`], free of collisions with each other.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PrioritizedPlan {
+    ///The route of each agent, in the order the agents were given. All start at time zero.
+    pub routes: Vec<TimedRoute>,
+    ///The order the agents were finally planned in, highest priority first.
+    pub order: Vec<usize>,
+    ///How many times planning started over with a new order after an agent found no route.
+    pub restarts: usize,
+}
+
+///Plans routes for several agents one at a time, each treating the routes of the agents planned
+///before it as moving obstacles. Far cheaper than searching for every agent at once, so it scales
+///to large groups, though it can fail where a joint search would succeed.
+///
+///Each agent is a start and goal, and all set off at time zero. Agents are planned in `order`,
+///a list of their indices, followed by any agents it leaves out in index order.
+///When an agent finds no route it is moved to the front of the order and planning starts over,
+///up to `max_restarts` times, after which its error is returned. Cancellation is returned at once.
+///
+///Each search is a [`space_time_a_star_reserved`] limited by the
+///[`time_horizon`](SearchOptions::time_horizon) of `options`.
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+/// use blitz_path::SearchOptions;
+///
+/// let map = movingai::parser::parse_map_file(Path::new("./tests/map/maze512-32-9.map")).expect("Could not load map.");
+///
+/// let agents = [((1, 1), (5, 1)), ((5, 1), (1, 1))];
+/// let plan = blitz_path::prioritized_planning(&map, &agents, &[], 4, &SearchOptions::new()).unwrap();
+///
+/// for time in 0..20 {
+///     assert_ne!(plan.routes[0].position_at(time), plan.routes[1].position_at(time));
+/// }
+/// ```
+pub fn prioritized_planning(
+    map: &MovingAiMap,
+    agents: &[(Coords2D, Coords2D)],
+    order: &[usize],
+    max_restarts: usize,
+    options: &SearchOptions,
+) -> Result<PrioritizedPlan, PathError> {
+    //Complete the order, ignoring unknown and repeated agents
+    let mut priorities: Vec<usize> = Vec::with_capacity(agents.len());
+    for agent in order.iter().copied().chain(0..agents.len()) {
+        if agent < agents.len() && !priorities.contains(&agent) {
+            priorities.push(agent);
+        }
+    }
+
+    let mut restarts = 0;
+    loop {
+        match plan_in_order(map, agents, &priorities, options) {
+            Ok(routes) => {
+                return Ok(PrioritizedPlan {
+                    routes,
+                    order: priorities,
+                    restarts,
+                })
+            }
+            Err((_, PathError::Cancelled)) => return Err(PathError::Cancelled),
+            Err((_, error)) if restarts == max_restarts => return Err(error),
+            Err((failed, _)) => {
+                priorities.retain(|agent| *agent != failed);
+                priorities.insert(0, failed);
+                restarts += 1;
+            }
+        }
+    }
+}
+
+//Plans every agent in turn, returning the first agent to fail and why
+fn plan_in_order(
+    map: &MovingAiMap,
+    agents: &[(Coords2D, Coords2D)],
+    priorities: &[usize],
+    options: &SearchOptions,
+) -> Result<Vec<TimedRoute>, (usize, PathError)> {
+    let mut table = ReservationTable::new();
+    let mut routes: Vec<Option<TimedRoute>> = vec![None; agents.len()];
+    for agent in priorities {
+        let (start, goal) = agents[*agent];
+        let route = space_time_a_star_reserved(map, start, goal, 0, &table, options)
+            .map_err(|error| (*agent, error))?;
+        table.reserve_route(*agent, &route);
+        routes[*agent] = Some(route);
+    }
+    Ok(routes.into_iter().flatten().collect())
+}
+
+impl MemoryFootprint for PrioritizedPlan {
+    fn heap_size(&self) -> usize {
+        self.routes.heap_size() + vec_size(&self.order)
+    }
+}
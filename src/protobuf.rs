@@ -0,0 +1,196 @@
+//Compact binary encoding of routes as protocol buffers, following proto/route.proto
+
+use prost::Message;
+
+use crate::{PathError, Route, RouteDecodeError};
+
+#[derive(Clone, PartialEq, Message)]
+struct RouteMessage {
+    #[prost(double, tag = "1")]
+    distance: f64,
+    #[prost(sint64, repeated, tag = "2")]
+    steps: Vec<i64>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct PathErrorMessage {
+    #[prost(int32, tag = "1")]
+    kind: i32,
+    #[prost(sint64, tag = "2")]
+    x: i64,
+    #[prost(sint64, tag = "3")]
+    y: i64,
+}
+
+#[derive(Clone, PartialEq, prost::Oneof)]
+enum Outcome {
+    #[prost(message, tag = "2")]
+    Route(RouteMessage),
+    #[prost(message, tag = "3")]
+    Error(PathErrorMessage),
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct RouteResultMessage {
+    #[prost(uint64, tag = "1")]
+    id: u64,
+    #[prost(oneof = "Outcome", tags = "2, 3")]
+    outcome: Option<Outcome>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct RouteBatchMessage {
+    #[prost(message, repeated, tag = "1")]
+    results: Vec<RouteResultMessage>,
+}
+
+impl From<&Route> for RouteMessage {
+    //Adjacent steps differ by at most one tile, so their offsets encode to a single byte each
+    fn from(route: &Route) -> RouteMessage {
+        let mut steps = Vec::with_capacity(route.steps().len() * 2);
+        let mut previous = (0, 0);
+        for (x, y) in route.steps() {
+            let (x, y) = (x as i64, y as i64);
+            steps.push(x - previous.0);
+            steps.push(y - previous.1);
+            previous = (x, y);
+        }
+
+        RouteMessage {
+            distance: route.distance(),
+            steps,
+        }
+    }
+}
+
+impl RouteMessage {
+    fn into_route(self) -> Result<Route, RouteDecodeError> {
+        if !self.steps.len().is_multiple_of(2) {
+            return Err(RouteDecodeError::Invalid(
+                "route has an odd number of coordinates",
+            ));
+        }
+
+        let mut steps = Vec::with_capacity(self.steps.len() / 2);
+        let mut current = (0i64, 0i64);
+        for offset in self.steps.chunks(2) {
+            current = (
+                current.0.wrapping_add(offset[0]),
+                current.1.wrapping_add(offset[1]),
+            );
+            if current.0 < 0 || current.1 < 0 {
+                return Err(RouteDecodeError::Invalid(
+                    "route step has negative coordinates",
+                ));
+            }
+            steps.push((current.0 as usize, current.1 as usize));
+        }
+
+        Ok(Route::from((self.distance, steps)))
+    }
+}
+
+impl From<&PathError> for PathErrorMessage {
+    fn from(error: &PathError) -> PathErrorMessage {
+        let (kind, (x, y)) = match *error {
+            PathError::StartOutOfBounds((x, y)) => (0, (x as i64, y as i64)),
+            PathError::GoalOutOfBounds((x, y)) => (1, (x as i64, y as i64)),
+            PathError::OutsideMap(position) => (2, position),
+            PathError::StartBlocked((x, y)) => (3, (x as i64, y as i64)),
+            PathError::GoalBlocked((x, y)) => (4, (x as i64, y as i64)),
+            PathError::NoPath => (5, (0, 0)),
+            PathError::ExceedsMaxDistance => (6, (0, 0)),
+            PathError::Cancelled => (7, (0, 0)),
+        };
+        PathErrorMessage { kind, x, y }
+    }
+}
+
+impl PathErrorMessage {
+    fn into_error(self) -> Result<PathError, RouteDecodeError> {
+        let position = || {
+            if self.x < 0 || self.y < 0 {
+                Err(RouteDecodeError::Invalid(
+                    "error position has negative coordinates",
+                ))
+            } else {
+                Ok((self.x as usize, self.y as usize))
+            }
+        };
+        Ok(match self.kind {
+            0 => PathError::StartOutOfBounds(position()?),
+            1 => PathError::GoalOutOfBounds(position()?),
+            2 => PathError::OutsideMap((self.x, self.y)),
+            3 => PathError::StartBlocked(position()?),
+            4 => PathError::GoalBlocked(position()?),
+            5 => PathError::NoPath,
+            6 => PathError::ExceedsMaxDistance,
+            7 => PathError::Cancelled,
+            _ => return Err(RouteDecodeError::Invalid("unknown path error kind")),
+        })
+    }
+}
+
+impl Route {
+    ///Encodes the route as a protocol buffer `Route` message, as described by `proto/route.proto`.
+    ///Steps are stored as offsets from one to the next, taking around two bytes per step.
+    /// # Examples
+    ///
+    /// ```
+    /// use blitz_path::Route;
+    ///
+    /// let route: Route = Route::from((2.0, vec![(3, 1), (2, 1), (1, 1)]));
+    /// let decoded = Route::from_protobuf(&route.to_protobuf()).unwrap();
+    ///
+    /// assert_eq!(decoded.steps(), route.steps());
+    /// ```
+    pub fn to_protobuf(&self) -> Vec<u8> {
+        RouteMessage::from(self).encode_to_vec()
+    }
+
+    ///Decodes a route from a protocol buffer `Route` message.
+    pub fn from_protobuf(bytes: &[u8]) -> Result<Route, RouteDecodeError> {
+        RouteMessage::decode(bytes).map_err(malformed)?.into_route()
+    }
+}
+
+///The outcome of one search in a batch, tagged with an id such as the entity or request it belongs to.
+pub type BatchEntry = (u64, Result<Route, PathError>);
+
+///Encodes the outcomes of many searches as a single protocol buffer `RouteBatch` message.
+pub fn encode_route_batch(results: &[BatchEntry]) -> Vec<u8> {
+    RouteBatchMessage {
+        results: results
+            .iter()
+            .map(|(id, result)| RouteResultMessage {
+                id: *id,
+                outcome: Some(match result {
+                    Ok(route) => Outcome::Route(route.into()),
+                    Err(error) => Outcome::Error(error.into()),
+                }),
+            })
+            .collect(),
+    }
+    .encode_to_vec()
+}
+
+///Decodes the outcomes of many searches from a protocol buffer `RouteBatch` message.
+pub fn decode_route_batch(bytes: &[u8]) -> Result<Vec<BatchEntry>, RouteDecodeError> {
+    RouteBatchMessage::decode(bytes)
+        .map_err(malformed)?
+        .results
+        .into_iter()
+        .map(|result| {
+            let outcome = match result.outcome {
+                Some(Outcome::Route(route)) => Ok(route.into_route()?),
+                Some(Outcome::Error(error)) => Err(error.into_error()?),
+                None => return Err(RouteDecodeError::Invalid("route result has no outcome")),
+            };
+            Ok((result.id, outcome))
+        })
+        .collect()
+}
+
+fn malformed(error: prost::DecodeError) -> RouteDecodeError {
+    RouteDecodeError::Malformed(error.to_string())
+}
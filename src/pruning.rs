@@ -0,0 +1,105 @@
+use alloc::vec::Vec;
+use core::fmt::Debug;
+
+use crate::grid::Coords2D;
+use crate::grid::Map2D;
+use crate::grid::MovingAiMap;
+
+use crate::utils::offset;
+
+///Decides which forced neighbours JPS adds while scanning in a straight line.
+///Implement this to change the pruning policy used by [`jps_path_with`](crate::jps_path_with),
+///for example to scan fewer tiles at the cost of optimality, and pass it in through
+///[`SearchOptions::jps_pruning`](crate::SearchOptions::jps_pruning).
+///
+///Each method pushes the tiles that must be examined as jump points onto `forced`. Tiles that can't be
+///stepped onto from `position` under the map's movement rules are skipped by the search.
+pub trait PruningRule: Debug + Send + Sync {
+    ///Finds the forced neighbours of `position` while scanning horizontally,
+    ///where `direction` is -1 for left and 1 for right.
+    fn forced_horizontal(
+        &self,
+        map: &MovingAiMap,
+        position: Coords2D,
+        direction: i32,
+        forced: &mut Vec<Coords2D>,
+    );
+
+    ///Finds the forced neighbours of `position` while scanning vertically,
+    ///where `direction` is -1 for up and 1 for down.
+    fn forced_vertical(
+        &self,
+        map: &MovingAiMap,
+        position: Coords2D,
+        direction: i32,
+        forced: &mut Vec<Coords2D>,
+    );
+}
+
+///The standard JPS pruning rules, used unless another rule is configured.
+///Diagonal steps may not cut corners, so a neighbour beside the scan is forced when the tile behind it is blocked,
+///along with the tile diagonally ahead of it, as neither can then be reached as cheaply without passing here.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct DefaultPruning;
+
+impl PruningRule for DefaultPruning {
+    fn forced_horizontal(
+        &self,
+        map: &MovingAiMap,
+        position: Coords2D,
+        direction: i32,
+        forced: &mut Vec<Coords2D>,
+    ) {
+        //Check if blocked up, then down
+        for side in [-1, 1] {
+            force(
+                map,
+                position,
+                (-direction, side),
+                (0, side),
+                (direction, side),
+                forced,
+            );
+        }
+    }
+
+    fn forced_vertical(
+        &self,
+        map: &MovingAiMap,
+        position: Coords2D,
+        direction: i32,
+        forced: &mut Vec<Coords2D>,
+    ) {
+        //Check if blocked left, then right
+        for side in [-1, 1] {
+            force(
+                map,
+                position,
+                (side, -direction),
+                (side, 0),
+                (side, direction),
+                forced,
+            );
+        }
+    }
+}
+
+//Force the open tile beside the scan and the open tile diagonally ahead of it if the tile behind is blocked.
+//Tiles off the map count as blocked, so scans along the edges never step outside it.
+fn force(
+    map: &MovingAiMap,
+    position: Coords2D,
+    behind: (i32, i32),
+    beside: (i32, i32),
+    ahead: (i32, i32),
+    forced: &mut Vec<Coords2D>,
+) {
+    let open =
+        |(dx, dy): (i32, i32)| offset(position, dx, dy).filter(|tile| map.is_traversable(*tile));
+    if open(behind).is_none() {
+        if let Some(beside) = open(beside) {
+            forced.push(beside);
+            forced.extend(open(ahead));
+        }
+    }
+}
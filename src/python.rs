@@ -0,0 +1,131 @@
+//Bindings for using the crate from Python through pyo3
+
+use std::path::Path;
+
+use crate::grid::{Coords2D, Map2D, MovingAiMap};
+use movingai::parser::{parse_map_file, parse_scen_file};
+use pyo3::create_exception;
+use pyo3::exceptions::{PyException, PyIOError, PyValueError};
+use pyo3::prelude::*;
+
+use crate::{PathError, Route};
+
+create_exception!(
+    blitz_path,
+    PyPathError,
+    PyException,
+    "Raised when a route cannot be found."
+);
+
+///A map that can be searched from Python.
+#[pyclass(name = "Map", frozen)]
+pub struct PyMap {
+    map: MovingAiMap,
+}
+
+#[pymethods]
+impl PyMap {
+    ///Loads a map from a MovingAI .map file.
+    #[staticmethod]
+    fn load(path: &str) -> PyResult<PyMap> {
+        let map = parse_map_file(Path::new(path))
+            .map_err(|error| PyIOError::new_err(error.to_string()))?;
+        Ok(PyMap { map })
+    }
+
+    ///Creates a map from rows of MovingAI tile characters, such as "." for open ground and "@" for walls.
+    #[new]
+    fn new(rows: Vec<String>) -> PyResult<PyMap> {
+        let width = rows.first().map_or(0, |row| row.chars().count());
+        if rows.iter().any(|row| row.chars().count() != width) {
+            return Err(PyValueError::new_err("every row must be the same length"));
+        }
+
+        let tiles = rows.iter().flat_map(|row| row.chars()).collect();
+        Ok(PyMap {
+            map: MovingAiMap::new(String::from("octile"), rows.len(), width, tiles),
+        })
+    }
+
+    #[getter]
+    fn width(&self) -> usize {
+        self.map.width()
+    }
+
+    #[getter]
+    fn height(&self) -> usize {
+        self.map.height()
+    }
+
+    ///Finds a route using the JPS algorithm. The search runs without holding the GIL.
+    fn jps_path(&self, py: Python<'_>, start: Coords2D, goal: Coords2D) -> PyResult<PyRoute> {
+        py.allow_threads(|| crate::jps_path(&self.map, start, goal))
+            .map(PyRoute::from)
+            .map_err(to_python)
+    }
+
+    ///Finds a route using the A* algorithm. The search runs without holding the GIL.
+    fn a_star_path(&self, py: Python<'_>, start: Coords2D, goal: Coords2D) -> PyResult<PyRoute> {
+        py.allow_threads(|| crate::a_star_path(&self.map, start, goal))
+            .map(PyRoute::from)
+            .map_err(to_python)
+    }
+}
+
+///A route found from Python, with the goal as the first step and the start as the last.
+#[pyclass(name = "Route", frozen)]
+pub struct PyRoute {
+    #[pyo3(get)]
+    distance: f64,
+    #[pyo3(get)]
+    steps: Vec<Coords2D>,
+}
+
+impl From<Route> for PyRoute {
+    fn from(route: Route) -> Self {
+        PyRoute {
+            distance: route.distance(),
+            steps: route.steps(),
+        }
+    }
+}
+
+#[pymethods]
+impl PyRoute {
+    fn __len__(&self) -> usize {
+        self.steps.len()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Route(distance={}, steps={})",
+            self.distance,
+            self.steps.len()
+        )
+    }
+}
+
+///Loads the scenarios from a MovingAI .scen file as (start, goal, optimal_length) tuples.
+#[pyfunction]
+fn load_scenarios(path: &str) -> PyResult<Vec<(Coords2D, Coords2D, f64)>> {
+    let scenes =
+        parse_scen_file(Path::new(path)).map_err(|error| PyIOError::new_err(error.to_string()))?;
+    Ok(scenes
+        .iter()
+        .map(|scene| (scene.start_pos, scene.goal_pos, scene.optimal_length))
+        .collect())
+}
+
+//Failed searches raise a PathError carrying the same message they display with in Rust
+fn to_python(error: PathError) -> PyErr {
+    PyPathError::new_err(error.to_string())
+}
+
+#[pymodule]
+fn blitz_path(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_class::<PyMap>()?;
+    module.add_class::<PyRoute>()?;
+    module.add_function(wrap_pyfunction!(load_scenarios, module)?)?;
+    module.add("PathError", module.py().get_type::<PyPathError>())?;
+    Ok(())
+}
@@ -0,0 +1,123 @@
+use alloc::collections::BinaryHeap;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+
+use crate::grid::Coords2D;
+use crate::grid::Map2D;
+use crate::grid::MovingAiMap;
+
+use crate::flood::flood;
+use crate::utils::{distance, index, neighbours};
+
+///Returns true if a route exists between `start` and `goal`.
+///Much cheaper than a full search when only a yes or no answer is needed, as it heads greedily
+///towards the goal and stops as soon as it is reached, without tracking costs or building a Route.
+///Returns false if either point is out of bounds or blocked.
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+///
+/// let map = movingai::parser::parse_map_file(Path::new("./tests/map/maze512-32-9.map")).expect("Could not load map.");
+/// let scenes = movingai::parser::parse_scen_file(Path::new("./tests/map/maze512-32-9.map.scen")).expect("Could not load scenario.");
+/// let scene = &scenes[0];
+///
+/// assert!(blitz_path::is_reachable(&map, scene.start_pos, scene.goal_pos));
+/// assert!(!blitz_path::is_reachable(&map, scene.start_pos, (0, 0)));
+/// ```
+pub fn is_reachable(map: &MovingAiMap, start: Coords2D, goal: Coords2D) -> bool {
+    if !map.is_traversable(start) || !map.is_traversable(goal) {
+        return false;
+    }
+
+    let mut visited = vec![false; map.width() * map.height()];
+    let mut open = BinaryHeap::new();
+    visited[index(map, start)] = true;
+    open.push(Candidate {
+        h: distance(start, goal),
+        position: start,
+    });
+
+    //Always move on from the tile closest to the goal
+    while let Some(current) = open.pop() {
+        if current.position == goal {
+            return true;
+        }
+
+        for successor in neighbours(map, current.position) {
+            let successor_index = index(map, successor);
+            if !visited[successor_index] {
+                visited[successor_index] = true;
+                open.push(Candidate {
+                    h: distance(successor, goal),
+                    position: successor,
+                });
+            }
+        }
+    }
+
+    false
+}
+
+///Returns every tile that can be reached from `origin` for a total cost of at most `budget`,
+///along with the cheapest cost of reaching it, in row-major order.
+///Useful for drawing the movement range of a unit. The origin is included at a cost of 0.0,
+///and nothing is returned if it is out of bounds or blocked.
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+///
+/// let map = movingai::parser::parse_map_file(Path::new("./tests/map/maze512-32-9.map")).expect("Could not load map.");
+///
+/// let range = blitz_path::reachable_within(&map, (1, 1), 1.5);
+/// assert!(range.contains(&((1, 1), 0.0)));
+/// assert!(range.contains(&((2, 1), 1.0)));
+/// assert!(range.contains(&((2, 2), 2f64.sqrt())));
+/// assert!(!range.iter().any(|(tile, _)| *tile == (3, 1)));
+/// ```
+pub fn reachable_within(map: &MovingAiMap, origin: Coords2D, budget: f64) -> Vec<(Coords2D, f64)> {
+    let reached = flood(
+        map,
+        &[(origin, 0.0)],
+        budget,
+        |from, to| Some(distance(from, to)),
+        |_, _| false,
+    );
+
+    map.coords()
+        .zip(reached.costs)
+        .filter(|(_, cost)| cost.is_finite())
+        .collect()
+}
+
+//Open list entry ordered on distance to goal only
+struct Candidate {
+    h: f64,
+    position: Coords2D,
+}
+
+impl PartialEq for Candidate {
+    fn eq(&self, other: &Candidate) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Candidate {}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Candidate) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Candidate) -> Ordering {
+        // This is reversed on purpose to make the max-heap into min-heap.
+        other
+            .h
+            .total_cmp(&self.h)
+            .then_with(|| other.position.cmp(&self.position))
+    }
+}
@@ -0,0 +1,216 @@
+use alloc::collections::{BTreeMap, BTreeSet, BinaryHeap};
+use core::cmp::Ordering;
+
+use crate::grid::Coords2D;
+use crate::grid::MovingAiMap;
+
+use crate::memory::map_size;
+use crate::utils::{distance, neighbours, validate};
+use crate::{MemoryFootprint, PathError};
+
+//An entry in the lookahead's open list
+#[derive(Debug, Copy, Clone)]
+struct Entry {
+    f: f64,
+    g: f64,
+    position: Coords2D,
+}
+
+impl PartialOrd for Entry {
+    fn partial_cmp(&self, other: &Entry) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Entry {
+    fn cmp(&self, other: &Entry) -> Ordering {
+        //Reversed to make the max-heap a min-heap, preferring the entry furthest along on ties
+        other
+            .f
+            .total_cmp(&self.f)
+            .then_with(|| self.g.total_cmp(&other.g))
+            .then_with(|| other.position.cmp(&self.position))
+    }
+}
+
+impl PartialEq for Entry {
+    fn eq(&self, other: &Entry) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Entry {}
+
+///An agent that plans as it moves, for units with a strict compute budget each tick on maps too large
+///to search completely. Each [`step`](RealTimeAgent::step) runs an A* search limited to `lookahead` expansions
+///around the agent, learns better estimates of the distance to the goal for the tiles it expanded,
+///as in Real-Time Adaptive A*, and takes a single step towards the most promising tile it found.
+///
+///Routes are not optimal, especially early on, and the agent may wander into dead ends before learning to
+///avoid them, but it always reaches a reachable goal. Agents [restarted](RealTimeAgent::restart) for the same goal,
+///or with a larger lookahead, take shorter routes. The map may change between steps.
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+/// use blitz_path::RealTimeAgent;
+///
+/// let map = movingai::parser::parse_map_file(Path::new("./tests/map/maze512-32-9.map")).expect("Could not load map.");
+///
+/// let mut agent = RealTimeAgent::new(&map, (1, 1), (20, 9), 16).unwrap();
+/// while agent.step(&map).unwrap().is_some() {}
+/// assert_eq!(agent.position(), (20, 9));
+/// assert!(agent.travelled() >= blitz_path::a_star_path(&map, (1, 1), (20, 9)).unwrap().distance());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RealTimeAgent {
+    position: Coords2D,
+    goal: Coords2D,
+    lookahead: usize,
+    travelled: f64,
+    //Learned estimates of the distance to the goal, where higher than the straight line distance
+    learned: BTreeMap<Coords2D, f64>,
+}
+
+impl RealTimeAgent {
+    ///Creates an agent at `start` heading for `goal`, expanding at most `lookahead` tiles each step.
+    ///A lookahead of zero is treated as one.
+    pub fn new(
+        map: &MovingAiMap,
+        start: Coords2D,
+        goal: Coords2D,
+        lookahead: usize,
+    ) -> Result<RealTimeAgent, PathError> {
+        validate(map, start, goal)?;
+        Ok(RealTimeAgent {
+            position: start,
+            goal,
+            lookahead: lookahead.max(1),
+            travelled: 0.0,
+            learned: BTreeMap::new(),
+        })
+    }
+
+    ///Returns the tile the agent is on.
+    pub fn position(&self) -> Coords2D {
+        self.position
+    }
+
+    ///Returns the goal the agent is heading for.
+    pub fn goal(&self) -> Coords2D {
+        self.goal
+    }
+
+    ///Returns how far the agent has moved so far.
+    pub fn travelled(&self) -> f64 {
+        self.travelled
+    }
+
+    ///Returns the agent's current estimate of the distance from `tile` to the goal, which never overestimates
+    ///while the map stays the same.
+    pub fn estimate(&self, tile: Coords2D) -> f64 {
+        self.learned
+            .get(&tile)
+            .copied()
+            .unwrap_or_else(|| distance(tile, self.goal))
+    }
+
+    ///Returns the number of tiles the agent has learned a better estimate for.
+    pub fn learned(&self) -> usize {
+        self.learned.len()
+    }
+
+    ///Sends the agent to a new goal from where it stands, forgetting what it learned about the old one.
+    pub fn set_goal(&mut self, map: &MovingAiMap, goal: Coords2D) -> Result<(), PathError> {
+        validate(map, self.position, goal)?;
+        if goal != self.goal {
+            self.goal = goal;
+            self.learned.clear();
+        }
+        Ok(())
+    }
+
+    ///Moves the agent back to `start` for another trip to the same goal, keeping what it learned
+    ///so repeated trips settle on the shortest route, and resetting the distance travelled.
+    pub fn restart(&mut self, map: &MovingAiMap, start: Coords2D) -> Result<(), PathError> {
+        validate(map, start, self.goal)?;
+        self.position = start;
+        self.travelled = 0.0;
+        Ok(())
+    }
+
+    ///Plans within the lookahead and moves one step, returning the tile moved to,
+    ///or `None` if the agent is already on the goal.
+    ///Returns `PathError::NoPath` without moving if the goal cannot be reached from where the agent stands.
+    pub fn step(&mut self, map: &MovingAiMap) -> Result<Option<Coords2D>, PathError> {
+        if self.position == self.goal {
+            return Ok(None);
+        }
+
+        let mut open = BinaryHeap::new();
+        let mut costs: BTreeMap<Coords2D, f64> = BTreeMap::new();
+        let mut parents: BTreeMap<Coords2D, Coords2D> = BTreeMap::new();
+        let mut closed: BTreeSet<Coords2D> = BTreeSet::new();
+        open.push(Entry {
+            f: self.estimate(self.position),
+            g: 0.0,
+            position: self.position,
+        });
+        costs.insert(self.position, 0.0);
+
+        //Expand up to the lookahead, stopping early on reaching the goal
+        let mut target = None;
+        while let Some(entry) = open.pop() {
+            if closed.contains(&entry.position) || entry.g > costs[&entry.position] {
+                continue;
+            }
+            if entry.position == self.goal || closed.len() == self.lookahead {
+                target = Some(entry);
+                break;
+            }
+            closed.insert(entry.position);
+
+            for next in neighbours(map, entry.position) {
+                let g = entry.g + distance(entry.position, next);
+                if closed.contains(&next) || costs.get(&next).is_some_and(|best| *best <= g) {
+                    continue;
+                }
+                costs.insert(next, g);
+                parents.insert(next, entry.position);
+                open.push(Entry {
+                    f: g + self.estimate(next),
+                    g,
+                    position: next,
+                });
+            }
+        }
+        let target = target.ok_or(PathError::NoPath)?;
+
+        //Every expanded tile is at least as far from the goal as the best frontier tile is through it
+        for tile in &closed {
+            let estimate = target.f - costs[tile];
+            if estimate > self.estimate(*tile) {
+                self.learned.insert(*tile, estimate);
+            }
+        }
+
+        //Take the first step towards the frontier tile
+        let mut next = target.position;
+        while let Some(parent) = parents.get(&next) {
+            if *parent == self.position {
+                break;
+            }
+            next = *parent;
+        }
+        self.travelled += distance(self.position, next);
+        self.position = next;
+        Ok(Some(next))
+    }
+}
+
+impl MemoryFootprint for RealTimeAgent {
+    fn heap_size(&self) -> usize {
+        map_size(&self.learned)
+    }
+}
@@ -0,0 +1,102 @@
+//Logging maps, searches and routes to the rerun viewer
+
+use crate::grid::Map2D;
+use crate::grid::MovingAiMap;
+use rerun::{Image, LineStrips2D, Points2D, RecordingStream, RecordingStreamResult};
+
+use crate::{Route, SearchEvent, SearchObserver};
+
+//Tiles are drawn as pixels of the map image, so points sit in the centre of their pixel
+fn centre(position: (usize, usize)) -> (f32, f32) {
+    (position.0 as f32 + 0.5, position.1 as f32 + 0.5)
+}
+
+///Logs a map to `entity_path` as a greyscale image, with open tiles white and blocked tiles black.
+///Routes and searches logged under the same 2D view line up with the map's pixels.
+pub fn log_map_to_rerun(
+    stream: &RecordingStream,
+    entity_path: &str,
+    map: &MovingAiMap,
+) -> RecordingStreamResult<()> {
+    let pixels: Vec<u8> = map
+        .coords()
+        .map(|tile| if map.is_traversable(tile) { 255 } else { 0 })
+        .collect();
+    stream.log(
+        entity_path,
+        &Image::from_l8(pixels, [map.width() as u32, map.height() as u32]),
+    )
+}
+
+///Logs a route to `entity_path` as a line through the centre of each step.
+pub fn log_route_to_rerun(
+    stream: &RecordingStream,
+    entity_path: &str,
+    route: &Route,
+) -> RecordingStreamResult<()> {
+    let points: Vec<(f32, f32)> = route.steps().iter().map(|step| centre(*step)).collect();
+    stream.log(entity_path, &LineStrips2D::new([points]))
+}
+
+///An observer logging the progress of a search to rerun, to watch it unfold step by step.
+///Every event advances the `search_step` timeline, and the tiles expanded and discovered so far
+///are logged as points to `expanded` and `discovered` under the observer's entity path.
+///
+///All tiles seen so far are logged with each event, so this is best suited to small searches.
+///Errors from the recording stream are ignored, as observers cannot fail a search.
+/// # Examples
+///
+/// ```no_run
+/// use std::path::Path;
+/// use blitz_path::{log_map_to_rerun, log_route_to_rerun, RerunObserver, SearchOptions};
+///
+/// let map = movingai::parser::parse_map_file(Path::new("./tests/map/maze512-32-9.map")).expect("Could not load map.");
+/// let stream = rerun::RecordingStreamBuilder::new("blitz-path").spawn().unwrap();
+///
+/// log_map_to_rerun(&stream, "map", &map).unwrap();
+/// let mut observer = RerunObserver::new(&stream, "map/search");
+/// let route = blitz_path::jps_path_observed(&map, (1, 1), (5, 5), &SearchOptions::new(), &mut observer).unwrap();
+/// log_route_to_rerun(&stream, "map/route", &route).unwrap();
+/// ```
+pub struct RerunObserver<'a> {
+    stream: &'a RecordingStream,
+    entity_path: String,
+    step: i64,
+    expanded: Vec<(f32, f32)>,
+    discovered: Vec<(f32, f32)>,
+}
+
+impl<'a> RerunObserver<'a> {
+    ///Creates an observer logging to entities under `entity_path`.
+    pub fn new(stream: &'a RecordingStream, entity_path: &str) -> RerunObserver<'a> {
+        RerunObserver {
+            stream,
+            entity_path: String::from(entity_path),
+            step: 0,
+            expanded: Vec::new(),
+            discovered: Vec::new(),
+        }
+    }
+}
+
+impl SearchObserver for RerunObserver<'_> {
+    fn observe(&mut self, event: SearchEvent) {
+        self.stream.set_time_sequence("search_step", self.step);
+        self.step += 1;
+
+        let (name, points) = match event {
+            SearchEvent::Expanded(node) => {
+                self.expanded.push(centre(node.position));
+                ("expanded", &self.expanded)
+            }
+            SearchEvent::Discovered(node) => {
+                self.discovered.push(centre(node.position));
+                ("discovered", &self.discovered)
+            }
+        };
+        let _ = self.stream.log(
+            format!("{}/{}", self.entity_path, name),
+            &Points2D::new(points.iter().copied()),
+        );
+    }
+}
@@ -0,0 +1,424 @@
+use alloc::collections::{btree_map, BTreeMap, BTreeSet, BinaryHeap};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+
+use crate::grid::Coords2D;
+use crate::grid::Map2D;
+use crate::grid::MovingAiMap;
+
+use crate::flood::{flood, Flood};
+use crate::memory::vec_size;
+use crate::utils::{compose, distance, index, neighbours, prepare};
+use crate::{MemoryFootprint, PathError, Route, SearchOptions};
+
+//Label given to tiles that are blocked or have no weight
+const NO_REGION: usize = usize::MAX;
+
+//An entry in the open list of the search over portals
+#[derive(Debug, Copy, Clone)]
+struct Entry {
+    f: f64,
+    g: f64,
+    node: usize,
+}
+
+impl PartialOrd for Entry {
+    fn partial_cmp(&self, other: &Entry) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Entry {
+    fn cmp(&self, other: &Entry) -> Ordering {
+        //Reversed to make the max-heap a min-heap, preferring nodes further along on ties
+        other
+            .f
+            .total_cmp(&self.f)
+            .then_with(|| self.g.total_cmp(&other.g))
+            .then_with(|| other.node.cmp(&self.node))
+    }
+}
+
+impl PartialEq for Entry {
+    fn eq(&self, other: &Entry) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Entry {}
+
+///A map split into regions of equal movement cost, such as patches of plains, forest and swamp,
+///for [`weighted_region_path`] to search between region borders rather than across every tile.
+///
+///Each step costs the distance travelled times the weight of the tile it enters. Regions are connected
+///tiles of the same weight, and the search only stops at portals, tiles either side of the border between
+///two regions. Portals on the same border are kept at least `spacing` tiles apart, so a larger spacing
+///gives a smaller graph and faster searches at the price of routes slightly longer than the best.
+///A spacing of 1 keeps every border tile and finds the cheapest route.
+///
+///Building measures the distance between every pair of portals in each region, so takes longer the
+///more portals a region has. The regions must be rebuilt if the map or the weights change.
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+/// use blitz_path::{SearchOptions, WeightedRegions};
+///
+/// let map = movingai::parser::parse_map_file(Path::new("./tests/map/maze512-32-9.map")).expect("Could not load map.");
+///
+/// // Swamp covers the middle of the room, costing three times as much to cross
+/// let swamp = |(x, y): (usize, usize)| (3..6).contains(&x) && (1..6).contains(&y);
+/// let regions = WeightedRegions::new(&map, |tile| Some(if swamp(tile) { 3.0 } else { 1.0 }), 1);
+/// assert_eq!(regions.weight((4, 3)), Some(3.0));
+///
+/// let route = blitz_path::weighted_region_path(&map, (1, 3), (7, 3), &regions, &SearchOptions::new()).unwrap();
+/// let exact = blitz_path::dijkstra_path_weighted(&map, (1, 3), (7, 3), &SearchOptions::new(), regions.step_cost()).unwrap();
+/// assert!((route.distance() - exact.distance()).abs() < 1e-9);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WeightedRegions {
+    width: usize,
+    height: usize,
+    //Region of every tile in row-major order, or NO_REGION
+    labels: Vec<usize>,
+    //Weight of each region
+    weights: Vec<f64>,
+    //Tile and region of each portal
+    portals: Vec<(Coords2D, usize)>,
+    //Portals reachable from each portal, with the cost of getting there
+    edges: Vec<Vec<(usize, f64)>>,
+}
+
+impl WeightedRegions {
+    ///Splits `map` into regions using the `weight` of each tile, where `None` or a blocked tile can never
+    ///be entered, and connects them through portals at least `spacing` tiles apart along each border.
+    ///Weights must be positive.
+    pub fn new<F: Fn(Coords2D) -> Option<f64>>(
+        map: &MovingAiMap,
+        weight: F,
+        spacing: usize,
+    ) -> WeightedRegions {
+        let mut regions = WeightedRegions {
+            width: map.width(),
+            height: map.height(),
+            labels: vec![NO_REGION; map.width() * map.height()],
+            weights: Vec::new(),
+            portals: Vec::new(),
+            edges: Vec::new(),
+        };
+        let weights: Vec<Option<f64>> = map
+            .coords()
+            .map(|tile| weight(tile).filter(|_| map.is_traversable(tile)))
+            .collect();
+
+        //Flood each region of equal weight
+        for tile in map.coords() {
+            let tile_weight = match weights[index(map, tile)] {
+                Some(tile_weight) if regions.labels[index(map, tile)] == NO_REGION => tile_weight,
+                _ => continue,
+            };
+            let label = regions.weights.len();
+            regions.weights.push(tile_weight);
+            regions.labels[index(map, tile)] = label;
+            let mut stack = vec![tile];
+            while let Some(current) = stack.pop() {
+                for next in neighbours(map, current) {
+                    let next_index = index(map, next);
+                    if regions.labels[next_index] == NO_REGION
+                        && weights[next_index].is_some_and(|w| w.to_bits() == tile_weight.to_bits())
+                    {
+                        regions.labels[next_index] = label;
+                        stack.push(next);
+                    }
+                }
+            }
+        }
+
+        //Keep crossings between regions spaced out along each border, measured on the side of the lower region
+        let mut borders: BTreeMap<(usize, usize), Vec<Coords2D>> = BTreeMap::new();
+        let mut kept = BTreeSet::new();
+        let mut crossings = Vec::new();
+        for tile in map.coords() {
+            let from = regions.labels[index(map, tile)];
+            if from == NO_REGION {
+                continue;
+            }
+            for next in neighbours(map, tile) {
+                let to = regions.labels[index(map, next)];
+                if to == NO_REGION || to == from || kept.contains(&(tile, next)) {
+                    continue;
+                }
+                let anchor = if from < to { tile } else { next };
+                let border = borders.entry((from.min(to), from.max(to))).or_default();
+                let crowded = border.iter().any(|other| {
+                    *other != anchor
+                        && other.0.abs_diff(anchor.0).max(other.1.abs_diff(anchor.1)) < spacing
+                });
+                if crowded {
+                    continue;
+                }
+                if !border.contains(&anchor) {
+                    border.push(anchor);
+                }
+
+                //Cross both ways where the map allows it
+                kept.insert((tile, next));
+                crossings.push((tile, next));
+                if map.is_traversable_from(next, tile) {
+                    kept.insert((next, tile));
+                    crossings.push((next, tile));
+                }
+            }
+        }
+
+        let mut portal_of: BTreeMap<Coords2D, usize> = BTreeMap::new();
+        for (from, to) in &crossings {
+            for portal in [*from, *to] {
+                if let btree_map::Entry::Vacant(entry) = portal_of.entry(portal) {
+                    entry.insert(regions.portals.len());
+                    regions
+                        .portals
+                        .push((portal, regions.labels[index(map, portal)]));
+                }
+            }
+        }
+        regions.edges = vec![Vec::new(); regions.portals.len()];
+        for (from, to) in crossings {
+            let cost = distance(from, to) * regions.weights[regions.labels[index(map, to)]];
+            regions.edges[portal_of[&from]].push((portal_of[&to], cost));
+        }
+
+        //Connect the portals of each region to one another through the region
+        for portal in 0..regions.portals.len() {
+            let (tile, region) = regions.portals[portal];
+            let costs = regions.flood_within(map, region, tile, |_| false).costs;
+            for (other, (other_tile, other_region)) in regions.portals.iter().enumerate() {
+                let cost = costs[index(map, *other_tile)];
+                if other != portal && *other_region == region && cost.is_finite() {
+                    regions.edges[portal].push((other, cost));
+                }
+            }
+        }
+
+        regions
+    }
+
+    ///Returns the weight of a tile, or `None` if it cannot be entered or is outside the map.
+    pub fn weight(&self, position: Coords2D) -> Option<f64> {
+        Some(self.weights[self.region(position)?])
+    }
+
+    ///Returns the region a tile belongs to, or `None` if it cannot be entered or is outside the map.
+    pub fn region(&self, position: Coords2D) -> Option<usize> {
+        if position.0 >= self.width || position.1 >= self.height {
+            return None;
+        }
+        let label = self.labels[position.1 * self.width + position.0];
+        if label == NO_REGION {
+            None
+        } else {
+            Some(label)
+        }
+    }
+
+    ///Returns the number of regions.
+    pub fn regions(&self) -> usize {
+        self.weights.len()
+    }
+
+    ///Returns the tile of every portal, in row-major order of the borders they were found on.
+    pub fn portals(&self) -> Vec<Coords2D> {
+        self.portals.iter().map(|(tile, _)| *tile).collect()
+    }
+
+    ///Returns a step cost for the weighted searches giving the same costs as the regions,
+    ///to search every tile instead. Weights below 1.0 need
+    ///[`dijkstra_path_weighted`](crate::dijkstra_path_weighted) rather than A*.
+    pub fn step_cost(&self) -> impl Fn(Coords2D, Coords2D) -> Option<f64> + '_ {
+        move |from, to| {
+            self.region(from)?;
+            Some(distance(from, to) * self.weight(to)?)
+        }
+    }
+
+    ///Returns the width of the underlying map.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    ///Returns the height of the underlying map.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    //Flood a region from one of its tiles without leaving it, until stop returns true for a settled tile
+    fn flood_within<S: FnMut(Coords2D) -> bool>(
+        &self,
+        map: &MovingAiMap,
+        region: usize,
+        source: Coords2D,
+        mut stop: S,
+    ) -> Flood {
+        let weight = self.weights[region];
+        flood(
+            map,
+            &[(source, 0.0)],
+            f64::INFINITY,
+            |from, to| {
+                if self.region(to) == Some(region) {
+                    Some(distance(from, to) * weight)
+                } else {
+                    None
+                }
+            },
+            |tile, _| stop(tile),
+        )
+    }
+}
+
+///Finds a route from `start` to `goal` across the regions of `regions`, searching between the portals
+///on region borders and then filling in the tiles between them. The route is the cheapest one when the
+///regions were built with a spacing of 1, and close to it otherwise. Its distance is the total cost of its steps.
+///
+///Returns `PathError::StartBlocked` or `PathError::GoalBlocked` if an end has no weight.
+pub fn weighted_region_path(
+    map: &MovingAiMap,
+    start: Coords2D,
+    goal: Coords2D,
+    regions: &WeightedRegions,
+    options: &SearchOptions,
+) -> Result<Route, PathError> {
+    let (start, goal) = prepare(map, start, goal, options)?;
+    let start_region = regions
+        .region(start)
+        .ok_or(PathError::StartBlocked(start))?;
+    let goal_region = regions.region(goal).ok_or(PathError::GoalBlocked(goal))?;
+    let lightest = regions
+        .weights
+        .iter()
+        .copied()
+        .fold(f64::INFINITY, f64::min);
+    let heuristic = |tile: Coords2D| distance(tile, goal) * lightest;
+    if options.exceeds_max_distance(heuristic(start)) {
+        return Err(PathError::ExceedsMaxDistance);
+    }
+
+    //Join the start and goal to the portals of their regions, after every portal
+    let portals = regions.portals.len();
+    let (start_node, goal_node) = (portals, portals + 1);
+    let position = |node: usize| match node {
+        node if node == start_node => start,
+        node if node == goal_node => goal,
+        node => regions.portals[node].0,
+    };
+    let from_start = regions
+        .flood_within(map, start_region, start, |_| false)
+        .costs;
+    let to_goal = regions
+        .flood_within(map, goal_region, goal, |_| false)
+        .costs;
+    let mut start_edges: Vec<(usize, f64)> = (0..portals)
+        .filter(|portal| regions.portals[*portal].1 == start_region)
+        .map(|portal| (portal, from_start[index(map, position(portal))]))
+        .filter(|(_, cost)| cost.is_finite())
+        .collect();
+    if from_start[index(map, goal)].is_finite() {
+        start_edges.push((goal_node, from_start[index(map, goal)]));
+    }
+    let edges = |node: usize| {
+        let mut edges = if node == start_node {
+            start_edges.clone()
+        } else {
+            regions.edges[node].clone()
+        };
+        if node != start_node && regions.portals[node].1 == goal_region {
+            let cost = to_goal[index(map, position(node))];
+            if cost.is_finite() {
+                edges.push((goal_node, cost));
+            }
+        }
+        edges
+    };
+
+    let mut best = vec![f64::INFINITY; portals + 2];
+    let mut parents = vec![start_node; portals + 2];
+    let mut open = BinaryHeap::new();
+    best[start_node] = 0.0;
+    open.push(Entry {
+        f: heuristic(start),
+        g: 0.0,
+        node: start_node,
+    });
+
+    let mut pruned = false;
+    let mut iteration = 0;
+    let mut found = None;
+    while let Some(entry) = open.pop() {
+        //Skip stale entries that have since been reached more cheaply
+        if entry.g > best[entry.node] {
+            continue;
+        }
+        if options.should_cancel(iteration) {
+            return Err(PathError::Cancelled);
+        }
+        iteration += 1;
+
+        if entry.node == goal_node {
+            found = Some(entry.g);
+            break;
+        }
+
+        for (next, cost) in edges(entry.node) {
+            let g = entry.g + cost;
+            let f = g + heuristic(position(next));
+            if options.exceeds_max_distance(f) {
+                pruned = true;
+                continue;
+            }
+            if g < best[next] {
+                best[next] = g;
+                parents[next] = entry.node;
+                open.push(Entry { f, g, node: next });
+            }
+        }
+    }
+
+    let cost = match found {
+        Some(cost) => cost,
+        None if pruned => return Err(PathError::ExceedsMaxDistance),
+        None => return Err(PathError::NoPath),
+    };
+
+    //Walk back through the portals, filling in each leg inside a region, goal first
+    let mut path = vec![goal];
+    let mut node = goal_node;
+    while node != start_node {
+        let parent = parents[node];
+        let (from, to) = (position(parent), position(node));
+        let region = regions.region(from).expect("portals lie in a region");
+        if regions.region(to) == Some(region) {
+            let leg = regions.flood_within(map, region, from, |tile| tile == to);
+            let steps = leg
+                .route_to(map, to)
+                .expect("portals were joined inside the region");
+            path.extend(steps.steps().into_iter().skip(1));
+        } else {
+            path.push(from);
+        }
+        node = parent;
+    }
+
+    Ok(Route::from((cost, compose(path, options))))
+}
+
+impl MemoryFootprint for WeightedRegions {
+    fn heap_size(&self) -> usize {
+        vec_size(&self.labels)
+            + vec_size(&self.weights)
+            + vec_size(&self.portals)
+            + vec_size(&self.edges)
+            + self.edges.iter().map(vec_size).sum::<usize>()
+    }
+}
@@ -0,0 +1,237 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::grid::Coords2D;
+
+use crate::{NodeInfo, PathError, Route, RouteDecodeError, SearchEvent, SearchObserver};
+
+///Every event raised by a search and the route it returned, kept so the search can be replayed later
+///in a viewer, or compared against a fresh run in a regression test.
+///
+///Recordings are saved as a compact text log with [`to_log`](SearchRecording::to_log), one line per event,
+///and can be loaded back with [`from_log`](SearchRecording::from_log). Costs are written with enough
+///precision to load back bitwise-identical, so two recordings of the same search always compare equal.
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+/// use blitz_path::{SearchOptions, SearchRecording};
+///
+/// let map = movingai::parser::parse_map_file(Path::new("./tests/map/maze512-32-9.map")).expect("Could not load map.");
+///
+/// let (route, recording) = SearchRecording::record(|observer| {
+///     blitz_path::jps_path_observed(&map, (1, 1), (5, 5), &SearchOptions::new(), observer)
+/// });
+///
+/// let loaded = SearchRecording::from_log(&recording.to_log()).unwrap();
+/// assert_eq!(loaded, recording);
+///
+/// let mut expanded = 0;
+/// let replayed = loaded.replay(&mut |_| expanded += 1).unwrap().unwrap();
+/// assert_eq!(replayed.steps(), route.unwrap().steps());
+/// assert!(expanded > 0);
+/// ```
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SearchRecording {
+    events: Vec<SearchEvent>,
+    outcome: Option<Result<(f64, Vec<Coords2D>), PathError>>,
+}
+
+impl SearchRecording {
+    ///Creates an empty recording. Pass it as the observer of a search, then call
+    ///[`finish`](SearchRecording::finish) with the result.
+    pub fn new() -> SearchRecording {
+        SearchRecording::default()
+    }
+
+    ///Runs `search` with a new recording as its observer, returning its result along with the recording.
+    pub fn record<F>(search: F) -> (Result<Route, PathError>, SearchRecording)
+    where
+        F: FnOnce(&mut SearchRecording) -> Result<Route, PathError>,
+    {
+        let mut recording = SearchRecording::new();
+        let result = search(&mut recording);
+        recording.finish(&result);
+        (result, recording)
+    }
+
+    ///Stores the result of the recorded search.
+    pub fn finish(&mut self, result: &Result<Route, PathError>) {
+        self.outcome = Some(
+            result
+                .as_ref()
+                .map(|route| (route.distance(), route.steps()))
+                .map_err(|error| *error),
+        );
+    }
+
+    ///Returns the recorded events, in the order they were raised.
+    pub fn events(&self) -> &[SearchEvent] {
+        &self.events
+    }
+
+    ///Feeds every recorded event to `observer` in order, then returns the recorded result.
+    ///Returns `None` if the recording was never finished.
+    pub fn replay<O: SearchObserver>(&self, observer: &mut O) -> Option<Result<Route, PathError>> {
+        for event in &self.events {
+            observer.observe(*event);
+        }
+        self.outcome.as_ref().map(|outcome| {
+            outcome
+                .as_ref()
+                .map(|(distance, steps)| Route::from((*distance, steps.clone())))
+                .map_err(|error| *error)
+        })
+    }
+
+    ///Returns the index of the first event where this recording and `other` differ,
+    ///or the length of the shorter one if it ends early. Returns `None` if every event matches.
+    pub fn first_divergence(&self, other: &SearchRecording) -> Option<usize> {
+        self.events
+            .iter()
+            .zip(other.events.iter())
+            .position(|(a, b)| a != b)
+            .or_else(|| {
+                if self.events.len() == other.events.len() {
+                    None
+                } else {
+                    Some(self.events.len().min(other.events.len()))
+                }
+            })
+    }
+
+    ///Writes the recording as a text log.
+    pub fn to_log(&self) -> String {
+        let mut log = String::new();
+        for event in &self.events {
+            let (kind, node) = match event {
+                SearchEvent::Expanded(node) => ("e", node),
+                SearchEvent::Discovered(node) => ("d", node),
+            };
+            //Rust prints the shortest form of a float that parses back to the same value
+            log.push_str(&format!(
+                "{} {} {} {} {} {} {}\n",
+                kind,
+                node.position.0,
+                node.position.1,
+                node.g,
+                node.h,
+                node.parent.0,
+                node.parent.1
+            ));
+        }
+        let outcome = match &self.outcome {
+            Some(Ok((distance, steps))) => {
+                let mut line = format!("route {}", distance);
+                for (x, y) in steps {
+                    line.push_str(&format!(" {} {}", x, y));
+                }
+                line
+            }
+            Some(Err(PathError::StartOutOfBounds((x, y)))) => {
+                format!("error start-out-of-bounds {} {}", x, y)
+            }
+            Some(Err(PathError::GoalOutOfBounds((x, y)))) => {
+                format!("error goal-out-of-bounds {} {}", x, y)
+            }
+            Some(Err(PathError::OutsideMap((x, y)))) => format!("error outside-map {} {}", x, y),
+            Some(Err(PathError::StartBlocked((x, y)))) => {
+                format!("error start-blocked {} {}", x, y)
+            }
+            Some(Err(PathError::GoalBlocked((x, y)))) => format!("error goal-blocked {} {}", x, y),
+            Some(Err(PathError::NoPath)) => String::from("error no-path"),
+            Some(Err(PathError::ExceedsMaxDistance)) => String::from("error exceeds-max-distance"),
+            Some(Err(PathError::Cancelled)) => String::from("error cancelled"),
+            None => return log,
+        };
+        log.push_str(&outcome);
+        log.push('\n');
+        log
+    }
+
+    ///Reads a recording from a text log written by [`to_log`](SearchRecording::to_log).
+    pub fn from_log(log: &str) -> Result<SearchRecording, RouteDecodeError> {
+        let mut recording = SearchRecording::new();
+        for (index, line) in log.lines().enumerate() {
+            let malformed = || RouteDecodeError::Malformed(format!("line {}", index + 1));
+            if recording.outcome.is_some() {
+                return Err(malformed());
+            }
+
+            let mut fields = line.split_whitespace();
+            match (fields.next(), fields.next()) {
+                (Some(kind @ ("e" | "d")), Some(x)) => {
+                    let numbers: Vec<&str> = core::iter::once(x).chain(fields).collect();
+                    let node = match numbers.as_slice() {
+                        [x, y, g, h, parent_x, parent_y] => NodeInfo {
+                            position: (
+                                x.parse().map_err(|_| malformed())?,
+                                y.parse().map_err(|_| malformed())?,
+                            ),
+                            g: g.parse().map_err(|_| malformed())?,
+                            h: h.parse().map_err(|_| malformed())?,
+                            parent: (
+                                parent_x.parse().map_err(|_| malformed())?,
+                                parent_y.parse().map_err(|_| malformed())?,
+                            ),
+                        },
+                        _ => return Err(malformed()),
+                    };
+                    recording.events.push(if kind == "e" {
+                        SearchEvent::Expanded(node)
+                    } else {
+                        SearchEvent::Discovered(node)
+                    });
+                }
+                (Some("route"), Some(distance)) => {
+                    let distance = distance.parse().map_err(|_| malformed())?;
+                    let coords = fields
+                        .map(|field| field.parse::<usize>())
+                        .collect::<Result<Vec<usize>, _>>()
+                        .map_err(|_| malformed())?;
+                    if !coords.len().is_multiple_of(2) {
+                        return Err(malformed());
+                    }
+                    let steps = coords.chunks(2).map(|pair| (pair[0], pair[1])).collect();
+                    recording.outcome = Some(Ok((distance, steps)));
+                }
+                (Some("error"), Some(kind)) => {
+                    let rest: Vec<&str> = fields.collect();
+                    let error = match (kind, rest.as_slice()) {
+                        ("no-path", []) => PathError::NoPath,
+                        ("exceeds-max-distance", []) => PathError::ExceedsMaxDistance,
+                        ("cancelled", []) => PathError::Cancelled,
+                        ("outside-map", [x, y]) => PathError::OutsideMap((
+                            x.parse().map_err(|_| malformed())?,
+                            y.parse().map_err(|_| malformed())?,
+                        )),
+                        (kind, [x, y]) => {
+                            let position = (
+                                x.parse().map_err(|_| malformed())?,
+                                y.parse().map_err(|_| malformed())?,
+                            );
+                            match kind {
+                                "start-out-of-bounds" => PathError::StartOutOfBounds(position),
+                                "goal-out-of-bounds" => PathError::GoalOutOfBounds(position),
+                                "start-blocked" => PathError::StartBlocked(position),
+                                "goal-blocked" => PathError::GoalBlocked(position),
+                                _ => return Err(malformed()),
+                            }
+                        }
+                        _ => return Err(malformed()),
+                    };
+                    recording.outcome = Some(Err(error));
+                }
+                _ => return Err(malformed()),
+            }
+        }
+        Ok(recording)
+    }
+}
+
+impl SearchObserver for SearchRecording {
+    fn observe(&mut self, event: SearchEvent) {
+        self.events.push(event);
+    }
+}
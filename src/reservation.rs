@@ -0,0 +1,185 @@
+use alloc::collections::BTreeMap;
+
+use crate::grid::Coords2D;
+
+use crate::memory::map_size;
+use crate::{MemoryFootprint, TimedRoute};
+
+///Tiles claimed by agents at particular timesteps, so that separately planned agents never collide.
+///Agents are identified by any number the caller chooses.
+///
+///Planners such as [`space_time_a_star_reserved`](crate::space_time_a_star_reserved) read the table,
+///and external movement systems can reserve and release tiles directly to coordinate with them.
+///An agent that finishes its route stays parked on its last tile until released.
+///
+///Reservations are kept in ordered collections rather than hash maps, so planning stays deterministic.
+///To stay bounded in memory, call [`advance`](ReservationTable::advance) as time passes to forget
+///past timesteps, and create the table [`with_window`](ReservationTable::with_window) to ignore
+///reservations too far into the future.
+/// # Examples
+///
+/// ```
+/// use blitz_path::{ReservationTable, TimedRoute};
+///
+/// let mut table = ReservationTable::new();
+/// table.reserve_route(7, &TimedRoute::from((0, vec![(1, 1), (2, 1), (2, 1), (3, 1)])));
+/// assert_eq!(table.holder((2, 1), 2), Some(7));
+/// assert_eq!(table.holder((3, 1), 100), Some(7));
+/// assert!(table.is_free((1, 1), 1));
+///
+/// table.release(7);
+/// assert!(table.is_free((3, 1), 100));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ReservationTable {
+    //Tiles held at a single timestep, ordered by tile so every reservation of a tile is a range
+    cells: BTreeMap<(Coords2D, usize), usize>,
+    //Moves from one tile to the next, starting at the given timestep
+    moves: BTreeMap<(Coords2D, Coords2D, usize), usize>,
+    //Tiles held forever from the given timestep, by agents that have finished their route
+    parked: BTreeMap<Coords2D, (usize, usize)>,
+    //The earliest timestep still stored
+    now: usize,
+    //How many timesteps past now reservations are stored for
+    window: Option<usize>,
+}
+
+impl ReservationTable {
+    ///Creates an empty table holding reservations for any time in the future.
+    pub fn new() -> ReservationTable {
+        ReservationTable::default()
+    }
+
+    ///Creates an empty table that only stores reservations less than `timesteps` after the current time,
+    ///as used by windowed cooperative pathfinding. Agents replan before the window runs out.
+    pub fn with_window(timesteps: usize) -> ReservationTable {
+        ReservationTable {
+            window: Some(timesteps),
+            ..ReservationTable::default()
+        }
+    }
+
+    ///Builds a table from routes planned earlier, numbering their agents from 0 in order.
+    pub fn from_routes(routes: &[TimedRoute]) -> ReservationTable {
+        let mut table = ReservationTable::new();
+        for (agent, route) in routes.iter().enumerate() {
+            table.reserve_route(agent, route);
+        }
+        table
+    }
+
+    ///Returns the earliest timestep still stored.
+    pub fn now(&self) -> usize {
+        self.now
+    }
+
+    ///Forgets every reservation before `time`, which becomes the current time.
+    ///Parked agents are kept, as they still hold their tile.
+    pub fn advance(&mut self, time: usize) {
+        self.now = self.now.max(time);
+        let now = self.now;
+        self.cells.retain(|(_, at), _| *at >= now);
+        self.moves.retain(|(_, _, at), _| *at >= now);
+    }
+
+    ///Claims `cell` at `time` for `agent`, returning false if another agent already holds it.
+    ///Times before the current time or beyond the window are not stored.
+    pub fn reserve(&mut self, agent: usize, cell: Coords2D, time: usize) -> bool {
+        if self
+            .holder(cell, time)
+            .is_some_and(|holder| holder != agent)
+        {
+            return false;
+        }
+        if self.stores(time) {
+            self.cells.insert((cell, time), agent);
+        }
+        true
+    }
+
+    ///Claims every tile and move of `route` for `agent`, then parks the agent on its last tile.
+    ///The route is expected not to conflict with earlier reservations, as when planned against this table.
+    pub fn reserve_route(&mut self, agent: usize, route: &TimedRoute) {
+        let positions = route.positions();
+        for (offset, position) in positions.iter().enumerate() {
+            let time = route.start_time() + offset;
+            if self.stores(time) {
+                self.cells.insert((*position, time), agent);
+            }
+        }
+        for (offset, pair) in positions.windows(2).enumerate() {
+            let time = route.start_time() + offset;
+            if pair[0] != pair[1] && self.stores(time) {
+                self.moves.insert((pair[0], pair[1], time), agent);
+            }
+        }
+        if let Some(last) = positions.last() {
+            self.parked.insert(*last, (route.end_time(), agent));
+        }
+    }
+
+    ///Removes every reservation held by `agent`, including its parked tile.
+    pub fn release(&mut self, agent: usize) {
+        self.cells.retain(|_, holder| *holder != agent);
+        self.moves.retain(|_, holder| *holder != agent);
+        self.parked.retain(|_, (_, holder)| *holder != agent);
+    }
+
+    ///Returns the agent holding `cell` at `time`, if any.
+    pub fn holder(&self, cell: Coords2D, time: usize) -> Option<usize> {
+        self.cells.get(&(cell, time)).copied().or_else(|| {
+            self.parked
+                .get(&cell)
+                .filter(|(from, _)| time >= *from)
+                .map(|(_, agent)| *agent)
+        })
+    }
+
+    ///Returns true if no agent holds `cell` at `time`.
+    pub fn is_free(&self, cell: Coords2D, time: usize) -> bool {
+        self.holder(cell, time).is_none()
+    }
+
+    //Whether moving between two tiles from this timestep would swap places with another agent
+    pub(crate) fn is_swap(&self, from: Coords2D, to: Coords2D, time: usize) -> bool {
+        from != to && self.moves.contains_key(&(to, from, time))
+    }
+
+    //Whether no agent holds the tile at this timestep or any later one
+    pub(crate) fn is_free_from(&self, cell: Coords2D, time: usize) -> bool {
+        !self.parked.contains_key(&cell)
+            && self
+                .cells
+                .range((cell, time)..=(cell, usize::MAX))
+                .next()
+                .is_none()
+    }
+
+    //Whether an agent has finished its route on the tile, so holds it from some time on forever
+    pub(crate) fn is_parked(&self, cell: Coords2D) -> bool {
+        self.parked.contains_key(&cell)
+    }
+
+    //The last timestep any reservation starts at, or the current time if there are none
+    pub(crate) fn latest(&self) -> usize {
+        let cells = self.cells.keys().map(|(_, time)| *time);
+        let moves = self.moves.keys().map(|(_, _, time)| *time);
+        let parked = self.parked.values().map(|(time, _)| *time);
+        cells.chain(moves).chain(parked).fold(self.now, usize::max)
+    }
+
+    //Whether a reservation at this timestep falls inside the stored range
+    fn stores(&self, time: usize) -> bool {
+        time >= self.now
+            && self
+                .window
+                .is_none_or(|window| time < self.now.saturating_add(window))
+    }
+}
+
+impl MemoryFootprint for ReservationTable {
+    fn heap_size(&self) -> usize {
+        map_size(&self.cells) + map_size(&self.moves) + map_size(&self.parked)
+    }
+}
@@ -0,0 +1,190 @@
+use alloc::collections::{BTreeSet, BinaryHeap};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+
+use crate::grid::Coords2D;
+use crate::grid::Map2D;
+use crate::grid::MovingAiMap;
+
+use crate::utils::{compose, distance, index, neighbours, prepare};
+use crate::{PathError, Route, SearchOptions};
+
+//Slack allowed when comparing amounts of the resource, so rounding never strands a route
+const EPSILON: f64 = 1e-9;
+
+//A way of reaching a tile, with the distance travelled and the resource left on arrival
+#[derive(Debug, Copy, Clone)]
+struct Label {
+    position: Coords2D,
+    cost: f64,
+    remaining: f64,
+    parent: Option<usize>,
+}
+
+//An entry in the open list, pointing at its label
+#[derive(Debug, Copy, Clone)]
+struct Entry {
+    f: f64,
+    h: f64,
+    position: Coords2D,
+    label: usize,
+}
+
+impl PartialOrd for Entry {
+    fn partial_cmp(&self, other: &Entry) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Entry {
+    fn cmp(&self, other: &Entry) -> Ordering {
+        //Reversed to make the max-heap a min-heap, with ties broken as for Node
+        //and then on the order labels were made, so results never depend on the heap
+        other
+            .f
+            .total_cmp(&self.f)
+            .then_with(|| other.h.total_cmp(&self.h))
+            .then_with(|| other.position.cmp(&self.position))
+            .then_with(|| other.label.cmp(&self.label))
+    }
+}
+
+impl PartialEq for Entry {
+    fn eq(&self, other: &Entry) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Entry {}
+
+///Finds the shortest route from `start` to `goal` for an agent that uses up a resource such as fuel or charge
+///as it moves, and can refill it at `stations`. Every step uses as much of the resource as its length,
+///the agent holds at most `capacity`, and it sets off with `initial`. Entering a station, or starting on one,
+///fills the agent back up to capacity. A route is only feasible if the agent never runs out between stations.
+///
+///Returns `PathError::NoPath` if no feasible route exists. The route may pass through the same tile
+///more than once, such as doubling back to a station.
+///
+///The search keeps every way of reaching a tile that is not both longer and emptier than another,
+///so it explores more than an unconstrained search, especially when stations are far apart.
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+/// use blitz_path::SearchOptions;
+///
+/// let map = movingai::parser::parse_map_file(Path::new("./tests/map/maze512-32-9.map")).expect("Could not load map.");
+///
+/// // Too far to fly straight there, so the drone stops to charge on the way
+/// let route = blitz_path::resource_path(&map, (1, 1), (13, 1), 8.0, 8.0, &[(7, 2)], &SearchOptions::new()).unwrap();
+/// assert!(route.steps().contains(&(7, 2)));
+///
+/// assert!(blitz_path::resource_path(&map, (1, 1), (13, 1), 8.0, 8.0, &[], &SearchOptions::new()).is_err());
+/// ```
+pub fn resource_path(
+    map: &MovingAiMap,
+    start: Coords2D,
+    goal: Coords2D,
+    capacity: f64,
+    initial: f64,
+    stations: &[Coords2D],
+    options: &SearchOptions,
+) -> Result<Route, PathError> {
+    let (start, goal) = prepare(map, start, goal, options)?;
+    if options.exceeds_max_distance(distance(start, goal)) {
+        return Err(PathError::ExceedsMaxDistance);
+    }
+    let stations: BTreeSet<Coords2D> = stations.iter().copied().collect();
+    let refill = |position: Coords2D, remaining: f64| {
+        if stations.contains(&position) {
+            capacity
+        } else {
+            remaining
+        }
+    };
+
+    let mut labels = vec![Label {
+        position: start,
+        cost: 0.0,
+        remaining: refill(start, initial.min(capacity)),
+        parent: None,
+    }];
+    //Labels already expanded at each tile, which no later label may be dominated by
+    let mut settled: Vec<Vec<usize>> = vec![Vec::new(); map.width() * map.height()];
+    let dominated = |labels: &[Label], settled: &[usize], cost: f64, remaining: f64| {
+        settled.iter().any(|other| {
+            labels[*other].cost <= cost + EPSILON && labels[*other].remaining >= remaining - EPSILON
+        })
+    };
+
+    let mut open = BinaryHeap::new();
+    let h = distance(start, goal);
+    open.push(Entry {
+        f: h,
+        h,
+        position: start,
+        label: 0,
+    });
+
+    let mut pruned = false;
+    let mut iteration = 0;
+    while let Some(entry) = open.pop() {
+        let label = labels[entry.label];
+        let tile_index = index(map, label.position);
+        if dominated(&labels, &settled[tile_index], label.cost, label.remaining) {
+            continue;
+        }
+        if options.should_cancel(iteration) {
+            return Err(PathError::Cancelled);
+        }
+        iteration += 1;
+
+        if label.position == goal {
+            let mut path = vec![label.position];
+            let mut parent = label.parent;
+            while let Some(current) = parent {
+                path.push(labels[current].position);
+                parent = labels[current].parent;
+            }
+            return Ok(Route::from((label.cost, compose(path, options))));
+        }
+        settled[tile_index].push(entry.label);
+
+        for next in neighbours(map, label.position) {
+            let step = distance(label.position, next);
+            if label.remaining + EPSILON < step {
+                continue;
+            }
+            let cost = label.cost + step;
+            let h = distance(next, goal);
+            if options.exceeds_max_distance(cost + h) {
+                pruned = true;
+                continue;
+            }
+            let remaining = refill(next, label.remaining - step);
+            if dominated(&labels, &settled[index(map, next)], cost, remaining) {
+                continue;
+            }
+
+            labels.push(Label {
+                position: next,
+                cost,
+                remaining,
+                parent: Some(entry.label),
+            });
+            open.push(Entry {
+                f: cost + h,
+                h,
+                position: next,
+                label: labels.len() - 1,
+            });
+        }
+    }
+
+    if pruned {
+        Err(PathError::ExceedsMaxDistance)
+    } else {
+        Err(PathError::NoPath)
+    }
+}
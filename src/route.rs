@@ -1,16 +1,30 @@
-use std::convert::From;
+use alloc::collections::BTreeSet;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
 
-use movingai::Coords2D;
+use crate::grid::Coords2D;
+
+use crate::memory::vec_size;
+use crate::utils::distance;
+use crate::MemoryFootprint;
 
 ///Describes a route between two points.
 ///Giving the total distance needed to travel and a vector of each step needed.
-pub struct Route {
+///Steps are map coordinates unless the route came from an [`OffsetMap`](crate::OffsetMap),
+///in which case they are signed world coordinates.
+///
+///When the start is the goal every search returns [`Route::empty`], a distance of 0.0 with the start
+///as its only step, and when the goal neighbours the start it returns [`Route::single_step`].
+///Search options that leave out the start or repeat the goal apply to these routes as to any other.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Route<C = Coords2D> {
     distance: f64,
-    steps: Vec<Coords2D>,
+    steps: Vec<C>,
 }
 
-impl From<(f64, Vec<Coords2D>)> for Route {
-    fn from(item: (f64, Vec<Coords2D>)) -> Self {
+impl<C> From<(f64, Vec<C>)> for Route<C> {
+    fn from(item: (f64, Vec<C>)) -> Self {
         Route {
             distance: item.0,
             steps: item.1,
@@ -18,10 +32,10 @@ impl From<(f64, Vec<Coords2D>)> for Route {
     }
 }
 
-impl Route {
-    ///Returns a vector of Coords2D, each representing a step in the path.
+impl<C: Copy> Route<C> {
+    ///Returns a vector of coordinates, each representing a step in the path.
     ///Organised in reverse order (destination is at [0]) to allow calling .pop() to get each step.
-    pub fn steps(&self) -> Vec<Coords2D> {
+    pub fn steps(&self) -> Vec<C> {
         self.steps.clone()
     }
 
@@ -29,4 +43,239 @@ impl Route {
     pub fn distance(&self) -> f64 {
         self.distance
     }
+
+    ///Converts every step of the route into another coordinate space, keeping the distance.
+    pub fn map_steps<T, F: FnMut(C) -> T>(self, convert: F) -> Route<T> {
+        Route {
+            distance: self.distance,
+            steps: self.steps.into_iter().map(convert).collect(),
+        }
+    }
+}
+
+impl Route {
+    ///Creates the route for a search whose start is its goal, with a distance of 0.0
+    ///and `start` as its only step.
+    /// # Examples
+    ///
+    /// ```
+    /// use std::path::Path;
+    /// use blitz_path::Route;
+    ///
+    /// let map = movingai::parser::parse_map_file(Path::new("./tests/map/maze512-32-9.map")).expect("Could not load map.");
+    ///
+    /// let path = blitz_path::a_star_path(&map, (1, 1), (1, 1)).unwrap();
+    /// assert_eq!(path.steps(), Route::empty((1, 1)).steps());
+    /// assert_eq!(path.distance(), 0.0);
+    /// ```
+    pub fn empty(start: Coords2D) -> Route {
+        Route {
+            distance: 0.0,
+            steps: vec![start],
+        }
+    }
+
+    ///Creates the route of a single step from `from` to its neighbour `to`, costing the distance between them,
+    ///with `to` first as for every route.
+    /// # Examples
+    ///
+    /// ```
+    /// use std::path::Path;
+    /// use blitz_path::Route;
+    ///
+    /// let map = movingai::parser::parse_map_file(Path::new("./tests/map/maze512-32-9.map")).expect("Could not load map.");
+    ///
+    /// let step = Route::single_step((1, 1), (2, 2));
+    /// assert_eq!(step.steps(), [(2, 2), (1, 1)]);
+    /// assert_eq!(step.distance(), 2f64.sqrt());
+    ///
+    /// let path = blitz_path::jps_path(&map, (1, 1), (2, 2)).unwrap();
+    /// assert_eq!((path.steps(), path.distance()), (step.steps(), step.distance()));
+    /// ```
+    pub fn single_step(from: Coords2D, to: Coords2D) -> Route {
+        Route {
+            distance: distance(from, to),
+            steps: vec![to, from],
+        }
+    }
+
+    ///Returns the smallest and largest x and y of any step, as the top-left and bottom-right tiles
+    ///of the rectangle the route stays inside, or `None` if the route has no steps.
+    /// # Examples
+    ///
+    /// ```
+    /// use blitz_path::Route;
+    ///
+    /// let route: Route = Route::from((4.0, vec![(5, 1), (3, 4), (1, 2)]));
+    /// assert_eq!(route.bounding_box(), Some(((1, 1), (5, 4))));
+    /// ```
+    pub fn bounding_box(&self) -> Option<(Coords2D, Coords2D)> {
+        let first = *self.steps.first()?;
+        Some(
+            self.steps
+                .iter()
+                .fold((first, first), |(low, high), (x, y)| {
+                    (
+                        (low.0.min(*x), low.1.min(*y)),
+                        (high.0.max(*x), high.1.max(*y)),
+                    )
+                }),
+        )
+    }
+
+    ///Returns every tile the route touches when followed from tile centre to tile centre,
+    ///filling in the tiles between steps that are not neighbours, such as jump points.
+    ///Where a line passes exactly through the corner of a tile, as every diagonal step does,
+    ///the tiles on both sides of the corner are included, since whether the move is allowed depends on them.
+    /// # Examples
+    ///
+    /// ```
+    /// use blitz_path::Route;
+    ///
+    /// let route: Route = Route::from((3.0, vec![(3, 0), (0, 0)]));
+    /// assert_eq!(route.cells_touched().len(), 4);
+    ///
+    /// let diagonal: Route = Route::from((2f64.sqrt(), vec![(1, 1), (0, 0)]));
+    /// assert_eq!(diagonal.cells_touched().len(), 4);
+    /// ```
+    pub fn cells_touched(&self) -> BTreeSet<Coords2D> {
+        let mut touched: BTreeSet<Coords2D> = self.steps.iter().copied().collect();
+        for pair in self.steps.windows(2) {
+            sweep(pair[1], pair[0], true, |tile| {
+                touched.insert(tile);
+            });
+        }
+        touched
+    }
+
+    ///Returns the point `lookahead` further along the route than the point of the route nearest `position`,
+    ///the target a pure pursuit controller steers towards. Positions are continuous, where tile `(x, y)` covers
+    ///`x..x + 1` and `y..y + 1`, and the route runs in straight lines between the centres of its steps.
+    ///The nearest point is the earliest along the route where several are equally near, and the target
+    ///stops at the goal. Returns `None` if the route has no steps.
+    /// # Examples
+    ///
+    /// ```
+    /// use blitz_path::Route;
+    ///
+    /// let route: Route = Route::from((6.0, vec![(3, 3), (3, 0), (0, 0)]));
+    ///
+    /// //An agent drifted below the first leg steers to a point 1.5 tiles on from level with it
+    /// assert_eq!(route.target_at((1.5, 0.9), 1.5), Some((3.0, 0.5)));
+    /// assert_eq!(route.target_at((2.0, 0.5), 2.0), Some((3.5, 1.0)));
+    /// assert_eq!(route.target_at((0.0, 0.0), 10.0), Some((3.5, 3.5)));
+    /// ```
+    pub fn target_at(&self, position: (f64, f64), lookahead: f64) -> Option<(f64, f64)> {
+        let points: Vec<(f64, f64)> = self
+            .steps
+            .iter()
+            .rev()
+            .map(|(x, y)| (*x as f64 + 0.5, *y as f64 + 0.5))
+            .collect();
+        let first = *points.first()?;
+
+        //Find the nearest point on any leg, as the distance along the route to it
+        let mut nearest = (squared_distance(first, position), 0.0);
+        let mut travelled = 0.0;
+        for leg in points.windows(2) {
+            let (length, along) = project(leg[0], leg[1], position);
+            let distance = squared_distance(lerp(leg[0], leg[1], along / length), position);
+            if distance < nearest.0 {
+                nearest = (distance, travelled + along);
+            }
+            travelled += length;
+        }
+
+        //Walk on from there
+        let mut remaining = (nearest.1 + lookahead).max(0.0);
+        for leg in points.windows(2) {
+            let length = leg_length(leg[0], leg[1]);
+            if remaining <= length && length > 0.0 {
+                return Some(lerp(leg[0], leg[1], remaining / length));
+            }
+            remaining -= length;
+        }
+        points.last().copied()
+    }
+
+    //Every tile along the route, start first, with the tiles between distant steps filled in
+    pub(crate) fn cells(&self) -> Vec<Coords2D> {
+        let steps: Vec<Coords2D> = self.steps().into_iter().rev().collect();
+        let mut cells: Vec<Coords2D> = steps.first().copied().into_iter().collect();
+        for pair in steps.windows(2) {
+            sweep(pair[0], pair[1], false, |tile| cells.push(tile));
+        }
+        cells
+    }
+}
+
+//Straight line distance between two continuous positions
+fn leg_length(a: (f64, f64), b: (f64, f64)) -> f64 {
+    libm::sqrt(squared_distance(a, b))
+}
+
+fn squared_distance(a: (f64, f64), b: (f64, f64)) -> f64 {
+    let (dx, dy) = (a.0 - b.0, a.1 - b.1);
+    dx * dx + dy * dy
+}
+
+//The point a fraction t of the way from a to b
+fn lerp(a: (f64, f64), b: (f64, f64), t: f64) -> (f64, f64) {
+    if t.is_nan() {
+        return a;
+    }
+    (a.0 + (b.0 - a.0) * t, a.1 + (b.1 - a.1) * t)
+}
+
+//Returns the length of the leg from a to b, and how far along it the point nearest p lies
+fn project(a: (f64, f64), b: (f64, f64), p: (f64, f64)) -> (f64, f64) {
+    let length = leg_length(a, b);
+    if length == 0.0 {
+        return (0.0, 0.0);
+    }
+    let along = ((p.0 - a.0) * (b.0 - a.0) + (p.1 - a.1) * (b.1 - a.1)) / length;
+    (length, along.clamp(0.0, length))
+}
+
+//Visits every tile after `from` that the line between two tile centres passes through, in order,
+//and if `corners` is set both tiles beside any corner it crosses
+fn sweep<V: FnMut(Coords2D)>(from: Coords2D, to: Coords2D, corners: bool, mut visit: V) {
+    let (nx, ny) = (from.0.abs_diff(to.0), from.1.abs_diff(to.1));
+    let step_x = |x: usize| if to.0 > from.0 { x + 1 } else { x - 1 };
+    let step_y = |y: usize| if to.1 > from.1 { y + 1 } else { y - 1 };
+
+    let (mut x, mut y) = from;
+    let (mut ix, mut iy) = (0, 0);
+    while ix < nx || iy < ny {
+        //Compares where the line next crosses a column boundary with where it next crosses a row boundary
+        let across = (1 + 2 * ix) * ny;
+        let down = (1 + 2 * iy) * nx;
+        match across.cmp(&down) {
+            Ordering::Equal => {
+                if corners {
+                    visit((step_x(x), y));
+                    visit((x, step_y(y)));
+                }
+                x = step_x(x);
+                y = step_y(y);
+                ix += 1;
+                iy += 1;
+            }
+            Ordering::Less => {
+                x = step_x(x);
+                ix += 1;
+            }
+            Ordering::Greater => {
+                y = step_y(y);
+                iy += 1;
+            }
+        }
+        visit((x, y));
+    }
+}
+
+impl<C> MemoryFootprint for Route<C> {
+    fn heap_size(&self) -> usize {
+        vec_size(&self.steps)
+    }
 }
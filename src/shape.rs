@@ -0,0 +1,74 @@
+use alloc::vec::Vec;
+
+use crate::utils::distance;
+use crate::{Direction, Route};
+
+impl Route {
+    ///Returns how far the route turns in total, counted in steps of 45 degrees as
+    ///[`a_star_path_turning`](crate::a_star_path_turning) charges for them, so a right angle counts as two turns
+    ///and turning back on itself as four. Turns are measured tile by tile, so routes count the same
+    ///however their steps are listed, and a diagonal staircase of alternating steps counts every step.
+    /// # Examples
+    ///
+    /// ```
+    /// use blitz_path::Route;
+    ///
+    /// //East along row 0, then south down column 3
+    /// let corner: Route = Route::from((6.0, vec![(3, 3), (3, 0), (0, 0)]));
+    /// assert_eq!(corner.turn_count(), 2);
+    /// assert_eq!(corner.direction_changes(), 1);
+    /// ```
+    pub fn turn_count(&self) -> usize {
+        self.headings()
+            .windows(2)
+            .map(|pair| {
+                let difference = (8 + pair[1] as usize - pair[0] as usize) % 8;
+                difference.min(8 - difference)
+            })
+            .sum()
+    }
+
+    ///Returns the number of tiles where the route changes direction, however sharply it turns there.
+    ///A straight route has none.
+    pub fn direction_changes(&self) -> usize {
+        self.headings()
+            .windows(2)
+            .filter(|pair| pair[0] != pair[1])
+            .count()
+    }
+
+    ///Returns the straight line distance from the start of the route to its goal divided by the length
+    ///of the route, from 1.0 for a straight route towards 0.0 for one that wanders far out of its way.
+    ///The length is measured along the steps, so costs added by weighted or turning searches are ignored.
+    ///Routes that go nowhere, including empty ones, count as straight.
+    /// # Examples
+    ///
+    /// ```
+    /// use blitz_path::Route;
+    ///
+    /// let straight: Route = Route::from((3.0, vec![(3, 0), (0, 0)]));
+    /// assert_eq!(straight.straightness(), 1.0);
+    ///
+    /// let corner: Route = Route::from((6.0, vec![(3, 3), (3, 0), (0, 0)]));
+    /// assert_eq!(corner.straightness(), 18f64.sqrt() / 6.0);
+    /// ```
+    pub fn straightness(&self) -> f64 {
+        let steps = self.steps();
+        let length: f64 = steps
+            .windows(2)
+            .map(|pair| distance(pair[0], pair[1]))
+            .sum();
+        match (steps.first(), steps.last()) {
+            (Some(goal), Some(start)) if length > 0.0 => distance(*start, *goal) / length,
+            _ => 1.0,
+        }
+    }
+
+    //The direction of every step between neighbouring tiles along the route, start first
+    fn headings(&self) -> Vec<Direction> {
+        self.cells()
+            .windows(2)
+            .filter_map(|pair| Direction::between(pair[0], pair[1]))
+            .collect()
+    }
+}
@@ -0,0 +1,58 @@
+use alloc::vec::Vec;
+
+use crate::utils::distance;
+use crate::Route;
+
+impl Route {
+    ///Returns the discrete Fréchet distance between this route and `other`: the shortest leash that lets
+    ///two walkers follow the routes from start to goal, each only ever moving forwards, while staying connected.
+    ///Identical routes score 0.0, and routes that only differ in how they thread a room score close to it,
+    ///so tests can check two searches find essentially the same route without demanding equal steps.
+    ///
+    ///Tiles between steps that are not neighbours, such as jump points, are filled in first,
+    ///so routes compare the same however their steps are listed. Returns infinity if only one route is empty.
+    /// # Examples
+    ///
+    /// ```
+    /// use blitz_path::Route;
+    ///
+    /// let straight: Route = Route::from((4.0, vec![(4, 0), (0, 0)]));
+    /// let bumped: Route = Route::from((4.0, vec![(4, 0), (3, 0), (2, 1), (1, 0), (0, 0)]));
+    /// assert_eq!(straight.frechet_distance(&bumped), 1.0);
+    /// assert!(straight.is_similar(&bumped, 1.0));
+    /// ```
+    pub fn frechet_distance(&self, other: &Route) -> f64 {
+        let (ours, theirs) = (self.cells(), other.cells());
+        if ours.is_empty() || theirs.is_empty() {
+            return if ours.len() == theirs.len() {
+                0.0
+            } else {
+                f64::INFINITY
+            };
+        }
+
+        //The shortest leash reaching each pair of tiles, a row of the other route at a time
+        let mut previous: Vec<f64> = Vec::with_capacity(theirs.len());
+        let mut current: Vec<f64> = Vec::with_capacity(theirs.len());
+        for (i, ours) in ours.iter().enumerate() {
+            current.clear();
+            for (j, theirs) in theirs.iter().enumerate() {
+                let reach = match (i, j) {
+                    (0, 0) => 0.0,
+                    (0, _) => current[j - 1],
+                    (_, 0) => previous[0],
+                    _ => previous[j].min(previous[j - 1]).min(current[j - 1]),
+                };
+                current.push(reach.max(distance(*ours, *theirs)));
+            }
+            core::mem::swap(&mut previous, &mut current);
+        }
+        previous[theirs.len() - 1]
+    }
+
+    ///Returns true if the [Fréchet distance](Route::frechet_distance) between this route and `other`
+    ///is no more than `tolerance` tiles.
+    pub fn is_similar(&self, other: &Route, tolerance: f64) -> bool {
+        self.frechet_distance(other) <= tolerance
+    }
+}
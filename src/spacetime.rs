@@ -0,0 +1,323 @@
+use alloc::collections::{BTreeMap, BTreeSet, BinaryHeap};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cmp::Reverse;
+
+use crate::grid::Coords2D;
+use crate::grid::Map2D;
+use crate::grid::MovingAiMap;
+
+use crate::memory::vec_size;
+use crate::utils::{distance, neighbours, prepare};
+use crate::{MemoryFootprint, PathError, ReservationTable, Route, SearchOptions};
+
+//Timesteps searched for each tile of the map's width and height when no time horizon is set,
+//enough for the longest routes through benchmark mazes while keeping the searched states linear in the map
+const HORIZON_PER_SIDE: usize = 4;
+
+///A route through space and time, giving the tile an agent occupies at each timestep.
+///Consecutive timesteps on the same tile are waits. Once the route ends the agent stays on its last tile.
+///
+///Unlike [`Route`], positions are listed from the start, one per timestep from the start time.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TimedRoute {
+    start_time: usize,
+    positions: Vec<Coords2D>,
+}
+
+impl From<(usize, Vec<Coords2D>)> for TimedRoute {
+    ///Builds a route from its start time and the tile occupied at each timestep from then on.
+    fn from(item: (usize, Vec<Coords2D>)) -> Self {
+        TimedRoute {
+            start_time: item.0,
+            positions: item.1,
+        }
+    }
+}
+
+impl TimedRoute {
+    ///Returns the timestep the route starts at.
+    pub fn start_time(&self) -> usize {
+        self.start_time
+    }
+
+    ///Returns the timestep the route reaches its last tile.
+    pub fn end_time(&self) -> usize {
+        self.start_time + self.positions.len().saturating_sub(1)
+    }
+
+    ///Returns the tile occupied at each timestep, starting from the start time.
+    pub fn positions(&self) -> &[Coords2D] {
+        &self.positions
+    }
+
+    ///Returns the tile occupied at `time`, which is the first tile before the route starts
+    ///and the last tile after it ends. Returns `None` if the route is empty.
+    pub fn position_at(&self, time: usize) -> Option<Coords2D> {
+        let offset = time.saturating_sub(self.start_time);
+        self.positions
+            .get(offset)
+            .or_else(|| self.positions.last())
+            .copied()
+    }
+
+    ///Converts the route into a plain [`Route`] through the same tiles, dropping the waits.
+    ///The distance is the length travelled rather than the time taken.
+    pub fn to_route(&self) -> Route {
+        let mut steps: Vec<Coords2D> = self.positions.clone();
+        steps.dedup();
+        let travelled = steps
+            .windows(2)
+            .map(|pair| distance(pair[0], pair[1]))
+            .sum();
+        steps.reverse();
+        Route::from((travelled, steps))
+    }
+}
+
+///Plans a route through space and time that avoids the tiles and moves of routes planned earlier,
+///the building block of cooperative pathfinding. The agent leaves `start` at `start_time`, and
+///each timestep either moves to a neighbouring tile or waits where it is.
+///
+///A tile cannot be occupied by two agents at once, and two agents cannot swap places.
+///Agents stay on the last tile of their route once they reach it, so the goal is only reached
+///at a time from which no other agent needs it. Routes arrive as early as possible.
+///
+///The search gives up with `PathError::NoPath` after the [`time_horizon`](SearchOptions::time_horizon)
+///of `options`, which by default runs a few times the width and height of the map past the last reservation.
+///It fails with `PathError::NoPath` straight away if another agent has finished its route on the goal,
+///and with `PathError::StartBlocked` if another agent holds the start at the start time.
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+/// use blitz_path::SearchOptions;
+///
+/// let map = movingai::parser::parse_map_file(Path::new("./tests/map/maze512-32-9.map")).expect("Could not load map.");
+/// let options = SearchOptions::new();
+///
+/// let first = blitz_path::space_time_a_star(&map, (1, 1), (5, 1), 0, &[], &options).unwrap();
+/// let second = blitz_path::space_time_a_star(&map, (5, 1), (1, 1), 0, &[first.clone()], &options).unwrap();
+///
+/// for time in 0..=second.end_time().max(first.end_time()) {
+///     assert_ne!(first.position_at(time), second.position_at(time));
+/// }
+/// ```
+pub fn space_time_a_star(
+    map: &MovingAiMap,
+    start: Coords2D,
+    goal: Coords2D,
+    start_time: usize,
+    reserved: &[TimedRoute],
+    options: &SearchOptions,
+) -> Result<TimedRoute, PathError> {
+    space_time_a_star_reserved(
+        map,
+        start,
+        goal,
+        start_time,
+        &ReservationTable::from_routes(reserved),
+        options,
+    )
+}
+
+///Plans a route through space and time like [`space_time_a_star`], avoiding the tiles held in `table`
+///rather than a list of routes. The route is not reserved; pass it to
+///[`ReservationTable::reserve_route`] once accepted.
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+/// use blitz_path::{ReservationTable, SearchOptions};
+///
+/// let map = movingai::parser::parse_map_file(Path::new("./tests/map/maze512-32-9.map")).expect("Could not load map.");
+/// let options = SearchOptions::new();
+///
+/// //Another system holds the goal for the first few timesteps
+/// let mut table = ReservationTable::new();
+/// for time in 0..6 {
+///     table.reserve(99, (5, 1), time);
+/// }
+///
+/// let route = blitz_path::space_time_a_star_reserved(&map, (1, 1), (5, 1), 0, &table, &options).unwrap();
+/// assert_eq!(route.end_time(), 6);
+/// table.reserve_route(0, &route);
+/// ```
+pub fn space_time_a_star_reserved(
+    map: &MovingAiMap,
+    start: Coords2D,
+    goal: Coords2D,
+    start_time: usize,
+    table: &ReservationTable,
+    options: &SearchOptions,
+) -> Result<TimedRoute, PathError> {
+    //The goal can never be held for good while another agent is parked on it
+    let (snapped_start, snapped_goal) = prepare(map, start, goal, options)?;
+    if table.is_parked(snapped_goal) && table.is_free(snapped_start, start_time) {
+        return Err(PathError::NoPath);
+    }
+    search(
+        map,
+        start,
+        goal,
+        start_time,
+        options,
+        table.latest(),
+        |tile, time| !table.is_free(tile, time),
+        |from, to, time| table.is_swap(from, to, time),
+        |goal, time| table.is_free_from(goal, time),
+    )
+}
+
+///Searches over every pair of tile and timestep for the earliest arrival at `goal`, leaving `start`
+///at `start_time`. Each timestep the agent either moves to a neighbouring tile or waits where it is,
+///and may never be on a tile while `blocked` returns true for that tile and timestep.
+///Suits scheduling problems where the map changes over time, such as doors that open and close
+///or tracks that are only free between trains.
+///
+///Unlike [`space_time_a_star`], the search ends as soon as the goal is reached,
+///whether or not it is blocked later on.
+///
+///The search gives up with `PathError::NoPath` after the [`time_horizon`](SearchOptions::time_horizon)
+///of `options`, which by default runs a few times the width and height of the map past the start time,
+///and with `PathError::StartBlocked` if the start is blocked at the start time.
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+/// use blitz_path::SearchOptions;
+///
+/// let map = movingai::parser::parse_map_file(Path::new("./tests/map/maze512-32-9.map")).expect("Could not load map.");
+///
+/// //A door on (3, 1) that is only open on even timesteps
+/// let door = |tile: (usize, usize), time: usize| tile == (3, 1) && time % 2 == 1;
+/// let route = blitz_path::time_expanded_search(&map, (1, 1), (5, 1), 0, door, &SearchOptions::new()).unwrap();
+///
+/// assert_eq!(route.end_time(), 4);
+/// ```
+pub fn time_expanded_search<B>(
+    map: &MovingAiMap,
+    start: Coords2D,
+    goal: Coords2D,
+    start_time: usize,
+    blocked: B,
+    options: &SearchOptions,
+) -> Result<TimedRoute, PathError>
+where
+    B: Fn(Coords2D, usize) -> bool,
+{
+    search(
+        map,
+        start,
+        goal,
+        start_time,
+        options,
+        start_time,
+        blocked,
+        |_, _, _| false,
+        |_, _| true,
+    )
+}
+
+//Earliest arrival search over (tile, timestep). occupied(tile, time) forbids being on a tile,
+//swaps(from, to, time) forbids the move leaving from at time, and arrives(goal, time) decides
+//whether reaching the goal, after snapping, at that time ends the search. The default horizon runs from busy_until,
+//after which nothing but the map changes
+#[allow(clippy::too_many_arguments)]
+fn search<O, S, A>(
+    map: &MovingAiMap,
+    start: Coords2D,
+    goal: Coords2D,
+    start_time: usize,
+    options: &SearchOptions,
+    busy_until: usize,
+    occupied: O,
+    swaps: S,
+    arrives: A,
+) -> Result<TimedRoute, PathError>
+where
+    O: Fn(Coords2D, usize) -> bool,
+    S: Fn(Coords2D, Coords2D, usize) -> bool,
+    A: Fn(Coords2D, usize) -> bool,
+{
+    //Every action takes one timestep, so the cost of a state is fixed by its time
+    //and the first time a state is reached is as good as any other
+    let (start, goal) = prepare(map, start, goal, options)?;
+    if occupied(start, start_time) {
+        return Err(PathError::StartBlocked(start));
+    }
+    let last_time = match options.time_horizon {
+        Some(horizon) => start_time.saturating_add(horizon),
+        None => busy_until
+            .max(start_time)
+            .saturating_add(HORIZON_PER_SIDE * (map.width() + map.height())),
+    };
+
+    //Ordered on estimated arrival time, then remaining moves, then tile, so ties never depend on insertion order
+    let mut open = BinaryHeap::new();
+    let mut reached = BTreeSet::new();
+    let mut parents: BTreeMap<(Coords2D, usize), Coords2D> = BTreeMap::new();
+    let h = moves_between(start, goal);
+    open.push(Reverse((start_time + h, h, start, start_time)));
+    reached.insert((start, start_time));
+
+    let mut iteration = 0;
+    while let Some(Reverse((_, _, position, time))) = open.pop() {
+        if options.should_cancel(iteration) {
+            return Err(PathError::Cancelled);
+        }
+        iteration += 1;
+
+        if position == goal && arrives(goal, time) {
+            return Ok(unwind(&parents, start_time, goal, time));
+        }
+        if time >= last_time {
+            continue;
+        }
+
+        //Waiting is always considered alongside moving
+        for next in core::iter::once(position).chain(neighbours(map, position)) {
+            let arrival = time + 1;
+            if occupied(next, arrival)
+                || swaps(position, next, time)
+                || !reached.insert((next, arrival))
+            {
+                continue;
+            }
+            parents.insert((next, arrival), position);
+            let h = moves_between(next, goal);
+            open.push(Reverse((arrival + h, h, next, arrival)));
+        }
+    }
+
+    Err(PathError::NoPath)
+}
+
+//The fewest moves between two tiles when diagonal moves take one timestep
+fn moves_between(a: Coords2D, b: Coords2D) -> usize {
+    a.0.abs_diff(b.0).max(a.1.abs_diff(b.1))
+}
+
+//Follow the parents back from the goal state
+fn unwind(
+    parents: &BTreeMap<(Coords2D, usize), Coords2D>,
+    start_time: usize,
+    goal: Coords2D,
+    goal_time: usize,
+) -> TimedRoute {
+    let mut positions = vec![goal];
+    let mut current = goal;
+    for time in (start_time + 1..=goal_time).rev() {
+        current = parents[&(current, time)];
+        positions.push(current);
+    }
+    positions.reverse();
+    TimedRoute::from((start_time, positions))
+}
+
+impl MemoryFootprint for TimedRoute {
+    fn heap_size(&self) -> usize {
+        vec_size(&self.positions)
+    }
+}
@@ -0,0 +1,289 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::grid::Coords2D;
+use crate::grid::Map2D;
+use crate::grid::MovingAiMap;
+
+use crate::memory::vec_size;
+use crate::{ClearanceMap, MemoryFootprint, Route};
+
+//Points each piece is divided into when measuring its length
+const SUBDIVISIONS: usize = 16;
+//Distance between the points checked against the map, in tiles
+const CHECK_SPACING: f64 = 0.05;
+
+type Point = (f64, f64);
+
+//A cubic Bezier curve, given by its end points and the two control points between them
+type Piece = [Point; 4];
+
+///How [`smooth_route`] turns the corners of a route into curves.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CurveKind {
+    ///A Catmull-Rom spline, which passes through the centre of every step of the route.
+    CatmullRom,
+    ///Quadratic Bezier curves between the midpoints of each step, using every corner as a control point.
+    ///Passes through neither the corners nor the centres of their tiles, but never swings wider than the route.
+    Bezier,
+}
+
+///A smooth curve along a route for animating vehicles, measured by distance travelled along it.
+///Positions are continuous, where tile `(x, y)` covers `x..x + 1` and `y..y + 1`,
+///so its centre is at `(x + 0.5, y + 0.5)`. The curve runs from the start of the route to its goal.
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+/// use blitz_path::CurveKind;
+///
+/// let map = movingai::parser::parse_map_file(Path::new("./tests/map/maze512-32-9.map")).expect("Could not load map.");
+///
+/// let route = blitz_path::a_star_path(&map, (1, 1), (6, 4)).unwrap();
+/// let curve = blitz_path::smooth_route(&map, &route, CurveKind::CatmullRom);
+/// assert_eq!(curve.point_at(0.0), Some((1.5, 1.5)));
+/// assert_eq!(curve.point_at(curve.length()), Some((6.5, 4.5)));
+/// assert_eq!(curve.straightened(), 0);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RouteCurve {
+    pieces: Vec<Piece>,
+    //Distance along the curve at the end of each subdivision of each piece
+    lengths: Vec<f64>,
+    straightened: usize,
+}
+
+impl RouteCurve {
+    ///Returns the length of the curve.
+    pub fn length(&self) -> f64 {
+        self.lengths.last().copied().unwrap_or(0.0)
+    }
+
+    ///Returns the position `distance` along the curve, clamped to its ends,
+    ///or `None` if the route had no steps.
+    pub fn point_at(&self, distance: f64) -> Option<(f64, f64)> {
+        let (piece, t) = self.locate(distance)?;
+        Some(evaluate(&self.pieces[piece], t))
+    }
+
+    ///Returns the unit direction of travel `distance` along the curve, clamped to its ends,
+    ///or `None` if the curve has no length there.
+    pub fn direction_at(&self, distance: f64) -> Option<(f64, f64)> {
+        let (piece, t) = self.locate(distance)?;
+        let (dx, dy) = derivative(&self.pieces[piece], t);
+        let length = libm::sqrt(dx * dx + dy * dy);
+        if length == 0.0 {
+            return None;
+        }
+        Some((dx / length, dy / length))
+    }
+
+    ///Returns positions every `spacing` along the curve, always including both ends.
+    pub fn sample(&self, spacing: f64) -> Vec<(f64, f64)> {
+        if self.pieces.is_empty() {
+            return Vec::new();
+        }
+        let length = self.length();
+        let count = if spacing > 0.0 {
+            libm::ceil(length / spacing) as usize
+        } else {
+            0
+        };
+        (0..count)
+            .map(|i| i as f64 * spacing)
+            .chain(core::iter::once(length))
+            .filter_map(|distance| self.point_at(distance))
+            .collect()
+    }
+
+    ///Returns how many pieces of the curve strayed onto tiles they were not allowed on,
+    ///and were replaced with the straight steps of the route.
+    pub fn straightened(&self) -> usize {
+        self.straightened
+    }
+
+    //Finds the piece and its parameter at a distance along the curve
+    fn locate(&self, distance: f64) -> Option<(usize, f64)> {
+        if self.pieces.is_empty() {
+            return None;
+        }
+        let distance = distance.clamp(0.0, self.length());
+        let sub = self
+            .lengths
+            .partition_point(|length| *length < distance)
+            .min(self.lengths.len() - 1);
+        let before = if sub == 0 { 0.0 } else { self.lengths[sub - 1] };
+        let span = self.lengths[sub] - before;
+        let within = if span > 0.0 {
+            (distance - before) / span
+        } else {
+            0.0
+        };
+        let t = ((sub % SUBDIVISIONS) as f64 + within) / SUBDIVISIONS as f64;
+        Some((sub / SUBDIVISIONS, t))
+    }
+}
+
+///Smooths a route into a curve of the given kind, checking that the curve never crosses a blocked tile.
+///Any piece of the curve that would is replaced by the straight steps of the route it was rounding,
+///so the curve stays on open ground as long as the route does. Routes should list every tile.
+pub fn smooth_route(map: &MovingAiMap, route: &Route, kind: CurveKind) -> RouteCurve {
+    build(route, kind, |tile| {
+        tile.0 < map.width() && tile.1 < map.height() && map.is_traversable(tile)
+    })
+}
+
+///Smooths a route as [`smooth_route`] does, but keeps the curve on tiles with a clearance of at least
+///`min_clearance`, so wide vehicles keep their distance from walls. Pieces that stray closer follow the route's
+///own steps instead, which are only as clear as the route itself.
+pub fn smooth_route_within(
+    route: &Route,
+    kind: CurveKind,
+    clearance: &ClearanceMap,
+    min_clearance: f64,
+) -> RouteCurve {
+    build(route, kind, |tile| clearance.get(tile) >= min_clearance)
+}
+
+fn build<A: Fn(Coords2D) -> bool>(route: &Route, kind: CurveKind, allowed: A) -> RouteCurve {
+    let points: Vec<Point> = route
+        .steps()
+        .into_iter()
+        .rev()
+        .map(|(x, y)| (x as f64 + 0.5, y as f64 + 0.5))
+        .collect();
+
+    //Each curved piece alongside the straight pieces that replace it if it strays
+    let candidates: Vec<(Piece, Vec<Piece>)> = match (kind, points.len()) {
+        (_, 0) => Vec::new(),
+        (_, 1) => vec![(line(points[0], points[0]), Vec::new())],
+        (CurveKind::CatmullRom, count) => (0..count - 1)
+            .map(|i| {
+                let before = points[i.saturating_sub(1)];
+                let after = points[(i + 2).min(count - 1)];
+                let (from, to) = (points[i], points[i + 1]);
+                let piece = [
+                    from,
+                    add(from, scale(sub(to, before), 1.0 / 6.0)),
+                    sub(to, scale(sub(after, from), 1.0 / 6.0)),
+                    to,
+                ];
+                (piece, vec![line(from, to)])
+            })
+            .collect(),
+        (CurveKind::Bezier, count) => {
+            let middles: Vec<Point> = points
+                .windows(2)
+                .map(|pair| scale(add(pair[0], pair[1]), 0.5))
+                .collect();
+            let mut candidates = vec![(line(points[0], middles[0]), Vec::new())];
+            for i in 1..count - 1 {
+                let (from, corner, to) = (middles[i - 1], points[i], middles[i]);
+                let piece = [
+                    from,
+                    add(from, scale(sub(corner, from), 2.0 / 3.0)),
+                    add(to, scale(sub(corner, to), 2.0 / 3.0)),
+                    to,
+                ];
+                candidates.push((piece, vec![line(from, corner), line(corner, to)]));
+            }
+            candidates.push((line(middles[count - 2], points[count - 1]), Vec::new()));
+            candidates
+        }
+    };
+
+    let mut pieces = Vec::new();
+    let mut straightened = 0;
+    for (piece, fallback) in candidates {
+        if fallback.is_empty() || stays_on(&piece, &allowed) {
+            pieces.push(piece);
+        } else {
+            straightened += 1;
+            pieces.extend(fallback);
+        }
+    }
+
+    let mut lengths = Vec::with_capacity(pieces.len() * SUBDIVISIONS);
+    let mut total = 0.0;
+    for piece in &pieces {
+        let mut previous = piece[0];
+        for i in 1..=SUBDIVISIONS {
+            let point = evaluate(piece, i as f64 / SUBDIVISIONS as f64);
+            let (dx, dy) = sub(point, previous);
+            total += libm::sqrt(dx * dx + dy * dy);
+            lengths.push(total);
+            previous = point;
+        }
+    }
+
+    RouteCurve {
+        pieces,
+        lengths,
+        straightened,
+    }
+}
+
+//Whether every tile the piece passes over is allowed, checked at closely spaced points along it
+fn stays_on<A: Fn(Coords2D) -> bool>(piece: &Piece, allowed: &A) -> bool {
+    //The control polygon is never shorter than the curve, so this spacing is never exceeded
+    let hull: f64 = piece
+        .windows(2)
+        .map(|pair| {
+            let (dx, dy) = sub(pair[1], pair[0]);
+            libm::sqrt(dx * dx + dy * dy)
+        })
+        .sum();
+    let checks = (libm::ceil(hull / CHECK_SPACING) as usize).max(1);
+    (0..=checks).all(|i| {
+        let (x, y) = evaluate(piece, i as f64 / checks as f64);
+        x >= 0.0 && y >= 0.0 && allowed((x as usize, y as usize))
+    })
+}
+
+fn line(from: Point, to: Point) -> Piece {
+    [
+        from,
+        add(from, scale(sub(to, from), 1.0 / 3.0)),
+        add(from, scale(sub(to, from), 2.0 / 3.0)),
+        to,
+    ]
+}
+
+fn evaluate(piece: &Piece, t: f64) -> Point {
+    let u = 1.0 - t;
+    let weights = [u * u * u, 3.0 * u * u * t, 3.0 * u * t * t, t * t * t];
+    piece
+        .iter()
+        .zip(weights.iter())
+        .fold((0.0, 0.0), |sum, (point, weight)| {
+            add(sum, scale(*point, *weight))
+        })
+}
+
+fn derivative(piece: &Piece, t: f64) -> Point {
+    let u = 1.0 - t;
+    let first = scale(sub(piece[1], piece[0]), 3.0 * u * u);
+    let second = scale(sub(piece[2], piece[1]), 6.0 * u * t);
+    let third = scale(sub(piece[3], piece[2]), 3.0 * t * t);
+    add(add(first, second), third)
+}
+
+fn add(a: Point, b: Point) -> Point {
+    (a.0 + b.0, a.1 + b.1)
+}
+
+fn sub(a: Point, b: Point) -> Point {
+    (a.0 - b.0, a.1 - b.1)
+}
+
+fn scale(a: Point, factor: f64) -> Point {
+    (a.0 * factor, a.1 * factor)
+}
+
+impl MemoryFootprint for RouteCurve {
+    fn heap_size(&self) -> usize {
+        vec_size(&self.pieces) + vec_size(&self.lengths)
+    }
+}
@@ -0,0 +1,68 @@
+use core::time::Duration;
+
+#[cfg(feature = "memory-stats")]
+use crate::MemoryFootprint;
+use crate::SearchContext;
+
+///Counters describing the work done by a search, for tuning heuristics and comparing algorithms.
+///Read from a [`SearchContext`](crate::SearchContext) after it has run a search.
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SearchStats {
+    ///Nodes taken from the open list and expanded.
+    pub expanded: usize,
+    ///Nodes added to the open list, including the start.
+    pub generated: usize,
+    ///The largest size the open list reached.
+    pub peak_open: usize,
+    ///Straight or diagonal scans made while looking for jump points. Always 0 for A* and Dijkstra.
+    pub jump_scans: usize,
+    ///Time spent searching, not including building the route.
+    ///Only measured when [`SearchOptions::measure_time`](crate::SearchOptions::measure_time) asks for it,
+    ///and always zero without the `std` feature or on `wasm32-unknown-unknown`, which have no clock.
+    pub elapsed: Duration,
+    ///Bytes held by the open and closed lists of the search, which only grow while it runs.
+    ///Includes space kept from earlier searches in the same context, as that is held too.
+    ///Only recorded with the `memory-stats` feature enabled, and `None` otherwise.
+    pub peak_memory: Option<usize>,
+}
+
+impl SearchStats {
+    //Count a node pushed to an open list now holding open_len nodes
+    pub(crate) fn generate(&mut self, open_len: usize) {
+        self.generated += 1;
+        self.peak_open = self.peak_open.max(open_len);
+    }
+}
+
+//Runs a search, also returning how long it took if `measure` is set, or zero if not.
+//The clock comes from std, and there is none on wasm32-unknown-unknown, where Instant::now panics.
+#[cfg(all(
+    feature = "std",
+    not(all(target_arch = "wasm32", target_os = "unknown"))
+))]
+pub(crate) fn timed<R, F: FnOnce() -> R>(measure: bool, search: F) -> (R, Duration) {
+    if !measure {
+        return (search(), Duration::ZERO);
+    }
+    let started = std::time::Instant::now();
+    let result = search();
+    (result, started.elapsed())
+}
+
+#[cfg(any(
+    not(feature = "std"),
+    all(target_arch = "wasm32", target_os = "unknown")
+))]
+pub(crate) fn timed<R, F: FnOnce() -> R>(_measure: bool, search: F) -> (R, Duration) {
+    (search(), Duration::ZERO)
+}
+
+//Records the memory held by a finished search, if memory tracking is enabled
+#[cfg(feature = "memory-stats")]
+pub(crate) fn track_memory(context: &mut SearchContext) {
+    context.stats.peak_memory = Some(context.heap_size());
+}
+
+#[cfg(not(feature = "memory-stats"))]
+pub(crate) fn track_memory(_context: &mut SearchContext) {}
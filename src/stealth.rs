@@ -0,0 +1,161 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::grid::Coords2D;
+use crate::grid::Map2D;
+use crate::grid::MovingAiMap;
+
+use crate::memory::vec_size;
+use crate::utils::distance;
+use crate::{
+    a_star_path_weighted, line_of_sight, MemoryFootprint, PathError, Route, SearchOptions,
+};
+
+///Someone watching the map, such as a guard or camera, who sees every tile within `range`
+///that it has a clear line of sight to.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Watcher {
+    ///The tile the watcher stands on.
+    pub position: Coords2D,
+    ///How far the watcher can see, in tiles.
+    pub range: f64,
+}
+
+///How many watchers can see each tile of a map, for stealth routes that stay out of sight.
+///Sight uses [`line_of_sight`], so walls block it and watchers cannot see past corners.
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+/// use blitz_path::{SearchOptions, VisibilityMap, Watcher};
+///
+/// let map = movingai::parser::parse_map_file(Path::new("./tests/map/maze512-32-9.map")).expect("Could not load map.");
+///
+/// let guards = [Watcher { position: (5, 1), range: 3.0 }];
+/// let visibility = VisibilityMap::new(&map, &guards);
+/// assert_eq!(visibility.watchers((5, 3)), 1);
+/// assert_eq!(visibility.watchers((5, 5)), 0);
+///
+/// let route = blitz_path::stealth_path(&map, (1, 1), (9, 1), &visibility, 10.0, &SearchOptions::new()).unwrap();
+/// assert_eq!(visibility.route_exposure(&route), 0.0);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct VisibilityMap {
+    width: usize,
+    height: usize,
+    watchers: Vec<usize>,
+}
+
+impl VisibilityMap {
+    ///Works out which tiles of `map` each of `watchers` can see.
+    pub fn new(map: &MovingAiMap, watchers: &[Watcher]) -> VisibilityMap {
+        let mut visibility = VisibilityMap {
+            width: map.width(),
+            height: map.height(),
+            watchers: vec![0; map.width() * map.height()],
+        };
+
+        for watcher in watchers {
+            //Only tiles within the square around the watcher can be in range
+            let reach = libm::floor(watcher.range.max(0.0)) as usize;
+            let (x, y) = watcher.position;
+            for ty in y.saturating_sub(reach)..=(y + reach).min(map.height().saturating_sub(1)) {
+                for tx in x.saturating_sub(reach)..=(x + reach).min(map.width().saturating_sub(1)) {
+                    let tile = (tx, ty);
+                    if distance(watcher.position, tile) <= watcher.range
+                        && line_of_sight(map, watcher.position, tile)
+                    {
+                        visibility.watchers[ty * map.width() + tx] += 1;
+                    }
+                }
+            }
+        }
+
+        visibility
+    }
+
+    ///Returns how many watchers can see a tile, or 0 if it is outside the map.
+    pub fn watchers(&self, position: Coords2D) -> usize {
+        if position.0 >= self.width || position.1 >= self.height {
+            return 0;
+        }
+        self.watchers[position.1 * self.width + position.0]
+    }
+
+    ///Returns true if any watcher can see a tile.
+    pub fn is_seen(&self, position: Coords2D) -> bool {
+        self.watchers(position) > 0
+    }
+
+    ///Returns a step cost for the weighted searches that never enters a tile a watcher can see,
+    ///for routes that must stay hidden. The start may be in sight, as it is never entered.
+    pub fn hidden_cost(&self) -> impl Fn(Coords2D, Coords2D) -> Option<f64> + '_ {
+        move |from, to| {
+            if self.is_seen(to) {
+                None
+            } else {
+                Some(distance(from, to))
+            }
+        }
+    }
+
+    ///Returns a step cost for the weighted searches that keeps exposure low, where entering a tile costs
+    ///the distance travelled times one plus `weight` times the number of watchers that can see it.
+    ///No step ever costs less than the distance it covers, so the cost can be used with
+    ///[`a_star_path_weighted`](crate::a_star_path_weighted).
+    pub fn exposure_cost(&self, weight: f64) -> impl Fn(Coords2D, Coords2D) -> Option<f64> + '_ {
+        move |from, to| Some(distance(from, to) * (1.0 + weight * self.watchers(to) as f64))
+    }
+
+    ///Returns the exposure of a route, as the distance travelled into tiles in sight,
+    ///counted once for every watcher that can see them. Routes should list every tile.
+    pub fn route_exposure(&self, route: &Route) -> f64 {
+        route
+            .steps()
+            .windows(2)
+            .map(|pair| distance(pair[0], pair[1]) * self.watchers(pair[0]) as f64)
+            .sum()
+    }
+
+    ///Returns the number of watchers that can see every tile, in row-major order.
+    pub fn values(&self) -> &[usize] {
+        &self.watchers
+    }
+
+    ///Returns the width of the underlying map.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    ///Returns the height of the underlying map.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+}
+
+///Finds a route from `start` to `goal` that stays out of sight of every watcher in `visibility` if it can.
+///If every route is seen, finds the route that best trades distance for exposure instead, as weighted
+///by `weight` in [`VisibilityMap::exposure_cost`]. Check [`VisibilityMap::route_exposure`] to tell which was found.
+pub fn stealth_path(
+    map: &MovingAiMap,
+    start: Coords2D,
+    goal: Coords2D,
+    visibility: &VisibilityMap,
+    weight: f64,
+    options: &SearchOptions,
+) -> Result<Route, PathError> {
+    match a_star_path_weighted(map, start, goal, options, visibility.hidden_cost()) {
+        Err(PathError::NoPath) | Err(PathError::ExceedsMaxDistance) => {
+            a_star_path_weighted(map, start, goal, options, visibility.exposure_cost(weight))
+        }
+        result => result,
+    }
+}
+
+impl MemoryFootprint for VisibilityMap {
+    fn heap_size(&self) -> usize {
+        vec_size(&self.watchers)
+    }
+}
@@ -0,0 +1,187 @@
+use alloc::vec::Vec;
+
+use crate::grid::Coords2D;
+use crate::grid::MovingAiMap;
+
+use crate::astar::{self, BestFirst};
+use crate::jps::{self, JumpSearch};
+use crate::node::Node;
+use crate::stats::track_memory;
+use crate::{NodeInfo, PathError, PruningRule, Route, SearchContext, SearchOptions};
+
+//Number of frontier nodes included in each snapshot unless configured otherwise
+const DEFAULT_SAMPLE: usize = 8;
+
+//The outcome of advancing a search by one expansion
+pub(crate) enum Step {
+    //A node was expanded and the search continues
+    Expanded(Node),
+    //The search has ended, reaching the goal node or failing
+    Finished(Result<Node, PathError>),
+}
+
+///The state of a search just after a node was expanded, for single-stepping in debuggers and teaching tools.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchSnapshot {
+    ///The node that was just expanded.
+    pub current: NodeInfo,
+    ///The number of nodes waiting to be expanded.
+    pub open_len: usize,
+    ///The number of nodes expanded so far.
+    pub closed_len: usize,
+    ///The most promising nodes waiting to be expanded, in the order the search would take them.
+    pub frontier: Vec<NodeInfo>,
+}
+
+type Cost = fn(Coords2D, Coords2D) -> Option<f64>;
+type Heuristic = fn(Coords2D, Coords2D) -> f64;
+
+enum Stepper<'a> {
+    BestFirst(BestFirst<'a, Cost, Heuristic>),
+    Jump(JumpSearch<'a, dyn PruningRule + 'a>),
+    Done,
+}
+
+///A search that runs one expansion each time it is advanced, yielding a [`SearchSnapshot`] after each.
+///Once the iterator ends, the route or error it finished with is available from [`result`](SearchSteps::result).
+///
+///Created by [`SearchContext::a_star_steps`], [`SearchContext::dijkstra_steps`] and [`SearchContext::jps_steps`].
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+/// use blitz_path::{SearchContext, SearchOptions};
+///
+/// let map = movingai::parser::parse_map_file(Path::new("./tests/map/maze512-32-9.map")).expect("Could not load map.");
+/// let options = SearchOptions::new();
+///
+/// let mut context = SearchContext::new();
+/// let mut steps = context.a_star_steps(&map, (1, 1), (5, 5), &options);
+///
+/// let first = steps.next().unwrap();
+/// assert_eq!(first.current.position, (1, 1));
+///
+/// for snapshot in &mut steps {
+///     assert!(snapshot.frontier.len() <= 8);
+/// }
+/// assert!(steps.result().unwrap().is_ok());
+/// ```
+pub struct SearchSteps<'a> {
+    context: &'a mut SearchContext,
+    options: &'a SearchOptions,
+    search: Stepper<'a>,
+    sample: usize,
+    result: Option<Result<Route, PathError>>,
+}
+
+impl<'a> SearchSteps<'a> {
+    pub(crate) fn a_star(
+        context: &'a mut SearchContext,
+        map: &'a MovingAiMap,
+        start: Coords2D,
+        goal: Coords2D,
+        options: &'a SearchOptions,
+        heuristic: Heuristic,
+    ) -> SearchSteps<'a> {
+        let search = BestFirst::start(
+            context,
+            map,
+            start,
+            goal,
+            options,
+            astar::step_cost as Cost,
+            heuristic,
+            &mut (),
+        );
+        SearchSteps::new(context, options, search.map(Stepper::BestFirst))
+    }
+
+    pub(crate) fn jps(
+        context: &'a mut SearchContext,
+        map: &'a MovingAiMap,
+        start: Coords2D,
+        goal: Coords2D,
+        options: &'a SearchOptions,
+    ) -> SearchSteps<'a> {
+        let rule: &'a (dyn PruningRule + 'a) = match &options.pruning {
+            Some(rule) => rule.as_ref(),
+            None => &crate::DefaultPruning,
+        };
+        let search = JumpSearch::start(context, map, start, goal, options, rule, &mut ());
+        SearchSteps::new(context, options, search.map(Stepper::Jump))
+    }
+
+    fn new(
+        context: &'a mut SearchContext,
+        options: &'a SearchOptions,
+        search: Result<Stepper<'a>, PathError>,
+    ) -> SearchSteps<'a> {
+        let (search, result) = match search {
+            Ok(search) => (search, None),
+            Err(error) => (Stepper::Done, Some(Err(error))),
+        };
+        SearchSteps {
+            context,
+            options,
+            search,
+            sample: DEFAULT_SAMPLE,
+            result,
+        }
+    }
+
+    ///Sets how many of the most promising frontier nodes are included in each snapshot. Defaults to 8.
+    pub fn frontier_sample(mut self, size: usize) -> SearchSteps<'a> {
+        self.sample = size;
+        self
+    }
+
+    ///Returns the route found or the reason the search failed, once it has finished.
+    pub fn result(&self) -> Option<&Result<Route, PathError>> {
+        self.result.as_ref()
+    }
+
+    fn snapshot(&self, current: Node) -> SearchSnapshot {
+        let mut frontier: Vec<Node> = self.context.open.iter().copied().collect();
+        //Nodes compare greater the sooner the search would expand them
+        if self.sample < frontier.len() {
+            if self.sample > 0 {
+                frontier.select_nth_unstable_by(self.sample - 1, |a, b| b.cmp(a));
+            }
+            frontier.truncate(self.sample);
+        }
+        frontier.sort_unstable_by(|a, b| b.cmp(a));
+
+        SearchSnapshot {
+            current: current.info(),
+            open_len: self.context.open.len(),
+            closed_len: self.context.closed.len(),
+            frontier: frontier.iter().map(Node::info).collect(),
+        }
+    }
+}
+
+impl Iterator for SearchSteps<'_> {
+    type Item = SearchSnapshot;
+
+    fn next(&mut self) -> Option<SearchSnapshot> {
+        let step = match &mut self.search {
+            Stepper::BestFirst(search) => search.step(self.context, &mut ()),
+            Stepper::Jump(search) => search.step(self.context, &mut ()),
+            Stepper::Done => return None,
+        };
+
+        match step {
+            Step::Expanded(node) => Some(self.snapshot(node)),
+            Step::Finished(result) => {
+                let route = |node: Node| match self.search {
+                    Stepper::Jump(_) => jps::route(self.context, &node, self.options),
+                    _ => astar::route(self.context, &node, self.options),
+                };
+                self.result = Some(result.map(route));
+                self.search = Stepper::Done;
+                track_memory(self.context);
+                None
+            }
+        }
+    }
+}
@@ -0,0 +1,101 @@
+use alloc::sync::Arc;
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::thread;
+
+use crate::grid::Coords2D;
+use crate::grid::MovingAiMap;
+
+use crate::{CancelToken, PathError, Route, SearchEvent, SearchObserver, SearchOptions};
+
+///Something sent by a search running on another thread.
+pub enum StreamEvent {
+    ///An event raised by the search. Discovered events from JPS are the jump points it finds.
+    Search(SearchEvent),
+    ///The search has ended with this result. Always the last message sent.
+    Finished(Result<Route, PathError>),
+}
+
+//Forwards events to a channel, cancelling the search once nobody is listening
+struct ChannelObserver {
+    sender: Sender<StreamEvent>,
+    token: CancelToken,
+}
+
+impl SearchObserver for ChannelObserver {
+    fn observe(&mut self, event: SearchEvent) {
+        if self.sender.send(StreamEvent::Search(event)).is_err() {
+            self.token.cancel();
+        }
+    }
+}
+
+///Sends each event to the channel. Events are dropped once the receiver has hung up.
+impl SearchObserver for Sender<SearchEvent> {
+    fn observe(&mut self, event: SearchEvent) {
+        let _ = self.send(event);
+    }
+}
+
+//Runs the search on a new thread with an observer feeding the returned channel
+fn spawn<F>(options: SearchOptions, search: F) -> Receiver<StreamEvent>
+where
+    F: FnOnce(&SearchOptions, &mut ChannelObserver) -> Result<Route, PathError> + Send + 'static,
+{
+    let (sender, receiver) = channel();
+    let token = options.cancel_token.clone().unwrap_or_default();
+    let options = options.cancel_token(token.clone());
+
+    thread::spawn(move || {
+        let mut observer = ChannelObserver { sender, token };
+        let result = search(&options, &mut observer);
+        let _ = observer.sender.send(StreamEvent::Finished(result));
+    });
+
+    receiver
+}
+
+///Creates a new route using the A* algorithm on a new thread, streaming its progress over a channel.
+///The search never waits for the receiver, which makes this suitable for live visualisers and profilers.
+///Dropping the receiver cancels the search through its cancel token, or one created if `options` has none.
+pub fn a_star_path_streamed(
+    map: Arc<MovingAiMap>,
+    start: Coords2D,
+    goal: Coords2D,
+    options: SearchOptions,
+) -> Receiver<StreamEvent> {
+    spawn(options, move |options, observer| {
+        crate::a_star_path_observed(&map, start, goal, options, observer)
+    })
+}
+
+///Creates a new route using the JPS algorithm on a new thread, streaming its progress over a channel.
+///See [`a_star_path_streamed`] for details.
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+/// use std::sync::Arc;
+/// use blitz_path::{SearchEvent, SearchOptions, StreamEvent};
+///
+/// let map = Arc::new(movingai::parser::parse_map_file(Path::new("./tests/map/maze512-32-9.map")).expect("Could not load map."));
+///
+/// let mut jump_points = 0;
+/// for event in blitz_path::jps_path_streamed(map, (1, 1), (5, 5), SearchOptions::new()) {
+///     match event {
+///         StreamEvent::Search(SearchEvent::Discovered(_)) => jump_points += 1,
+///         StreamEvent::Search(_) => {}
+///         StreamEvent::Finished(result) => assert!(result.is_ok()),
+///     }
+/// }
+/// assert!(jump_points > 0);
+/// ```
+pub fn jps_path_streamed(
+    map: Arc<MovingAiMap>,
+    start: Coords2D,
+    goal: Coords2D,
+    options: SearchOptions,
+) -> Receiver<StreamEvent> {
+    spawn(options, move |options, observer| {
+        crate::jps_path_observed(&map, start, goal, options, observer)
+    })
+}
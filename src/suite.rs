@@ -0,0 +1,276 @@
+use core::time::Duration;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::grid::Coords2D;
+use crate::grid::MovingAiMap;
+use movingai::parser::{parse_map_file, parse_scen_file};
+use movingai::SceneRecord;
+
+use crate::stats::timed;
+use crate::{Algorithm, MapLoadError, PathError, SearchContext, SearchOptions};
+
+//Largest difference from the recorded optimal length still counted as a pass,
+//as .scen files only store lengths to eight decimal places
+const TOLERANCE: f64 = 1e-4;
+
+///The outcome of a single scenario run by [`run_scen_suite`].
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ScenarioResult {
+    ///The position of the scenario in the list given.
+    pub index: usize,
+    ///The start of the scenario.
+    pub start: Coords2D,
+    ///The goal of the scenario.
+    pub goal: Coords2D,
+    ///The optimal length recorded in the scenario file.
+    pub expected: f64,
+    ///The length of the route found, or why none was found.
+    pub found: Result<f64, PathError>,
+    ///Time taken by the search, including building the route.
+    ///Always zero on `wasm32-unknown-unknown`, which has no clock.
+    pub elapsed: Duration,
+    ///Nodes taken from the open list and expanded by the search, as in [`SearchStats::expanded`](crate::SearchStats::expanded).
+    pub expanded: usize,
+}
+
+impl ScenarioResult {
+    ///Returns how much longer the route found is than the recorded optimum,
+    ///negative if it is shorter, or `None` if no route was found.
+    pub fn error(&self) -> Option<f64> {
+        self.found.ok().map(|distance| distance - self.expected)
+    }
+
+    ///Returns true if a route was found and its length matches the recorded optimum.
+    pub fn passed(&self) -> bool {
+        self.error().is_some_and(|error| error.abs() <= TOLERANCE)
+    }
+}
+
+///A summary of every scenario run by [`run_scen_suite`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct SuiteReport {
+    ///The result of each scenario, in the order they were given.
+    pub results: Vec<ScenarioResult>,
+}
+
+impl SuiteReport {
+    ///Returns the number of scenarios whose route matched the recorded optimum.
+    pub fn passed(&self) -> usize {
+        self.results.iter().filter(|result| result.passed()).count()
+    }
+
+    ///Returns the scenarios that found no route, or a route of the wrong length.
+    pub fn failures(&self) -> impl Iterator<Item = &ScenarioResult> {
+        self.results.iter().filter(|result| !result.passed())
+    }
+
+    ///Returns the largest difference between a route found and its recorded optimum,
+    ///ignoring scenarios that found no route. Zero if there are none.
+    pub fn max_error(&self) -> f64 {
+        self.results
+            .iter()
+            .filter_map(ScenarioResult::error)
+            .map(f64::abs)
+            .fold(0.0, f64::max)
+    }
+
+    ///Returns the total time spent searching.
+    pub fn total_time(&self) -> Duration {
+        self.results.iter().map(|result| result.elapsed).sum()
+    }
+}
+
+///Runs `algorithm` on every scenario, comparing each route against the optimal length recorded for it.
+///Scenarios are usually loaded from a MovingAI .scen file with `movingai::parser::parse_scen_file`.
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+/// use blitz_path::Algorithm;
+///
+/// let map = movingai::parser::parse_map_file(Path::new("./tests/map/maze512-32-9.map")).expect("Could not load map.");
+/// let scenes = movingai::parser::parse_scen_file(Path::new("./tests/map/maze512-32-9.map.scen")).expect("Could not load scenario.");
+///
+/// let report = blitz_path::run_scen_suite(&map, &scenes[..20], Algorithm::Jps);
+/// assert_eq!(report.passed(), 20);
+/// assert_eq!(report.failures().count(), 0);
+/// ```
+pub fn run_scen_suite(
+    map: &MovingAiMap,
+    scenarios: &[SceneRecord],
+    algorithm: Algorithm,
+) -> SuiteReport {
+    let mut context = SearchContext::new();
+    let options = SearchOptions::new();
+
+    let results = scenarios
+        .iter()
+        .enumerate()
+        .map(|(index, scene)| {
+            let (result, elapsed) = timed(true, || {
+                algorithm.search(&mut context, map, scene.start_pos, scene.goal_pos, &options)
+            });
+            ScenarioResult {
+                index,
+                start: scene.start_pos,
+                goal: scene.goal_pos,
+                expected: scene.optimal_length,
+                found: result.map(|route| route.distance()),
+                elapsed,
+                expanded: context.stats().expanded,
+            }
+        })
+        .collect();
+    SuiteReport { results }
+}
+
+///The results of one map of a benchmark set run by [`run_benchmark_set`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct MapReport {
+    ///The file name of the map, such as `maze512-32-9.map`.
+    pub name: String,
+    ///The results of the scenarios run on the map.
+    pub report: SuiteReport,
+}
+
+///A summary of every map and scenario run by [`run_benchmark_set`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct BenchmarkReport {
+    ///The report of each map, ordered by file name.
+    pub maps: Vec<MapReport>,
+}
+
+impl BenchmarkReport {
+    ///Returns the number of scenarios run across every map.
+    pub fn scenarios(&self) -> usize {
+        self.maps.iter().map(|map| map.report.results.len()).sum()
+    }
+
+    ///Returns the number of scenarios whose route matched the recorded optimum.
+    pub fn passed(&self) -> usize {
+        self.maps.iter().map(|map| map.report.passed()).sum()
+    }
+
+    ///Returns the name of the map and the result of every scenario that found no route,
+    ///or a route of the wrong length.
+    pub fn failures(&self) -> impl Iterator<Item = (&str, &ScenarioResult)> {
+        self.maps.iter().flat_map(|map| {
+            map.report
+                .failures()
+                .map(move |result| (map.name.as_str(), result))
+        })
+    }
+
+    ///Returns the largest difference between a route found and its recorded optimum on any map,
+    ///ignoring scenarios that found no route. Zero if there are none.
+    pub fn max_error(&self) -> f64 {
+        self.maps
+            .iter()
+            .map(|map| map.report.max_error())
+            .fold(0.0, f64::max)
+    }
+
+    ///Returns the total time spent searching.
+    pub fn total_time(&self) -> Duration {
+        self.maps.iter().map(|map| map.report.total_time()).sum()
+    }
+
+    ///Returns the mean time spent on each scenario, or zero if none were run.
+    pub fn mean_time(&self) -> Duration {
+        match self.scenarios() {
+            0 => Duration::ZERO,
+            scenarios => self.total_time() / scenarios as u32,
+        }
+    }
+}
+
+///Runs `algorithm` on a MovingAI benchmark set: every .map file in `directory` with a matching .scen file
+///beside it, named as in `maze512-32-9.map.scen`. Maps without a scenario file are skipped.
+///At most `limit` scenarios are run on each map, or all of them if `limit` is `None`.
+///
+///Fails with `MapLoadError::Parse` if the directory, or any map or scenario file in it, cannot be read.
+/// # Examples
+///
+/// ```
+/// use blitz_path::Algorithm;
+///
+/// let report = blitz_path::run_benchmark_set("./tests/map", Algorithm::Jps, Some(20)).unwrap();
+/// assert_eq!(report.maps[0].name, "maze512-32-9.map");
+/// assert_eq!(report.scenarios(), 20);
+/// assert_eq!(report.passed(), 20);
+/// ```
+pub fn run_benchmark_set<P: AsRef<Path>>(
+    directory: P,
+    algorithm: Algorithm,
+    limit: Option<usize>,
+) -> Result<BenchmarkReport, MapLoadError> {
+    run_benchmark_set_with(directory, limit, |map, scenes| {
+        run_scen_suite(map, scenes, algorithm)
+    })
+}
+
+///Runs a MovingAI benchmark set like [`run_benchmark_set`], with `suite` running the scenarios of each map,
+///so searches from other crates can be timed against the same files.
+/// # Examples
+///
+/// ```
+/// use blitz_path::Algorithm;
+///
+/// let report = blitz_path::run_benchmark_set_with("./tests/map", Some(5), |map, scenes| {
+///     blitz_path::run_scen_suite(map, scenes, Algorithm::AStar)
+/// })
+/// .unwrap();
+/// assert_eq!(report.passed(), 5);
+/// ```
+pub fn run_benchmark_set_with<P, S>(
+    directory: P,
+    limit: Option<usize>,
+    mut suite: S,
+) -> Result<BenchmarkReport, MapLoadError>
+where
+    P: AsRef<Path>,
+    S: FnMut(&MovingAiMap, &[SceneRecord]) -> SuiteReport,
+{
+    let directory = directory.as_ref();
+    let unreadable = |path: &Path, error: &dyn core::fmt::Display| {
+        MapLoadError::Parse(format!("{}: {}", path.display(), error))
+    };
+
+    let mut paths = Vec::new();
+    for entry in fs::read_dir(directory).map_err(|error| unreadable(directory, &error))? {
+        let path = entry.map_err(|error| unreadable(directory, &error))?.path();
+        if path.extension().is_some_and(|extension| extension == "map")
+            && scen_path(&path).is_file()
+        {
+            paths.push(path);
+        }
+    }
+    paths.sort();
+
+    let mut maps = Vec::with_capacity(paths.len());
+    for path in paths {
+        let map = parse_map_file(&path).map_err(|error| unreadable(&path, &error))?;
+        let scen = scen_path(&path);
+        let mut scenes = parse_scen_file(&scen).map_err(|error| unreadable(&scen, &error))?;
+        if let Some(limit) = limit {
+            scenes.truncate(limit);
+        }
+        maps.push(MapReport {
+            name: path
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .into_owned(),
+            report: suite(&map, &scenes),
+        });
+    }
+    Ok(BenchmarkReport { maps })
+}
+
+//MovingAI scenario files are named after their map, as in maze512-32-9.map.scen
+fn scen_path(map: &Path) -> PathBuf {
+    let mut scen = map.as_os_str().to_owned();
+    scen.push(".scen");
+    PathBuf::from(scen)
+}
@@ -0,0 +1,136 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::grid::Coords2D;
+use crate::grid::Map2D;
+use crate::grid::MovingAiMap;
+
+use crate::flood::flood;
+use crate::utils::{compose, distance, index, validate};
+use crate::{PathError, Route, SearchOptions};
+
+///Finds the cheapest route from `start` to any of `targets` using a single search,
+///rather than one search per target. Returns the index of the chosen target along with the route.
+///Targets that are out of bounds or blocked are ignored.
+///
+///`options` controls the maximum distance, cancellation and the steps listed in the route.
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+/// use blitz_path::SearchOptions;
+///
+/// let map = movingai::parser::parse_map_file(Path::new("./tests/map/maze512-32-9.map")).expect("Could not load map.");
+///
+/// let resources = [(40, 40), (5, 1), (0, 0)];
+/// let (nearest, path) = blitz_path::nearest_target_path(&map, (1, 1), &resources, &SearchOptions::new()).unwrap();
+///
+/// assert_eq!(nearest, 1);
+/// assert_eq!(path.distance(), 4.0);
+/// ```
+pub fn nearest_target_path(
+    map: &MovingAiMap,
+    start: Coords2D,
+    targets: &[Coords2D],
+    options: &SearchOptions,
+) -> Result<(usize, Route), PathError> {
+    let (mut found, pruned) = search(map, start, targets, 1, options)?;
+    match found.pop() {
+        Some(nearest) => Ok(nearest),
+        None if pruned => Err(PathError::ExceedsMaxDistance),
+        None => Err(PathError::NoPath),
+    }
+}
+
+///Finds the `k` cheapest targets to reach from `start` using a single search, along with the route to each.
+///Results are ordered from nearest to furthest, each giving the index of the target and the route to it.
+///Fewer than `k` are returned if not enough targets can be reached within the maximum distance.
+///Targets that are out of bounds or blocked are ignored, and targets sharing a tile are returned together.
+///
+///`options` controls the maximum distance, cancellation and the steps listed in each route.
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+/// use blitz_path::SearchOptions;
+///
+/// let map = movingai::parser::parse_map_file(Path::new("./tests/map/maze512-32-9.map")).expect("Could not load map.");
+///
+/// let enemies = [(40, 40), (5, 1), (3, 1), (0, 0)];
+/// let nearest = blitz_path::nearest_targets_paths(&map, (1, 1), &enemies, 2, &SearchOptions::new()).unwrap();
+///
+/// assert_eq!(nearest.len(), 2);
+/// assert_eq!(nearest[0].0, 2);
+/// assert_eq!(nearest[1].0, 1);
+/// assert_eq!(nearest[1].1.distance(), 4.0);
+/// ```
+pub fn nearest_targets_paths(
+    map: &MovingAiMap,
+    start: Coords2D,
+    targets: &[Coords2D],
+    k: usize,
+    options: &SearchOptions,
+) -> Result<Vec<(usize, Route)>, PathError> {
+    search(map, start, targets, k, options).map(|(found, _)| found)
+}
+
+//Flood outwards until k targets are settled, also reporting whether the maximum distance cut the search short
+fn search(
+    map: &MovingAiMap,
+    start: Coords2D,
+    targets: &[Coords2D],
+    k: usize,
+    options: &SearchOptions,
+) -> Result<(Vec<(usize, Route)>, bool), PathError> {
+    validate(map, start, start)?;
+
+    //Mark targets on a grid so each settled tile is checked in constant time,
+    //chaining together targets that share a tile
+    let mut first_target = vec![None; map.width() * map.height()];
+    let mut next_target = vec![None; targets.len()];
+    for (target, position) in targets.iter().enumerate().rev() {
+        if map.is_traversable(*position) {
+            next_target[target] = first_target[index(map, *position)];
+            first_target[index(map, *position)] = Some(target);
+        }
+    }
+
+    let max_cost = options.max_distance.unwrap_or(f64::INFINITY);
+    let mut found = Vec::new();
+    let mut cancelled = false;
+    let mut iteration = 0;
+    let reached = flood(
+        map,
+        &[(start, 0.0)],
+        max_cost,
+        |from, to| Some(distance(from, to)),
+        |position, _| {
+            if options.should_cancel(iteration) {
+                cancelled = true;
+                return true;
+            }
+            iteration += 1;
+
+            let mut target = first_target[index(map, position)];
+            while let Some(current) = target {
+                if found.len() == k {
+                    break;
+                }
+                found.push((current, position));
+                target = next_target[current];
+            }
+            found.len() == k
+        },
+    );
+
+    if cancelled {
+        return Err(PathError::Cancelled);
+    }
+    let mut routes = Vec::with_capacity(found.len());
+    for (target, position) in found {
+        let route = reached.route_to(map, position).ok_or(PathError::NoPath)?;
+        let steps = compose(route.steps(), options);
+        routes.push((target, Route::from((route.distance(), steps))));
+    }
+    Ok((routes, reached.pruned))
+}
@@ -0,0 +1,116 @@
+//Running searches on tokio's blocking thread pool
+
+use alloc::sync::Arc;
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+
+use crate::grid::Coords2D;
+use crate::grid::MovingAiMap;
+use tokio::task::JoinHandle;
+
+use crate::{CancelToken, PathError, Route, SearchOptions};
+
+///A search running on tokio's blocking thread pool, resolving to its route once finished.
+///Dropping the future before it completes cancels the search through its cancel token,
+///so wrapping it in `tokio::time::timeout` or losing a `tokio::select!` stops the work too.
+pub struct PathFuture {
+    handle: JoinHandle<Result<Route, PathError>>,
+    token: CancelToken,
+    finished: bool,
+}
+
+impl PathFuture {
+    ///Returns the token polled by the search, which can be used to cancel it without dropping the future.
+    pub fn cancel_token(&self) -> &CancelToken {
+        &self.token
+    }
+}
+
+impl Future for PathFuture {
+    type Output = Result<Route, PathError>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let result = match Pin::new(&mut self.handle).poll(cx) {
+            Poll::Pending => return Poll::Pending,
+            Poll::Ready(result) => result,
+        };
+        self.finished = true;
+
+        match result {
+            Ok(found) => Poll::Ready(found),
+            //Searches only panic on bugs, which should surface in the awaiting task
+            Err(error) if error.is_panic() => std::panic::resume_unwind(error.into_panic()),
+            //The runtime is shutting down, so the search never ran to completion
+            Err(_) => Poll::Ready(Err(PathError::Cancelled)),
+        }
+    }
+}
+
+impl Drop for PathFuture {
+    fn drop(&mut self) {
+        if !self.finished {
+            self.token.cancel();
+        }
+    }
+}
+
+///Runs any search on tokio's blocking thread pool, passing it `options` with a cancel token attached.
+///The token already in `options` is used if there is one, otherwise a new one is created.
+///Must be called from within a tokio runtime.
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+/// use std::sync::Arc;
+/// use blitz_path::SearchOptions;
+///
+/// let map = Arc::new(movingai::parser::parse_map_file(Path::new("./tests/map/maze512-32-9.map")).expect("Could not load map."));
+/// let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+///
+/// let path = runtime.block_on(async {
+///     blitz_path::spawn_search(SearchOptions::new(), move |options| {
+///         blitz_path::dijkstra_path_weighted(&map, (1, 1), (5, 5), options, |_, _| Some(1.0))
+///     })
+///     .await
+/// });
+/// assert!(path.is_ok());
+/// ```
+pub fn spawn_search<F>(options: SearchOptions, search: F) -> PathFuture
+where
+    F: FnOnce(&SearchOptions) -> Result<Route, PathError> + Send + 'static,
+{
+    let token = options.cancel_token.clone().unwrap_or_default();
+    let options = options.cancel_token(token.clone());
+    PathFuture {
+        handle: tokio::task::spawn_blocking(move || search(&options)),
+        token,
+        finished: false,
+    }
+}
+
+///Creates a new route using the A* algorithm on tokio's blocking thread pool.
+///See [`spawn_search`] for how cancellation works.
+pub fn a_star_path_async(
+    map: Arc<MovingAiMap>,
+    start: Coords2D,
+    goal: Coords2D,
+    options: SearchOptions,
+) -> PathFuture {
+    spawn_search(options, move |options| {
+        crate::a_star_path_with(&map, start, goal, options)
+    })
+}
+
+///Creates a new route using the JPS algorithm on tokio's blocking thread pool.
+///See [`spawn_search`] for how cancellation works.
+pub fn jps_path_async(
+    map: Arc<MovingAiMap>,
+    start: Coords2D,
+    goal: Coords2D,
+    options: SearchOptions,
+) -> PathFuture {
+    spawn_search(options, move |options| {
+        crate::jps_path_with(&map, start, goal, options)
+    })
+}
@@ -0,0 +1,89 @@
+//Building maps from levels made in the Tiled editor
+
+use std::path::Path;
+
+use crate::grid::MovingAiMap;
+use tiled::{Loader, PropertyValue, TileLayer};
+
+use crate::MapLoadError;
+
+///Chooses which tiles of a Tiled map are obstacles.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum TiledObstacles<'a> {
+    ///Every tile placed on the tile layer with this name is an obstacle.
+    Layer(&'a str),
+    ///Tiles with this boolean property set to true, on any top-level tile layer, are obstacles.
+    Property(&'a str),
+}
+
+///Loads a map from a Tiled .tmx file, with tiles chosen by `obstacles` blocked and everything else open.
+///Only finite, orthogonal style grids are supported; each cell of the Tiled map becomes one tile.
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+/// use blitz_path::TiledObstacles;
+///
+/// let map = blitz_path::map_from_tiled(Path::new("./tests/map/tiled.tmx"), TiledObstacles::Layer("walls")).unwrap();
+/// assert!(blitz_path::is_reachable(&map, (1, 1), (3, 2)));
+/// ```
+pub fn map_from_tiled(
+    path: &Path,
+    obstacles: TiledObstacles<'_>,
+) -> Result<MovingAiMap, MapLoadError> {
+    let level = Loader::new()
+        .load_tmx_map(path)
+        .map_err(|error| MapLoadError::Parse(error.to_string()))?;
+    if level.infinite() {
+        return Err(MapLoadError::Unbounded);
+    }
+    let width = level.width as usize;
+    let height = level.height as usize;
+    let mut blocked = vec![false; width * height];
+
+    match obstacles {
+        TiledObstacles::Layer(name) => {
+            let layer = level
+                .layers()
+                .find(|layer| layer.name == name)
+                .ok_or_else(|| MapLoadError::MissingLayer(String::from(name)))?;
+            let tiles = layer
+                .as_tile_layer()
+                .ok_or_else(|| MapLoadError::NotTileLayer(String::from(name)))?;
+            mark(&tiles, width, &mut blocked, |_| true);
+        }
+        TiledObstacles::Property(property) => {
+            for tiles in level.layers().filter_map(|layer| layer.as_tile_layer()) {
+                mark(&tiles, width, &mut blocked, |tile| {
+                    tile.get_tile().is_some_and(|data| {
+                        data.properties.get(property) == Some(&PropertyValue::BoolValue(true))
+                    })
+                });
+            }
+        }
+    }
+
+    let tiles = blocked
+        .iter()
+        .map(|blocked| if *blocked { '@' } else { '.' })
+        .collect();
+    Ok(MovingAiMap::new(
+        String::from("octile"),
+        height,
+        width,
+        tiles,
+    ))
+}
+
+//Block every cell of the layer holding a tile that is_obstacle accepts
+fn mark<F>(tiles: &TileLayer<'_>, width: usize, blocked: &mut [bool], is_obstacle: F)
+where
+    F: Fn(&tiled::LayerTile<'_>) -> bool,
+{
+    for (index, cell) in blocked.iter_mut().enumerate() {
+        let (x, y) = ((index % width) as i32, (index / width) as i32);
+        if tiles.get_tile(x, y).is_some_and(|tile| is_obstacle(&tile)) {
+            *cell = true;
+        }
+    }
+}
@@ -0,0 +1,105 @@
+//Instrumentation shared by the search entry points.
+//Without the `tracing` feature this is a zero-cost passthrough.
+
+use crate::grid::Coords2D;
+
+#[cfg(feature = "tracing")]
+use crate::{PathError, Route, SearchEvent, SearchObserver};
+
+#[cfg(not(feature = "tracing"))]
+#[inline(always)]
+pub fn instrument<O, R, F>(
+    _algorithm: &'static str,
+    _start: Coords2D,
+    _goal: Coords2D,
+    observer: &mut O,
+    search: F,
+) -> R
+where
+    F: FnOnce(&mut O) -> R,
+{
+    search(observer)
+}
+
+//Runs a search inside a span, counting its events and logging the outcome
+#[cfg(feature = "tracing")]
+pub fn instrument<O, R, F>(
+    algorithm: &'static str,
+    start: Coords2D,
+    goal: Coords2D,
+    observer: &mut O,
+    search: F,
+) -> Result<R, PathError>
+where
+    O: SearchObserver,
+    R: Outcome,
+    F: FnOnce(&mut Counting<'_, O>) -> Result<R, PathError>,
+{
+    let span = tracing::debug_span!("search", algorithm, ?start, ?goal);
+    let _entered = span.enter();
+    tracing::debug!("search started");
+
+    let mut counting = Counting {
+        inner: observer,
+        expanded: 0,
+        discovered: 0,
+    };
+    let result = search(&mut counting);
+
+    match &result {
+        Ok(route) => tracing::debug!(
+            expanded = counting.expanded,
+            discovered = counting.discovered,
+            distance = route.cost(),
+            "search finished"
+        ),
+        Err(error) => tracing::debug!(
+            expanded = counting.expanded,
+            discovered = counting.discovered,
+            %error,
+            "search failed"
+        ),
+    }
+
+    result
+}
+
+//Successful search results, reduced to the cost they report
+#[cfg(feature = "tracing")]
+pub trait Outcome {
+    fn cost(&self) -> f64;
+}
+
+#[cfg(feature = "tracing")]
+impl Outcome for Route {
+    fn cost(&self) -> f64 {
+        self.distance()
+    }
+}
+
+#[cfg(feature = "tracing")]
+impl Outcome for f64 {
+    fn cost(&self) -> f64 {
+        *self
+    }
+}
+
+//Forwards events to the caller's observer while keeping totals for the finish event
+#[cfg(feature = "tracing")]
+pub struct Counting<'a, O> {
+    inner: &'a mut O,
+    expanded: usize,
+    discovered: usize,
+}
+
+#[cfg(feature = "tracing")]
+impl<'a, O: SearchObserver> SearchObserver for Counting<'a, O> {
+    #[inline]
+    fn observe(&mut self, event: SearchEvent) {
+        match event {
+            SearchEvent::Expanded(_) => self.expanded += 1,
+            SearchEvent::Discovered(_) => self.discovered += 1,
+        }
+        self.inner.observe(event);
+    }
+}
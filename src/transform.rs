@@ -0,0 +1,121 @@
+use alloc::string::String;
+use alloc::vec;
+
+use crate::grid::Coords2D;
+use crate::grid::Map2D;
+use crate::grid::MovingAiMap;
+#[cfg(feature = "movingai")]
+use movingai::SceneRecord;
+
+///A rotation or reflection of a whole map, for augmenting benchmark datasets with copies of each map
+///that keep every scenario's optimal length. Rotations are clockwise with y increasing down the map,
+///as on screen.
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+/// use movingai::Map2D;
+/// use blitz_path::MapTransform;
+///
+/// let map = movingai::parser::parse_map_file(Path::new("./tests/map/maze512-32-9.map")).expect("Could not load map.");
+/// let scenes = movingai::parser::parse_scen_file(Path::new("./tests/map/maze512-32-9.map.scen")).expect("Could not load scenario.");
+///
+/// let (rotated, scenes_rotated) = MapTransform::Rotate90.apply_with_scenarios(&map, &scenes[..1]);
+/// let start = MapTransform::Rotate90.position(scenes[0].start_pos, map.width(), map.height());
+/// assert_eq!(scenes_rotated[0].start_pos, start);
+/// assert_eq!(rotated.get(start), map.get(scenes[0].start_pos));
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MapTransform {
+    ///A quarter turn clockwise, so the top row becomes the right-hand column.
+    Rotate90,
+    ///A half turn.
+    Rotate180,
+    ///A quarter turn anticlockwise, so the top row becomes the left-hand column.
+    Rotate270,
+    ///A reflection swapping left and right.
+    MirrorHorizontal,
+    ///A reflection swapping top and bottom.
+    MirrorVertical,
+}
+
+impl MapTransform {
+    ///Every transform.
+    pub const ALL: [MapTransform; 5] = [
+        MapTransform::Rotate90,
+        MapTransform::Rotate180,
+        MapTransform::Rotate270,
+        MapTransform::MirrorHorizontal,
+        MapTransform::MirrorVertical,
+    ];
+
+    ///Returns the transform that undoes this one.
+    pub fn inverse(self) -> MapTransform {
+        match self {
+            MapTransform::Rotate90 => MapTransform::Rotate270,
+            MapTransform::Rotate270 => MapTransform::Rotate90,
+            other => other,
+        }
+    }
+
+    ///Returns the width and height of a map of the given size once transformed.
+    pub fn size(self, width: usize, height: usize) -> (usize, usize) {
+        match self {
+            MapTransform::Rotate90 | MapTransform::Rotate270 => (height, width),
+            _ => (width, height),
+        }
+    }
+
+    ///Returns where a tile of a map of the given size ends up once transformed.
+    pub fn position(self, (x, y): Coords2D, width: usize, height: usize) -> Coords2D {
+        match self {
+            MapTransform::Rotate90 => (height - 1 - y, x),
+            MapTransform::Rotate180 => (width - 1 - x, height - 1 - y),
+            MapTransform::Rotate270 => (y, width - 1 - x),
+            MapTransform::MirrorHorizontal => (width - 1 - x, y),
+            MapTransform::MirrorVertical => (x, height - 1 - y),
+        }
+    }
+
+    ///Returns a transformed copy of `map`. The result is octile, like every map built by this crate.
+    pub fn apply(self, map: &MovingAiMap) -> MovingAiMap {
+        let (width, height) = (map.width(), map.height());
+        let (new_width, new_height) = self.size(width, height);
+        let mut tiles = vec!['@'; width * height];
+        for tile in map.coords() {
+            let (x, y) = self.position(tile, width, height);
+            tiles[y * new_width + x] = *map.get(tile);
+        }
+        MovingAiMap::new(String::from("octile"), new_height, new_width, tiles)
+    }
+
+    ///Returns a copy of `scenario` for the transformed map, moving its start and goal and swapping
+    ///the recorded width and height if needed. The optimal length is unchanged.
+    #[cfg(feature = "movingai")]
+    pub fn scenario(self, scenario: &SceneRecord) -> SceneRecord {
+        let (width, height) = (scenario.map_width, scenario.map_height);
+        let (map_width, map_height) = self.size(width, height);
+        SceneRecord {
+            map_width,
+            map_height,
+            start_pos: self.position(scenario.start_pos, width, height),
+            goal_pos: self.position(scenario.goal_pos, width, height),
+            ..scenario.clone()
+        }
+    }
+
+    ///Transforms `map` along with every scenario recorded for it.
+    #[cfg(feature = "movingai")]
+    pub fn apply_with_scenarios(
+        self,
+        map: &MovingAiMap,
+        scenarios: &[SceneRecord],
+    ) -> (MovingAiMap, Vec<SceneRecord>) {
+        let scenarios = scenarios
+            .iter()
+            .map(|scenario| self.scenario(scenario))
+            .collect();
+        (self.apply(map), scenarios)
+    }
+}
@@ -0,0 +1,150 @@
+use alloc::collections::{BTreeMap, BinaryHeap};
+use alloc::vec;
+use core::cmp::Ordering;
+
+use crate::grid::Coords2D;
+use crate::grid::MovingAiMap;
+
+use crate::utils::{compose, distance, neighbours, prepare};
+use crate::{Direction, PathError, Route, SearchOptions};
+
+//A tile together with the direction it was entered in, or None for the start
+type State = (Coords2D, Option<usize>);
+
+//A search node that remembers its heading, so turns can be charged for
+#[derive(Debug, Copy, Clone)]
+struct TurnNode {
+    f: f64,
+    g: f64,
+    h: f64,
+    state: State,
+}
+
+impl PartialOrd for TurnNode {
+    fn partial_cmp(&self, other: &TurnNode) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for TurnNode {
+    fn cmp(&self, other: &TurnNode) -> Ordering {
+        //Reversed to make the max-heap a min-heap, with ties broken as for Node
+        other
+            .f
+            .total_cmp(&self.f)
+            .then_with(|| other.h.total_cmp(&self.h))
+            .then_with(|| other.state.cmp(&self.state))
+    }
+}
+
+impl PartialEq for TurnNode {
+    fn eq(&self, other: &TurnNode) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for TurnNode {}
+
+///Creates a new route using the A* algorithm, adding `penalty` to the cost of the route for every
+///45 degrees it turns, so vehicles follow smooth routes without needless zig-zags.
+///A right angle turn costs twice the penalty and turning back on itself four times.
+///
+///The search tracks the direction each tile is entered in, so it explores up to eight times as
+///many nodes as [`a_star_path_with`](crate::a_star_path_with). The distance of the route includes the penalties.
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+/// use blitz_path::SearchOptions;
+///
+/// let map = movingai::parser::parse_map_file(Path::new("./tests/map/maze512-32-9.map")).expect("Could not load map.");
+///
+/// // A single 45 degree turn, rather than weaving across the room
+/// let path = blitz_path::a_star_path_turning(&map, (1, 1), (9, 3), 1.0, &SearchOptions::new()).unwrap();
+/// assert_eq!(path.distance(), 6.0 + 2.0 * 2f64.sqrt() + 1.0);
+/// ```
+pub fn a_star_path_turning(
+    map: &MovingAiMap,
+    start: Coords2D,
+    goal: Coords2D,
+    penalty: f64,
+    options: &SearchOptions,
+) -> Result<Route, PathError> {
+    let (start, goal) = prepare(map, start, goal, options)?;
+    if options.exceeds_max_distance(distance(start, goal)) {
+        return Err(PathError::ExceedsMaxDistance);
+    }
+
+    let mut open = BinaryHeap::new();
+    let mut costs: BTreeMap<State, f64> = BTreeMap::new();
+    let mut parents: BTreeMap<State, State> = BTreeMap::new();
+    let start_state = (start, None);
+    let h = distance(start, goal);
+    open.push(TurnNode {
+        f: h,
+        g: 0.0,
+        h,
+        state: start_state,
+    });
+    costs.insert(start_state, 0.0);
+
+    let mut pruned = false;
+    let mut iteration = 0;
+    while let Some(node) = open.pop() {
+        //Skip stale entries that have since been reached more cheaply
+        if node.g > costs[&node.state] {
+            continue;
+        }
+        if options.should_cancel(iteration) {
+            return Err(PathError::Cancelled);
+        }
+        iteration += 1;
+
+        let (position, heading) = node.state;
+        if position == goal {
+            let mut path = vec![position];
+            let mut state = node.state;
+            while let Some(parent) = parents.get(&state) {
+                path.push(parent.0);
+                state = *parent;
+            }
+            return Ok(Route::from((node.g, compose(path, options))));
+        }
+
+        for next in neighbours(map, position) {
+            let direction = match Direction::between(position, next) {
+                Some(direction) => direction as usize,
+                None => continue,
+            };
+            let turns = heading.map_or(0, |heading| {
+                let difference = (8 + direction - heading) % 8;
+                difference.min(8 - difference)
+            });
+            let g = node.g + distance(position, next) + penalty * turns as f64;
+            let h = distance(next, goal);
+            if options.exceeds_max_distance(g + h) {
+                pruned = true;
+                continue;
+            }
+
+            let state = (next, Some(direction));
+            if costs.get(&state).is_some_and(|best| *best <= g) {
+                continue;
+            }
+            costs.insert(state, g);
+            parents.insert(state, node.state);
+            open.push(TurnNode {
+                f: g + h,
+                g,
+                h,
+                state,
+            });
+        }
+    }
+
+    if pruned {
+        Err(PathError::ExceedsMaxDistance)
+    } else {
+        Err(PathError::NoPath)
+    }
+}
@@ -1,54 +1,233 @@
-use movingai::Coords2D;
-use std::cmp::{max, min};
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::grid::Coords2D;
+use crate::grid::Map2D;
+use crate::grid::MovingAiMap;
+use core::cmp::{max, min};
 
 use crate::node::Node;
+use crate::{PathError, RouteSteps, SearchOptions};
 
+///Returns the straight line distance between two tiles, the heuristic used by A* and JPS.
+///It never overestimates the length of a route, so is admissible for any search where steps cost at least their length.
 pub fn distance(a: Coords2D, b: Coords2D) -> f64 {
     let (x, y) = (a.0 as f64, a.1 as f64);
     let (p, q) = (b.0 as f64, b.1 as f64);
-    ((x - p) * (x - p) + (y - q) * (y - q)).sqrt()
+    libm::sqrt((x - p) * (x - p) + (y - q) * (y - q))
+}
+
+///Checks both endpoints are inside the map and traversable, returning the error a search would fail with if not.
+pub fn validate(map: &MovingAiMap, start: Coords2D, goal: Coords2D) -> Result<(), PathError> {
+    if map.is_out_of_bound(start) {
+        return Err(PathError::StartOutOfBounds(start));
+    }
+    if map.is_out_of_bound(goal) {
+        return Err(PathError::GoalOutOfBounds(goal));
+    }
+    if !map.is_traversable(start) {
+        return Err(PathError::StartBlocked(start));
+    }
+    if !map.is_traversable(goal) {
+        return Err(PathError::GoalBlocked(goal));
+    }
+
+    Ok(())
+}
+
+///Snaps the endpoints to open tiles if the options ask for it, then validates them, returning the endpoints to search between.
+///Fails with `PathError::NoPath` without searching if the options hold [`Components`](crate::Components) that put them in different regions.
+pub fn prepare(
+    map: &MovingAiMap,
+    start: Coords2D,
+    goal: Coords2D,
+    options: &SearchOptions,
+) -> Result<(Coords2D, Coords2D), PathError> {
+    #[cfg(feature = "tracing")]
+    let _span = tracing::trace_span!("prepare").entered();
+
+    let (start, goal) = match options.snap_radius {
+        Some(radius) => (
+            nearest_traversable(map, start, radius).unwrap_or(start),
+            nearest_traversable(map, goal, radius).unwrap_or(goal),
+        ),
+        None => (start, goal),
+    };
+
+    validate(map, start, goal)?;
+
+    //Reject routes between disconnected regions without searching
+    if let Some(components) = &options.components {
+        if !components.same_component(start, goal) {
+            return Err(PathError::NoPath);
+        }
+    }
+
+    #[cfg(feature = "tracing")]
+    tracing::trace!(?start, ?goal, "endpoints prepared");
+
+    Ok((start, goal))
+}
+
+///Finds the traversable tile closest to `position` that is no more than `radius` tiles away.
+///`position` itself may be blocked or lie outside the map. Ties are broken in row-major order.
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+///
+/// let map = movingai::parser::parse_map_file(Path::new("./tests/map/maze512-32-9.map")).expect("Could not load map.");
+///
+/// assert_eq!(blitz_path::nearest_traversable(&map, (0, 0), 2), Some((1, 1)));
+/// assert_eq!(blitz_path::nearest_traversable(&map, (0, 0), 1), None);
+/// ```
+pub fn nearest_traversable(
+    map: &MovingAiMap,
+    position: Coords2D,
+    radius: usize,
+) -> Option<Coords2D> {
+    if map.is_traversable(position) {
+        return Some(position);
+    }
+
+    //No tile is further away than the far corner of the map, so larger radii search the whole map
+    let reach = max(position.0, map.width()).saturating_add(max(position.1, map.height()));
+    let radius = min(radius, reach) as i64;
+
+    //Clip the square around the position to the map
+    let (x, y) = (position.0 as i64, position.1 as i64);
+    let min_x = max(x.saturating_sub(radius), 0);
+    let min_y = max(y.saturating_sub(radius), 0);
+    let max_x = min(x.saturating_add(radius), map.width() as i64 - 1);
+    let max_y = min(y.saturating_add(radius), map.height() as i64 - 1);
+
+    let limit = (radius as f64) * (radius as f64);
+    let mut best: Option<(Coords2D, f64)> = None;
+    for check_y in min_y..=max_y {
+        for check_x in min_x..=max_x {
+            let coords = Coords2D::from((check_x as usize, check_y as usize));
+            let (dx, dy) = ((check_x - x) as f64, (check_y - y) as f64);
+            let check_distance = dx * dx + dy * dy;
+            let closer = best.is_none_or(|(_, best_distance)| check_distance < best_distance);
+            if check_distance <= limit && closer && map.is_traversable(coords) {
+                best = Some((coords, check_distance));
+            }
+        }
+    }
+
+    best.map(|(coords, _)| coords)
 }
 
-//Helper function to recreate path once goal is located
-pub fn rewind(start: &Node, closed: &Vec<Node>) -> Vec<Coords2D> {
+///Rebuilds the path to `start` once a search has reached it, using `find` to look up the closed node at each parent position.
+///Tiles skipped between a node and its parent, as by jump points, are filled in along the straight or diagonal line between them.
+///The path is goal first and ends at the node whose parent is itself, normally the start.
+///Returns `None` if `find` cannot find the node at one of the parents.
+pub fn rewind<F: Fn(Coords2D) -> Option<Node>>(start: &Node, find: F) -> Option<Vec<Coords2D>> {
     let mut path = Vec::new();
     path.push(start.position);
     let mut parent = start.parent;
     let mut node = start.position;
 
     while parent != node {
-        if let Some(step) = closed.iter().find(|x| x.position == parent) {
-            let direction = direction(parent, node);
-            let mut next = Coords2D::from((
-                (node.0 as i32 + direction.0) as usize,
-                (node.1 as i32 + direction.1) as usize,
-            ));
-
-            //Push intermidiate nodes if any
-            while next != parent {
-                path.push(next);
-                next = Coords2D::from((
-                    (next.0 as i32 + direction.0) as usize,
-                    (next.1 as i32 + direction.1) as usize,
-                ));
-            }
+        let step = find(parent)?;
+        let direction = direction(parent, node);
+        //Intermediate nodes lie between two tiles on the map, so never leave it
+        let advance = |tile| offset(tile, direction.0, direction.1).expect("step stays on the map");
+        let mut next = advance(node);
 
-            //Push actual steps
-            parent = step.parent;
-            node = step.position;
-            path.push(node);
+        //Push intermidiate nodes if any
+        while next != parent {
+            path.push(next);
+            next = advance(next);
+        }
+
+        //Push actual steps
+        parent = step.parent;
+        node = step.position;
+        path.push(node);
+    }
+
+    Some(path)
+}
+
+///Applies the route composition options to a path ordered goal first and start last, as returned by [`rewind`].
+pub fn compose(path: Vec<Coords2D>, options: &SearchOptions) -> Vec<Coords2D> {
+    let mut path = match options.route_steps {
+        RouteSteps::Cells => path,
+        RouteSteps::JumpPoints => turning_points(&path),
+    };
+
+    if options.exclude_start {
+        path.pop();
+    }
+    if options.repeat_goal {
+        if let Some(goal) = path.first() {
+            path.insert(0, *goal);
         }
     }
 
     path
 }
 
-pub fn direction(current: Coords2D, parent: Coords2D) -> (i32, i32) {
-    //Calculate direction and restrict to range -1..=1
-    let mut direction_x = current.0 as i32 - parent.0 as i32;
-    let mut direction_y = current.1 as i32 - parent.1 as i32;
-    direction_x = max(-1, min(direction_x, 1));
-    direction_y = max(-1, min(direction_y, 1));
+//Keep only the ends of the path and the tiles where it changes direction
+fn turning_points(path: &[Coords2D]) -> Vec<Coords2D> {
+    if path.len() < 3 {
+        return path.to_vec();
+    }
+
+    let mut points = vec![path[0]];
+    for window in path.windows(3) {
+        if direction(window[1], window[0]) != direction(window[2], window[1]) {
+            points.push(window[1]);
+        }
+    }
+    points.push(path[path.len() - 1]);
+
+    points
+}
 
-    (direction_x, direction_y)
+///Returns the neighbours of a tile that can be stepped to from it, without underflowing at the top and left edges of the map.
+///Follows the MovingAI rules, so diagonal steps never cut corners.
+pub fn neighbours(map: &MovingAiMap, tile: Coords2D) -> impl Iterator<Item = Coords2D> + '_ {
+    const OFFSETS: [(i32, i32); 8] = [
+        (1, 0),
+        (1, 1),
+        (1, -1),
+        (0, 1),
+        (0, -1),
+        (-1, 0),
+        (-1, -1),
+        (-1, 1),
+    ];
+
+    OFFSETS.iter().filter_map(move |(dx, dy)| {
+        let next = offset(tile, *dx, *dy)?;
+        if map.is_traversable_from(tile, next) {
+            Some(next)
+        } else {
+            None
+        }
+    })
+}
+
+///Returns the tile reached by moving `dx` and `dy` from a tile, or `None` if that would leave the top or left edge.
+///Tiles past the bottom and right edges are still returned, and are never traversable.
+pub fn offset(tile: Coords2D, dx: i32, dy: i32) -> Option<Coords2D> {
+    let x = tile.0.checked_add_signed(dx as isize)?;
+    let y = tile.1.checked_add_signed(dy as isize)?;
+    Some(Coords2D::from((x, y)))
+}
+
+///Returns the index of a tile in row-major order, for per-tile lookup tables.
+pub fn index(map: &MovingAiMap, tile: Coords2D) -> usize {
+    tile.1 * map.width() + tile.0
+}
+
+///Returns the direction of travel from `parent` to `current`, with each component between -1 and 1.
+pub fn direction(current: Coords2D, parent: Coords2D) -> (i32, i32) {
+    //Calculate direction, restricted to the range -1..=1 without converting coordinates to signed values
+    (
+        current.0.cmp(&parent.0) as i32,
+        current.1.cmp(&parent.1) as i32,
+    )
 }
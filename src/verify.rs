@@ -0,0 +1,152 @@
+use alloc::format;
+use core::error::Error;
+use core::fmt;
+
+use crate::grid::Coords2D;
+use crate::grid::Map2D;
+use crate::grid::MovingAiMap;
+
+use crate::astar::{explore, step_cost};
+use crate::jps::explore_with_rule;
+use crate::{PathError, SearchContext, SearchOptions};
+
+//Largest relative difference between two route lengths still treated as equal,
+//as JPS adds up whole jumps where Dijkstra adds up single steps
+const TOLERANCE: f64 = 1e-9;
+
+///A scenario where JPS and Dijkstra's algorithm disagree on the length of the shortest route,
+///or on whether one exists. Found by [`verify_jps`], or raised as a panic by every JPS search
+///when the `verify` feature is enabled.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Discrepancy {
+    ///The width and height of the map searched.
+    pub map_size: (usize, usize),
+    ///The start of the scenario.
+    pub start: Coords2D,
+    ///The goal of the scenario.
+    pub goal: Coords2D,
+    ///The length of the route found by JPS, or why it found none.
+    pub jps: Result<f64, PathError>,
+    ///The length of the route found by Dijkstra's algorithm, or why it found none.
+    pub dijkstra: Result<f64, PathError>,
+}
+
+impl fmt::Display for Discrepancy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let outcome = |result: &Result<f64, PathError>| match result {
+            Ok(distance) => format!("a route of length {}", distance),
+            Err(error) => format!("an error ({})", error),
+        };
+        write!(
+            f,
+            "JPS found {} but Dijkstra found {} from {:?} to {:?} on a {}x{} map",
+            outcome(&self.jps),
+            outcome(&self.dijkstra),
+            self.start,
+            self.goal,
+            self.map_size.0,
+            self.map_size.1
+        )
+    }
+}
+
+impl Error for Discrepancy {}
+
+///Checks JPS against Dijkstra's algorithm on a single scenario, returning the [`Discrepancy`] if their
+///routes differ in length or only one of them finds a route. Searches that are cancelled are not compared.
+///
+///Dijkstra's algorithm is far slower than JPS on open maps, so this is intended for tests and debugging.
+///A custom [`PruningRule`](crate::PruningRule) that gives up optimality will be reported too.
+///JPS ignores congestion, cost stacks and swamp avoidance, so Dijkstra's algorithm does too.
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+/// use blitz_path::SearchOptions;
+///
+/// let map = movingai::parser::parse_map_file(Path::new("./tests/map/maze512-32-9.map")).expect("Could not load map.");
+/// let scenes = movingai::parser::parse_scen_file(Path::new("./tests/map/maze512-32-9.map.scen")).expect("Could not load scenario.");
+///
+/// for scene in scenes.iter().take(10) {
+///     blitz_path::verify_jps(&map, scene.start_pos, scene.goal_pos, &SearchOptions::new()).unwrap();
+/// }
+/// ```
+pub fn verify_jps(
+    map: &MovingAiMap,
+    start: Coords2D,
+    goal: Coords2D,
+    options: &SearchOptions,
+) -> Result<(), Discrepancy> {
+    let jps = explore_with_rule(
+        &mut SearchContext::new(),
+        map,
+        start,
+        goal,
+        options,
+        &mut (),
+    )
+    .map(|node| node.g);
+    compare(map, start, goal, options, jps)
+}
+
+//Panics with the discrepancy if a finished JPS search disagrees with Dijkstra's algorithm
+#[cfg(feature = "verify")]
+pub(crate) fn assert_optimal(
+    map: &MovingAiMap,
+    start: Coords2D,
+    goal: Coords2D,
+    options: &SearchOptions,
+    jps: Result<f64, PathError>,
+) {
+    if let Err(discrepancy) = compare(map, start, goal, options, jps) {
+        panic!("{}", discrepancy);
+    }
+}
+
+//Runs Dijkstra's algorithm as an oracle for a JPS result
+fn compare(
+    map: &MovingAiMap,
+    start: Coords2D,
+    goal: Coords2D,
+    options: &SearchOptions,
+    jps: Result<f64, PathError>,
+) -> Result<(), Discrepancy> {
+    if jps == Err(PathError::Cancelled) {
+        return Ok(());
+    }
+    //JPS takes every step at its length, so Dijkstra leaves out the options that change step costs
+    let uniform = SearchOptions {
+        congestion: None,
+        cost_stack: None,
+        avoid_swamps: false,
+        ..options.clone()
+    };
+    let dijkstra = explore(
+        &mut SearchContext::new(),
+        map,
+        start,
+        goal,
+        &uniform,
+        step_cost,
+        |_, _| 0.0,
+        &mut (),
+    )
+    .map(|node| node.g);
+
+    let agrees = match (jps, dijkstra) {
+        (_, Err(PathError::Cancelled)) => true,
+        (Ok(jps), Ok(dijkstra)) => (jps - dijkstra).abs() <= TOLERANCE * dijkstra.max(1.0),
+        (jps, dijkstra) => jps == dijkstra,
+    };
+    if agrees {
+        Ok(())
+    } else {
+        Err(Discrepancy {
+            map_size: (map.width(), map.height()),
+            start,
+            goal,
+            jps,
+            dijkstra,
+        })
+    }
+}
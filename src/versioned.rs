@@ -0,0 +1,261 @@
+//!A versioned envelope for saving routes and preprocessed data, such as navigation data kept in save files.
+//!
+//!The `serde` feature derives `Serialize` and `Deserialize` for many types, but their plain layout may change
+//!between releases. Wrapping a [`Stable`] type in [`Versioned`], or tagging a field with
+//!`#[serde(with = "blitz_path::versioned")]`, records what kind of data it is and the [`FORMAT_VERSION`]
+//!it was written in, as `{"kind": ..., "version": ..., "data": ...}`.
+//!
+//!# Stability policy
+//!
+//!- The layout of every [`Stable`] type is fixed for a given format version.
+//!- Any change to one of those layouts increases [`FORMAT_VERSION`], and the type's [`Stable::migrate`] gains a
+//!  case reading the old layout into the new type, so data written by every earlier version keeps loading.
+//!  Migrations are only dropped in a release that changes the major version of the crate.
+//!- Data written by a newer format version, or holding a different kind of data, fails to load with an error
+//!  rather than loading wrongly.
+//!- Data saved without the envelope carries no guarantee.
+//!
+//!Maps use the layout of [`MovingAiMap`](crate::grid::MovingAiMap), which the standalone grid shares with movingai.
+//! # Examples
+//!
+//! ```
+//! use std::path::Path;
+//! use blitz_path::{components, Components, Versioned};
+//!
+//! let map = movingai::parser::parse_map_file(Path::new("./tests/map/maze512-32-9.map")).expect("Could not load map.");
+//! let regions = components(&map);
+//!
+//! let saved = serde_json::to_string(&Versioned::new(regions)).unwrap();
+//! assert!(saved.starts_with(r#"{"kind":"Components","version":1,"#));
+//!
+//! let loaded: Versioned<Components> = serde_json::from_str(&saved).unwrap();
+//! assert_eq!(loaded.version(), 1);
+//! assert!(loaded.get().same_component((1, 1), (5, 5)));
+//! ```
+
+use alloc::string::String;
+use core::fmt;
+use core::marker::PhantomData;
+
+use serde::de::{self, DeserializeOwned, DeserializeSeed, MapAccess, SeqAccess, Visitor};
+use serde::ser::SerializeStruct;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::grid::MovingAiMap;
+use crate::{
+    Anisotropy, ClearanceMap, Components, CongestionMap, Crowd, DesireMap, DirectionalCosts,
+    FlowField, FogOfWar, Heightmap, InfluenceMap, Landmarks, MapDiff, OccupancyGrid,
+    PrioritizedPlan, RealTimeAgent, ReservationTable, Route, RouteCurve, TimedRoute, VisibilityMap,
+    VoronoiRegions, WeightedRegions,
+};
+
+///The format version written by this release. See the [stability policy](self#stability-policy).
+pub const FORMAT_VERSION: u32 = 1;
+
+const FIELDS: &[&str] = &["kind", "version", "data"];
+
+///A type whose serialized layout is covered by the [stability policy](self#stability-policy).
+pub trait Stable: Serialize + DeserializeOwned {
+    ///The name recorded with the data, checked when loading so one kind of data is never read as another.
+    const KIND: &'static str;
+
+    ///Reads data written in format `version`, which is never newer than [`FORMAT_VERSION`].
+    ///The provided method reads the current layout and rejects every other version, and is overridden
+    ///once a type's layout changes.
+    fn migrate<'de, D: Deserializer<'de>>(version: u32, data: D) -> Result<Self, D::Error> {
+        if version == FORMAT_VERSION {
+            Self::deserialize(data)
+        } else {
+            Err(de::Error::custom(format_args!(
+                "{} data in format version {} cannot be read",
+                Self::KIND,
+                version
+            )))
+        }
+    }
+}
+
+macro_rules! stable {
+    ($($kind:ident),* $(,)?) => {
+        $(
+            impl Stable for $kind {
+                const KIND: &'static str = stringify!($kind);
+            }
+        )*
+    };
+}
+
+stable!(
+    Anisotropy,
+    ClearanceMap,
+    Components,
+    CongestionMap,
+    Crowd,
+    DesireMap,
+    DirectionalCosts,
+    FlowField,
+    FogOfWar,
+    Heightmap,
+    InfluenceMap,
+    Landmarks,
+    MapDiff,
+    MovingAiMap,
+    OccupancyGrid,
+    PrioritizedPlan,
+    RealTimeAgent,
+    ReservationTable,
+    Route,
+    RouteCurve,
+    TimedRoute,
+    VisibilityMap,
+    VoronoiRegions,
+    WeightedRegions,
+);
+
+///Data together with the kind and format version it was saved with, migrated to the current layout when loaded.
+///Always saved in the current [`FORMAT_VERSION`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Versioned<T> {
+    version: u32,
+    data: T,
+}
+
+impl<T: Stable> Versioned<T> {
+    ///Wraps data to be saved in the current format version.
+    pub fn new(data: T) -> Versioned<T> {
+        Versioned {
+            version: FORMAT_VERSION,
+            data,
+        }
+    }
+
+    ///Returns the format version the data was loaded from, or the current one for new data.
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    ///Returns the data.
+    pub fn get(&self) -> &T {
+        &self.data
+    }
+
+    ///Returns the data, discarding its version.
+    pub fn into_inner(self) -> T {
+        self.data
+    }
+}
+
+impl<T: Stable> Serialize for Versioned<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize(&self.data, serializer)
+    }
+}
+
+impl<'de, T: Stable> Deserialize<'de> for Versioned<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Versioned<T>, D::Error> {
+        deserializer.deserialize_struct("Versioned", FIELDS, VersionedVisitor(PhantomData))
+    }
+}
+
+///Saves `value` in the versioned envelope, for use with `#[serde(with = "blitz_path::versioned")]`.
+pub fn serialize<T: Stable, S: Serializer>(value: &T, serializer: S) -> Result<S::Ok, S::Error> {
+    let mut state = serializer.serialize_struct("Versioned", FIELDS.len())?;
+    state.serialize_field("kind", T::KIND)?;
+    state.serialize_field("version", &FORMAT_VERSION)?;
+    state.serialize_field("data", value)?;
+    state.end()
+}
+
+///Loads a value saved in the versioned envelope, migrating it from older format versions,
+///for use with `#[serde(with = "blitz_path::versioned")]`.
+pub fn deserialize<'de, T: Stable, D: Deserializer<'de>>(deserializer: D) -> Result<T, D::Error> {
+    Versioned::deserialize(deserializer).map(Versioned::into_inner)
+}
+
+//Reject data of another kind or from a newer release before reading it
+fn check<T: Stable, E: de::Error>(kind: &str, version: u32) -> Result<(), E> {
+    if kind != T::KIND {
+        return Err(E::custom(format_args!(
+            "expected {} data, found {}",
+            T::KIND,
+            kind
+        )));
+    }
+    if version > FORMAT_VERSION {
+        return Err(E::custom(format_args!(
+            "{} data in format version {} is newer than the latest supported version {}",
+            kind, version, FORMAT_VERSION
+        )));
+    }
+    Ok(())
+}
+
+//Reads the data itself, once the version it was written in is known
+struct Data<T> {
+    version: u32,
+    kind: PhantomData<T>,
+}
+
+impl<'de, T: Stable> DeserializeSeed<'de> for Data<T> {
+    type Value = T;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<T, D::Error> {
+        T::migrate(self.version, deserializer)
+    }
+}
+
+struct VersionedVisitor<T>(PhantomData<T>);
+
+impl<'de, T: Stable> Visitor<'de> for VersionedVisitor<T> {
+    type Value = Versioned<T>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(formatter, "versioned {} data", T::KIND)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Versioned<T>, A::Error> {
+        let kind: String = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+        let version: u32 = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(1, &self))?;
+        check::<T, A::Error>(&kind, version)?;
+        let data = seq
+            .next_element_seed(Data {
+                version,
+                kind: PhantomData,
+            })?
+            .ok_or_else(|| de::Error::invalid_length(2, &self))?;
+        Ok(Versioned { version, data })
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Versioned<T>, A::Error> {
+        let mut kind: Option<String> = None;
+        let mut version: Option<u32> = None;
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
+                "kind" => kind = Some(map.next_value()?),
+                "version" => version = Some(map.next_value()?),
+                "data" => {
+                    //The data can only be read once its kind and version are known, which are always saved first
+                    let kind = kind.ok_or_else(|| de::Error::missing_field("kind"))?;
+                    let version = version.ok_or_else(|| de::Error::missing_field("version"))?;
+                    check::<T, A::Error>(&kind, version)?;
+                    let data = map.next_value_seed(Data {
+                        version,
+                        kind: PhantomData,
+                    })?;
+                    while map.next_key::<de::IgnoredAny>()?.is_some() {
+                        map.next_value::<de::IgnoredAny>()?;
+                    }
+                    return Ok(Versioned { version, data });
+                }
+                _ => {
+                    map.next_value::<de::IgnoredAny>()?;
+                }
+            }
+        }
+        Err(de::Error::missing_field("data"))
+    }
+}
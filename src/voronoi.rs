@@ -0,0 +1,118 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::grid::Coords2D;
+use crate::grid::Map2D;
+use crate::grid::MovingAiMap;
+
+use crate::flood::flood;
+use crate::memory::vec_size;
+use crate::utils::{distance, index};
+use crate::MemoryFootprint;
+
+///Every traversable tile of a map assigned to the seed it is closest to by path distance,
+///for uses like territory assignment or balancing spawn points.
+///Tiles equally close to several seeds are given to one of them consistently between runs.
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+///
+/// let map = movingai::parser::parse_map_file(Path::new("./tests/map/maze512-32-9.map")).expect("Could not load map.");
+///
+/// let regions = blitz_path::VoronoiRegions::new(&map, &[(1, 1), (5, 1)]);
+/// assert_eq!(regions.owner((2, 1)), Some(0));
+/// assert_eq!(regions.owner((4, 1)), Some(1));
+/// assert_eq!(regions.distance((4, 1)), Some(1.0));
+/// assert_eq!(regions.owner((0, 0)), None);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct VoronoiRegions {
+    width: usize,
+    height: usize,
+    owners: Vec<Option<usize>>,
+    distances: Vec<f64>,
+}
+
+impl VoronoiRegions {
+    ///Assigns every tile of `map` to the nearest of `seeds`, given as the index of the seed in the slice.
+    ///Blocked seeds, and tiles no seed can reach, are left unassigned.
+    pub fn new(map: &MovingAiMap, seeds: &[Coords2D]) -> VoronoiRegions {
+        let sources: Vec<(Coords2D, f64)> = seeds.iter().map(|seed| (*seed, 0.0)).collect();
+
+        //Tiles settle in order of distance, so every tile settles after the tile it was reached from
+        let mut settled = Vec::new();
+        let reached = flood(
+            map,
+            &sources,
+            f64::INFINITY,
+            |from, to| Some(distance(from, to)),
+            |position, _| {
+                settled.push(position);
+                false
+            },
+        );
+
+        let mut owners = vec![None; map.width() * map.height()];
+        //Reversed so a tile used as a seed more than once belongs to the first
+        for (number, seed) in seeds.iter().enumerate().rev() {
+            if map.is_traversable(*seed) {
+                owners[index(map, *seed)] = Some(number);
+            }
+        }
+        for position in settled {
+            let parent = reached.parents[index(map, position)];
+            if parent != position {
+                owners[index(map, position)] = owners[index(map, parent)];
+            }
+        }
+
+        VoronoiRegions {
+            width: map.width(),
+            height: map.height(),
+            owners,
+            distances: reached.costs,
+        }
+    }
+
+    ///Returns the index of the seed closest to a tile, or None if it is blocked, unreachable or outside the map.
+    pub fn owner(&self, position: Coords2D) -> Option<usize> {
+        if position.0 >= self.width || position.1 >= self.height {
+            return None;
+        }
+        self.owners[position.1 * self.width + position.0]
+    }
+
+    ///Returns the path distance from a tile to its closest seed, or None if it has no owner.
+    pub fn distance(&self, position: Coords2D) -> Option<f64> {
+        self.owner(position)
+            .map(|_| self.distances[position.1 * self.width + position.0])
+    }
+
+    ///Returns the owner of every tile in row-major order.
+    pub fn owners(&self) -> &[Option<usize>] {
+        &self.owners
+    }
+
+    ///Returns the distance from every tile to its owner in row-major order, infinite for tiles without one.
+    pub fn distances(&self) -> &[f64] {
+        &self.distances
+    }
+
+    ///Returns the width of the underlying map.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    ///Returns the height of the underlying map.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+}
+
+impl MemoryFootprint for VoronoiRegions {
+    fn heap_size(&self) -> usize {
+        vec_size(&self.owners) + vec_size(&self.distances)
+    }
+}
@@ -0,0 +1,83 @@
+//Bindings for using the crate from JavaScript through wasm-bindgen
+
+use crate::grid::MovingAiMap;
+use wasm_bindgen::prelude::*;
+
+use crate::{PathError, SearchOptions};
+
+///A map that can be searched from JavaScript.
+#[wasm_bindgen(js_name = Map)]
+pub struct WasmMap {
+    map: MovingAiMap,
+}
+
+#[wasm_bindgen(js_class = Map)]
+impl WasmMap {
+    ///Creates a map from one byte per tile in row-major order, with zero for blocked tiles and anything else for open ones.
+    ///Accepts a Uint8Array.
+    #[wasm_bindgen(constructor)]
+    pub fn new(width: usize, height: usize, tiles: &[u8]) -> Result<WasmMap, JsError> {
+        let count = width
+            .checked_mul(height)
+            .ok_or_else(|| JsError::new(&format!("a {}x{} map is too large", width, height)))?;
+        if tiles.len() != count {
+            return Err(JsError::new(&format!(
+                "expected {} tiles for a {}x{} map but got {}",
+                count,
+                width,
+                height,
+                tiles.len()
+            )));
+        }
+
+        let tiles = tiles
+            .iter()
+            .map(|tile| if *tile == 0 { '@' } else { '.' })
+            .collect();
+        Ok(WasmMap {
+            map: MovingAiMap::new(String::from("octile"), height, width, tiles),
+        })
+    }
+
+    ///Finds a route using the JPS algorithm, returned as a Uint32Array of alternating x and y coordinates.
+    ///Steps are in the same order as a Route, with the goal first and the start last.
+    #[wasm_bindgen(js_name = jpsPath)]
+    pub fn jps_path(
+        &self,
+        start_x: usize,
+        start_y: usize,
+        goal_x: usize,
+        goal_y: usize,
+    ) -> Result<Vec<u32>, JsError> {
+        let route =
+            crate::jps_path(&self.map, (start_x, start_y), (goal_x, goal_y)).map_err(to_js)?;
+        Ok(route
+            .steps()
+            .iter()
+            .flat_map(|(x, y)| [*x as u32, *y as u32])
+            .collect())
+    }
+
+    ///Finds the length of the shortest route using the JPS algorithm.
+    #[wasm_bindgen(js_name = jpsDistance)]
+    pub fn jps_distance(
+        &self,
+        start_x: usize,
+        start_y: usize,
+        goal_x: usize,
+        goal_y: usize,
+    ) -> Result<f64, JsError> {
+        crate::jps_distance(
+            &self.map,
+            (start_x, start_y),
+            (goal_x, goal_y),
+            &SearchOptions::new(),
+        )
+        .map_err(to_js)
+    }
+}
+
+//Errors are raised in JavaScript with the same message they display with in Rust
+fn to_js(error: PathError) -> JsError {
+    JsError::new(&error.to_string())
+}
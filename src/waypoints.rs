@@ -0,0 +1,154 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::grid::Coords2D;
+use crate::grid::MovingAiMap;
+
+use crate::flood::flood;
+use crate::utils::{compose, distance, index, prepare, validate};
+use crate::{PathError, Route, RouteSteps, SearchContext, SearchOptions};
+
+///Creates a single route visiting each of `waypoints` in order using the A* algorithm, such as for a patrol.
+///Each leg is searched separately and the legs are joined, with the route's distance being the total of every leg.
+///Steps are ordered as for any other route, with the last waypoint at [0] and the first at the end.
+///Returns the error from the first leg that fails, or `PathError::NoPath` if no waypoints are given.
+///
+///`options` applies to the route as a whole: the maximum distance limits the total of every leg,
+///and the steps listed are chosen once the legs have been joined.
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+/// use blitz_path::SearchOptions;
+///
+/// let map = movingai::parser::parse_map_file(Path::new("./tests/map/maze512-32-9.map")).expect("Could not load map.");
+///
+/// let path = blitz_path::route_through(&map, &[(1, 1), (5, 1), (1, 1)], &SearchOptions::new()).unwrap();
+/// assert_eq!(path.distance(), 8.0);
+/// assert_eq!(path.steps().len(), 9);
+/// ```
+pub fn route_through(
+    map: &MovingAiMap,
+    waypoints: &[Coords2D],
+    options: &SearchOptions,
+) -> Result<Route, PathError> {
+    let mut context = SearchContext::new();
+    let mut legs = options.clone();
+    legs.route_steps = RouteSteps::Cells;
+    legs.exclude_start = false;
+    legs.repeat_goal = false;
+
+    //A lone waypoint is a route that goes nowhere
+    let first = *waypoints.first().ok_or(PathError::NoPath)?;
+    let (first, _) = prepare(map, first, first, options)?;
+    let mut total = 0.0;
+    let mut cells = vec![first];
+
+    for leg in waypoints.windows(2) {
+        //Whatever earlier legs used up is no longer available to later ones
+        legs.max_distance = options.max_distance.map(|max| max - total);
+        let route = context.a_star_path(map, leg[0], leg[1], &legs)?;
+
+        //Legs list their start last, and it is already the end of the route so far
+        total += route.distance();
+        cells.extend(route.steps().iter().rev().skip(1));
+    }
+
+    cells.reverse();
+    Ok(Route::from((total, compose(cells, options))))
+}
+
+///Visits every one of `destinations` starting from `start`, choosing a short order to visit them in.
+///Returns the order chosen, as indices into `destinations`, along with the route through them.
+///
+///The distance between every pair of points is found first, then an order is built by always heading to the
+///nearest unvisited destination and improved by reversing parts of it while that makes it shorter.
+///This finds a good order quickly but not always the best one, and is meant for a handful of destinations.
+///Returns `PathError::NoPath` if any destination cannot be reached, and `options` applies as for [`route_through`].
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+/// use blitz_path::SearchOptions;
+///
+/// let map = movingai::parser::parse_map_file(Path::new("./tests/map/maze512-32-9.map")).expect("Could not load map.");
+///
+/// let (order, path) = blitz_path::visit_all(&map, (1, 1), &[(5, 1), (3, 1), (4, 1)], &SearchOptions::new()).unwrap();
+/// assert_eq!(order, vec![1, 2, 0]);
+/// assert_eq!(path.distance(), 4.0);
+/// ```
+pub fn visit_all(
+    map: &MovingAiMap,
+    start: Coords2D,
+    destinations: &[Coords2D],
+    options: &SearchOptions,
+) -> Result<(Vec<usize>, Route), PathError> {
+    for destination in destinations {
+        validate(map, start, *destination)?;
+    }
+
+    //Costs between every pair of points, with the start as point 0, priced as the legs will be
+    let points: Vec<Coords2D> = core::iter::once(start)
+        .chain(destinations.iter().copied())
+        .collect();
+    let mut distances = Vec::with_capacity(points.len());
+    for point in &points {
+        let reached = flood(
+            map,
+            &[(*point, 0.0)],
+            f64::INFINITY,
+            |from, to| options.step_cost(from, to, distance(from, to)),
+            |_, _| false,
+        );
+        let row: Vec<f64> = points
+            .iter()
+            .map(|other| reached.costs[index(map, *other)])
+            .collect();
+        if row.iter().any(|cost| !cost.is_finite()) {
+            return Err(PathError::NoPath);
+        }
+        distances.push(row);
+    }
+
+    //Start from the nearest unvisited point each time
+    let mut order = vec![0];
+    let mut unvisited: Vec<usize> = (1..points.len()).collect();
+    while !unvisited.is_empty() {
+        let current = order[order.len() - 1];
+        let mut nearest = 0;
+        for (position, point) in unvisited.iter().enumerate() {
+            if distances[current][*point] < distances[current][unvisited[nearest]] {
+                nearest = position;
+            }
+        }
+        order.push(unvisited.remove(nearest));
+    }
+
+    //Reverse sections of the order while doing so shortens it, leaving the start in place
+    let cost = |a: usize, b: usize| distances[a][b];
+    let mut improved = true;
+    while improved {
+        improved = false;
+        for first in 1..order.len() {
+            for last in first + 1..order.len() {
+                let before = cost(order[first - 1], order[first]);
+                let after = cost(order[first - 1], order[last]);
+                let (before, after) = match order.get(last + 1) {
+                    Some(next) => (
+                        before + cost(order[last], *next),
+                        after + cost(order[first], *next),
+                    ),
+                    None => (before, after),
+                };
+                if after + 1e-9 < before {
+                    order[first..=last].reverse();
+                    improved = true;
+                }
+            }
+        }
+    }
+
+    let waypoints: Vec<Coords2D> = order.iter().map(|point| points[*point]).collect();
+    let route = route_through(map, &waypoints, options)?;
+    Ok((order[1..].iter().map(|point| point - 1).collect(), route))
+}
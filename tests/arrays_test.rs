@@ -0,0 +1,50 @@
+#[cfg(all(test, feature = "ndarray"))]
+mod tests {
+    use ndarray::{array, Array2};
+
+    use blitz_path::{
+        array_cost, dijkstra_path_weighted, map_from_costs, map_from_occupancy, occupancy_from_map,
+        PathError, SearchOptions,
+    };
+
+    #[test]
+    fn occupancy_grids() {
+        //Indexed [y, x], so the blocked column is x = 2
+        let mut occupancy = Array2::from_elem((4, 5), false);
+        for y in 0..3 {
+            occupancy[[y, 2]] = true;
+        }
+        let map = map_from_occupancy(occupancy.view());
+        assert!(blitz_path::is_reachable(&map, (0, 0), (4, 0)));
+        assert!(!blitz_path::line_of_sight(&map, (0, 0), (4, 0)));
+        assert_eq!(occupancy_from_map(&map), occupancy);
+
+        //Views with other memory layouts are read in logical order
+        let transposed = map_from_occupancy(occupancy.t());
+        assert_eq!(occupancy_from_map(&transposed), occupancy.t());
+    }
+
+    #[test]
+    fn cost_grids() {
+        let wall = f32::INFINITY;
+        let costs = array![
+            [wall, wall, wall, wall, wall],
+            [wall, 1.0, 0.5, 1.0, wall],
+            [wall, 1.0, 2.0, 1.0, wall],
+            [wall, 1.0, f32::NAN, 1.0, wall],
+            [wall, wall, wall, wall, wall],
+        ];
+        let map = map_from_costs(costs.view());
+        let options = SearchOptions::new();
+
+        let path = dijkstra_path_weighted(&map, (1, 2), (3, 2), &options, array_cost(costs.view()))
+            .unwrap();
+        assert_eq!(path.steps(), vec![(3, 2), (2, 1), (1, 2)]);
+        assert!((path.distance() - 1.5 * 2f64.sqrt()).abs() < 1e-9);
+
+        assert_eq!(
+            dijkstra_path_weighted(&map, (1, 2), (2, 3), &options, array_cost(costs.view())).err(),
+            Some(PathError::GoalBlocked((2, 3)))
+        );
+    }
+}
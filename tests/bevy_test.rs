@@ -0,0 +1,34 @@
+#[cfg(all(test, feature = "bevy"))]
+mod tests {
+    use bevy_app::App;
+
+    use blitz_path::{PathError, PathGrid, PathRequest, PathResult, PathfindingPlugin};
+
+    #[test]
+    fn requests_are_solved() {
+        //Converted from a collision grid, with a wall down the middle
+        let grid = PathGrid::from_fn(6, 4, |(x, y)| {
+            !(x == 0 || y == 0 || x == 5 || y == 3 || (x == 3 && y < 2))
+        });
+
+        let mut app = App::new();
+        app.add_plugins(PathfindingPlugin).insert_resource(grid);
+        let found = app.world_mut().spawn(PathRequest::new((1, 1), (4, 1))).id();
+        let blocked = app.world_mut().spawn(PathRequest::new((1, 1), (3, 1))).id();
+        app.update();
+
+        let world = app.world();
+        assert!(world.get::<PathRequest>(found).is_none());
+        let route = world.get::<PathResult>(found).unwrap().0.as_ref().unwrap();
+        assert_eq!(route.steps().first(), Some(&(4, 1)));
+        assert_eq!(route.steps().last(), Some(&(1, 1)));
+        //Down past the wall and back up, without cutting its corners
+        assert!((route.distance() - (3.0 + 2f64.sqrt())).abs() < 1e-9);
+
+        let result = world.get::<PathResult>(blocked).unwrap();
+        assert_eq!(
+            result.0.as_ref().err(),
+            Some(&PathError::GoalBlocked((3, 1)))
+        );
+    }
+}
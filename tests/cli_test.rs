@@ -0,0 +1,77 @@
+#[cfg(all(test, feature = "movingai"))]
+mod tests {
+    use std::process::Command;
+
+    const MAP: &str = "./tests/map/maze512-32-9.map";
+    const SCEN: &str = "./tests/map/maze512-32-9.map.scen";
+
+    fn run(args: &[&str]) -> (Option<i32>, String) {
+        let output = Command::new(env!("CARGO_BIN_EXE_blitz-path"))
+            .args(args)
+            .output()
+            .unwrap();
+        (
+            output.status.code(),
+            String::from_utf8(output.stdout).unwrap(),
+        )
+    }
+
+    #[test]
+    fn solves_scenarios() {
+        let (code, output) = run(&[MAP, SCEN, "--algorithm", "astar", "--limit", "3"]);
+        assert_eq!(code, Some(0));
+
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 5);
+        assert!(lines[1].starts_with("0\t(295, 95)\t(292, 96)\t3.41421356\t3.41421356\t"));
+        assert!(lines[1].ends_with("\tok"));
+        assert!(lines[4].starts_with("3 scenarios, 0 mismatches"));
+    }
+
+    #[test]
+    fn benchmarks_directory() {
+        let (code, output) = run(&["bench", "./tests/map", "--limit", "2"]);
+        assert_eq!(code, Some(0));
+
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(
+            lines[0],
+            "map,scenario,algorithm,expected,cost,micros,expanded"
+        );
+        let rows: Vec<Vec<&str>> = lines[1..]
+            .iter()
+            .map(|line| line.split(',').collect())
+            .collect();
+        assert!(rows.len() >= 6);
+        assert_eq!(
+            &rows[0][..5],
+            &["maze512-32-9.map", "0", "jps", "3.41421356", "3.41421356"]
+        );
+        assert!(rows[0][6].parse::<usize>().unwrap() > 0);
+        let dijkstra = rows.iter().find(|row| row[2] == "dijkstra").unwrap();
+        assert!(dijkstra[6].parse::<usize>().unwrap() > 0);
+
+        let (code, output) = run(&[
+            "bench",
+            "./tests/map",
+            "--limit",
+            "1",
+            "--algorithm",
+            "astar",
+        ]);
+        assert_eq!(code, Some(0));
+        assert_eq!(output.lines().count(), 2);
+    }
+
+    #[test]
+    fn rejects_bad_arguments() {
+        assert_eq!(run(&[MAP]).0, Some(2));
+        assert_eq!(run(&[MAP, SCEN, "--algorithm", "bfs"]).0, Some(2));
+        assert_eq!(run(&[MAP, "missing.scen"]).0, Some(2));
+        assert_eq!(run(&["bench"]).0, Some(2));
+        assert_eq!(
+            run(&["bench", "./tests/map", "--algorithm", "bfs"]).0,
+            Some(2)
+        );
+    }
+}
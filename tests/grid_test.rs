@@ -0,0 +1,74 @@
+//Only uses the map types re-exported by the crate, so runs against movingai's maps by default
+//and against the standalone grid with `cargo test --no-default-features --tests`
+#[cfg(test)]
+mod tests {
+    use blitz_path::grid::{Map2D, MovingAiMap};
+    use blitz_path::{a_star_path, jps_path, PathError};
+
+    fn map_from(map_type: &str, rows: &[&str]) -> MovingAiMap {
+        let tiles = rows.iter().flat_map(|row| row.chars()).collect();
+        MovingAiMap::new(String::from(map_type), rows.len(), rows[0].len(), tiles)
+    }
+
+    #[test]
+    fn movement_rules() {
+        let map = map_from(
+            "octile",
+            &[
+                "@@@@@@", //
+                "@.@.W@", //
+                "@...W@", //
+                "@.SST@", //
+                "@@@@@@", //
+            ],
+        );
+        assert_eq!((map.width(), map.height()), (6, 5));
+        assert_eq!(map[(1, 1)], '.');
+        assert_eq!(map.free_states(), 10);
+        assert_eq!(map.coords().nth(7), Some((1, 1)));
+        assert!(map.is_out_of_bound((6, 0)));
+        assert!(!map.is_traversable((4, 3)));
+
+        //Diagonals may not cut corners
+        assert!(!map.is_traversable_from((1, 1), (2, 2)));
+        assert!(map.is_traversable_from((1, 2), (2, 3)));
+        assert!(!map.is_traversable_from((1, 1), (3, 3)));
+
+        //Water is only entered from water, and swamp from open ground or swamp
+        assert!(!map.is_traversable_from((3, 2), (4, 2)));
+        assert!(map.is_traversable_from((4, 1), (4, 2)));
+        assert!(map.is_traversable_from((2, 2), (2, 3)));
+        assert!(map.is_traversable_from((2, 3), (3, 3)));
+        assert!(!map.is_traversable_from((4, 2), (3, 3)));
+        let mut neighbours = map.neighbors((2, 2));
+        neighbours.sort();
+        assert_eq!(neighbours, [(1, 2), (1, 3), (2, 3), (3, 2), (3, 3)]);
+    }
+
+    #[test]
+    fn searches_on_grids() {
+        let map = map_from(
+            "octile",
+            &[
+                "......", //
+                ".@@@@.", //
+                "......", //
+            ],
+        );
+        let path = a_star_path(&map, (0, 2), (5, 0)).unwrap();
+        assert_eq!(path.distance(), 7.0);
+        assert_eq!(jps_path(&map, (0, 0), (5, 0)).unwrap().distance(), 5.0);
+        assert_eq!(
+            a_star_path(&map, (0, 0), (1, 1)).err(),
+            Some(PathError::GoalBlocked((1, 1)))
+        );
+
+        //Only straight steps on maps that are not octile
+        let straight = map_from("4-connected", &["...", "..."]);
+        assert!(!straight.is_traversable_from((0, 0), (1, 1)));
+        assert_eq!(
+            a_star_path(&straight, (0, 0), (2, 1)).unwrap().distance(),
+            3.0
+        );
+    }
+}
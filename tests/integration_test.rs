@@ -1,12 +1,33 @@
-#[cfg(test)]
+//The scenario tests are kept as originally written
+#![allow(
+    clippy::len_zero,
+    clippy::manual_range_contains,
+    clippy::needless_borrow,
+    clippy::ptr_arg,
+    clippy::upper_case_acronyms
+)]
+
+#[cfg(all(test, feature = "movingai"))]
 mod tests {
     use std::path::Path;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::thread;
+    use std::time::Duration;
 
     use movingai::parser::parse_map_file;
     use movingai::parser::parse_scen_file;
-    use movingai::{MovingAiMap, SceneRecord};
+    use movingai::{Coords2D, MovingAiMap, SceneRecord};
 
-    use blitz_path::{a_star_path, jps_path};
+    use blitz_path::{
+        a_star_distance, a_star_path, a_star_path_observed, a_star_path_streamed,
+        a_star_path_weighted, a_star_path_with, dijkstra_path, find_path, jps_distance, jps_path,
+        jps_path_streamed, jps_path_with, nearest_traversable, run_benchmark_set, run_scen_suite,
+        verify_jps, CancelToken, DefaultPruning, MapLoadError, OffsetMap, PathError, PruningRule,
+        RouteSteps, SearchContext, SearchEvent, SearchOptions, SearchRecording, StreamEvent,
+    };
+    //Named apart from the enum the scenario tests choose their search with
+    use blitz_path::Algorithm as SearchAlgorithm;
 
     const MAP: &str = "./tests/map/maze512-32-9.map";
     const SCEN: &str = "./tests/map/maze512-32-9.map.scen";
@@ -34,10 +55,10 @@ mod tests {
             };
 
             match path {
-                None => {
+                Err(_) => {
                     errors.push(format!("Test #{} found no path", index));
                 }
-                Some(path) => {
+                Ok(path) => {
                     for num in 0..path.steps().len() - 1 {
                         //Test wheter the x or y diustance between any adjacent steps is more than 1
                         let direction_x =
@@ -120,4 +141,743 @@ mod tests {
             errors
         );
     }
+
+    #[test]
+    fn out_of_bounds_endpoints() {
+        let map = parse_map_file(Path::new(MAP)).unwrap();
+
+        assert_eq!(
+            a_star_path(&map, (512, 1), (1, 1)).err(),
+            Some(PathError::StartOutOfBounds((512, 1)))
+        );
+        assert_eq!(
+            jps_path(&map, (1, 1), (1, 600)).err(),
+            Some(PathError::GoalOutOfBounds((1, 600)))
+        );
+    }
+
+    #[test]
+    fn blocked_endpoints() {
+        let map = parse_map_file(Path::new(MAP)).unwrap();
+
+        assert_eq!(
+            a_star_path(&map, (0, 0), (1, 1)).err(),
+            Some(PathError::StartBlocked((0, 0)))
+        );
+        assert_eq!(
+            jps_path(&map, (1, 1), (0, 0)).err(),
+            Some(PathError::GoalBlocked((0, 0)))
+        );
+    }
+
+    #[test]
+    fn snapped_endpoints() {
+        let map = parse_map_file(Path::new(MAP)).unwrap();
+        let options = SearchOptions::new().snap_radius(2);
+
+        //Blocked start and goal both snap to the nearest open tile
+        let path = a_star_path_with(&map, (0, 0), (0, 5), &options).unwrap();
+        assert_eq!(path.steps().first(), Some(&(1, 5)));
+        assert_eq!(path.steps().last(), Some(&(1, 1)));
+
+        //Out of bounds positions snap back onto the map
+        assert_eq!(nearest_traversable(&map, (513, 1), 2), Some((511, 1)));
+        //Radii far larger than the map are clamped rather than overflowing
+        assert_eq!(
+            nearest_traversable(&map, (0, 0), usize::MAX),
+            nearest_traversable(&map, (0, 0), 2)
+        );
+
+        let path = jps_path_with(&map, (0, 0), (3, 3), &options).unwrap();
+        assert_eq!(path.steps().last(), Some(&(1, 1)));
+
+        //Nothing traversable within the radius
+        let options = SearchOptions::new().snap_radius(1);
+        assert_eq!(
+            a_star_path_with(&map, (0, 0), (3, 3), &options).err(),
+            Some(PathError::StartBlocked((0, 0)))
+        );
+    }
+
+    #[test]
+    fn deterministic_routes() {
+        let map = parse_map_file(Path::new(MAP)).unwrap();
+        let scenes = parse_scen_file(Path::new(SCEN)).unwrap();
+
+        for index in TEST_NUMS_A_STAR.iter() {
+            let scene = &scenes[*index];
+            let first = a_star_path(&map, scene.start_pos, scene.goal_pos).unwrap();
+            let second = a_star_path(&map, scene.start_pos, scene.goal_pos).unwrap();
+            assert_eq!(first.steps(), second.steps());
+            assert_eq!(first.distance().to_bits(), second.distance().to_bits());
+
+            let first = jps_path(&map, scene.start_pos, scene.goal_pos).unwrap();
+            let second = jps_path(&map, scene.start_pos, scene.goal_pos).unwrap();
+            assert_eq!(first.steps(), second.steps());
+            assert_eq!(first.distance().to_bits(), second.distance().to_bits());
+        }
+    }
+
+    #[test]
+    fn max_distance() {
+        let map = parse_map_file(Path::new(MAP)).unwrap();
+        let scenes = parse_scen_file(Path::new(SCEN)).unwrap();
+        let scene = &scenes[34];
+
+        //Scenario 34 has an optimal length of ~14.66
+        let options = SearchOptions::new().max_distance(15.0);
+        assert!(a_star_path_with(&map, scene.start_pos, scene.goal_pos, &options).is_ok());
+        assert!(jps_path_with(&map, scene.start_pos, scene.goal_pos, &options).is_ok());
+
+        let options = SearchOptions::new().max_distance(14.0);
+        assert_eq!(
+            a_star_path_with(&map, scene.start_pos, scene.goal_pos, &options).err(),
+            Some(PathError::ExceedsMaxDistance)
+        );
+        assert_eq!(
+            jps_path_with(&map, scene.start_pos, scene.goal_pos, &options).err(),
+            Some(PathError::ExceedsMaxDistance)
+        );
+    }
+
+    #[test]
+    fn observer_events() {
+        let map = parse_map_file(Path::new(MAP)).unwrap();
+        let scenes = parse_scen_file(Path::new(SCEN)).unwrap();
+        let scene = &scenes[34];
+
+        let mut events = Vec::new();
+        let mut record = |event: SearchEvent| events.push(event);
+        let path = a_star_path_observed(
+            &map,
+            scene.start_pos,
+            scene.goal_pos,
+            &SearchOptions::new(),
+            &mut record,
+        )
+        .unwrap();
+
+        //The start is discovered first and every expanded node was discovered earlier
+        match events[0] {
+            SearchEvent::Discovered(info) => assert_eq!(info.position, scene.start_pos),
+            _ => panic!("Expected start to be discovered first"),
+        }
+        for (index, event) in events.iter().enumerate() {
+            if let SearchEvent::Expanded(info) = event {
+                assert!(events[..index].iter().any(|earlier| match earlier {
+                    SearchEvent::Discovered(found) => found.position == info.position,
+                    _ => false,
+                }));
+            }
+        }
+
+        //Observing does not change the result
+        let plain = a_star_path(&map, scene.start_pos, scene.goal_pos).unwrap();
+        assert_eq!(plain.steps(), path.steps());
+    }
+
+    #[test]
+    fn cancel_from_other_thread() {
+        let map = parse_map_file(Path::new(MAP)).unwrap();
+        let scenes = parse_scen_file(Path::new(SCEN)).unwrap();
+        let scene = scenes[1740].clone();
+
+        let token = CancelToken::new();
+        let options = SearchOptions::new().cancel_token(token.clone());
+        let search = thread::spawn(move || {
+            a_star_path_with(&map, scene.start_pos, scene.goal_pos, &options)
+        });
+
+        thread::sleep(Duration::from_millis(20));
+        token.cancel();
+
+        assert_eq!(search.join().unwrap().err(), Some(PathError::Cancelled));
+    }
+
+    #[test]
+    fn streamed_events() {
+        let map = Arc::new(parse_map_file(Path::new(MAP)).unwrap());
+        let scenes = parse_scen_file(Path::new(SCEN)).unwrap();
+        let scene = &scenes[34];
+
+        let events: Vec<StreamEvent> = jps_path_streamed(
+            map.clone(),
+            scene.start_pos,
+            scene.goal_pos,
+            SearchOptions::new(),
+        )
+        .into_iter()
+        .collect();
+        let (last, searched) = events.split_last().unwrap();
+        assert!(searched
+            .iter()
+            .all(|event| matches!(event, StreamEvent::Search(_))));
+        assert!(searched
+            .iter()
+            .any(|event| matches!(event, StreamEvent::Search(SearchEvent::Expanded(_)))));
+        match last {
+            StreamEvent::Finished(Ok(route)) => {
+                let plain = jps_path(&map, scene.start_pos, scene.goal_pos).unwrap();
+                assert_eq!(route.steps(), plain.steps());
+            }
+            _ => panic!("Expected the search to finish last"),
+        }
+
+        //Hanging up cancels a long search
+        let scene = &scenes[1740];
+        let token = CancelToken::new();
+        let options = SearchOptions::new().cancel_token(token.clone());
+        let events = a_star_path_streamed(map, scene.start_pos, scene.goal_pos, options);
+        assert!(events.recv().is_ok());
+        drop(events);
+        for _ in 0..1000 {
+            if token.is_cancelled() {
+                break;
+            }
+            thread::sleep(Duration::from_millis(1));
+        }
+        assert!(token.is_cancelled());
+    }
+
+    #[derive(Debug, Default)]
+    struct CountingPruning {
+        calls: Arc<AtomicUsize>,
+    }
+
+    impl PruningRule for CountingPruning {
+        fn forced_horizontal(
+            &self,
+            map: &MovingAiMap,
+            position: Coords2D,
+            direction: i32,
+            forced: &mut Vec<Coords2D>,
+        ) {
+            self.calls.fetch_add(1, Ordering::Relaxed);
+            DefaultPruning.forced_horizontal(map, position, direction, forced);
+        }
+
+        fn forced_vertical(
+            &self,
+            map: &MovingAiMap,
+            position: Coords2D,
+            direction: i32,
+            forced: &mut Vec<Coords2D>,
+        ) {
+            self.calls.fetch_add(1, Ordering::Relaxed);
+            DefaultPruning.forced_vertical(map, position, direction, forced);
+        }
+    }
+
+    #[test]
+    fn custom_pruning_rule() {
+        let map = parse_map_file(Path::new(MAP)).unwrap();
+        let scenes = parse_scen_file(Path::new(SCEN)).unwrap();
+        let scene = &scenes[34];
+
+        let calls = Arc::new(AtomicUsize::new(0));
+        let rule = CountingPruning {
+            calls: calls.clone(),
+        };
+        let options = SearchOptions::new().jps_pruning(rule);
+        let custom = jps_path_with(&map, scene.start_pos, scene.goal_pos, &options).unwrap();
+        let default = jps_path(&map, scene.start_pos, scene.goal_pos).unwrap();
+
+        assert!(calls.load(Ordering::Relaxed) > 0);
+        assert_eq!(custom.steps(), default.steps());
+    }
+
+    #[test]
+    fn offset_map() {
+        let map = parse_map_file(Path::new(MAP)).unwrap();
+        let scenes = parse_scen_file(Path::new(SCEN)).unwrap();
+        let scene = &scenes[34];
+        let world = OffsetMap::new(&map, (-100, 40)).unwrap();
+
+        let start = world.to_world(scene.start_pos);
+        let goal = world.to_world(scene.goal_pos);
+        assert_eq!(world.to_local(start), Some(scene.start_pos));
+
+        //Same route as searching the map directly, shifted into world space
+        let local = a_star_path(&map, scene.start_pos, scene.goal_pos).unwrap();
+        let shifted = world
+            .a_star_path(start, goal, &SearchOptions::new())
+            .unwrap();
+        assert_eq!(local.distance(), shifted.distance());
+        for (local_step, world_step) in local.steps().iter().zip(shifted.steps()) {
+            assert_eq!(world.to_world(*local_step), world_step);
+        }
+
+        //Positions left of or above the map are rejected rather than wrapping
+        assert_eq!(
+            world
+                .jps_path((-101, 41), goal, &SearchOptions::new())
+                .err(),
+            Some(PathError::OutsideMap((-101, 41)))
+        );
+        assert_eq!(world.to_local((i64::MIN, 0)), None);
+
+        //Origins that would push the far side of the map past the end of world space are refused
+        assert!(OffsetMap::new(&map, (i64::MAX - 1, 0)).is_none());
+        assert!(OffsetMap::new(&map, (0, i64::MAX - 1)).is_none());
+        assert!(OffsetMap::new(&map, (i64::MAX - 512, i64::MIN)).is_some());
+
+        //Blocked endpoints are reported in world coordinates too
+        assert_eq!(
+            world
+                .a_star_path((-100, 40), goal, &SearchOptions::new())
+                .err(),
+            Some(PathError::StartBlocked((-100, 40)))
+        );
+        assert_eq!(
+            world
+                .jps_path(start, (-100, 40), &SearchOptions::new())
+                .err(),
+            Some(PathError::GoalBlocked((-100, 40)))
+        );
+    }
+
+    #[test]
+    fn dijkstra() {
+        let map = parse_map_file(Path::new(MAP)).unwrap();
+        let scenes = parse_scen_file(Path::new(SCEN)).unwrap();
+
+        //Routes are unwound into single steps and are as short as the scenarios record
+        for index in TEST_NUMS_A_STAR {
+            let scene = &scenes[index];
+            let path = dijkstra_path(&map, scene.start_pos, scene.goal_pos).unwrap();
+            assert_eq!(path.distance() as f32, scene.optimal_length as f32);
+            for pair in path.steps().windows(2) {
+                assert!(pair[0].0.abs_diff(pair[1].0) <= 1 && pair[0].1.abs_diff(pair[1].1) <= 1);
+            }
+        }
+    }
+
+    #[test]
+    fn weighted_costs() {
+        let map = parse_map_file(Path::new(MAP)).unwrap();
+        let scenes = parse_scen_file(Path::new(SCEN)).unwrap();
+        let scene = &scenes[34];
+        let options = SearchOptions::new();
+
+        //Plain distances reproduce the unweighted result
+        let plain = a_star_path(&map, scene.start_pos, scene.goal_pos).unwrap();
+        let distance = |from: Coords2D, to: Coords2D| {
+            let (dx, dy) = (from.0 as f64 - to.0 as f64, from.1 as f64 - to.1 as f64);
+            Some((dx * dx + dy * dy).sqrt())
+        };
+        let weighted =
+            a_star_path_weighted(&map, scene.start_pos, scene.goal_pos, &options, distance)
+                .unwrap();
+        assert_eq!(plain.distance(), weighted.distance());
+
+        //Forbidding every move leaves no route
+        let blocked = |_: Coords2D, _: Coords2D| None;
+        assert_eq!(
+            a_star_path_weighted(&map, scene.start_pos, scene.goal_pos, &options, blocked).err(),
+            Some(PathError::NoPath)
+        );
+    }
+
+    #[test]
+    fn route_composition() {
+        let map = parse_map_file(Path::new(MAP)).unwrap();
+        let scenes = parse_scen_file(Path::new(SCEN)).unwrap();
+        let scene = &scenes[34];
+        let (start, goal) = (scene.start_pos, scene.goal_pos);
+        let cells = jps_path(&map, start, goal).unwrap().steps();
+
+        let options = SearchOptions::new().include_start(false);
+        let steps = jps_path_with(&map, start, goal, &options).unwrap().steps();
+        assert_eq!(steps, cells[..cells.len() - 1].to_vec());
+
+        let options = SearchOptions::new().repeat_goal(true);
+        let steps = a_star_path_with(&map, start, goal, &options)
+            .unwrap()
+            .steps();
+        assert_eq!(&steps[..2], &[goal, goal]);
+
+        //Turning points keep the ends and are a subset of the full route
+        let options = SearchOptions::new().route_steps(RouteSteps::JumpPoints);
+        let points = jps_path_with(&map, start, goal, &options).unwrap().steps();
+        assert_eq!(points.first(), Some(&goal));
+        assert_eq!(points.last(), Some(&start));
+        assert!(points.len() < cells.len());
+        assert!(points.iter().all(|point| cells.contains(point)));
+    }
+
+    #[test]
+    fn explored_cells() {
+        let map = parse_map_file(Path::new(MAP)).unwrap();
+        let scenes = parse_scen_file(Path::new(SCEN)).unwrap();
+        let scene = &scenes[34];
+        let options = SearchOptions::new();
+
+        let mut context = SearchContext::new();
+        context
+            .a_star_path(&map, scene.start_pos, scene.goal_pos, &options)
+            .unwrap();
+        let explored = context.explored();
+        assert_eq!(explored[0], (scene.start_pos, 0.0));
+        assert!(!context.frontier().is_empty());
+
+        //A* never expands a tile costing more than the route
+        assert!(explored
+            .iter()
+            .all(|(_, g)| *g <= scene.optimal_length + 1e-6));
+
+        //Reusing the context replaces the previous search's state
+        context
+            .jps_path(&map, scene.start_pos, scene.goal_pos, &options)
+            .unwrap();
+        assert_eq!(context.explored()[0], (scene.start_pos, 0.0));
+
+        //Failed searches leave nothing behind
+        assert!(context.jps_path(&map, (0, 0), (1, 1), &options).is_err());
+        assert!(context.explored().is_empty());
+        assert!(context.frontier().is_empty());
+    }
+
+    #[test]
+    fn single_stepping() {
+        let map = parse_map_file(Path::new(MAP)).unwrap();
+        let scenes = parse_scen_file(Path::new(SCEN)).unwrap();
+        let scene = &scenes[34];
+        let options = SearchOptions::new();
+        let mut context = SearchContext::new();
+
+        let mut steps = context
+            .a_star_steps(&map, scene.start_pos, scene.goal_pos, &options)
+            .frontier_sample(3);
+        let snapshots: Vec<_> = (&mut steps).collect();
+        let stepped = steps.result().unwrap().as_ref().unwrap();
+        let plain = a_star_path(&map, scene.start_pos, scene.goal_pos).unwrap();
+        assert_eq!(stepped.steps(), plain.steps());
+
+        //Each step expands one node, and the frontier sample is the most promising first
+        assert_eq!(snapshots[0].current.position, scene.start_pos);
+        for (index, snapshot) in snapshots.iter().enumerate() {
+            assert!(snapshot.closed_len <= index + 1);
+            assert!(snapshot.frontier.len() <= 3);
+            assert!(snapshot.frontier.len() <= snapshot.open_len);
+            assert!(snapshot
+                .frontier
+                .windows(2)
+                .all(|pair| pair[0].g + pair[0].h <= pair[1].g + pair[1].h));
+        }
+        assert_eq!(snapshots.len(), context.stats().expanded);
+
+        let mut steps = context.jps_steps(&map, scene.start_pos, scene.goal_pos, &options);
+        assert!((&mut steps).count() > 0);
+        let stepped = steps.result().unwrap().as_ref().unwrap();
+        let plain = jps_path(&map, scene.start_pos, scene.goal_pos).unwrap();
+        assert_eq!(stepped.steps(), plain.steps());
+
+        //Searches that cannot start finish straight away
+        let mut steps = context.dijkstra_steps(&map, (0, 0), (1, 1), &options);
+        assert!(steps.next().is_none());
+        assert_eq!(
+            steps.result().unwrap().as_ref().err(),
+            Some(&PathError::StartBlocked((0, 0)))
+        );
+    }
+
+    #[test]
+    fn search_stats() {
+        let map = parse_map_file(Path::new(MAP)).unwrap();
+        let scenes = parse_scen_file(Path::new(SCEN)).unwrap();
+        let scene = &scenes[34];
+        let options = SearchOptions::new();
+        let mut context = SearchContext::new();
+
+        //Counts agree with the events seen by an observer
+        let mut expanded = 0;
+        let mut discovered = 0;
+        let mut count = |event: SearchEvent| match event {
+            SearchEvent::Expanded(_) => expanded += 1,
+            SearchEvent::Discovered(_) => discovered += 1,
+        };
+        a_star_path_observed(&map, scene.start_pos, scene.goal_pos, &options, &mut count).unwrap();
+        context
+            .a_star_path(&map, scene.start_pos, scene.goal_pos, &options)
+            .unwrap();
+        let a_star = context.stats();
+        assert_eq!(a_star.expanded, expanded);
+        assert_eq!(a_star.generated, discovered);
+        assert!(a_star.peak_open > 0 && a_star.peak_open <= a_star.generated);
+        assert_eq!(a_star.jump_scans, 0);
+        //Memory is only recorded when the memory-stats feature asks for it
+        assert_eq!(a_star.peak_memory.is_some(), cfg!(feature = "memory-stats"));
+
+        //JPS expands fewer nodes by scanning ahead, and Dijkstra more by ignoring the goal
+        context
+            .jps_path(&map, scene.start_pos, scene.goal_pos, &options)
+            .unwrap();
+        let jps = context.stats();
+        assert!(jps.expanded < a_star.expanded);
+        assert!(jps.jump_scans > 0);
+        context
+            .dijkstra_path(&map, scene.start_pos, scene.goal_pos, &options)
+            .unwrap();
+        assert!(context.stats().expanded > a_star.expanded);
+
+        //Searches are only timed when asked to be
+        assert_eq!(jps.elapsed, Duration::ZERO);
+        let timed = SearchOptions::new().measure_time(true);
+        context
+            .jps_path(&map, scene.start_pos, scene.goal_pos, &timed)
+            .unwrap();
+        assert!(context.stats().elapsed > Duration::ZERO);
+
+        //Counts from the previous search are cleared even when the next fails immediately
+        assert!(context.jps_path(&map, (0, 0), (1, 1), &options).is_err());
+        assert_eq!(context.stats().expanded, 0);
+    }
+
+    #[test]
+    fn distance_only() {
+        let map = parse_map_file(Path::new(MAP)).unwrap();
+        let scenes = parse_scen_file(Path::new(SCEN)).unwrap();
+        let options = SearchOptions::new();
+
+        for &num in &TEST_NUMS_A_STAR {
+            let scene = &scenes[num];
+            let route = a_star_path(&map, scene.start_pos, scene.goal_pos).unwrap();
+            assert_eq!(
+                a_star_distance(&map, scene.start_pos, scene.goal_pos, &options),
+                Ok(route.distance())
+            );
+        }
+        for &num in &TEST_NUMS_JPS {
+            let scene = &scenes[num];
+            let route = jps_path(&map, scene.start_pos, scene.goal_pos).unwrap();
+            assert_eq!(
+                jps_distance(&map, scene.start_pos, scene.goal_pos, &options),
+                Ok(route.distance())
+            );
+        }
+
+        //Errors are the same as for full searches
+        assert_eq!(
+            jps_distance(&map, (0, 0), (1, 1), &options),
+            Err(PathError::StartBlocked((0, 0)))
+        );
+
+        //Contexts can be reused between queries
+        let mut context = SearchContext::new();
+        let scene = &scenes[34];
+        let route = jps_path(&map, scene.start_pos, scene.goal_pos).unwrap();
+        for _ in 0..2 {
+            let distance = context
+                .jps_distance(&map, scene.start_pos, scene.goal_pos, &options)
+                .unwrap();
+            assert_eq!(distance, route.distance());
+            let distance = context
+                .a_star_distance(&map, scene.start_pos, scene.goal_pos, &options)
+                .unwrap();
+            assert_eq!(scene.optimal_length as f32, distance as f32);
+        }
+    }
+
+    #[test]
+    fn recorded_searches() {
+        let map = parse_map_file(Path::new(MAP)).unwrap();
+        let scenes = parse_scen_file(Path::new(SCEN)).unwrap();
+        let scene = &scenes[0];
+        let options = SearchOptions::new();
+
+        let record = |start, goal| {
+            SearchRecording::record(|observer| {
+                a_star_path_observed(&map, start, goal, &options, observer)
+            })
+        };
+        let (route, recording) = record(scene.start_pos, scene.goal_pos);
+        let route = route.unwrap();
+
+        //A saved recording loads back identical and replays the same events and route
+        let loaded = SearchRecording::from_log(&recording.to_log()).unwrap();
+        assert_eq!(loaded, recording);
+        let mut replayed = Vec::new();
+        let replayed_route = loaded
+            .replay(&mut |event| replayed.push(event))
+            .unwrap()
+            .unwrap();
+        assert_eq!(replayed, recording.events());
+        assert_eq!(replayed_route.steps(), route.steps());
+        assert_eq!(replayed_route.distance(), route.distance());
+
+        //Recording the same search again matches, and a different search diverges
+        let (_, again) = record(scene.start_pos, scene.goal_pos);
+        assert_eq!(again.first_divergence(&recording), None);
+        let (_, other) = record(scene.start_pos, scene.start_pos);
+        assert!(other.first_divergence(&recording).is_some());
+
+        //Failed searches are recorded too
+        let (result, failed) = record((0, 0), scene.goal_pos);
+        let loaded = SearchRecording::from_log(&failed.to_log()).unwrap();
+        assert_eq!(loaded.replay(&mut ()).unwrap().err(), result.err());
+        assert_eq!(SearchRecording::new().replay(&mut ()).map(|_| ()), None);
+
+        assert!(SearchRecording::from_log("e 1 1 0 2").is_err());
+        assert!(SearchRecording::from_log("route 1 1 1 2\ne 1 1 0 2 1 1").is_err());
+        assert!(SearchRecording::from_log("error lost").is_err());
+    }
+
+    //Forces no neighbours at all, so JPS misses routes that turn around walls
+    #[derive(Debug)]
+    struct NoForcedNeighbours;
+
+    impl PruningRule for NoForcedNeighbours {
+        fn forced_horizontal(&self, _: &MovingAiMap, _: Coords2D, _: i32, _: &mut Vec<Coords2D>) {}
+
+        fn forced_vertical(&self, _: &MovingAiMap, _: Coords2D, _: i32, _: &mut Vec<Coords2D>) {}
+    }
+
+    #[test]
+    fn verified_jps() {
+        let map = parse_map_file(Path::new(MAP)).unwrap();
+        let scenes = parse_scen_file(Path::new(SCEN)).unwrap();
+        let options = SearchOptions::new();
+
+        for scene in scenes.iter().take(30) {
+            assert_eq!(
+                verify_jps(&map, scene.start_pos, scene.goal_pos, &options),
+                Ok(())
+            );
+        }
+        assert_eq!(verify_jps(&map, (0, 0), (1, 1), &options), Ok(()));
+
+        //A pruning rule that loses routes is caught, along with the scenario
+        let rows = ["@@@@@@", "@....@", "@.@@@@", "@....@", "@@@@@@"];
+        let map = MovingAiMap::new(
+            String::from("octile"),
+            rows.len(),
+            rows[0].len(),
+            rows.iter().flat_map(|row| row.chars()).collect(),
+        );
+        let broken = SearchOptions::new().jps_pruning(NoForcedNeighbours);
+        let discrepancy = verify_jps(&map, (4, 1), (4, 3), &broken).unwrap_err();
+        assert_eq!(discrepancy.map_size, (6, 5));
+        assert_eq!(discrepancy.start, (4, 1));
+        assert_eq!(discrepancy.jps, Err(PathError::NoPath));
+        assert_eq!(discrepancy.dijkstra, Ok(8.0));
+        assert!(discrepancy.to_string().starts_with("JPS found"));
+    }
+
+    #[test]
+    fn scenario_suite() {
+        let map = parse_map_file(Path::new(MAP)).unwrap();
+        let mut scenes = parse_scen_file(Path::new(SCEN)).unwrap();
+        scenes.truncate(6);
+
+        for algorithm in [
+            SearchAlgorithm::Jps,
+            SearchAlgorithm::AStar,
+            SearchAlgorithm::Dijkstra,
+        ] {
+            let report = run_scen_suite(&map, &scenes, algorithm);
+            assert_eq!(report.passed(), 6);
+            assert_eq!(report.failures().count(), 0);
+            assert!(report.max_error() < 1e-4);
+        }
+
+        //A wrong recorded length and a blocked start are both reported as failures
+        scenes[2].optimal_length += 1.0;
+        scenes[4].start_pos = (0, 0);
+        let report = run_scen_suite(&map, &scenes, SearchAlgorithm::Jps);
+        assert_eq!(report.passed(), 4);
+        let failures: Vec<usize> = report.failures().map(|result| result.index).collect();
+        assert_eq!(failures, vec![2, 4]);
+        assert!((report.max_error() - 1.0).abs() < 1e-4);
+        assert_eq!(
+            report.results[4].found,
+            Err(PathError::StartBlocked((0, 0)))
+        );
+        assert_eq!(report.results[4].error(), None);
+        assert_eq!(
+            report.total_time(),
+            report.results.iter().map(|result| result.elapsed).sum()
+        );
+    }
+
+    #[test]
+    fn benchmark_set() {
+        let report = run_benchmark_set("./tests/map", SearchAlgorithm::AStar, Some(5)).unwrap();
+        assert_eq!(report.maps.len(), 1);
+        assert_eq!(report.maps[0].name, "maze512-32-9.map");
+        assert_eq!(report.scenarios(), 5);
+        assert_eq!(report.passed(), 5);
+        assert_eq!(report.failures().count(), 0);
+        assert!(report.max_error() < 1e-4);
+        assert_eq!(report.total_time(), report.maps[0].report.total_time());
+        assert_eq!(report.mean_time(), report.total_time() / 5);
+
+        //Each map gives the same results as running its scenarios directly
+        let map = parse_map_file(Path::new(MAP)).unwrap();
+        let scenes = parse_scen_file(Path::new(SCEN)).unwrap();
+        let direct = run_scen_suite(&map, &scenes[..5], SearchAlgorithm::AStar);
+        let found = |results: &[blitz_path::ScenarioResult]| {
+            results
+                .iter()
+                .map(|result| result.found)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            found(&report.maps[0].report.results),
+            found(&direct.results)
+        );
+
+        //Directories without maps give an empty report, and missing ones an error
+        let empty = run_benchmark_set("./src", SearchAlgorithm::Jps, None).unwrap();
+        assert_eq!(empty.scenarios(), 0);
+        assert_eq!(empty.mean_time(), Duration::ZERO);
+        assert!(matches!(
+            run_benchmark_set("./tests/missing", SearchAlgorithm::Jps, None),
+            Err(MapLoadError::Parse(_))
+        ));
+    }
+
+    #[test]
+    fn selected_algorithm() {
+        let map = parse_map_file(Path::new(MAP)).unwrap();
+        let scenes = parse_scen_file(Path::new(SCEN)).unwrap();
+        let options = SearchOptions::new();
+        let mut context = SearchContext::new();
+
+        for scene in &scenes[..5] {
+            let (start, goal) = (scene.start_pos, scene.goal_pos);
+            let expected = [
+                (
+                    SearchAlgorithm::AStar,
+                    a_star_path_with(&map, start, goal, &options),
+                ),
+                (SearchAlgorithm::Dijkstra, dijkstra_path(&map, start, goal)),
+                (
+                    SearchAlgorithm::Jps,
+                    jps_path_with(&map, start, goal, &options),
+                ),
+            ];
+            for (algorithm, expected) in expected {
+                let expected = expected.unwrap();
+                let found = find_path(algorithm, &map, start, goal, &options).unwrap();
+                assert_eq!(found.steps(), expected.steps());
+                assert_eq!(found.distance(), expected.distance());
+                let reused = context
+                    .find_path(algorithm, &map, start, goal, &options)
+                    .unwrap();
+                assert_eq!(reused.steps(), expected.steps());
+            }
+        }
+
+        //Errors are the same whichever algorithm is chosen
+        for algorithm in [
+            SearchAlgorithm::AStar,
+            SearchAlgorithm::Dijkstra,
+            SearchAlgorithm::Jps,
+        ] {
+            assert_eq!(
+                find_path(algorithm, &map, (0, 0), (1, 1), &options).err(),
+                Some(PathError::StartBlocked((0, 0)))
+            );
+        }
+    }
 }
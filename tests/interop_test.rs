@@ -0,0 +1,64 @@
+#[cfg(all(test, feature = "pathfinding", feature = "movingai"))]
+mod tests {
+    use std::path::Path;
+
+    use movingai::parser::parse_map_file;
+    use movingai::parser::parse_scen_file;
+    use movingai::Map2D;
+    use pathfinding::directed::astar::astar;
+    use pathfinding::grid::Grid;
+
+    use blitz_path::a_star_path;
+    use blitz_path::interop::{
+        grid_from_map, heuristic, map_from_grid, route_from_path, successors,
+    };
+
+    const MAP: &str = "./tests/map/maze512-32-9.map";
+    const SCEN: &str = "./tests/map/maze512-32-9.map.scen";
+
+    #[test]
+    fn matches_pathfinding_crate() {
+        let map = parse_map_file(Path::new(MAP)).unwrap();
+        let scenes = parse_scen_file(Path::new(SCEN)).unwrap();
+
+        for &num in &[0, 34] {
+            let scene = &scenes[num];
+            let (path, cost) = astar(
+                &scene.start_pos,
+                successors(&map),
+                heuristic(scene.goal_pos),
+                |tile| *tile == scene.goal_pos,
+            )
+            .unwrap();
+            let theirs = route_from_path(path, cost);
+            let ours = a_star_path(&map, scene.start_pos, scene.goal_pos).unwrap();
+
+            assert!((theirs.distance() - ours.distance()).abs() < 1e-9);
+            assert_eq!(theirs.steps().first(), Some(&scene.goal_pos));
+            assert_eq!(theirs.steps().last(), Some(&scene.start_pos));
+        }
+    }
+
+    #[test]
+    fn grid_conversion() {
+        let map = parse_map_file(Path::new(MAP)).unwrap();
+        let grid = grid_from_map(&map);
+        assert_eq!((grid.width, grid.height), (512, 512));
+        assert!(grid.has_vertex((1, 1)));
+        assert!(!grid.has_vertex((0, 0)));
+
+        let round_trip = map_from_grid(&grid);
+        assert!(map
+            .coords()
+            .all(|tile| map.is_traversable(tile) == round_trip.is_traversable(tile)));
+
+        //Grids built elsewhere can be searched here
+        let mut grid = Grid::new(5, 4);
+        for tile in [(1, 1), (1, 2), (2, 2), (3, 2), (3, 1)] {
+            grid.add_vertex(tile);
+        }
+        let map = map_from_grid(&grid);
+        let route = a_star_path(&map, (1, 1), (3, 1)).unwrap();
+        assert_eq!(route.steps(), vec![(3, 1), (3, 2), (2, 2), (1, 2), (1, 1)]);
+    }
+}
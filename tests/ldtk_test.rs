@@ -0,0 +1,56 @@
+#[cfg(all(test, feature = "ldtk"))]
+mod tests {
+    use std::path::Path;
+
+    use blitz_path::grid::Map2D;
+
+    use blitz_path::{map_from_ldtk, map_from_ldtk_str, MapLoadError};
+
+    const PROJECT: &str = "./tests/map/level.ldtk";
+
+    #[test]
+    fn obstacles_from_int_grid_values() {
+        let walls = map_from_ldtk(Path::new(PROJECT), "Level_0", "Collisions", |value| {
+            value == 1
+        })
+        .unwrap();
+        assert_eq!((walls.width(), walls.height()), (5, 4));
+        let open: Vec<_> = walls
+            .coords()
+            .filter(|tile| walls.is_traversable(*tile))
+            .collect();
+        assert_eq!(open, vec![(1, 1), (2, 1), (3, 1), (1, 2), (3, 2)]);
+
+        //Every non-empty value blocks, including the value 2 at (3, 2)
+        let solid = map_from_ldtk(Path::new(PROJECT), "Level_0", "Collisions", |value| {
+            value != 0
+        })
+        .unwrap();
+        assert!(!solid.is_traversable((3, 2)));
+    }
+
+    #[test]
+    fn load_errors() {
+        let is_wall = |value| value == 1;
+        assert_eq!(
+            map_from_ldtk(Path::new(PROJECT), "Level_0", "Walls", is_wall).err(),
+            Some(MapLoadError::MissingLayer(String::from("Walls")))
+        );
+        assert_eq!(
+            map_from_ldtk(Path::new(PROJECT), "Level_0", "Entities", is_wall).err(),
+            Some(MapLoadError::NotTileLayer(String::from("Entities")))
+        );
+        assert!(matches!(
+            map_from_ldtk(Path::new(PROJECT), "Level_1", "Collisions", is_wall),
+            Err(MapLoadError::Parse(_))
+        ));
+        assert!(matches!(
+            map_from_ldtk(Path::new("missing.ldtk"), "Level_0", "Collisions", is_wall),
+            Err(MapLoadError::Parse(_))
+        ));
+        assert!(matches!(
+            map_from_ldtk_str("{}", "Level_0", "Collisions", is_wall),
+            Err(MapLoadError::Parse(_))
+        ));
+    }
+}
@@ -0,0 +1,36 @@
+#[cfg(all(test, feature = "memory-stats", feature = "movingai"))]
+mod tests {
+    use std::path::Path;
+
+    use movingai::parser::parse_map_file;
+
+    use blitz_path::{MemoryFootprint, SearchContext, SearchOptions};
+
+    const MAP: &str = "./tests/map/maze512-32-9.map";
+
+    #[test]
+    fn peak_search_memory() {
+        let map = parse_map_file(Path::new(MAP)).unwrap();
+        let options = SearchOptions::new().capacity_hint(16);
+        let mut context = SearchContext::new();
+
+        context.a_star_path(&map, (1, 1), (5, 5), &options).unwrap();
+        let short = context.stats().peak_memory.unwrap();
+        assert!(short > 0);
+        assert_eq!(short, context.heap_size());
+
+        //A longer search needs more room, which is kept for later searches
+        context
+            .jps_path(&map, (1, 1), (300, 300), &options)
+            .unwrap();
+        let long = context.stats().peak_memory.unwrap();
+        assert!(long > short);
+        context.a_star_path(&map, (1, 1), (5, 5), &options).unwrap();
+        assert_eq!(context.stats().peak_memory, Some(long));
+
+        let mut steps = context.a_star_steps(&map, (1, 1), (5, 5), &options);
+        for _ in &mut steps {}
+        assert!(steps.result().unwrap().is_ok());
+        assert!(context.stats().peak_memory.unwrap() >= long);
+    }
+}
@@ -0,0 +1,67 @@
+#[cfg(all(test, feature = "protobuf", feature = "movingai"))]
+mod tests {
+    use std::path::Path;
+
+    use movingai::parser::parse_map_file;
+
+    use blitz_path::{
+        decode_route_batch, encode_route_batch, jps_path, PathError, Route, RouteDecodeError,
+    };
+
+    #[test]
+    fn route_round_trip() {
+        let map = parse_map_file(Path::new("./tests/map/maze512-32-9.map")).unwrap();
+        let route = jps_path(&map, (1, 1), (40, 40)).unwrap();
+
+        let bytes = route.to_protobuf();
+        let decoded = Route::from_protobuf(&bytes).unwrap();
+        assert_eq!(decoded.distance(), route.distance());
+        assert_eq!(decoded.steps(), route.steps());
+        //Nine bytes of distance, a few of length and first step, then two per step
+        assert!(bytes.len() <= 16 + 2 * route.steps().len());
+
+        let empty: Route = Route::from((0.0, Vec::new()));
+        assert!(Route::from_protobuf(&empty.to_protobuf())
+            .unwrap()
+            .steps()
+            .is_empty());
+    }
+
+    #[test]
+    fn batch_round_trip() {
+        let results: Vec<(u64, Result<Route, PathError>)> = vec![
+            (7, Ok(Route::from((2.0, vec![(3, 1), (2, 1), (1, 1)])))),
+            (8, Err(PathError::GoalBlocked((4, 5)))),
+            (9, Err(PathError::OutsideMap((-3, 2)))),
+            (10, Err(PathError::Cancelled)),
+        ];
+
+        let decoded = decode_route_batch(&encode_route_batch(&results)).unwrap();
+        assert_eq!(decoded.len(), results.len());
+        for ((id, result), (decoded_id, decoded_result)) in results.iter().zip(&decoded) {
+            assert_eq!(id, decoded_id);
+            match (result, decoded_result) {
+                (Ok(route), Ok(decoded_route)) => {
+                    assert_eq!(route.distance(), decoded_route.distance());
+                    assert_eq!(route.steps(), decoded_route.steps());
+                }
+                (Err(error), Err(decoded_error)) => assert_eq!(error, decoded_error),
+                _ => panic!("outcome of {} changed kind", id),
+            }
+        }
+        assert!(decode_route_batch(&[]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn decode_errors() {
+        assert!(matches!(
+            Route::from_protobuf(&[0xff]).err(),
+            Some(RouteDecodeError::Malformed(_))
+        ));
+        //A single coordinate, tag 2 packed with one zigzag encoded value
+        assert!(matches!(
+            Route::from_protobuf(&[0x12, 0x01, 0x02]).err(),
+            Some(RouteDecodeError::Invalid(_))
+        ));
+    }
+}
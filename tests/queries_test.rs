@@ -0,0 +1,2826 @@
+#[cfg(all(test, feature = "movingai"))]
+mod tests {
+    use std::collections::BinaryHeap;
+    use std::slice;
+    use std::sync::Arc;
+
+    use movingai::Map2D;
+    use movingai::MovingAiMap;
+    use movingai::SceneRecord;
+
+    use blitz_path::primitives::{
+        compose, direction, distance, index, neighbours, offset, prepare, rewind, validate, Node,
+    };
+    use blitz_path::{
+        a_star_path, a_star_path_anisotropic, a_star_path_landmarks, a_star_path_turning,
+        a_star_path_weighted, a_star_path_with, components, coverage_path, crop, dijkstra_path,
+        dijkstra_path_weighted, evacuation_paths, flee_path, inflate, is_reachable, jps_path,
+        jps_path_with, line_of_sight, line_of_sight_tiles, local_detour, nearest_target_path,
+        nearest_targets_paths, optimal_corridor, pareto_paths, prioritized_planning,
+        reachable_within, resource_path, route_through, smooth_route, smooth_route_within,
+        space_time_a_star, space_time_a_star_reserved, stealth_path, time_expanded_search,
+        visit_all, weighted_region_path, ActivePath, Algorithm, Anisotropy, CellChange,
+        ClearanceMap, CongestionMap, CostStack, Crowd, CrowdAgent, CurveKind, Decay,
+        DefaultPruning, DesireMap, Direction, DirectionalCosts, FlowField, FogOfWar, Formation,
+        HeatmapValue, Heightmap, InfluenceMap, InfluenceSource, Landmarks, MapDiff, MapDiffError,
+        MapTransform, MemoryFootprint, OccupancyGrid, PathError, PathUpdate, PayloadLayer, Pose2D,
+        PruningRule, RealTimeAgent, ReservationTable, Route, RouteConflict, RouteCurve, RouteSteps,
+        SearchContext, SearchOptions, SignedCoords, TimedRoute, VisibilityMap, VoronoiRegions,
+        Watcher, WeightedRegions,
+    };
+
+    //Builds an octile map from rows of MovingAI tile characters
+    fn map_from(rows: &[&str]) -> MovingAiMap {
+        let height = rows.len();
+        let width = rows[0].len();
+        let tiles = rows.iter().flat_map(|row| row.chars()).collect();
+        MovingAiMap::new(String::from("octile"), height, width, tiles)
+    }
+
+    #[test]
+    fn reachability() {
+        //Open edges on every side, with a wall splitting off the right hand column
+        let map = map_from(&[
+            "....@.", //
+            "....@.", //
+            "....@.", //
+        ]);
+
+        assert!(is_reachable(&map, (0, 0), (3, 2)));
+        assert!(is_reachable(&map, (0, 2), (0, 0)));
+        assert!(is_reachable(&map, (5, 0), (5, 2)));
+        assert!(!is_reachable(&map, (0, 0), (5, 0)));
+        assert!(!is_reachable(&map, (0, 0), (4, 0)));
+        assert!(!is_reachable(&map, (0, 0), (9, 9)));
+    }
+
+    #[test]
+    fn influence_map() {
+        let map = map_from(&[
+            "......", //
+            ".@@@@.", //
+            "......", //
+        ]);
+        let sources = [
+            InfluenceSource {
+                position: (0, 0),
+                strength: 8.0,
+            },
+            InfluenceSource {
+                position: (5, 0),
+                strength: -8.0,
+            },
+        ];
+        let influence = InfluenceMap::new(&map, &sources, Decay::Linear(0.25), 0.0);
+
+        //Opposing sources cancel out halfway between them
+        assert_eq!(influence.get((0, 0)), 8.0);
+        assert_eq!(influence.get((5, 0)), -8.0);
+        assert!(influence.get((1, 0)) > 0.0);
+        assert!(influence.get((4, 0)) < 0.0);
+
+        //Walls get nothing and influence travels around them
+        assert_eq!(influence.get((2, 1)), 0.0);
+        assert!((influence.get((0, 2)) - 8.0 * (1.0 - 0.25 * 2.0)).abs() < 1e-9);
+
+        let decaying = InfluenceMap::new(&map, &sources[..1], Decay::Exponential(0.5), 0.2);
+        assert_eq!(decaying.get((1, 0)), 4.0);
+        assert_eq!(decaying.get((2, 0)), 2.0);
+        assert_eq!(decaying.get((3, 0)), 0.0);
+    }
+
+    #[test]
+    fn cells_within_cost() {
+        let map = map_from(&[
+            "..@..", //
+            "..@..", //
+            ".....", //
+        ]);
+
+        //The wall forces a detour, so (3, 0) is out of range despite being close
+        let range = reachable_within(&map, (1, 0), 3.0);
+        let cost_of = |tile| {
+            range
+                .iter()
+                .find(|(found, _)| *found == tile)
+                .map(|(_, cost)| *cost)
+        };
+        assert_eq!(cost_of((1, 0)), Some(0.0));
+        assert_eq!(cost_of((0, 2)), Some(1.0 + 2f64.sqrt()));
+        //No cutting the corner of the wall
+        assert_eq!(cost_of((2, 2)), Some(3.0));
+        assert_eq!(cost_of((3, 0)), None);
+        assert!(range.iter().all(|(_, cost)| *cost <= 3.0));
+
+        assert!(reachable_within(&map, (2, 0), 10.0).is_empty());
+    }
+
+    #[test]
+    fn nearest_target() {
+        let map = map_from(&[
+            "...@....", //
+            "...@.@@.", //
+            "........", //
+        ]);
+        let options = SearchOptions::new();
+
+        //(4, 0) is closer as the crow flies but (0, 2) is closer to walk to
+        let targets = [(4, 0), (0, 2), (3, 0)];
+        let (nearest, path) = nearest_target_path(&map, (1, 0), &targets, &options).unwrap();
+        assert_eq!(nearest, 1);
+        assert_eq!(path.steps().first(), Some(&(0, 2)));
+        assert_eq!(path.steps().last(), Some(&(1, 0)));
+        assert_eq!(path.distance(), 1.0 + 2f64.sqrt());
+
+        assert_eq!(
+            nearest_target_path(&map, (1, 0), &[(3, 0)], &options).err(),
+            Some(PathError::NoPath)
+        );
+        let options = SearchOptions::new().max_distance(2.0);
+        assert_eq!(
+            nearest_target_path(&map, (1, 0), &[(4, 0)], &options).err(),
+            Some(PathError::ExceedsMaxDistance)
+        );
+    }
+
+    #[test]
+    fn line_of_sight_checks() {
+        let map = map_from(&[
+            "......", //
+            "..@...", //
+            "......", //
+            ".@....", //
+        ]);
+
+        //Straight lines either side of the wall
+        assert!(line_of_sight(&map, (0, 0), (5, 0)));
+        assert!(!line_of_sight(&map, (0, 1), (5, 1)));
+
+        //Symmetric in direction
+        assert_eq!(
+            line_of_sight(&map, (0, 2), (5, 0)),
+            line_of_sight(&map, (5, 0), (0, 2))
+        );
+
+        //A diagonal through the corner between (1, 2) and (2, 1) is blocked by the wall
+        assert!(!line_of_sight(&map, (1, 1), (2, 2)));
+        assert!(!line_of_sight(&map, (0, 3), (3, 0)));
+        assert!(line_of_sight(&map, (3, 0), (5, 2)));
+
+        //Shallow lines touch every tile they pass over
+        assert_eq!(line_of_sight_tiles(&map, (0, 0), (3, 1)), None);
+        assert_eq!(
+            line_of_sight_tiles(&map, (2, 2), (5, 3)),
+            Some(vec![(2, 2), (3, 2), (4, 2), (3, 3), (4, 3), (5, 3)])
+        );
+
+        //Blocked or out of bounds endpoints
+        assert!(!line_of_sight(&map, (0, 0), (2, 1)));
+        assert!(!line_of_sight(&map, (0, 0), (9, 0)));
+    }
+
+    #[test]
+    fn connected_components() {
+        let map = map_from(&[
+            "..@...", //
+            "..@.@@", //
+            "..@.@.", //
+        ]);
+        let regions = components(&map);
+
+        assert_eq!(regions.count(), 3);
+        assert!(regions.same_component((0, 0), (1, 2)));
+        assert!(regions.same_component((3, 2), (5, 0)));
+        assert!(!regions.same_component((0, 0), (3, 0)));
+        assert!(!regions.same_component((5, 2), (3, 2)));
+        assert_eq!(regions.label((2, 0)), None);
+        assert_eq!(regions.label((9, 9)), None);
+
+        //Searches can use the labels to fail straight away
+        let options = SearchOptions::new().components(Arc::new(regions));
+        assert_eq!(
+            jps_path_with(&map, (0, 0), (3, 0), &options).err(),
+            Some(PathError::NoPath)
+        );
+        assert!(jps_path_with(&map, (3, 2), (5, 0), &options).is_ok());
+
+        //Swamp can't be entered from the grass beside it, but the tiles are still one region
+        let one_way = map_from(&["GS"]);
+        let regions = components(&one_way);
+        assert_eq!(regions.count(), 1);
+        let options = SearchOptions::new().components(Arc::new(regions));
+        assert!(jps_path_with(&one_way, (1, 0), (0, 0), &options).is_ok());
+        assert_eq!(
+            jps_path_with(&one_way, (0, 0), (1, 0), &options).err(),
+            Some(PathError::NoPath)
+        );
+    }
+
+    #[test]
+    fn voronoi_regions() {
+        let map = map_from(&[
+            ".....@..", //
+            ".@@@.@..", //
+            "........", //
+        ]);
+        let regions = VoronoiRegions::new(&map, &[(0, 0), (4, 0), (7, 0), (5, 0)]);
+
+        //Closer to the second seed in a straight line, but the wall makes the first nearer
+        assert_eq!(regions.owner((0, 2)), Some(0));
+        assert_eq!(regions.distance((0, 2)), Some(2.0));
+        assert_eq!(regions.owner((3, 0)), Some(1));
+        assert_eq!(regions.owner((3, 2)), Some(1));
+        assert_eq!(regions.owner((6, 2)), Some(2));
+        assert_eq!(regions.distance((6, 2)), Some(1.0 + 2f64.sqrt()));
+
+        //Blocked seeds and tiles own nothing
+        assert!(!regions.owners().contains(&Some(3)));
+        assert_eq!(regions.owner((5, 0)), None);
+        assert_eq!(regions.distance((5, 0)), None);
+        assert_eq!(regions.owner((9, 0)), None);
+        assert_eq!(regions.distances().len(), 24);
+    }
+
+    #[test]
+    fn flee() {
+        let map = map_from(&[
+            "......", //
+            ".@@@@.", //
+            "......", //
+        ]);
+        let options = SearchOptions::new();
+
+        //Running right puts the wall between us and the threat
+        let path = flee_path(&map, (1, 0), &[(0, 0)], 10.0, &options).unwrap();
+        assert_eq!(path.steps().first(), Some(&(5, 2)));
+        assert_eq!(path.steps().last(), Some(&(1, 0)));
+        assert_eq!(path.distance(), 6.0);
+
+        //A small budget only gets part of the way
+        let path = flee_path(&map, (1, 0), &[(0, 0)], 2.0, &options).unwrap();
+        assert_eq!(path.steps(), vec![(3, 0), (2, 0), (1, 0)]);
+
+        //Threats on both sides leave the middle as the safest place
+        let path = flee_path(&map, (5, 0), &[(0, 0), (0, 2)], 10.0, &options).unwrap();
+        assert_eq!(path.steps().first(), Some(&(5, 1)));
+
+        //Already as safe as possible
+        let path = flee_path(&map, (5, 1), &[(0, 0), (0, 2)], 10.0, &options).unwrap();
+        assert_eq!(path.steps(), vec![(5, 1)]);
+        assert_eq!(path.distance(), 0.0);
+
+        assert_eq!(
+            flee_path(&map, (1, 1), &[(0, 0)], 10.0, &options).err(),
+            Some(PathError::StartBlocked((1, 1)))
+        );
+    }
+
+    #[test]
+    fn waypoint_routes() {
+        //Walled in, as A* does not yet handle open map edges
+        let map = map_from(&[
+            "@@@@@@@@", //
+            "@......@", //
+            "@.@@@@.@", //
+            "@......@", //
+            "@@@@@@@@", //
+        ]);
+        let options = SearchOptions::new();
+
+        //A loop around the wall and back to the start
+        let path =
+            route_through(&map, &[(1, 1), (6, 1), (6, 3), (1, 3), (1, 1)], &options).unwrap();
+        assert_eq!(path.distance(), 14.0);
+        let steps = path.steps();
+        assert_eq!(steps.len(), 15);
+        assert_eq!(steps.first(), Some(&(1, 1)));
+        assert_eq!(steps.last(), Some(&(1, 1)));
+        assert_eq!(steps[9], (6, 1));
+        assert!(steps.windows(2).all(|pair| pair[0] != pair[1]));
+
+        //Options apply to the joined route
+        let options = SearchOptions::new()
+            .include_start(false)
+            .route_steps(RouteSteps::JumpPoints);
+        let path = route_through(&map, &[(1, 1), (6, 1), (6, 3)], &options).unwrap();
+        assert_eq!(path.steps(), vec![(6, 3), (6, 1)]);
+
+        let options = SearchOptions::new().max_distance(6.0);
+        assert_eq!(
+            route_through(&map, &[(1, 1), (6, 1), (6, 3)], &options).err(),
+            Some(PathError::ExceedsMaxDistance)
+        );
+
+        assert_eq!(
+            route_through(&map, &[(1, 1)], &SearchOptions::new()).map(|path| path.steps()),
+            Ok(vec![(1, 1)])
+        );
+        assert_eq!(
+            route_through(&map, &[], &SearchOptions::new()).err(),
+            Some(PathError::NoPath)
+        );
+        assert_eq!(
+            route_through(&map, &[(1, 1), (2, 2)], &SearchOptions::new()).err(),
+            Some(PathError::GoalBlocked((2, 2)))
+        );
+    }
+
+    #[test]
+    fn visiting_order() {
+        let map = map_from(&[
+            "@@@@@@@@@@", //
+            "@........@", //
+            "@.@@@@@@.@", //
+            "@........@", //
+            "@@@@@@@@@@", //
+        ]);
+        let options = SearchOptions::new();
+
+        //Along the top corridor in order, then round to the far side of the wall
+        let destinations = [(2, 1), (8, 1), (7, 3), (4, 1)];
+        let (order, path) = visit_all(&map, (1, 1), &destinations, &options).unwrap();
+        assert_eq!(order, vec![0, 3, 1, 2]);
+        assert_eq!(path.distance(), 10.0);
+        assert_eq!(path.steps().first(), Some(&(7, 3)));
+        assert_eq!(path.steps().last(), Some(&(1, 1)));
+
+        //Every destination is visited even when they share a tile
+        let (order, _) = visit_all(&map, (1, 1), &[(3, 3), (3, 3)], &options).unwrap();
+        assert_eq!(order.len(), 2);
+
+        let (order, path) = visit_all(&map, (1, 1), &[], &options).unwrap();
+        assert!(order.is_empty());
+        assert_eq!(path.steps(), vec![(1, 1)]);
+
+        assert_eq!(
+            visit_all(&map, (1, 1), &[(3, 3), (2, 2)], &options).err(),
+            Some(PathError::GoalBlocked((2, 2)))
+        );
+
+        //The order is chosen on the same step costs the legs are searched with
+        let map = map_from(&["........."]);
+        let destinations = [(2, 0), (7, 0)];
+        let (order, _) = visit_all(&map, (4, 0), &destinations, &options).unwrap();
+        assert_eq!(order, vec![0, 1]);
+        let mut congestion = CongestionMap::new(&map, 10.0, 0.5);
+        congestion.claim((3, 0));
+        let congested = SearchOptions::new().congestion(Arc::new(congestion));
+        let (order, _) = visit_all(&map, (4, 0), &destinations, &congested).unwrap();
+        assert_eq!(order, vec![1, 0]);
+    }
+
+    #[test]
+    fn shortest_route_corridor() {
+        let map = map_from(&[
+            ".....", //
+            ".@@@.", //
+            ".....", //
+            "..@..", //
+        ]);
+
+        //Either side of the wall is as short as the other
+        let corridor = optimal_corridor(&map, (0, 1), (4, 1)).unwrap();
+        assert_eq!(
+            corridor,
+            vec![
+                (0, 0),
+                (1, 0),
+                (2, 0),
+                (3, 0),
+                (4, 0),
+                (0, 1),
+                (4, 1),
+                (0, 2),
+                (1, 2),
+                (2, 2),
+                (3, 2),
+                (4, 2),
+            ]
+        );
+
+        //Corners can't be cut, so there is only one way round
+        let corridor = optimal_corridor(&map, (0, 2), (2, 0)).unwrap();
+        assert_eq!(corridor, vec![(0, 0), (1, 0), (2, 0), (0, 1), (0, 2)]);
+        let corridor = optimal_corridor(&map, (0, 2), (4, 2)).unwrap();
+        assert_eq!(corridor.len(), 5);
+
+        assert_eq!(optimal_corridor(&map, (0, 0), (0, 0)), Ok(vec![(0, 0)]));
+        assert_eq!(
+            optimal_corridor(&map, (0, 0), (1, 1)),
+            Err(PathError::GoalBlocked((1, 1)))
+        );
+    }
+
+    #[test]
+    fn wall_clearance() {
+        let map = map_from(&[
+            "@@@@@@@@@@@", //
+            "@.........@", //
+            "@.........@", //
+            "@.........@", //
+            "@.........@", //
+            "@.........@", //
+            "@@@@@@@@@@@", //
+        ]);
+        let clearance = ClearanceMap::new(&map);
+        assert_eq!(clearance.get((0, 0)), 0.0);
+        assert_eq!(clearance.get((1, 1)), 1.0);
+        assert_eq!(clearance.get((2, 2)), 2.0);
+        assert_eq!(clearance.get((5, 3)), 3.0);
+        assert_eq!(clearance.get((20, 20)), 0.0);
+
+        //Without a penalty the route runs along the wall, with one it moves into the room
+        let plain = a_star_path(&map, (1, 1), (9, 1)).unwrap();
+        assert!(plain.steps().iter().all(|step| step.1 == 1));
+
+        let cost = clearance.wall_penalty(2.0, 4.0);
+        let biased =
+            a_star_path_weighted(&map, (1, 1), (9, 1), &SearchOptions::new(), cost).unwrap();
+        assert_eq!(
+            biased.steps(),
+            vec![
+                (9, 1),
+                (8, 2),
+                (7, 3),
+                (6, 3),
+                (5, 3),
+                (4, 3),
+                (3, 3),
+                (2, 2),
+                (1, 1)
+            ]
+        );
+        assert!(biased.distance() > plain.distance());
+    }
+
+    #[test]
+    fn k_nearest_targets() {
+        let map = map_from(&[
+            "...@....", //
+            "...@.@@.", //
+            "........", //
+        ]);
+        let options = SearchOptions::new();
+        let targets = [(4, 0), (0, 2), (3, 0), (7, 0), (0, 2), (2, 0)];
+
+        let nearest = nearest_targets_paths(&map, (1, 0), &targets, 4, &options).unwrap();
+        let found: Vec<(usize, f64)> = nearest
+            .iter()
+            .map(|(target, path)| (*target, path.distance()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (5, 1.0),
+                (1, 1.0 + 2f64.sqrt()),
+                (4, 1.0 + 2f64.sqrt()),
+                (0, 5.0 + 2f64.sqrt()),
+            ]
+        );
+        assert_eq!(nearest[3].1.steps().first(), Some(&(4, 0)));
+        assert_eq!(nearest[3].1.steps().last(), Some(&(1, 0)));
+
+        //Everything reachable when asking for more than there are, nothing when asking for none
+        let all = nearest_targets_paths(&map, (1, 0), &targets, 10, &options).unwrap();
+        assert_eq!(all.len(), 5);
+        assert!(nearest_targets_paths(&map, (1, 0), &targets, 0, &options)
+            .unwrap()
+            .is_empty());
+
+        let options = SearchOptions::new().max_distance(2.0);
+        let close = nearest_targets_paths(&map, (1, 0), &targets, 4, &options).unwrap();
+        assert_eq!(close.len(), 1);
+    }
+
+    #[test]
+    fn json_export() {
+        let map = map_from(&[
+            "....", //
+            ".@..", //
+            "....", //
+        ]);
+        let path = nearest_target_path(&map, (0, 2), &[(2, 0)], &SearchOptions::new())
+            .unwrap()
+            .1;
+        assert_eq!(path.steps(), vec![(2, 0), (1, 0), (0, 0), (0, 1), (0, 2)]);
+
+        assert_eq!(
+            path.to_json(),
+            r#"{"distance":4,"steps":[[2,0],[1,0],[0,0],[0,1],[0,2]]}"#
+        );
+        assert_eq!(
+            path.to_geojson_scaled(2.5, (100.0, -1.25)),
+            concat!(
+                r#"{"type":"Feature","geometry":{"type":"LineString","#,
+                r#""coordinates":[[100,3.75],[100,1.25],[100,-1.25],[102.5,-1.25],[105,-1.25]]},"#,
+                r#""properties":{"distance":10}}"#
+            )
+        );
+
+        //World coordinates from offset maps are supported too
+        let path: Route<SignedCoords> = Route::from((2.0, vec![(0, -2), (-1, -2), (-2, -2)]));
+        assert_eq!(
+            path.to_geojson(),
+            r#"{"type":"Feature","geometry":{"type":"LineString","coordinates":[[-2,-2],[-1,-2],[0,-2]]},"properties":{"distance":2}}"#
+        );
+    }
+
+    #[test]
+    fn compressed_routes() {
+        let map = map_from(&[
+            "@@@@@@@@@@@@@@", //
+            "@............@", //
+            "@.@@@@@@@@@@.@", //
+            "@...........@@", //
+            "@@@@@@@@@@..@@", //
+            "@............@", //
+            "@@@@@@@@@@@@@@", //
+        ]);
+        let options = SearchOptions::new();
+        let path = jps_path_with(&map, (1, 1), (1, 5), &options).unwrap();
+
+        let bytes = path.to_compressed().unwrap();
+        assert!(bytes.len() < path.steps().len());
+        let decoded = Route::from_compressed(&bytes).unwrap();
+        assert_eq!(decoded.steps(), path.steps());
+        assert_eq!(decoded.distance(), path.distance());
+
+        //Jump points come back as every tile, and a repeated goal survives
+        let options = SearchOptions::new()
+            .route_steps(RouteSteps::JumpPoints)
+            .repeat_goal(true);
+        let jumps = jps_path_with(&map, (1, 1), (1, 5), &options).unwrap();
+        let decoded = Route::from_compressed(&jumps.to_compressed().unwrap()).unwrap();
+        let mut expected = path.steps();
+        expected.insert(0, expected[0]);
+        assert_eq!(decoded.steps(), expected);
+
+        //Empty routes and single tiles
+        let empty: Route = Route::from((0.0, vec![]));
+        let decoded = Route::from_compressed(&empty.to_compressed().unwrap()).unwrap();
+        assert!(decoded.steps().is_empty());
+        let single: Route = Route::from((0.0, vec![(4, 5)]));
+        let decoded = Route::from_compressed(&single.to_compressed().unwrap()).unwrap();
+        assert_eq!(decoded.steps(), vec![(4, 5)]);
+
+        //Steps off a straight or diagonal line cannot be compressed
+        let knight: Route = Route::from((2.2, vec![(2, 1), (0, 0)]));
+        assert!(knight.to_compressed().is_none());
+
+        assert!(Route::from_compressed(&[0; 4]).is_err());
+        assert!(Route::from_compressed(&[0, 0, 0, 0, 0, 0, 0, 0, 0x80]).is_err());
+        //One step left of x = 0
+        assert!(Route::from_compressed(&[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 12]).is_err());
+    }
+
+    #[test]
+    fn occupancy_grids() {
+        let mut grid = OccupancyGrid {
+            width: 4,
+            height: 3,
+            resolution: 0.5,
+            origin: Pose2D {
+                x: 2.0,
+                y: 1.0,
+                yaw: 0.0,
+            },
+            data: vec![0, 0, 0, 0, 0, 100, 70, 0, -1, 0, 10],
+        };
+
+        //Unknown cells, including the one missing from the end, only block when asked to
+        let open = grid.to_map(65, false);
+        let cautious = grid.to_map(65, true);
+        for (cell, open_traversable, cautious_traversable) in &[
+            ((0, 0), true, true),
+            ((1, 1), false, false),
+            ((2, 1), false, false),
+            ((2, 2), true, true),
+            ((0, 2), true, false),
+            ((3, 2), true, false),
+        ] {
+            assert_eq!(open.is_traversable(*cell), *open_traversable);
+            assert_eq!(cautious.is_traversable(*cell), *cautious_traversable);
+        }
+
+        assert_eq!(grid.world_to_cell(2.1, 1.9), Some((0, 1)));
+        assert_eq!(grid.world_to_cell(1.9, 1.1), None);
+        assert_eq!(grid.world_to_cell(4.1, 1.1), None);
+        assert_eq!(grid.cell_to_world((3, 2)), (3.75, 2.25));
+
+        let route = nearest_target_path(&open, (0, 0), &[(3, 0)], &SearchOptions::new())
+            .unwrap()
+            .1;
+        let poses = grid.route_to_poses(&route);
+        assert_eq!(poses.len(), 4);
+        assert_eq!((poses[0].x, poses[0].y), (2.25, 1.25));
+        assert_eq!((poses[3].x, poses[3].y), (3.75, 1.25));
+        assert!(poses.iter().all(|pose| pose.yaw == 0.0));
+
+        //A grid turned a quarter turn anticlockwise runs its rows along the world's negative x axis
+        grid.origin.yaw = core::f64::consts::FRAC_PI_2;
+        let (x, y) = grid.cell_to_world((3, 2));
+        assert!((x - 0.75).abs() < 1e-9 && (y - 2.75).abs() < 1e-9);
+        assert_eq!(grid.world_to_cell(x, y), Some((3, 2)));
+        let poses = grid.route_to_poses(&route);
+        assert!((poses[3].yaw - core::f64::consts::FRAC_PI_2).abs() < 1e-9);
+    }
+
+    #[test]
+    fn ascii_dump() {
+        let map = map_from(&[
+            "@@@@@@@", //
+            "@..@..@", //
+            "@..@..@", //
+            "@.....@", //
+            "@@@@@@@", //
+        ]);
+        let mut context = SearchContext::new();
+        let options = SearchOptions::new();
+
+        let path = context.a_star_path(&map, (1, 1), (5, 1), &options).unwrap();
+        let dump = context.ascii_dump(&map, Some(&path));
+        let lines: Vec<&str> = dump.lines().collect();
+        assert_eq!(lines[0], "(0, 0)");
+        assert_eq!(lines[1], "@@@@@@@");
+        assert!(lines[2].starts_with("@S") && lines[2].ends_with("G@"));
+        assert_eq!(lines[4].matches('*').count(), 3);
+        assert_eq!(lines.len(), 6);
+
+        //Without a route only the search itself is drawn
+        let dump = context.ascii_dump(&map, None);
+        assert!(!dump.contains('S') && !dump.contains('*'));
+        assert!(dump.contains('x'));
+
+        //Nothing is drawn after a search that never started
+        assert!(context.a_star_path(&map, (0, 0), (5, 1), &options).is_err());
+        assert_eq!(context.ascii_dump(&map, None), "");
+    }
+
+    #[test]
+    fn search_tree_dot() {
+        let map = map_from(&[
+            "@@@@@@", //
+            "@....@", //
+            "@.@@.@", //
+            "@....@", //
+            "@@@@@@", //
+        ]);
+        let mut context = SearchContext::new();
+        let options = SearchOptions::new();
+
+        context.a_star_path(&map, (1, 1), (4, 3), &options).unwrap();
+        let dot = context.to_dot();
+        assert!(dot.starts_with("digraph search {\n"));
+        assert!(dot.ends_with("}\n"));
+        assert!(dot.contains("\"1_1\" [label=\"(1, 1)\\nf=3.606 g=0.000 h=3.606\"];"));
+
+        //Every tile appears once, and every node but the start has exactly one parent
+        let nodes = dot.lines().filter(|line| line.contains("[label")).count();
+        let edges = dot.lines().filter(|line| line.contains("->")).count();
+        assert_eq!(edges, nodes - 1);
+
+        //The same search always produces the same tree
+        context.a_star_path(&map, (1, 1), (4, 3), &options).unwrap();
+        assert_eq!(context.to_dot(), dot);
+    }
+
+    #[test]
+    fn heatmaps() {
+        let map = map_from(&[
+            "@@@@@", //
+            "@...@", //
+            "@@@.@", //
+            "@@@@@", //
+        ]);
+        let mut context = SearchContext::new();
+        let options = SearchOptions::new();
+
+        context
+            .dijkstra_path(&map, (1, 1), (3, 2), &options)
+            .unwrap();
+        assert_eq!(
+            context.heatmap_csv(&map, HeatmapValue::Cost),
+            ",,,,\n,0.000,1.000,2.000,\n,,,,\n,,,,\n"
+        );
+        assert_eq!(
+            context.heatmap_csv(&map, HeatmapValue::ExpansionOrder),
+            ",,,,\n,0,1,2,\n,,,,\n,,,,\n"
+        );
+
+        let image = context.heatmap_pgm(&map, HeatmapValue::Cost);
+        let header = b"P5\n5 4\n255\n";
+        assert_eq!(&image[..header.len()], header);
+        let pixels = &image[header.len()..];
+        assert_eq!(pixels.len(), 20);
+        assert_eq!(&pixels[5..10], &[0, 1, 128, 255, 0]);
+        assert_eq!(pixels.iter().filter(|pixel| **pixel > 0).count(), 3);
+    }
+
+    //No two routes share a tile at the same time or swap places
+    fn assert_no_conflicts(routes: &[TimedRoute]) {
+        let end = routes.iter().map(TimedRoute::end_time).max().unwrap_or(0);
+        for time in 0..=end {
+            for (index, a) in routes.iter().enumerate() {
+                for b in &routes[index + 1..] {
+                    assert_ne!(a.position_at(time), b.position_at(time));
+                    assert!(
+                        a.position_at(time) != b.position_at(time + 1)
+                            || a.position_at(time + 1) != b.position_at(time)
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn space_time_planning() {
+        let map = map_from(&[
+            "@@@@@", //
+            "@@.@@", //
+            "@...@", //
+            "@@.@@", //
+            "@@@@@", //
+        ]);
+        let options = SearchOptions::new();
+
+        //The second agent waits for the first to clear the crossing
+        let first = space_time_a_star(&map, (1, 2), (3, 2), 0, &[], &options).unwrap();
+        assert_eq!(first.positions(), &[(1, 2), (2, 2), (3, 2)]);
+        let second =
+            space_time_a_star(&map, (2, 1), (2, 3), 0, slice::from_ref(&first), &options).unwrap();
+        assert_eq!(second.positions(), &[(2, 1), (2, 1), (2, 2), (2, 3)]);
+        assert_eq!(second.end_time(), 3);
+        assert_eq!(second.position_at(10), Some((2, 3)));
+        assert_no_conflicts(&[first.clone(), second.clone()]);
+
+        //Waits are dropped when converting to a plain route
+        let route = second.to_route();
+        assert_eq!(route.steps(), vec![(2, 3), (2, 2), (2, 1)]);
+        assert_eq!(route.distance(), 2.0);
+
+        //A goal another agent parks on can never be reached, and a held start cannot be left
+        let options = SearchOptions::new().time_horizon(20);
+        assert_eq!(
+            space_time_a_star(&map, (2, 1), (3, 2), 0, slice::from_ref(&first), &options),
+            Err(PathError::NoPath)
+        );
+        assert_eq!(
+            space_time_a_star(&map, (2, 2), (2, 3), 1, &[first], &options),
+            Err(PathError::StartBlocked((2, 2)))
+        );
+    }
+
+    #[test]
+    fn space_time_passing_bay() {
+        let map = map_from(&[
+            "@@@@@@@", //
+            "@.....@", //
+            "@@@@.@@", //
+            "@@@@@@@", //
+        ]);
+        let options = SearchOptions::new();
+
+        //Agents heading opposite ways down a corridor pass using the bay
+        let east = space_time_a_star(&map, (1, 1), (5, 1), 0, &[], &options).unwrap();
+        let west =
+            space_time_a_star(&map, (5, 1), (1, 1), 0, slice::from_ref(&east), &options).unwrap();
+        assert!(west.positions().contains(&(4, 2)));
+        assert_eq!(west.positions().last(), Some(&(1, 1)));
+        assert_no_conflicts(&[east, west]);
+    }
+
+    #[test]
+    fn reservation_tables() {
+        let map = map_from(&[
+            "@@@@@", //
+            "@@.@@", //
+            "@...@", //
+            "@@.@@", //
+            "@@@@@", //
+        ]);
+        let options = SearchOptions::new();
+
+        let mut table = ReservationTable::new();
+        let first = space_time_a_star_reserved(&map, (1, 2), (3, 2), 0, &table, &options).unwrap();
+        table.reserve_route(4, &first);
+        assert_eq!(table.holder((2, 2), 1), Some(4));
+        assert_eq!(table.holder((3, 2), 50), Some(4));
+        assert!(!table.reserve(5, (2, 2), 1));
+        assert!(table.reserve(4, (2, 2), 1));
+
+        //Planning against the table waits at the crossing, until the first agent is released
+        let second = space_time_a_star_reserved(&map, (2, 1), (2, 3), 0, &table, &options).unwrap();
+        assert_eq!(second.end_time(), 3);
+        table.release(4);
+        assert!(table.is_free((3, 2), 50));
+        let second = space_time_a_star_reserved(&map, (2, 1), (2, 3), 0, &table, &options).unwrap();
+        assert_eq!(second.end_time(), 2);
+
+        //Advancing forgets the past, but parked agents keep their tile
+        table.reserve_route(4, &first);
+        table.advance(2);
+        assert_eq!(table.now(), 2);
+        assert!(table.is_free((2, 2), 1));
+        assert_eq!(table.holder((3, 2), 2), Some(4));
+        assert!(table.reserve(6, (2, 2), 1));
+        assert!(table.is_free((2, 2), 1));
+
+        //A windowed table ignores reservations too far ahead
+        let mut table = ReservationTable::with_window(3);
+        assert!(table.reserve(1, (2, 2), 2));
+        assert!(table.reserve(1, (2, 2), 3));
+        assert_eq!(table.holder((2, 2), 2), Some(1));
+        assert!(table.is_free((2, 2), 3));
+        table.advance(1);
+        assert!(table.reserve(1, (2, 2), 3));
+        assert_eq!(table.holder((2, 2), 3), Some(1));
+
+        assert_eq!(
+            ReservationTable::from_routes(&[first]).holder((1, 2), 0),
+            Some(0)
+        );
+    }
+
+    #[test]
+    fn local_detours() {
+        let map = map_from(&[
+            "@@@@@@@@", //
+            "@......@", //
+            "@......@", //
+            "@......@", //
+            "@@@@@@@@", //
+        ]);
+        let options = SearchOptions::new();
+        let steps: Vec<(usize, usize)> = (1..=6).rev().map(|x| (x, 2)).collect();
+        let route = Route::from((5.0, steps));
+
+        //Blockers off the route or beyond the lookahead need no detour
+        assert!(
+            local_detour(&map, &route, (1, 2), &[(3, 1)], 4, 1, &options)
+                .unwrap()
+                .is_none()
+        );
+        assert!(
+            local_detour(&map, &route, (1, 2), &[(5, 2)], 3, 1, &options)
+                .unwrap()
+                .is_none()
+        );
+
+        //The detour steps around the blockers and rejoins the route just past them
+        let detour = local_detour(&map, &route, (1, 2), &[(3, 2), (4, 2)], 4, 1, &options)
+            .unwrap()
+            .unwrap();
+        assert_eq!(detour.steps().first(), Some(&(5, 2)));
+        assert_eq!(detour.steps().last(), Some(&(1, 2)));
+        assert!(!detour.steps().contains(&(3, 2)));
+        assert!(!detour.steps().contains(&(4, 2)));
+
+        //The agent is placed on the nearest step of the route
+        let detour = local_detour(&map, &route, (1, 1), &[(2, 2)], 2, 1, &options)
+            .unwrap()
+            .unwrap();
+        assert_eq!(detour.steps().first(), Some(&(3, 2)));
+        assert_eq!(detour.steps().last(), Some(&(1, 1)));
+
+        //The corridor limits how far the detour may stray
+        assert_eq!(
+            local_detour(&map, &route, (1, 2), &[(3, 2)], 4, 0, &options).err(),
+            Some(PathError::NoPath)
+        );
+        assert_eq!(
+            local_detour(&map, &route, (4, 2), &[(5, 2), (6, 2)], 3, 1, &options).err(),
+            Some(PathError::GoalBlocked((6, 2)))
+        );
+    }
+
+    #[test]
+    fn crowd_flow_fields() {
+        let open = map_from(&[
+            "@@@@@@@", //
+            "@.....@", //
+            "@.@@@.@", //
+            "@.....@", //
+            "@@@@@@@", //
+        ]);
+        let blocked = map_from(&[
+            "@@@@@@@", //
+            "@..@..@", //
+            "@.@@@.@", //
+            "@.....@", //
+            "@@@@@@@", //
+        ]);
+
+        let field = FlowField::new(&open, &[(5, 1), (1, 3)]);
+        assert_eq!(field.next((1, 1)), Some((1, 2)));
+        assert_eq!(field.cost((1, 1)), 2.0);
+        assert_eq!(field.next((4, 1)), Some((5, 1)));
+        assert_eq!(field.direction((4, 1)), Some((1.0, 0.0)));
+        assert_eq!(field.next((1, 3)), None);
+        assert_eq!(field.next((0, 0)), None);
+        assert_eq!(field.cost((9, 9)), f64::INFINITY);
+
+        let mut crowd = Crowd::new(&open, &[(5, 1)]);
+        let agents = [
+            CrowdAgent {
+                position: (1.5, 1.5),
+                max_speed: 2.0,
+            },
+            CrowdAgent {
+                position: (5.2, 1.5),
+                max_speed: 1.0,
+            },
+            CrowdAgent {
+                position: (0.5, 0.5),
+                max_speed: 1.0,
+            },
+        ];
+        let velocities = crowd.tick(&open, &agents);
+        assert_eq!(velocities[0], (2.0, 0.0));
+        assert!((velocities[1].0 - 0.3).abs() < 1e-9 && velocities[1].1 == 0.0);
+        assert_eq!(velocities[2], (0.0, 0.0));
+        assert!(!crowd.refresh(&open));
+
+        //Blocking the corridor sends the crowd the long way round on the next tick
+        let velocities = crowd.tick(&blocked, &agents);
+        assert_eq!(velocities[0], (0.0, 2.0));
+        assert_eq!(crowd.field().cost((1, 1)), 8.0);
+        assert!(!crowd.refresh(&blocked));
+
+        crowd.set_goals(&blocked, &[(2, 1)]);
+        assert_eq!(crowd.goals(), &[(2, 1)]);
+        assert_eq!(crowd.tick(&blocked, &agents)[0], (2.0, 0.0));
+    }
+
+    #[test]
+    fn active_paths() {
+        let open = map_from(&[
+            "@@@@@@@@", //
+            "@......@", //
+            "@......@", //
+            "@@@@@@@@", //
+        ]);
+        let narrowed = map_from(&[
+            "@@@@@@@@", //
+            "@..@...@", //
+            "@......@", //
+            "@@@@@@@@", //
+        ]);
+        let closed = map_from(&[
+            "@@@@@@@@", //
+            "@..@...@", //
+            "@..@...@", //
+            "@@@@@@@@", //
+        ]);
+        let options = SearchOptions::new().route_steps(RouteSteps::JumpPoints);
+
+        let mut path =
+            ActivePath::new(&open, (1, 1), (6, 1), Algorithm::Dijkstra, &options).unwrap();
+        assert_eq!(path.goal(), (6, 1));
+        assert_eq!(path.route().steps().len(), 6);
+        assert_eq!(path.update(&open, (2, 1)), Ok(PathUpdate::Unchanged));
+        assert_eq!(path.route().steps().last(), Some(&(2, 1)));
+        assert_eq!(path.route().distance(), 4.0);
+
+        //A blocked tile is bypassed and the rest of the route kept
+        assert_eq!(path.update(&narrowed, (2, 1)), Ok(PathUpdate::Repaired));
+        let steps = path.route().steps();
+        assert!(!steps.contains(&(3, 1)));
+        assert_eq!(steps.first(), Some(&(6, 1)));
+        assert_eq!(steps.last(), Some(&(2, 1)));
+        assert!(steps
+            .windows(2)
+            .all(|pair| narrowed.is_traversable_from(pair[1], pair[0])));
+        assert_eq!(path.route().distance(), 6.0);
+
+        //With no way through the old route is kept until one opens up
+        assert_eq!(path.update(&closed, (2, 1)), Err(PathError::NoPath));
+        assert!(path.route().steps().contains(&(3, 2)));
+
+        //An agent that strays from the route is replanned from where it is
+        assert_eq!(path.update(&narrowed, (1, 2)), Ok(PathUpdate::Replanned));
+        assert_eq!(path.route().steps().last(), Some(&(1, 2)));
+        assert_eq!(path.update(&narrowed, (1, 2)), Ok(PathUpdate::Unchanged));
+    }
+
+    #[test]
+    fn prioritized_plans() {
+        let map = map_from(&[
+            "@@@@@@@", //
+            "@.....@", //
+            "@@@.@@@", //
+            "@@@@@@@", //
+        ]);
+        let options = SearchOptions::new();
+        let agents = [((3, 1), (4, 1)), ((1, 1), (5, 1))];
+
+        //Planned first, the short trip parks in the corridor, so the long trip goes first instead
+        let plan = prioritized_planning(&map, &agents, &[], 3, &options).unwrap();
+        assert_eq!(plan.order, vec![1, 0]);
+        assert_eq!(plan.restarts, 1);
+        assert_eq!(plan.routes[0].positions()[0], (3, 1));
+        assert_eq!(plan.routes[0].positions().last(), Some(&(4, 1)));
+        assert_eq!(plan.routes[1].end_time(), 4);
+        assert!(plan.routes[0].positions().contains(&(3, 2)));
+        assert_no_conflicts(&plan.routes);
+
+        let ordered = prioritized_planning(&map, &agents, &[1], 0, &options).unwrap();
+        assert_eq!(ordered.restarts, 0);
+        assert_eq!(ordered.routes, plan.routes);
+        assert_eq!(
+            prioritized_planning(&map, &agents, &[0, 0, 9], 0, &options),
+            Err(PathError::NoPath)
+        );
+
+        //Two agents can never share a goal
+        let shared = [((1, 1), (5, 1)), ((2, 1), (5, 1))];
+        assert_eq!(
+            prioritized_planning(&map, &shared, &[], 2, &options),
+            Err(PathError::NoPath)
+        );
+    }
+
+    #[test]
+    fn formations() {
+        let map = map_from(&[
+            "@@@@@@@@@", //
+            "@.......@", //
+            "@.......@", //
+            "@@@@.@@@@", //
+            "@.......@", //
+            "@.......@", //
+            "@@@@@@@@@", //
+        ]);
+        let options = SearchOptions::new();
+        let squad = Formation::grid(3, 2);
+        assert_eq!(squad.offsets().len(), 6);
+        assert!(squad.fits(&map, (1, 1)));
+        assert!(!squad.fits(&map, (6, 1)));
+        assert_eq!(squad.members_at((1, 1)).unwrap()[5], (3, 2));
+
+        //The squad cannot fit through the single tile gap, but a column can
+        assert_eq!(
+            squad.path(&map, (1, 1), (1, 4), &options).err(),
+            Some(PathError::NoPath)
+        );
+        assert_eq!(
+            squad.path(&map, (6, 1), (1, 4), &options).err(),
+            Some(PathError::StartBlocked((6, 1)))
+        );
+        let column = Formation::new(vec![(0, -1), (0, 0)]);
+        let route = column.path(&map, (2, 2), (4, 5), &options).unwrap();
+        assert!(route.steps().contains(&(4, 4)));
+
+        let members = column.member_routes(&route).unwrap();
+        assert_eq!(members.len(), 2);
+        for (member, offset) in members.iter().zip(column.offsets()) {
+            assert_eq!(member.distance(), route.distance());
+            for (step, anchor) in member.steps().iter().zip(route.steps()) {
+                assert!(map.is_traversable(*step));
+                assert_eq!(step.1 as i64 - anchor.1 as i64, offset.1);
+            }
+        }
+
+        let off_edge = Route::from((0.0, vec![(0, 0)]));
+        assert!(column.member_routes(&off_edge).is_none());
+    }
+
+    #[test]
+    fn time_expanded_searches() {
+        let map = map_from(&[
+            "@@@@@@@", //
+            "@.....@", //
+            "@@@@@@@", //
+        ]);
+        let options = SearchOptions::new();
+
+        //A train occupies (3, 1) until timestep 5, so the agent waits for it to pass
+        let train = |tile: (usize, usize), time: usize| tile == (3, 1) && time <= 5;
+        let route = time_expanded_search(&map, (1, 1), (5, 1), 0, train, &options).unwrap();
+        assert_eq!(route.end_time(), 8);
+        assert_eq!(route.position_at(6), Some((3, 1)));
+        for time in 0..=5 {
+            assert_ne!(route.position_at(time), Some((3, 1)));
+        }
+
+        let open = time_expanded_search(&map, (1, 1), (5, 1), 3, |_, _| false, &options).unwrap();
+        assert_eq!(open.start_time(), 3);
+        assert_eq!(open.end_time(), 7);
+
+        assert_eq!(
+            time_expanded_search(
+                &map,
+                (1, 1),
+                (5, 1),
+                0,
+                train,
+                &options.clone().time_horizon(6)
+            )
+            .err(),
+            Some(PathError::NoPath)
+        );
+        assert_eq!(
+            time_expanded_search(&map, (3, 1), (5, 1), 0, train, &options).err(),
+            Some(PathError::StartBlocked((3, 1)))
+        );
+    }
+
+    #[test]
+    fn congestion() {
+        let map = map_from(&[
+            "@@@@@@@", //
+            "@.....@", //
+            "@.@@@.@", //
+            "@.....@", //
+            "@@@@@@@", //
+        ]);
+        let mut congestion = CongestionMap::new(&map, 1.0, 0.5);
+        let mut context = SearchContext::new();
+
+        //Each agent takes whichever of the two corridors has been used least recently
+        let mut rows = Vec::new();
+        for _ in 0..3 {
+            let options = SearchOptions::new().congestion(Arc::new(congestion.clone()));
+            let route = context
+                .dijkstra_path(&map, (1, 2), (5, 2), &options)
+                .unwrap();
+            rows.push(route.steps()[3].1);
+            congestion.claim_route(&route);
+        }
+        assert_ne!(rows[0], rows[1]);
+        assert_eq!(rows[0], rows[2]);
+
+        assert_eq!(congestion.get((3, 0)), 0.0);
+        assert_eq!(congestion.get((1, 2)), 3.0);
+        congestion.tick();
+        assert_eq!(congestion.get((1, 2)), 1.5);
+        assert_eq!(congestion.get((9, 9)), 0.0);
+        congestion.claim((9, 9));
+        assert_eq!(congestion.values().len(), 35);
+
+        //A single busy corridor is still used when the detour costs more
+        let route = context
+            .a_star_path(
+                &map,
+                (1, 1),
+                (5, 1),
+                &SearchOptions::new().congestion(Arc::new(congestion)),
+            )
+            .unwrap();
+        assert!(route.steps().contains(&(3, 1)));
+        assert!(route.distance() > 4.0);
+    }
+
+    #[test]
+    fn slopes() {
+        let map = map_from(&[
+            "@@@@@@@", //
+            "@.....@", //
+            "@.....@", //
+            "@.....@", //
+            "@@@@@@@", //
+        ]);
+        //A hill on the middle row, with a cliff at its eastern end
+        let heights = Heightmap::new(&map, |(x, y)| match (x, y) {
+            (5, 2) => 10.0,
+            (_, 2) => 1.0,
+            _ => 0.0,
+        });
+        assert_eq!(heights.get((3, 2)), 1.0);
+        assert_eq!(heights.get((9, 9)), 0.0);
+        assert_eq!(heights.slope((3, 2), (3, 1)), -1.0);
+        assert_eq!(heights.slope((3, 2), (3, 2)), 0.0);
+        assert_eq!(heights.values().len(), 35);
+
+        //Crossing the hill costs more the steeper its weights make it
+        let options = SearchOptions::new();
+        let cost = heights.slope_cost(4.0, 4.0, 2.0);
+        let route = dijkstra_path_weighted(&map, (1, 1), (1, 3), &options, &cost).unwrap();
+        assert_eq!(route.distance(), 2.0 + 2.0 * 4.0);
+        let flat = heights.slope_cost(0.0, 0.0, 2.0);
+        let route = dijkstra_path_weighted(&map, (1, 1), (1, 3), &options, flat).unwrap();
+        assert_eq!(route.distance(), 2.0);
+
+        //The cliff cannot be climbed, leaving no way onto it
+        assert_eq!(cost((4, 2), (5, 2)), None);
+        assert_eq!(
+            dijkstra_path_weighted(&map, (1, 1), (5, 2), &options, &cost).err(),
+            Some(PathError::NoPath)
+        );
+    }
+
+    #[test]
+    fn directional_costs() {
+        let map = map_from(&[
+            "@@@@@@@", //
+            "@.....@", //
+            "@.@@@.@", //
+            "@.....@", //
+            "@@@@@@@", //
+        ]);
+        let options = SearchOptions::new();
+        assert_eq!(
+            Direction::between((2, 2), (3, 1)),
+            Some(Direction::NorthEast)
+        );
+        assert_eq!(Direction::between((2, 2), (4, 2)), None);
+        assert_eq!(Direction::South.offset(), (0, 1));
+
+        //The top row only runs east, so heading west goes round the bottom
+        let mut costs = DirectionalCosts::new(&map);
+        for x in 1..=5 {
+            costs.one_way((x, 1), Direction::East);
+        }
+        assert_eq!(costs.get((3, 1), Direction::SouthWest), None);
+        assert_eq!(costs.get((3, 1), Direction::South), Some(1.0));
+        let east =
+            dijkstra_path_weighted(&map, (1, 1), (5, 1), &options, costs.step_cost()).unwrap();
+        assert_eq!(east.distance(), 4.0);
+        let west =
+            dijkstra_path_weighted(&map, (5, 1), (1, 1), &options, costs.step_cost()).unwrap();
+        assert_eq!(west.distance(), 8.0);
+
+        //A westward current along the bottom row speeds the trip up
+        for x in 1..=5 {
+            costs.flow((x, 3), Direction::West, 0.5);
+        }
+        assert_eq!(costs.get((3, 3), Direction::East), Some(1.5));
+        let west =
+            dijkstra_path_weighted(&map, (5, 1), (1, 1), &options, costs.step_cost()).unwrap();
+        assert_eq!(west.distance(), 6.0);
+
+        costs.set((1, 2), Direction::North, None);
+        assert_eq!(
+            dijkstra_path_weighted(&map, (5, 1), (1, 1), &options, costs.step_cost()).err(),
+            Some(PathError::NoPath)
+        );
+        assert_eq!(costs.get((9, 9), Direction::North), None);
+    }
+
+    #[test]
+    fn turn_penalties() {
+        let map = map_from(&[
+            "@@@@@@@@", //
+            "@......@", //
+            "@......@", //
+            "@......@", //
+            "@......@", //
+            "@@@@@@@@", //
+        ]);
+        let options = SearchOptions::new();
+        let turns = |route: &Route| {
+            let steps = route.steps();
+            steps
+                .windows(3)
+                .filter(|step| {
+                    (step[0].0 + step[2].0, step[0].1 + step[2].1) != (2 * step[1].0, 2 * step[1].1)
+                })
+                .count()
+        };
+
+        //Without a penalty the route is as short as any other
+        let free = a_star_path_turning(&map, (1, 1), (6, 4), 0.0, &options).unwrap();
+        assert!((free.distance() - (2.0 + 3.0 * 2f64.sqrt())).abs() < 1e-9);
+
+        //With one it turns only once, for a single 45 degrees
+        let smooth = a_star_path_turning(&map, (1, 1), (6, 4), 1.0, &options).unwrap();
+        assert_eq!(turns(&smooth), 1);
+        assert!((smooth.distance() - (3.0 + 3.0 * 2f64.sqrt())).abs() < 1e-9);
+        assert_eq!(smooth.steps().len(), 6);
+
+        //Going round a corner costs a right angle
+        let corner = a_star_path_turning(&map, (1, 1), (1, 4), 5.0, &options).unwrap();
+        assert_eq!(corner.distance(), 3.0);
+        let corner = a_star_path_turning(&map, (6, 1), (1, 4), 5.0, &options).unwrap();
+        assert_eq!(turns(&corner), 1);
+
+        assert_eq!(
+            a_star_path_turning(
+                &map,
+                (1, 1),
+                (6, 4),
+                1.0,
+                &options.clone().max_distance(6.0)
+            )
+            .err(),
+            Some(PathError::ExceedsMaxDistance)
+        );
+        assert_eq!(
+            a_star_path_turning(&map, (1, 1), (0, 0), 1.0, &options).err(),
+            Some(PathError::GoalBlocked((0, 0)))
+        );
+    }
+
+    #[test]
+    fn risk_weighting() {
+        let map = map_from(&[
+            "@@@@@@@@@", //
+            "@.......@", //
+            "@.@@@@@.@", //
+            "@.@@@@@.@", //
+            "@.......@", //
+            "@@@@@@@@@", //
+        ]);
+        let options = SearchOptions::new();
+        let tower = [InfluenceSource {
+            position: (4, 1),
+            strength: 4.0,
+        }];
+        let threat = InfluenceMap::new(&map, &tower, Decay::Linear(0.25), 0.01);
+
+        //A little risk is worth the short way, a lot is not
+        let bold =
+            dijkstra_path_weighted(&map, (1, 1), (7, 1), &options, threat.risk_cost(0.1)).unwrap();
+        assert!(bold.steps().contains(&(4, 1)));
+        assert_eq!(threat.route_risk(&bold), 15.0);
+        let cautious =
+            dijkstra_path_weighted(&map, (1, 1), (7, 1), &options, threat.risk_cost(1.0)).unwrap();
+        assert!(cautious.steps().contains(&(4, 4)));
+        assert!(threat.route_risk(&cautious) < 3.0);
+        assert!(cautious.distance() > 12.0);
+
+        //Friendly influence is never a reason to go out of the way
+        let friendly = [InfluenceSource {
+            position: (4, 4),
+            strength: -4.0,
+        }];
+        let support = InfluenceMap::new(&map, &friendly, Decay::Linear(0.25), 0.01);
+        let route =
+            dijkstra_path_weighted(&map, (1, 1), (7, 1), &options, support.risk_cost(1.0)).unwrap();
+        assert_eq!(route.distance(), 6.0);
+        assert_eq!(support.route_risk(&route), 0.0);
+    }
+
+    #[test]
+    fn stealth_paths() {
+        let map = map_from(&[
+            "@@@@@@@@@", //
+            "@.......@", //
+            "@.@@@@@.@", //
+            "@.......@", //
+            "@@@@@@@@@", //
+        ]);
+        let options = SearchOptions::new();
+        let low = Watcher {
+            position: (4, 3),
+            range: 2.5,
+        };
+        let visibility = VisibilityMap::new(&map, &[low]);
+        assert!(visibility.is_seen((2, 3)));
+        assert!(!visibility.is_seen((7, 3)));
+        assert!(!visibility.is_seen((3, 1)));
+        assert_eq!(visibility.watchers((9, 9)), 0);
+        assert_eq!(visibility.values().len(), 45);
+        assert_eq!(visibility.hidden_cost()((1, 3), (2, 3)), None);
+
+        //The long way round stays out of sight entirely
+        let hidden = stealth_path(&map, (1, 3), (7, 3), &visibility, 1.0, &options).unwrap();
+        assert!(hidden.steps().contains(&(4, 1)));
+        assert_eq!(hidden.distance(), 10.0);
+        assert_eq!(visibility.route_exposure(&hidden), 0.0);
+
+        //With both ways watched, the weight decides how much exposure is worth avoiding
+        let high = Watcher {
+            position: (4, 1),
+            range: 1.5,
+        };
+        let visibility = VisibilityMap::new(&map, &[low, high]);
+        let careful = stealth_path(&map, (1, 3), (7, 3), &visibility, 10.0, &options).unwrap();
+        assert!(careful.steps().contains(&(4, 1)));
+        assert_eq!(visibility.route_exposure(&careful), 3.0);
+        let hasty = stealth_path(&map, (1, 3), (7, 3), &visibility, 0.1, &options).unwrap();
+        assert!(hasty.steps().contains(&(4, 3)));
+        assert_eq!(visibility.route_exposure(&hasty), 5.0);
+    }
+
+    #[test]
+    fn resource_constrained_paths() {
+        let map = map_from(&[
+            "@@@@@@@@@", //
+            "@.......@", //
+            "@@@@.@@@@", //
+            "@@@@@@@@@", //
+        ]);
+        let options = SearchOptions::new();
+
+        //Enough fuel to fly straight there
+        let direct = resource_path(&map, (1, 1), (7, 1), 6.0, 6.0, &[], &options).unwrap();
+        assert_eq!(direct.distance(), 6.0);
+
+        //Otherwise the route detours into the charging bay and back out
+        let charged = resource_path(&map, (1, 1), (7, 1), 4.0, 4.0, &[(4, 2)], &options).unwrap();
+        assert_eq!(charged.distance(), 8.0);
+        let steps = charged.steps();
+        assert!(steps.contains(&(4, 2)));
+        assert_eq!(steps.iter().filter(|step| **step == (4, 1)).count(), 2);
+
+        //Starting on a station fills the tank
+        let refuelled =
+            resource_path(&map, (1, 1), (7, 1), 4.0, 0.0, &[(1, 1), (4, 2)], &options).unwrap();
+        assert_eq!(refuelled.distance(), 8.0);
+
+        assert_eq!(
+            resource_path(&map, (1, 1), (7, 1), 3.0, 3.0, &[(4, 2)], &options).err(),
+            Some(PathError::NoPath)
+        );
+        assert_eq!(
+            resource_path(
+                &map,
+                (1, 1),
+                (7, 1),
+                4.0,
+                4.0,
+                &[(4, 2)],
+                &options.max_distance(7.0)
+            )
+            .err(),
+            Some(PathError::ExceedsMaxDistance)
+        );
+    }
+
+    #[test]
+    fn pareto_frontiers() {
+        let map = map_from(&[
+            "@@@@@@@@@", //
+            "@.......@", //
+            "@.@@@@@.@", //
+            "@.@@@@@.@", //
+            "@.......@", //
+            "@@@@@@@@@", //
+        ]);
+        let options = SearchOptions::new();
+        let danger = |_: (usize, usize), to: (usize, usize)| {
+            Some(if to.1 == 1 && (2..=6).contains(&to.0) {
+                1.0
+            } else {
+                0.0
+            })
+        };
+
+        let frontier = pareto_paths(&map, (1, 1), (7, 1), danger, &options).unwrap();
+        assert_eq!(frontier.len(), 2);
+        assert!(!frontier.is_empty());
+        let totals: Vec<(f64, f64)> = frontier
+            .routes()
+            .iter()
+            .map(|(route, danger)| (route.distance(), *danger))
+            .collect();
+        assert_eq!(totals, vec![(6.0, 5.0), (12.0, 0.0)]);
+        assert!(frontier.best_second().unwrap().0.steps().contains(&(4, 4)));
+
+        //A weighting picks a compromise after the search
+        assert_eq!(frontier.weighted(0.5).unwrap().0.distance(), 6.0);
+        assert_eq!(frontier.weighted(2.0).unwrap().0.distance(), 12.0);
+        assert_eq!(frontier.shortest().unwrap().1, 5.0);
+
+        //Without a trade off there is only one route
+        let frontier = pareto_paths(&map, (1, 1), (7, 1), |_, _| Some(1.0), &options).unwrap();
+        assert_eq!(frontier.len(), 1);
+        assert_eq!(
+            pareto_paths(&map, (1, 1), (7, 1), |_, _| None, &options).err(),
+            Some(PathError::NoPath)
+        );
+    }
+
+    #[test]
+    fn anisotropic_costs() {
+        let map = map_from(&[
+            "@@@@@@@@", //
+            "@......@", //
+            "@.@@@@.@", //
+            "@......@", //
+            "@@@@@@@@", //
+        ]);
+        let options = SearchOptions::new();
+
+        //A wind blowing east makes heading east cheap and heading west dear
+        let wind = Anisotropy::wind(Direction::East, 0.5);
+        assert_eq!(wind.multiplier(Direction::East), 0.5);
+        assert_eq!(wind.multiplier(Direction::West), 1.5);
+        assert_eq!(wind.lower_bound((1, 1), (6, 1)), 2.5);
+        assert_eq!(wind.lower_bound((6, 1), (1, 1)), 7.5);
+        assert_eq!(wind.lower_bound((3, 3), (3, 3)), 0.0);
+
+        //The bound never overestimates, so A* agrees with Dijkstra
+        for (start, goal) in [((1, 1), (6, 3)), ((6, 3), (1, 1)), ((6, 1), (1, 3))] {
+            let route = a_star_path_anisotropic(&map, start, goal, &wind, &options).unwrap();
+            let best =
+                dijkstra_path_weighted(&map, start, goal, &options, wind.step_cost()).unwrap();
+            assert!((route.distance() - best.distance()).abs() < 1e-9);
+            assert!(wind.lower_bound(start, goal) <= route.distance() + 1e-9);
+        }
+
+        //Per-tile costs combine with the wind, and forbidding a direction everywhere removes it from the bound
+        let mut cells = DirectionalCosts::new(&map);
+        for tile in map.coords() {
+            cells.set(tile, Direction::West, None);
+        }
+        let blocked = Anisotropy::new([1.0; 8]).with_cells(cells);
+        assert!(blocked.cells().is_some());
+        assert_eq!(blocked.lower_bound((6, 1), (1, 1)), 5.0 * 2f64.sqrt());
+        //Without heading west, and with no room to zig-zag, there is no way back
+        assert_eq!(
+            a_star_path_anisotropic(&map, (6, 1), (1, 1), &blocked, &options).err(),
+            Some(PathError::NoPath)
+        );
+        let route = a_star_path_anisotropic(&map, (1, 1), (6, 3), &blocked, &options).unwrap();
+        assert_eq!(route.distance(), 7.0);
+    }
+
+    #[test]
+    fn smoothed_routes() {
+        let map = map_from(&[
+            "@@@@@@@@@@@", //
+            "@.........@", //
+            "@@@@@@@@@.@", //
+            "@@@@@@@@@.@", //
+            "@@@@@@@@@.@", //
+            "@@@@@@@@@.@", //
+            "@@@@@@@@@@@", //
+        ]);
+        let on_open_ground = |curve: &RouteCurve| {
+            curve
+                .sample(0.01)
+                .iter()
+                .all(|(x, y)| map.is_traversable((*x as usize, *y as usize)))
+        };
+
+        //Every tile of the corridor, turning a single right angle
+        let route = a_star_path(&map, (1, 1), (9, 5)).unwrap();
+        let curve = smooth_route(&map, &route, CurveKind::CatmullRom);
+        assert_eq!(curve.straightened(), 0);
+        assert_eq!(curve.point_at(-1.0), Some((1.5, 1.5)));
+        assert_eq!(curve.point_at(curve.length() + 1.0), Some((9.5, 5.5)));
+        assert_eq!(curve.direction_at(0.0), Some((1.0, 0.0)));
+        assert!(on_open_ground(&curve));
+        //Rounding the corner rather than passing through it cuts it short
+        let rounded = smooth_route(&map, &route, CurveKind::Bezier);
+        assert!(on_open_ground(&rounded));
+        assert!(rounded.length() < 12.0);
+
+        //Only the corners, so the spline swings out into the wall and is straightened
+        let waypoints = Route::from((12.0, vec![(9, 5), (9, 1), (1, 1)]));
+        let curve = smooth_route(&map, &waypoints, CurveKind::CatmullRom);
+        assert!(curve.straightened() > 0);
+        assert!(on_open_ground(&curve));
+        //Rounding the corner so widely cuts across the wall inside it instead
+        let curve = smooth_route(&map, &waypoints, CurveKind::Bezier);
+        assert_eq!(curve.straightened(), 1);
+        assert!(on_open_ground(&curve));
+        let samples = curve.sample(1.0);
+        assert_eq!(samples.first(), Some(&(1.5, 1.5)));
+        assert_eq!(samples.last(), Some(&(9.5, 5.5)));
+        assert_eq!(samples.len(), curve.length().ceil() as usize + 1);
+
+        //No tile of the corridor is clear enough, so the curve keeps to the route
+        let clearance = ClearanceMap::new(&map);
+        let curve = smooth_route_within(&route, CurveKind::Bezier, &clearance, 2.0);
+        assert_eq!(curve.straightened(), route.steps().len() - 2);
+        assert!((curve.length() - 12.0).abs() < 1e-9);
+
+        let single = smooth_route(&map, &Route::from((0.0, vec![(3, 1)])), CurveKind::Bezier);
+        assert_eq!(single.point_at(0.0), Some((3.5, 1.5)));
+        assert_eq!(single.direction_at(0.0), None);
+        let empty = smooth_route(&map, &Route::from((0.0, Vec::new())), CurveKind::Bezier);
+        assert_eq!(empty.point_at(0.0), None);
+        assert!(empty.sample(1.0).is_empty());
+    }
+
+    #[test]
+    fn route_coverage() {
+        let map = map_from(&[
+            "@@@@@@@@", //
+            "@......@", //
+            "@......@", //
+            "@......@", //
+            "@@@@@@@@", //
+        ]);
+
+        //Listing only jump points still touches every tile between them
+        let cells = jps_path_with(&map, (1, 1), (6, 3), &SearchOptions::new()).unwrap();
+        let options = SearchOptions::new().route_steps(RouteSteps::JumpPoints);
+        let jumps = jps_path_with(&map, (1, 1), (6, 3), &options).unwrap();
+        assert_eq!(cells.bounding_box(), Some(((1, 1), (6, 3))));
+        assert_eq!(jumps.bounding_box(), cells.bounding_box());
+        let touched = jumps.cells_touched();
+        assert!(cells.steps().iter().all(|step| touched.contains(step)));
+        assert!(touched.iter().all(|tile| map.is_traversable(*tile)));
+
+        //A line that is neither straight nor diagonal touches the tiles it sweeps through,
+        //here crossing the corner between (1, 0), (2, 0), (1, 1) and (2, 1)
+        let slanted: Route = Route::from((0.0, vec![(3, 1), (0, 0)]));
+        let touched: Vec<(usize, usize)> = slanted.cells_touched().into_iter().collect();
+        assert_eq!(
+            touched,
+            vec![(0, 0), (1, 0), (1, 1), (2, 0), (2, 1), (3, 1)]
+        );
+        let shallow: Route = Route::from((0.0, vec![(4, 1), (0, 0)]));
+        let touched: Vec<(usize, usize)> = shallow.cells_touched().into_iter().collect();
+        assert_eq!(
+            touched,
+            vec![(0, 0), (1, 0), (2, 0), (2, 1), (3, 1), (4, 1)]
+        );
+        let diagonal: Route = Route::from((0.0, vec![(0, 0), (2, 2)]));
+        assert_eq!(
+            diagonal.cells_touched().into_iter().collect::<Vec<_>>(),
+            vec![(0, 0), (0, 1), (1, 0), (1, 1), (1, 2), (2, 1), (2, 2)]
+        );
+
+        let empty: Route = Route::from((0.0, Vec::new()));
+        assert_eq!(empty.bounding_box(), None);
+        assert!(empty.cells_touched().is_empty());
+    }
+
+    #[test]
+    fn route_conflicts() {
+        let map = map_from(&[
+            "@@@@@@@", //
+            "@.....@", //
+            "@.....@", //
+            "@.....@", //
+            "@@@@@@@", //
+        ]);
+
+        //Two units crossing the room at right angles meet in the middle
+        let across = a_star_path(&map, (1, 2), (5, 2)).unwrap();
+        let down = Route::from((2.0, vec![(3, 3), (3, 1)]));
+        assert_eq!(across.conflicts(&down), vec![RouteConflict::Cell((3, 2))]);
+        assert_eq!(down.conflicts(&across), vec![RouteConflict::Cell((3, 2))]);
+
+        //Routes running the same way report each shared tile once, in order
+        let along = Route::from((4.0, vec![(5, 2), (1, 2)]));
+        assert_eq!(
+            along.conflicts(&across),
+            (1..=5)
+                .map(|x| RouteConflict::Cell((x, 2)))
+                .collect::<Vec<_>>()
+        );
+
+        //Diagonals crossing in the middle of a square share no tile
+        let falling = Route::from((2f64.sqrt(), vec![(2, 2), (1, 1)]));
+        let rising = Route::from((2f64.sqrt(), vec![(1, 2), (2, 1)]));
+        assert_eq!(
+            falling.conflicts(&rising),
+            vec![RouteConflict::Crossing {
+                first: ((1, 1), (2, 2)),
+                second: ((2, 1), (1, 2)),
+            }]
+        );
+        assert!(rising.intersects(&falling));
+
+        let apart = Route::from((4.0, vec![(5, 3), (1, 3)]));
+        assert!(!apart.intersects(&along));
+        assert_eq!(apart.conflicts(&down), vec![RouteConflict::Cell((3, 3))]);
+        assert!(!falling.intersects(&Route::from((0.0, Vec::new()))));
+    }
+
+    #[test]
+    fn route_similarity() {
+        let map = map_from(&[
+            "@@@@@@@@@@", //
+            "@........@", //
+            "@........@", //
+            "@........@", //
+            "@@@@@@@@@@", //
+        ]);
+
+        //A* and JPS thread the room differently, but take essentially the same route
+        let a_star = a_star_path(&map, (1, 1), (8, 3)).unwrap();
+        let options = SearchOptions::new().route_steps(RouteSteps::JumpPoints);
+        let jps = jps_path_with(&map, (1, 1), (8, 3), &options).unwrap();
+        assert!(a_star.is_similar(&jps, 2.0));
+        assert_eq!(a_star.frechet_distance(&a_star), 0.0);
+        assert_eq!(a_star.frechet_distance(&jps), jps.frechet_distance(&a_star));
+
+        //Going the other way round the room is not the same route
+        let reversed = Route::from((0.0, a_star.steps().into_iter().rev().collect()));
+        assert!(!a_star.is_similar(&reversed, 2.0));
+        assert!(a_star.frechet_distance(&reversed) >= 7.0);
+
+        let empty = Route::from((0.0, Vec::new()));
+        assert_eq!(empty.frechet_distance(&Route::from((0.0, Vec::new()))), 0.0);
+        assert_eq!(empty.frechet_distance(&a_star), f64::INFINITY);
+    }
+
+    #[test]
+    fn map_diffs() {
+        let map = map_from(&[
+            "@@@@@@@", //
+            "@.....@", //
+            "@.....@", //
+            "@@@@@@@", //
+        ]);
+
+        //Later edits win, and edits that change nothing or fall outside the map are dropped
+        let diff = MapDiff::new(
+            &map,
+            &[
+                ((3, 2), '.'),
+                ((3, 2), '@'),
+                ((3, 1), '@'),
+                ((1, 1), '.'),
+                ((9, 9), '@'),
+            ],
+        );
+        assert_eq!(
+            diff.changes(),
+            &[
+                CellChange {
+                    position: (3, 1),
+                    before: '.',
+                    after: '@'
+                },
+                CellChange {
+                    position: (3, 2),
+                    before: '.',
+                    after: '@'
+                },
+            ]
+        );
+        assert!(diff.contains((3, 2)));
+        assert!(!diff.contains((1, 1)));
+        assert_eq!(diff.bounding_box(), Some(((3, 1), (3, 2))));
+
+        let walled = diff.apply(&map).unwrap();
+        assert!(!walled.is_traversable((3, 1)));
+        assert_eq!(MapDiff::between(&map, &walled).unwrap(), diff);
+        assert_eq!(MapDiff::between(&walled, &map).unwrap(), diff.inverse());
+        assert!(MapDiff::between(&map, &diff.revert(&walled).unwrap())
+            .unwrap()
+            .is_empty());
+
+        //A diff only applies to the revision it was made from
+        assert_eq!(
+            diff.apply(&walled).err(),
+            Some(MapDiffError::Conflict {
+                position: (3, 1),
+                expected: '.',
+                found: '@'
+            })
+        );
+        let small = map_from(&["@@@", "@.@", "@@@"]);
+        assert_eq!(
+            diff.apply(&small).err(),
+            Some(MapDiffError::SizeMismatch {
+                expected: (7, 4),
+                found: (3, 3)
+            })
+        );
+
+        //Planners only need to look again when the diff touches them
+        let options = SearchOptions::new();
+        let path = ActivePath::new(&map, (1, 1), (5, 1), Algorithm::AStar, &options).unwrap();
+        assert!(path.is_affected(&diff));
+        let far = MapDiff::new(&map, &[((5, 2), '@')]);
+        assert!(!path.is_affected(&far));
+
+        let mut crowd = Crowd::new(&map, &[(5, 1)]);
+        assert!(!crowd.apply_diff(&map, &MapDiff::new(&map, &[((2, 2), 'G')])));
+        assert!(crowd.apply_diff(&walled, &diff));
+        assert!(!crowd.refresh(&walled));
+        assert!(!crowd.field().cost((1, 1)).is_finite());
+    }
+
+    #[test]
+    fn inflated_obstacles() {
+        let map = map_from(&[
+            "@@@@@@@@@", //
+            "@.......@", //
+            "@.......@", //
+            "@...@...@", //
+            "@.......@", //
+            "@.......@", //
+            "@@@@@@@@@", //
+        ]);
+
+        //Nothing grows with a radius of zero
+        let same = inflate(&map, 0);
+        assert!(map
+            .coords()
+            .all(|tile| same.is_traversable(tile) == map.is_traversable(tile)));
+
+        //A radius of one blocks every tile next to an obstacle, but not diagonally
+        let grown = inflate(&map, 1);
+        let open: Vec<(usize, usize)> = grown
+            .coords()
+            .filter(|tile| grown.is_traversable(*tile))
+            .collect();
+        assert_eq!(
+            open,
+            vec![
+                (2, 2),
+                (3, 2),
+                (5, 2),
+                (6, 2),
+                (2, 3),
+                (6, 3),
+                (2, 4),
+                (3, 4),
+                (5, 4),
+                (6, 4)
+            ]
+        );
+
+        //Diagonal neighbours are within a radius of two, leaving no room at all
+        let wide = inflate(&map, 2);
+        assert!(wide.coords().all(|tile| !wide.is_traversable(tile)));
+
+        //The gaps either side of the pillar are too narrow for an agent of radius one
+        assert!(a_star_path(&map, (2, 2), (6, 2)).is_ok());
+        assert_eq!(
+            a_star_path(&grown, (2, 2), (6, 2)).err(),
+            Some(PathError::NoPath)
+        );
+        assert_eq!(a_star_path(&grown, (2, 2), (3, 4)).unwrap().distance(), 3.0);
+    }
+
+    #[test]
+    fn cost_stacks() {
+        let map = map_from(&[
+            "@@@@@@@", //
+            "@.....@", //
+            "@.....@", //
+            "@.....@", //
+            "@@@@@@@", //
+        ]);
+        let hazards = [(3, 1)];
+        let congestion = CongestionMap::new(&map, 1.0, 0.5);
+
+        let mut stack = CostStack::new();
+        stack.push("terrain", 1.0, |_, to: (usize, usize)| {
+            Some(to.1 as f64 - 1.0)
+        });
+        stack.push("hazards", 1.0, |_, to| {
+            if hazards.contains(&to) {
+                None
+            } else {
+                Some(0.0)
+            }
+        });
+        stack.push("congestion", 2.0, |_, to| Some(congestion.get(to)));
+        assert_eq!(stack.names(), vec!["terrain", "hazards", "congestion"]);
+        assert_eq!(stack.len(), 3);
+        assert_eq!(stack.penalty((1, 1), (1, 2)), Some(1.0));
+        assert_eq!(stack.penalty((2, 1), (3, 1)), None);
+
+        //The hazard forces the route off row 1, where the terrain is dearer
+        let options = SearchOptions::new();
+        let route =
+            a_star_path_weighted(&map, (1, 1), (5, 1), &options, stack.step_cost()).unwrap();
+        assert!(!route.steps().contains(&(3, 1)));
+        assert!(route.distance() > 4.0);
+
+        //Switching a layer off removes what it forbids, and replacing a layer keeps its place
+        assert!(stack.set_weight("hazards", 0.0));
+        stack.push("terrain", 0.5, |_, _| Some(0.0));
+        assert_eq!(stack.weight("terrain"), Some(0.5));
+        assert_eq!(stack.names()[0], "terrain");
+        let route =
+            a_star_path_weighted(&map, (1, 1), (5, 1), &options, stack.step_cost()).unwrap();
+        assert_eq!(route.distance(), 4.0);
+        assert!(stack.remove("hazards"));
+        assert!(!stack.remove("hazards"));
+        assert!(!stack.set_weight("hazards", 1.0));
+
+        //Plain searches consult a stack passed through the options
+        let mut wall = CostStack::new();
+        wall.push("wall", 1.0, |_, to: (usize, usize)| {
+            if to.0 == 3 && to.1 < 3 {
+                None
+            } else {
+                Some(0.0)
+            }
+        });
+        let options = SearchOptions::new().cost_stack(Arc::new(wall));
+        let route = a_star_path_with(&map, (1, 1), (5, 1), &options).unwrap();
+        assert!(route.steps().contains(&(3, 3)));
+        let route =
+            dijkstra_path_weighted(&map, (1, 1), (5, 1), &options, |_, _| Some(1.0)).unwrap();
+        assert!(route.steps().contains(&(3, 3)));
+    }
+
+    #[test]
+    fn cropped_maps() {
+        let map = map_from(&[
+            "@@@@@@@@", //
+            "@@@@@@@@", //
+            "@@.....@", //
+            "@@.@@@.@", //
+            "@@.....@", //
+            "@@@@@@@@", //
+        ]);
+
+        let area = crop(&map, (1, 1), (7, 4)).unwrap();
+        assert_eq!((area.map().width(), area.map().height()), (7, 4));
+        assert_eq!(area.origin(), (1, 1));
+        assert!(area.map().coords().all(|tile| {
+            area.map().is_traversable(tile) == map.is_traversable(area.to_global(tile))
+        }));
+        assert_eq!(area.to_local((3, 3)), Some((2, 2)));
+        assert_eq!(area.to_local((0, 1)), None);
+        assert_eq!(area.to_local((3, 5)), None);
+
+        //Routes found on the crop line up with routes on the whole map
+        let local = a_star_path(area.map(), (1, 1), (5, 3)).unwrap();
+        let route = area.route_to_global(local);
+        let whole = a_star_path(&map, (2, 2), (6, 4)).unwrap();
+        assert_eq!(route.steps(), whole.steps());
+        assert_eq!(route.distance(), whole.distance());
+
+        //Rectangles are clipped to the map, and may come from a route's bounding box
+        let (top_left, bottom_right) = route.bounding_box().unwrap();
+        let around = crop(&map, top_left, bottom_right).unwrap();
+        assert_eq!((around.map().width(), around.map().height()), (5, 3));
+        let corner = crop(&map, (5, 3), (20, 20)).unwrap();
+        assert_eq!((corner.map().width(), corner.map().height()), (3, 3));
+        assert!(crop(&map, (8, 0), (9, 9)).is_none());
+        assert!(crop(&map, (3, 3), (2, 3)).is_none());
+        assert_eq!(corner.into_map().width(), 3);
+    }
+
+    #[test]
+    fn map_transforms() {
+        let map = map_from(&[
+            "@@@@@@@@", //
+            "@..@...@", //
+            "@..@.@.@", //
+            "@....@.@", //
+            "@@@@@@@@", //
+        ]);
+        let scenario = |start, goal| SceneRecord {
+            bucket: 0,
+            map_file: String::from("test.map"),
+            map_width: map.width(),
+            map_height: map.height(),
+            start_pos: start,
+            goal_pos: goal,
+            optimal_length: a_star_path(&map, start, goal).unwrap().distance(),
+        };
+        let scenarios = vec![scenario((1, 1), (6, 1)), scenario((2, 2), (4, 1))];
+
+        for transform in MapTransform::ALL {
+            let (moved, remapped) = transform.apply_with_scenarios(&map, &scenarios);
+            assert_eq!((moved.width(), moved.height()), transform.size(8, 5));
+            for (before, after) in scenarios.iter().zip(remapped.iter()) {
+                assert_eq!(
+                    (after.map_width, after.map_height),
+                    (moved.width(), moved.height())
+                );
+                assert_eq!(after.map_file, before.map_file);
+                let route = a_star_path(&moved, after.start_pos, after.goal_pos).unwrap();
+                assert!((route.distance() - after.optimal_length).abs() < 1e-9);
+            }
+
+            //Undoing the transform restores the map and scenarios exactly
+            let restored = transform.inverse().apply(&moved);
+            assert!(map.coords().all(|tile| map.get(tile) == restored.get(tile)));
+            let back: Vec<SceneRecord> = remapped
+                .iter()
+                .map(|after| transform.inverse().scenario(after))
+                .collect();
+            assert_eq!(back, scenarios);
+        }
+
+        //A quarter turn clockwise takes the top-left corner to the top-right
+        assert_eq!(MapTransform::Rotate90.position((0, 0), 8, 5), (4, 0));
+        assert_eq!(MapTransform::Rotate270.position((0, 0), 8, 5), (0, 7));
+        assert_eq!(MapTransform::MirrorVertical.position((1, 1), 8, 5), (1, 3));
+    }
+
+    #[test]
+    fn swamp_avoidance() {
+        let map = map_from(&[
+            "@@@@@@@", //
+            "@.SSS.@", //
+            "@.@@@.@", //
+            "@.....@", //
+            "@@@@@@@", //
+        ]);
+        let options = SearchOptions::new().avoid_swamps(true);
+
+        //Swamps are crossed freely unless asked otherwise
+        assert_eq!(a_star_path(&map, (1, 1), (5, 1)).unwrap().distance(), 4.0);
+        let route = a_star_path_with(&map, (1, 1), (5, 1), &options).unwrap();
+        assert_eq!(route.distance(), 8.0);
+        assert!(!route.steps().contains(&(3, 1)));
+        let route =
+            dijkstra_path_weighted(&map, (1, 1), (5, 1), &options, |_, _| Some(1.0)).unwrap();
+        assert_eq!(route.distance(), 8.0);
+
+        //Only a swamp leads to a goal in the swamp
+        assert_eq!(
+            a_star_path_with(&map, (1, 1), (3, 1), &options)
+                .unwrap()
+                .distance(),
+            2.0
+        );
+
+        //When every route crosses the swamp, the search runs again through it
+        let cut = map_from(&[
+            "@@@@@@@", //
+            "@.SSS.@", //
+            "@.@@@.@", //
+            "@..@..@", //
+            "@@@@@@@", //
+        ]);
+        let mut context = SearchContext::new();
+        let route = context.a_star_path(&cut, (1, 1), (5, 1), &options).unwrap();
+        assert_eq!(route.distance(), 4.0);
+        let mut stepped = context.a_star_steps(&cut, (1, 1), (5, 1), &options);
+        stepped.by_ref().for_each(drop);
+        let distance = stepped
+            .result()
+            .map(|result| result.as_ref().map(|route| route.distance()));
+        assert_eq!(distance, Some(Ok(4.0)));
+    }
+
+    #[test]
+    fn real_time_agents() {
+        //A dead end pointing at the goal, which the agent has to learn to leave
+        let map = map_from(&[
+            "@@@@@@@@@", //
+            "@.......@", //
+            "@.@@@@@.@", //
+            "@.@.@.@.@", //
+            "@.@.@.@.@", //
+            "@...@...@", //
+            "@@@@@@@@@", //
+        ]);
+        let optimal = a_star_path(&map, (3, 3), (5, 3)).unwrap().distance();
+
+        let mut agent = RealTimeAgent::new(&map, (3, 3), (5, 3), 1).unwrap();
+        let mut moves = 0;
+        while let Some(next) = agent.step(&map).unwrap() {
+            assert!(map.is_traversable(next));
+            moves += 1;
+            assert!(moves < 1000);
+        }
+        assert_eq!(agent.position(), (5, 3));
+        assert!(agent.travelled() >= optimal);
+        assert!(agent.learned() > 0);
+        assert!(agent.estimate((3, 4)) > 2.0);
+        assert_eq!(agent.step(&map), Ok(None));
+
+        //Learning carries over, so repeated trips settle on the shortest route
+        agent.restart(&map, (3, 3)).unwrap();
+        assert_eq!(agent.travelled(), 0.0);
+        for _ in 0..10 {
+            agent.restart(&map, (3, 3)).unwrap();
+            while agent.step(&map).unwrap().is_some() {}
+        }
+        assert!((agent.travelled() - optimal).abs() < 1e-9);
+
+        //A lookahead covering the map finds the shortest route
+        let mut planner = RealTimeAgent::new(&map, (3, 3), (5, 3), 100).unwrap();
+        while planner.step(&map).unwrap().is_some() {}
+        assert!((planner.travelled() - optimal).abs() < 1e-9);
+
+        //Unreachable goals are reported rather than wandered towards forever
+        let walled = map_from(&["@@@@@@", "@..@.@", "@@@@@@"]);
+        let mut stuck = RealTimeAgent::new(&walled, (1, 1), (4, 1), 4).unwrap();
+        assert_eq!(stuck.step(&walled), Err(PathError::NoPath));
+        assert_eq!(stuck.position(), (1, 1));
+        assert!(stuck.set_goal(&walled, (2, 1)).is_ok());
+        assert_eq!(stuck.step(&walled), Ok(Some((2, 1))));
+        assert_eq!(
+            RealTimeAgent::new(&walled, (1, 1), (3, 1), 4).err(),
+            Some(PathError::GoalBlocked((3, 1)))
+        );
+    }
+
+    #[test]
+    fn landmark_heuristic() {
+        //A long wall between start and goal, with the only gap at the far end
+        let map = map_from(&[
+            "@@@@@@@@@@@@@@", //
+            "@............@", //
+            "@............@", //
+            "@@@@@@@@@@@@.@", //
+            "@............@", //
+            "@............@", //
+            "@@@@@@@@@@@@@@", //
+        ]);
+        let landmarks = Landmarks::spread(&map, 3);
+        assert_eq!(landmarks.len(), 3);
+        assert_eq!(landmarks.width(), 14);
+        assert!(landmarks.landmarks().contains(&(1, 5)));
+
+        //Never overestimates, and sees the detour the straight line misses
+        let tiles: Vec<_> = map
+            .coords()
+            .filter(|tile| map.is_traversable(*tile))
+            .collect();
+        for from in &tiles {
+            for to in &tiles {
+                let exact = a_star_path(&map, *from, *to).unwrap().distance();
+                assert!(landmarks.heuristic(*from, *to) <= exact + 1e-9);
+            }
+        }
+        assert!(landmarks.heuristic((1, 1), (1, 5)) > 20.0);
+
+        let options = SearchOptions::new();
+        let mut plain = SearchContext::new();
+        let expected = plain.a_star_path(&map, (1, 1), (1, 5), &options).unwrap();
+        let mut guided = SearchContext::new();
+        let path = guided
+            .a_star_path_landmarks(&map, (1, 1), (1, 5), &landmarks, &options)
+            .unwrap();
+        assert_eq!(path.distance(), expected.distance());
+        assert!(guided.stats().expanded < plain.stats().expanded);
+
+        //Landmarks on walls are left out, and none leaves only the straight line
+        let empty = Landmarks::new(&map, &[(0, 0)]);
+        assert!(empty.is_empty());
+        assert_eq!(empty.heuristic((1, 1), (1, 5)), 4.0);
+        assert_eq!(landmarks.distance(0, (0, 0)), None);
+        assert_eq!(
+            a_star_path_landmarks(&map, (1, 1), (1, 5), &empty, &options)
+                .unwrap()
+                .distance(),
+            expected.distance()
+        );
+
+        //Swamp can't be entered from the grass beside it, so the way back to a landmark is far longer
+        let terrain = map_from(&[".GS.", "...."]);
+        let one_way = Landmarks::new(&terrain, &[(0, 0)]);
+        let around = one_way.distance(0, (2, 0)).unwrap();
+        assert!((around - (2.0 + 2f64.sqrt())).abs() < 1e-9);
+        assert_eq!(one_way.heuristic((2, 0), (1, 0)), 1.0);
+        assert_eq!(
+            a_star_path_landmarks(&terrain, (2, 0), (1, 0), &one_way, &options)
+                .unwrap()
+                .distance(),
+            1.0
+        );
+    }
+
+    #[test]
+    fn map_edge_endpoints() {
+        //No border of walls, so searches start and scan right along the edges
+        let map = map_from(&[
+            "......", //
+            ".@@@@.", //
+            ".@....", //
+            "...@..", //
+        ]);
+        let corners = [(0, 0), (5, 0), (0, 3), (5, 3)];
+        for start in corners {
+            for goal in corners {
+                for route in [a_star_path(&map, start, goal), jps_path(&map, start, goal)] {
+                    let route = route.unwrap();
+                    assert!(route.steps().iter().all(|tile| map.is_traversable(*tile)));
+                    assert_eq!(route.steps().first(), Some(&goal));
+                    assert_eq!(route.steps().last(), Some(&start));
+                }
+            }
+        }
+
+        //Forced neighbours are never looked for off the map, but tiles off it count as blocked
+        let mut forced = Vec::new();
+        DefaultPruning.forced_vertical(&map, (0, 2), -1, &mut forced);
+        assert_eq!(forced, []);
+        DefaultPruning.forced_horizontal(&map, (0, 0), 1, &mut forced);
+        assert_eq!(forced, [(0, 1)]);
+        forced.clear();
+        DefaultPruning.forced_horizontal(&map, (4, 2), 1, &mut forced);
+        assert_eq!(forced, [(4, 3), (5, 3)]);
+    }
+
+    #[test]
+    fn jps_corners() {
+        //Every route around the wall has to step past one of its corners
+        let map = map_from(&[
+            "@@@@@@", //
+            "@..@.@", //
+            "@....@", //
+            "@@@@@@", //
+        ]);
+
+        //Neither search cuts the corners at the bottom of the wall
+        for route in [
+            a_star_path(&map, (1, 1), (4, 1)),
+            jps_path(&map, (1, 1), (4, 1)),
+        ] {
+            let route = route.unwrap();
+            assert!((route.distance() - (3.0 + 2f64.sqrt())).abs() < 1e-9);
+            for pair in route.steps().windows(2) {
+                assert!(map.is_traversable_from(pair[1], pair[0]));
+            }
+        }
+    }
+
+    #[test]
+    fn trivial_routes() {
+        let map = map_from(&[
+            "....", //
+            ".@..", //
+            "....", //
+        ]);
+        let options = SearchOptions::new();
+        let landmarks = Landmarks::spread(&map, 2);
+        let same = |route: Route, expected: &Route| {
+            assert_eq!(route.steps(), expected.steps());
+            assert_eq!(route.distance(), expected.distance());
+        };
+
+        //On the start, next to it straight and diagonally, including from the edge of the map
+        for (start, goal) in [
+            ((0, 0), (0, 0)),
+            ((2, 1), (2, 1)),
+            ((0, 0), (1, 0)),
+            ((2, 1), (3, 2)),
+        ] {
+            let expected = if start == goal {
+                Route::empty(start)
+            } else {
+                Route::single_step(start, goal)
+            };
+            same(a_star_path(&map, start, goal).unwrap(), &expected);
+            same(jps_path(&map, start, goal).unwrap(), &expected);
+            same(dijkstra_path(&map, start, goal).unwrap(), &expected);
+            same(
+                a_star_path_turning(&map, start, goal, 1.0, &options).unwrap(),
+                &expected,
+            );
+            same(
+                a_star_path_landmarks(&map, start, goal, &landmarks, &options).unwrap(),
+                &expected,
+            );
+        }
+
+        //Options leaving out the start or repeating the goal still apply
+        let excluded = SearchOptions::new().include_start(false);
+        assert_eq!(
+            jps_path_with(&map, (0, 0), (0, 0), &excluded)
+                .unwrap()
+                .steps(),
+            []
+        );
+        assert_eq!(
+            a_star_path_with(&map, (0, 0), (1, 0), &excluded)
+                .unwrap()
+                .steps(),
+            [(1, 0)]
+        );
+        let repeated = SearchOptions::new().repeat_goal(true);
+        assert_eq!(
+            a_star_path_with(&map, (0, 0), (0, 0), &repeated)
+                .unwrap()
+                .steps(),
+            [(0, 0), (0, 0)]
+        );
+        assert_eq!(Route::empty((3, 2)).bounding_box(), Some(((3, 2), (3, 2))));
+    }
+
+    #[test]
+    fn weighted_regions() {
+        //Plains around two forest patches and a swamp, with a wall jutting into the plains
+        let map = map_from(&[
+            "@@@@@@@@@@@@@@", //
+            "@....GGGG....@", //
+            "@....GGGG..@.@", //
+            "@.SS.GGGG..@.@", //
+            "@.SS.......@.@", //
+            "@.SSSS.GG..@.@", //
+            "@......GG....@", //
+            "@@@@@@@@@@@@@@", //
+        ]);
+        let terrain = |tile: (usize, usize)| match map.get(tile) {
+            'G' => Some(2.0),
+            'S' => Some(4.0),
+            _ => Some(1.0),
+        };
+        let exact = WeightedRegions::new(&map, terrain, 1);
+        assert_eq!(exact.regions(), 4);
+        assert_eq!(exact.weight((6, 2)), Some(2.0));
+        assert_eq!(exact.region((0, 0)), None);
+        assert_ne!(exact.region((7, 1)), exact.region((7, 5)));
+
+        //Matches a search over every tile, or comes close with fewer portals, and each route pays for its own steps
+        let options = SearchOptions::new();
+        let tiles: Vec<_> = map
+            .coords()
+            .filter(|tile| map.is_traversable(*tile))
+            .collect();
+        let sparse = WeightedRegions::new(&map, terrain, 3);
+        assert!(sparse.portals().len() < exact.portals().len());
+        let (mut best_total, mut sparse_total) = (0.0, 0.0);
+        for start in tiles.iter().step_by(5) {
+            for goal in tiles.iter().step_by(3) {
+                let best = dijkstra_path_weighted(&map, *start, *goal, &options, exact.step_cost())
+                    .unwrap()
+                    .distance();
+                for regions in [&exact, &sparse] {
+                    let route =
+                        weighted_region_path(&map, *start, *goal, regions, &options).unwrap();
+                    assert!(route.distance() >= best - 1e-9);
+                    if regions == &exact {
+                        assert!(route.distance() <= best + 1e-9);
+                    }
+                    let steps = route.steps();
+                    assert_eq!((steps[0], steps[steps.len() - 1]), (*goal, *start));
+                    let cost = exact.step_cost();
+                    let paid: f64 = steps
+                        .windows(2)
+                        .map(|pair| cost(pair[1], pair[0]).unwrap())
+                        .sum();
+                    assert!((paid - route.distance()).abs() < 1e-9);
+                }
+                best_total += best;
+                sparse_total += weighted_region_path(&map, *start, *goal, &sparse, &options)
+                    .unwrap()
+                    .distance();
+            }
+        }
+        assert!(sparse_total < best_total * 1.2);
+
+        //Tiles without a weight cannot be entered
+        let no_swamp = WeightedRegions::new(&map, |tile| terrain(tile).filter(|w| *w < 4.0), 1);
+        assert_eq!(
+            weighted_region_path(&map, (2, 3), (1, 1), &no_swamp, &options).err(),
+            Some(PathError::StartBlocked((2, 3)))
+        );
+        let around = weighted_region_path(&map, (1, 6), (1, 1), &no_swamp, &options).unwrap();
+        assert!(around
+            .steps()
+            .iter()
+            .all(|tile| no_swamp.weight(*tile).is_some()));
+    }
+
+    #[test]
+    fn search_primitives() {
+        let map = map_from(&[
+            "....", //
+            ".@..", //
+            "....", //
+        ]);
+
+        //Nodes pop cheapest first, breaking ties towards the goal
+        let mut open = BinaryHeap::new();
+        open.push(Node::new(2.0, 2.0, (0, 0), (0, 0)));
+        open.push(Node::new(3.0, 1.0, (1, 0), (0, 0)));
+        open.push(Node::new(1.0, 2.0, (2, 0), (0, 0)));
+        let order: Vec<_> = core::iter::from_fn(|| open.pop())
+            .map(|node| node.position)
+            .collect();
+        assert_eq!(order, [(2, 0), (1, 0), (0, 0)]);
+        let start = Node::new(0.0, 3.0, (0, 0), (0, 0));
+        let next = Node::from_parent(&start, (1, 0), (3, 0));
+        assert_eq!(
+            (next.g, next.h, next.f, next.parent),
+            (1.0, 2.0, 3.0, (0, 0))
+        );
+        assert_eq!(next.info().position, (1, 0));
+        //Equality agrees with the order, so nodes at one tile with different costs differ
+        assert_ne!(start, Node::new(1.0, 3.0, (0, 0), (0, 0)));
+        assert_eq!(start, Node::new(0.0, 3.0, (0, 0), (1, 1)));
+
+        //Tile helpers stay on the map and follow its corner rules
+        assert_eq!(offset((0, 0), -1, 0), None);
+        assert_eq!(offset((0, 0), 1, 1), Some((1, 1)));
+        assert_eq!(index(&map, (2, 1)), 6);
+        assert_eq!(direction((0, 2), (3, 0)), (-1, 1));
+        assert_eq!(distance((0, 0), (3, 4)), 5.0);
+        let mut around: Vec<_> = neighbours(&map, (0, 0)).collect();
+        around.sort();
+        assert_eq!(around, [(0, 1), (1, 0)]);
+
+        //Endpoints are checked and snapped as the options ask
+        assert_eq!(
+            validate(&map, (1, 1), (0, 0)),
+            Err(PathError::StartBlocked((1, 1)))
+        );
+        let snapping = SearchOptions::new().snap_radius(1);
+        assert_eq!(
+            prepare(&map, (1, 1), (3, 2), &snapping),
+            Ok(((1, 0), (3, 2)))
+        );
+
+        //Jumps are filled in when rewinding, and the options applied to the result
+        let closed = [
+            Node::new(0.0, 0.0, (0, 0), (0, 0)),
+            Node::new(2.0, 0.0, (2, 0), (0, 0)),
+        ];
+        let goal = Node::new(3.0, 0.0, (3, 0), (2, 0));
+        let find = |position| {
+            closed
+                .iter()
+                .find(|node| node.position == position)
+                .copied()
+        };
+        let path = rewind(&goal, find).unwrap();
+        assert_eq!(path, [(3, 0), (2, 0), (1, 0), (0, 0)]);
+        //A parent that cannot be found stops the walk
+        assert_eq!(rewind(&goal, |_| None), None);
+        let excluded = SearchOptions::new().include_start(false);
+        assert_eq!(compose(path, &excluded), [(3, 0), (2, 0), (1, 0)]);
+    }
+
+    #[test]
+    fn memory_footprints() {
+        let map = map_from(&[
+            "........", //
+            "..@@@@..", //
+            "........", //
+        ]);
+
+        //Per-tile data grows with the map
+        let clearance = ClearanceMap::new(&map);
+        assert!(clearance.heap_size() >= 24 * 8);
+        assert_eq!(
+            clearance.memory_footprint(),
+            std::mem::size_of::<ClearanceMap>() + clearance.heap_size()
+        );
+        assert!(map.heap_size() >= 24);
+        assert!(Landmarks::spread(&map, 2).heap_size() > Landmarks::spread(&map, 1).heap_size());
+
+        //Routes count their steps, and contexts keep their lists between searches
+        let route = a_star_path(&map, (0, 0), (7, 2)).unwrap();
+        assert!(route.heap_size() >= route.steps().len() * 16);
+        let mut context = SearchContext::new();
+        assert_eq!(context.heap_size(), 0);
+        context
+            .a_star_path(&map, (0, 0), (7, 2), &SearchOptions::new())
+            .unwrap();
+        let searched = context.heap_size();
+        assert!(searched > 0);
+        context
+            .a_star_path(&map, (0, 0), (1, 0), &SearchOptions::new())
+            .unwrap();
+        assert_eq!(context.heap_size(), searched);
+
+        //Nested data is included
+        let agents = [((0, 0), (7, 0)), ((0, 2), (7, 2))];
+        let plan = prioritized_planning(&map, &agents, &[], 0, &SearchOptions::new()).unwrap();
+        let routes: usize = plan.routes.iter().map(|route| route.heap_size()).sum();
+        assert!(plan.heap_size() > routes);
+    }
+
+    #[test]
+    fn capacity_presizing() {
+        let map = map_from(&[
+            "............", //
+            "............", //
+            "..@@@@@@@@..", //
+            "............", //
+            "............", //
+        ]);
+
+        //Contexts can be sized up front, for a hint or for the whole map
+        assert!(SearchContext::with_capacity(100).capacity() >= 100);
+        let mut context = SearchContext::for_map(&map);
+        assert!(context.capacity() >= 52);
+        let reserved = context.capacity();
+        context
+            .a_star_path(&map, (0, 0), (11, 4), &SearchOptions::new())
+            .unwrap();
+        assert_eq!(context.capacity(), reserved);
+
+        //Searches reserve from the map size, or from the options' hint
+        let mut context = SearchContext::new();
+        context
+            .jps_path(&map, (0, 0), (11, 4), &SearchOptions::new())
+            .unwrap();
+        assert!(context.capacity() >= 2 * (12 + 5));
+        let hinted = SearchOptions::new().capacity_hint(500);
+        let route = context.a_star_path(&map, (0, 0), (11, 4), &hinted).unwrap();
+        assert!(context.capacity() >= 500);
+        assert_eq!(
+            route.distance(),
+            a_star_path(&map, (0, 0), (11, 4)).unwrap().distance()
+        );
+    }
+
+    #[test]
+    fn fog_of_war() {
+        let world = map_from(&[
+            ".........", //
+            ".@@@@@@@.", //
+            ".@.....@.", //
+            "...@@@.@.", //
+            ".@.....@.", //
+        ]);
+        let mut fog = FogOfWar::new(9, 5);
+        assert_eq!(fog.known_count(), 0);
+        assert!(fog.map().is_traversable((3, 1)));
+
+        //Sight stops at walls, which are seen themselves
+        let diff = fog.sense(&world, (0, 2), 2);
+        assert!(fog.is_known((1, 2)) && fog.is_known((0, 4)));
+        assert!(!fog.is_known((2, 2)));
+        assert!(diff.contains((1, 2)) && !diff.contains((0, 2)));
+        assert!(fog.sense(&world, (0, 2), 2).is_empty());
+
+        //Routes are planned optimistically through what has not been seen, and kept clear as it is
+        let options = SearchOptions::new();
+        let mut path =
+            ActivePath::new(fog.map(), (0, 2), (4, 2), Algorithm::AStar, &options).unwrap();
+        let optimistic = path.route().distance();
+        let (mut position, mut travelled, mut updates) = ((0, 2), 0.0, Vec::new());
+        while position != (4, 2) {
+            let next = path.route().steps()[path.route().steps().len() - 2];
+            assert!(world.is_traversable_from(position, next));
+            travelled += distance(position, next);
+            position = next;
+            fog.sense(&world, position, 2);
+            updates.push(path.update(fog.map(), position).unwrap());
+        }
+        assert!(travelled > optimistic);
+        assert!(
+            updates.contains(&PathUpdate::Replanned) || updates.contains(&PathUpdate::Repaired)
+        );
+
+        //Revealing tiles directly works too, ignoring those outside the map
+        let diff = fog.reveal(&[((8, 0), '@'), ((20, 0), '@')]);
+        assert_eq!(diff.len(), 1);
+        assert!(!fog.map().is_traversable((8, 0)));
+    }
+
+    #[test]
+    fn desire_maps() {
+        let map = map_from(&[
+            "........", //
+            ".@@@@@@.", //
+            "........", //
+        ]);
+        let goal = DesireMap::new(&map, &[((0, 0), 0.0), ((7, 2), 1.0)]);
+        assert_eq!(goal.get((0, 0)), 0.0);
+        assert_eq!(goal.get((7, 2)), 1.0);
+        assert_eq!(goal.get((3, 0)), 3.0);
+        assert_eq!(goal.get((3, 1)), f64::INFINITY);
+        assert_eq!(goal.get((9, 9)), f64::INFINITY);
+
+        //Downhill leads to the cheapest source
+        let route = goal.descent_path(&map, (6, 2), 20);
+        assert_eq!(route.steps(), [(7, 2), (6, 2)]);
+        assert_eq!(route.distance(), 1.0);
+        assert_eq!(goal.descend(&map, (0, 0)), None);
+        assert_eq!(goal.descent_path(&map, (4, 0), 2).steps().len(), 3);
+
+        //Scaling and adding keep blocked tiles blocked
+        let doubled = goal.scale(2.0);
+        assert_eq!(doubled.get((3, 0)), 6.0);
+        let sum = goal.add(&doubled);
+        assert_eq!(sum.get((3, 0)), 9.0);
+        assert_eq!(sum.get((3, 1)), f64::INFINITY);
+        let combined = DesireMap::combine(&[(&goal, 1.0), (&goal, 2.0)]).unwrap();
+        assert_eq!(combined, sum);
+        assert!(DesireMap::combine(&[]).is_none());
+
+        //Rescanning limits how steeply values fall
+        let steep = DesireMap::new(&map, &[((0, 0), 0.0)])
+            .scale(-5.0)
+            .rescan(&map);
+        for pair in [((1, 0), (2, 0)), ((0, 2), (1, 2))] {
+            assert!((steep.get(pair.0) - steep.get(pair.1)).abs() <= 1.0 + 1e-9);
+        }
+
+        //Fleeing leads away from the sources
+        let threat = DesireMap::new(&map, &[((0, 0), 0.0)]);
+        let flee = threat.flee(&map, 1.2);
+        let escape = flee.descent_path(&map, (1, 0), 20);
+        let end = escape.steps()[0];
+        assert!(threat.get(end) > threat.get((1, 0)) + 5.0);
+    }
+
+    #[test]
+    fn coverage_paths() {
+        let map = map_from(&[
+            "......@...", //
+            ".@@...@.@.", //
+            "......@.@.", //
+            "@@.@......", //
+            "......@@..", //
+        ]);
+        let options = SearchOptions::new();
+
+        //Every reachable tile is covered by moves between neighbours, starting at the start
+        let route = coverage_path(&map, (0, 0), |_| true, &options).unwrap();
+        let steps = route.steps();
+        assert_eq!(steps.last(), Some(&(0, 0)));
+        for tile in map.coords().filter(|tile| map.is_traversable(*tile)) {
+            assert!(steps.contains(&tile), "{:?} was not covered", tile);
+        }
+        let mut walked = 0.0;
+        for pair in steps.windows(2) {
+            assert!(map.is_traversable_from(pair[1], pair[0]));
+            walked += distance(pair[1], pair[0]);
+        }
+        assert_eq!(route.distance(), walked);
+
+        //An open room is swept without retracing
+        let room = |(x, y): (usize, usize)| (3..=5).contains(&x) && y <= 2;
+        let route = coverage_path(&map, (3, 0), room, &options).unwrap();
+        assert_eq!(route.steps().len(), 9);
+        assert_eq!(route.distance(), 8.0);
+        assert!(route.steps().iter().all(|tile| room(*tile)));
+
+        //A region of only the start is covered by standing still
+        let route = coverage_path(&map, (9, 0), |_| false, &options).unwrap();
+        assert_eq!(route.steps(), [(9, 0)]);
+        assert_eq!(
+            coverage_path(&map, (1, 1), |_| true, &options).err(),
+            Some(PathError::StartBlocked((1, 1)))
+        );
+    }
+
+    #[test]
+    fn evacuation() {
+        let map = map_from(&[
+            "..........", //
+            "..........", //
+            "@@@@@@@@.@", //
+            "......@...", //
+        ]);
+        let agents = [
+            (1, 0),
+            (2, 0),
+            (3, 0),
+            (4, 0),
+            (7, 3),
+            (9, 9),
+            (6, 3),
+            (0, 3),
+        ];
+        let exits = [(0, 0), (9, 0), (7, 7)];
+        let options = SearchOptions::new();
+
+        //Each agent takes its nearest exit by the shortest route
+        let nearest = evacuation_paths(&map, &agents, &exits, 0.0, &options).unwrap();
+        assert_eq!(nearest.len(), agents.len());
+        let (exit, route) = nearest[3].as_ref().unwrap();
+        assert_eq!(*exit, 0);
+        assert_eq!(route.steps().first(), Some(&(0, 0)));
+        assert_eq!(route.steps().last(), Some(&(4, 0)));
+        assert_eq!(route.distance(), 4.0);
+        let (exit, route) = nearest[4].as_ref().unwrap();
+        assert_eq!(*exit, 1);
+        assert_eq!(
+            route.distance(),
+            a_star_path(&map, (7, 3), (9, 0)).unwrap().distance()
+        );
+        //Out of bounds, blocked and cut off tiles get no exit
+        assert!(nearest[5..].iter().all(|found| found.is_none()));
+
+        //Balancing sends some agents further to spread the load
+        let balanced = evacuation_paths(&map, &agents, &exits, 4.0, &options).unwrap();
+        let loads: Vec<usize> = (0..exits.len())
+            .map(|exit| {
+                balanced
+                    .iter()
+                    .flatten()
+                    .filter(|(e, _)| *e == exit)
+                    .count()
+            })
+            .collect();
+        assert_eq!(loads, [2, 3, 0]);
+        assert!(balanced[5..].iter().all(|found| found.is_none()));
+
+        //Agents further than the maximum distance are left without an exit
+        let near = SearchOptions::new().max_distance(3.0);
+        let limited = evacuation_paths(&map, &agents, &exits, 0.0, &near).unwrap();
+        assert!(limited[3].is_none());
+        assert_eq!(limited[2].as_ref().map(|(exit, _)| *exit), Some(0));
+    }
+
+    #[test]
+    fn route_targets() {
+        let route = Route::from((6.0, vec![(3, 3), (3, 0), (0, 0)]));
+
+        //The target lies ahead of the nearest point, turning the corner onto the next leg
+        assert_eq!(route.target_at((0.5, 0.5), 1.0), Some((1.5, 0.5)));
+        assert_eq!(route.target_at((2.5, 2.0), 1.0), Some((3.5, 3.0)));
+        assert_eq!(route.target_at((3.0, 0.5), 1.0), Some((3.5, 1.0)));
+        //Agents off the route are matched to its nearest point
+        assert_eq!(route.target_at((5.0, 2.5), 0.5), Some((3.5, 3.0)));
+        assert_eq!(route.target_at((-4.0, -4.0), 0.0), Some((0.5, 0.5)));
+        //The target stops at the goal
+        assert_eq!(route.target_at((3.5, 3.5), 2.0), Some((3.5, 3.5)));
+
+        //Single steps and empty routes
+        assert_eq!(
+            Route::empty((2, 2)).target_at((0.0, 0.0), 1.0),
+            Some((2.5, 2.5))
+        );
+        assert_eq!(Route::from((0.0, vec![])).target_at((0.0, 0.0), 1.0), None);
+    }
+
+    #[test]
+    fn route_payloads() {
+        let map = map_from(&["......", "......", "......", "......"]);
+        let mut layer: PayloadLayer<&str> = [((2, 0), "key"), ((5, 3), "exit")]
+            .iter()
+            .copied()
+            .collect();
+        assert_eq!(layer.insert((0, 3), "trap"), None);
+        assert_eq!(layer.insert((2, 0), "gold"), Some("key"));
+        assert_eq!(layer.len(), 3);
+        assert_eq!(layer.get((2, 0)), Some(&"gold"));
+        assert_eq!(layer.get((1, 1)), None);
+        let tiles: Vec<(usize, usize)> = layer.iter().map(|(tile, _)| tile).collect();
+        assert_eq!(tiles, [(0, 3), (2, 0), (5, 3)]);
+
+        //Payloads come in the order they are reached, including between distant steps
+        let route = Route::from((8.0, vec![(5, 3), (5, 0), (0, 0)]));
+        assert_eq!(
+            route.collect_payloads(&layer),
+            [((2, 0), &"gold"), ((5, 3), &"exit")]
+        );
+        let searched = a_star_path(&map, (0, 3), (5, 3)).unwrap();
+        assert_eq!(
+            searched.collect_payloads(&layer),
+            [((0, 3), &"trap"), ((5, 3), &"exit")]
+        );
+
+        //Tiles crossed twice are only listed once
+        let there_and_back = Route::from((4.0, vec![(0, 0), (2, 0), (0, 0)]));
+        assert_eq!(there_and_back.collect_payloads(&layer), [((2, 0), &"gold")]);
+
+        *layer.get_mut((2, 0)).unwrap() = "spent";
+        assert_eq!(layer.remove((5, 3)), Some("exit"));
+        assert_eq!(route.collect_payloads(&layer), [((2, 0), &"spent")]);
+        assert!(layer.memory_footprint() > 0);
+        layer.clear();
+        assert!(layer.is_empty());
+        assert!(route.collect_payloads(&layer).is_empty());
+    }
+
+    #[test]
+    fn route_shape() {
+        let straight = Route::from((4.0, vec![(4, 0), (0, 0)]));
+        assert_eq!(straight.turn_count(), 0);
+        assert_eq!(straight.direction_changes(), 0);
+        assert_eq!(straight.straightness(), 1.0);
+
+        //Jump points and listing every tile give the same shape
+        let corner = Route::from((6.0, vec![(3, 3), (3, 0), (0, 0)]));
+        let cells = Route::from((
+            6.0,
+            vec![(3, 3), (3, 2), (3, 1), (3, 0), (2, 0), (1, 0), (0, 0)],
+        ));
+        assert_eq!(corner.turn_count(), 2);
+        assert_eq!(cells.turn_count(), 2);
+        assert_eq!(corner.direction_changes(), 1);
+        assert_eq!(cells.direction_changes(), 1);
+        assert_eq!(corner.straightness(), cells.straightness());
+
+        //Gentle bends turn less than sharp ones, and a staircase turns at every step
+        let bend = Route::from((2.0 + 2f64.sqrt(), vec![(3, 1), (2, 0), (0, 0)]));
+        assert_eq!(bend.turn_count(), 1);
+        let back = Route::from((4.0, vec![(0, 0), (2, 0), (0, 0)]));
+        assert_eq!(back.turn_count(), 4);
+        assert_eq!(back.direction_changes(), 1);
+        assert_eq!(back.straightness(), 0.0);
+        let stairs = Route::from((4.0, vec![(2, 2), (2, 1), (1, 1), (1, 0), (0, 0)]));
+        assert_eq!(stairs.direction_changes(), 3);
+        assert_eq!(stairs.turn_count(), 6);
+        assert!((0.7..0.71).contains(&stairs.straightness()));
+
+        //Searched routes on open ground are straight
+        let map = map_from(&["........", "........", "........"]);
+        let searched = a_star_path(&map, (0, 1), (7, 1)).unwrap();
+        assert_eq!(searched.direction_changes(), 0);
+        assert_eq!(searched.straightness(), 1.0);
+
+        //Routes that go nowhere count as straight
+        assert_eq!(Route::empty((1, 1)).straightness(), 1.0);
+        assert_eq!(Route::empty((1, 1)).turn_count(), 0);
+        assert_eq!(Route::from((0.0, vec![])).straightness(), 1.0);
+    }
+
+    #[test]
+    fn space_time_default_horizon() {
+        let map = map_from(&["....", "....", "...."]);
+        let options = SearchOptions::new();
+
+        //Waiting out a reservation longer than the map is wide and high still succeeds
+        let mut table = ReservationTable::new();
+        for time in 0..100 {
+            table.reserve(9, (3, 2), time);
+        }
+        let route = space_time_a_star_reserved(&map, (0, 0), (3, 2), 0, &table, &options).unwrap();
+        assert_eq!(route.end_time(), 100);
+
+        //A goal held for good fails at once rather than searching every timestep
+        table.reserve_route(9, &TimedRoute::from((0, vec![(3, 2)])));
+        assert_eq!(
+            space_time_a_star_reserved(&map, (0, 0), (3, 2), 0, &table, &options),
+            Err(PathError::NoPath)
+        );
+        //Walls cutting the goal off give up after a horizon linear in the map size
+        let walled = map_from(&["..@.", "..@.", "..@."]);
+        assert_eq!(
+            time_expanded_search(&walled, (0, 0), (3, 2), 0, |_, _| false, &options),
+            Err(PathError::NoPath)
+        );
+
+        //A goal snapped off a wall is held on the tile it snaps to, so the route waits for another agent to pass
+        let cornered = map_from(&["..@@", "....", "...."]);
+        let mut table = ReservationTable::new();
+        table.reserve(9, (3, 1), 10);
+        let snapping = SearchOptions::new().snap_radius(1);
+        let route =
+            space_time_a_star_reserved(&cornered, (0, 1), (3, 0), 0, &table, &snapping).unwrap();
+        assert_eq!(route.positions().last(), Some(&(3, 1)));
+        assert!(route.end_time() > 10);
+    }
+}
@@ -0,0 +1,27 @@
+#[cfg(all(test, feature = "rerun", feature = "movingai"))]
+mod tests {
+    use std::path::Path;
+
+    use movingai::parser::parse_map_file;
+
+    use blitz_path::{
+        jps_path_observed, log_map_to_rerun, log_route_to_rerun, RerunObserver, SearchOptions,
+    };
+
+    #[test]
+    fn logs_search_to_memory() {
+        let map = parse_map_file(Path::new("./tests/map/maze512-32-9.map")).unwrap();
+        let (stream, storage) = rerun::RecordingStreamBuilder::new("blitz-path-test")
+            .memory()
+            .unwrap();
+
+        log_map_to_rerun(&stream, "map", &map).unwrap();
+        let mut observer = RerunObserver::new(&stream, "map/search");
+        let route =
+            jps_path_observed(&map, (1, 1), (5, 5), &SearchOptions::new(), &mut observer).unwrap();
+        log_route_to_rerun(&stream, "map/route", &route).unwrap();
+        stream.flush_blocking().unwrap();
+
+        assert!(!storage.take().is_empty());
+    }
+}
@@ -0,0 +1,129 @@
+#[cfg(all(test, feature = "serde", feature = "movingai"))]
+mod tests {
+    use std::path::Path;
+
+    use movingai::parser::parse_map_file;
+    use movingai::MovingAiMap;
+
+    use serde::{Deserialize, Deserializer, Serialize};
+
+    use blitz_path::{
+        components, jps_path, ClearanceMap, Components, Route, Stable, TimedRoute, Versioned,
+        FORMAT_VERSION,
+    };
+
+    const MAP: &str = "./tests/map/maze512-32-9.map";
+
+    #[test]
+    fn round_trip_preprocessed_world() {
+        let map = parse_map_file(Path::new(MAP)).unwrap();
+        let regions = components(&map);
+        let clearance = ClearanceMap::new(&map);
+
+        //Save everything, then search using only what was loaded back
+        let saved = serde_json::to_string(&(&map, &regions, &clearance)).unwrap();
+        let (loaded_map, loaded_regions, loaded_clearance): (
+            MovingAiMap,
+            Components,
+            ClearanceMap,
+        ) = serde_json::from_str(&saved).unwrap();
+
+        assert_eq!(loaded_regions, regions);
+        assert_eq!(loaded_clearance, clearance);
+        assert!(loaded_regions.same_component((1, 1), (5, 5)));
+
+        let route = jps_path(&loaded_map, (1, 1), (5, 5)).unwrap();
+        let expected = jps_path(&map, (1, 1), (5, 5)).unwrap();
+        assert_eq!(route.steps(), expected.steps());
+
+        let saved = serde_json::to_string(&route).unwrap();
+        let loaded: Route = serde_json::from_str(&saved).unwrap();
+        assert_eq!(loaded.steps(), route.steps());
+        assert_eq!(loaded.distance(), route.distance());
+    }
+
+    #[test]
+    fn versioned_envelope() {
+        let route = Route::from((2.0, vec![(2, 0), (1, 0), (0, 0)]));
+        let saved = serde_json::to_string(&Versioned::new(route)).unwrap();
+        assert!(saved.starts_with(r#"{"kind":"Route","version":1,"data":"#));
+
+        let loaded: Versioned<Route> = serde_json::from_str(&saved).unwrap();
+        assert_eq!(loaded.version(), FORMAT_VERSION);
+        let route = loaded.into_inner();
+        assert_eq!(route.steps(), [(2, 0), (1, 0), (0, 0)]);
+
+        //Kind and version may also be read after unknown fields, but never after the data
+        let data = serde_json::to_string(&route).unwrap();
+        let extra = format!(r#"{{"note":1,"version":1,"kind":"Route","data":{}}}"#, data);
+        assert!(serde_json::from_str::<Versioned<Route>>(&extra).is_ok());
+        let late = format!(r#"{{"data":{},"kind":"Route","version":1}}"#, data);
+        assert!(serde_json::from_str::<Versioned<Route>>(&late).is_err());
+
+        //Newer saves and other kinds of data are refused rather than misread
+        let newer = saved.replace(r#""version":1"#, r#""version":2"#);
+        let error = serde_json::from_str::<Versioned<Route>>(&newer)
+            .err()
+            .unwrap();
+        assert!(error.to_string().contains("newer"));
+        let error = serde_json::from_str::<Versioned<TimedRoute>>(&saved).unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("expected TimedRoute data, found Route"));
+    }
+
+    #[derive(Serialize, Deserialize)]
+    struct SaveFile {
+        #[serde(with = "blitz_path::versioned")]
+        route: Route,
+        turn: u32,
+    }
+
+    #[test]
+    fn versioned_fields() {
+        let save = SaveFile {
+            route: Route::single_step((0, 0), (1, 1)),
+            turn: 3,
+        };
+        let saved = serde_json::to_string(&save).unwrap();
+        assert!(saved.contains(r#""route":{"kind":"Route","version":1,"#));
+        let loaded: SaveFile = serde_json::from_str(&saved).unwrap();
+        assert_eq!(loaded.route.steps(), save.route.steps());
+        assert_eq!(loaded.turn, 3);
+    }
+
+    //A type whose layout changed in format version 1, keeping saves of version 0 loadable
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Waypoints {
+        tiles: Vec<(usize, usize)>,
+    }
+
+    impl Stable for Waypoints {
+        const KIND: &'static str = "Waypoints";
+
+        fn migrate<'de, D: Deserializer<'de>>(version: u32, data: D) -> Result<Self, D::Error> {
+            match version {
+                //Version 0 saved the tiles as a bare list
+                0 => Vec::deserialize(data).map(|tiles| Waypoints { tiles }),
+                _ => Waypoints::deserialize(data),
+            }
+        }
+    }
+
+    #[test]
+    fn versioned_migration() {
+        let old = r#"{"kind":"Waypoints","version":0,"data":[[1,2],[3,4]]}"#;
+        let loaded: Versioned<Waypoints> = serde_json::from_str(old).unwrap();
+        assert_eq!(loaded.version(), 0);
+        assert_eq!(loaded.get().tiles, [(1, 2), (3, 4)]);
+
+        //Loaded data is saved again in the current version
+        let saved = serde_json::to_string(&loaded).unwrap();
+        assert_eq!(
+            saved,
+            r#"{"kind":"Waypoints","version":1,"data":{"tiles":[[1,2],[3,4]]}}"#
+        );
+        let reloaded: Versioned<Waypoints> = serde_json::from_str(&saved).unwrap();
+        assert_eq!(reloaded.into_inner(), loaded.into_inner());
+    }
+}
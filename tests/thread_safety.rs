@@ -0,0 +1,142 @@
+#[cfg(all(test, feature = "movingai"))]
+mod tests {
+    use std::path::Path;
+    use std::sync::Arc;
+    use std::thread;
+
+    use movingai::parser::parse_map_file;
+    use movingai::parser::parse_scen_file;
+    use movingai::MovingAiMap;
+
+    use blitz_path::primitives::Node;
+    use blitz_path::{
+        ActivePath, Algorithm, Anisotropy, BenchmarkReport, CancelToken, CellChange, ClearanceMap,
+        Components, CongestionMap, CostStack, CroppedMap, Crowd, CrowdAgent, CurveKind, Decay,
+        DefaultPruning, DesireMap, Direction, DirectionalCosts, Discrepancy, FlowField, FogOfWar,
+        Formation, HeatmapValue, Heightmap, InfluenceMap, InfluenceSource, Landmarks, MapDiff,
+        MapDiffError, MapLoadError, MapReport, MapTransform, NodeInfo, OccupancyGrid, OffsetMap,
+        ParetoFrontier, PathError, PathUpdate, PayloadLayer, Pose2D, PrioritizedPlan,
+        RealTimeAgent, ReservationTable, Route, RouteConflict, RouteCurve, RouteDecodeError,
+        RouteSteps, ScenarioResult, SearchContext, SearchEvent, SearchOptions, SearchRecording,
+        SearchSnapshot, SearchStats, SearchSteps, StreamEvent, SuiteReport, TimedRoute,
+        VisibilityMap, VoronoiRegions, Watcher, WeightedRegions,
+    };
+
+    const MAP: &str = "./tests/map/maze512-32-9.map";
+    const SCEN: &str = "./tests/map/maze512-32-9.map.scen";
+
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    //Every public type must stay shareable between threads.
+    //Add new public types here as they are introduced.
+    #[test]
+    fn public_types_are_send_sync() {
+        assert_send_sync::<MovingAiMap>();
+        assert_send_sync::<ActivePath>();
+        assert_send_sync::<Algorithm>();
+        assert_send_sync::<Anisotropy>();
+        assert_send_sync::<BenchmarkReport>();
+        assert_send_sync::<CancelToken>();
+        assert_send_sync::<CellChange>();
+        assert_send_sync::<ClearanceMap>();
+        assert_send_sync::<Components>();
+        assert_send_sync::<CongestionMap>();
+        assert_send_sync::<CostStack<'static>>();
+        assert_send_sync::<CroppedMap>();
+        assert_send_sync::<Crowd>();
+        assert_send_sync::<CrowdAgent>();
+        assert_send_sync::<CurveKind>();
+        assert_send_sync::<Decay>();
+        assert_send_sync::<DefaultPruning>();
+        assert_send_sync::<DesireMap>();
+        assert_send_sync::<Direction>();
+        assert_send_sync::<DirectionalCosts>();
+        assert_send_sync::<Discrepancy>();
+        assert_send_sync::<FlowField>();
+        assert_send_sync::<FogOfWar>();
+        assert_send_sync::<Formation>();
+        assert_send_sync::<HeatmapValue>();
+        assert_send_sync::<Heightmap>();
+        assert_send_sync::<InfluenceMap>();
+        assert_send_sync::<InfluenceSource>();
+        assert_send_sync::<Landmarks>();
+        assert_send_sync::<MapDiff>();
+        assert_send_sync::<MapDiffError>();
+        assert_send_sync::<MapLoadError>();
+        assert_send_sync::<MapReport>();
+        assert_send_sync::<MapTransform>();
+        assert_send_sync::<Node>();
+        assert_send_sync::<NodeInfo>();
+        assert_send_sync::<OccupancyGrid>();
+        assert_send_sync::<OffsetMap<'static>>();
+        assert_send_sync::<ParetoFrontier>();
+        assert_send_sync::<PathError>();
+        assert_send_sync::<PathUpdate>();
+        assert_send_sync::<PayloadLayer<u32>>();
+        assert_send_sync::<Pose2D>();
+        assert_send_sync::<PrioritizedPlan>();
+        assert_send_sync::<RealTimeAgent>();
+        assert_send_sync::<ReservationTable>();
+        assert_send_sync::<Route>();
+        assert_send_sync::<RouteConflict>();
+        assert_send_sync::<RouteCurve>();
+        assert_send_sync::<RouteDecodeError>();
+        assert_send_sync::<RouteSteps>();
+        assert_send_sync::<ScenarioResult>();
+        assert_send_sync::<SearchContext>();
+        assert_send_sync::<SearchEvent>();
+        assert_send_sync::<SearchOptions>();
+        assert_send_sync::<SearchRecording>();
+        assert_send_sync::<SearchSnapshot>();
+        assert_send_sync::<SearchStats>();
+        assert_send_sync::<SearchSteps<'static>>();
+        assert_send_sync::<StreamEvent>();
+        assert_send_sync::<SuiteReport>();
+        assert_send_sync::<TimedRoute>();
+        assert_send_sync::<VisibilityMap>();
+        assert_send_sync::<VoronoiRegions>();
+        assert_send_sync::<Watcher>();
+        assert_send_sync::<WeightedRegions>();
+    }
+
+    #[test]
+    fn shared_map_thread_pool() {
+        let map = Arc::new(parse_map_file(Path::new(MAP)).unwrap());
+        let options = Arc::new(SearchOptions::new());
+        let scenes = parse_scen_file(Path::new(SCEN)).unwrap();
+
+        let workers: Vec<_> = (0..4)
+            .map(|worker| {
+                let map = Arc::clone(&map);
+                let options = Arc::clone(&options);
+                let scenes: Vec<_> = scenes
+                    .iter()
+                    .skip(worker)
+                    .step_by(4)
+                    .take(5)
+                    .cloned()
+                    .collect();
+
+                thread::spawn(move || {
+                    //Each worker owns its scratch state, the map and options are shared
+                    let mut context = SearchContext::new();
+                    scenes
+                        .iter()
+                        .map(|scene| {
+                            let route = context
+                                .jps_path(&map, scene.start_pos, scene.goal_pos, &options)
+                                .unwrap();
+                            (scene.optimal_length, route.distance())
+                        })
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+
+        for worker in workers {
+            for (optimal, distance) in worker.join().unwrap() {
+                assert_eq!(optimal as f32, distance as f32);
+            }
+        }
+    }
+}
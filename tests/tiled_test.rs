@@ -0,0 +1,42 @@
+#[cfg(all(test, feature = "tiled"))]
+mod tests {
+    use std::path::Path;
+
+    use blitz_path::grid::Map2D;
+
+    use blitz_path::{map_from_tiled, MapLoadError, TiledObstacles};
+
+    const LEVEL: &str = "./tests/map/tiled.tmx";
+
+    #[test]
+    fn obstacles_from_layer_or_property() {
+        let by_layer = map_from_tiled(Path::new(LEVEL), TiledObstacles::Layer("walls")).unwrap();
+        let by_property =
+            map_from_tiled(Path::new(LEVEL), TiledObstacles::Property("solid")).unwrap();
+
+        for map in &[by_layer, by_property] {
+            assert_eq!((map.width(), map.height()), (5, 4));
+            let open: Vec<_> = map
+                .coords()
+                .filter(|tile| map.is_traversable(*tile))
+                .collect();
+            assert_eq!(open, vec![(1, 1), (2, 1), (3, 1), (1, 2), (3, 2)]);
+        }
+
+        //A layer where every tile is placed blocks everything
+        let ground = map_from_tiled(Path::new(LEVEL), TiledObstacles::Layer("ground")).unwrap();
+        assert!(ground.coords().all(|tile| !ground.is_traversable(tile)));
+    }
+
+    #[test]
+    fn load_errors() {
+        assert_eq!(
+            map_from_tiled(Path::new(LEVEL), TiledObstacles::Layer("doors")).err(),
+            Some(MapLoadError::MissingLayer(String::from("doors")))
+        );
+        assert!(matches!(
+            map_from_tiled(Path::new("missing.tmx"), TiledObstacles::Layer("walls")),
+            Err(MapLoadError::Parse(_))
+        ));
+    }
+}
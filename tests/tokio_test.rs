@@ -0,0 +1,56 @@
+#[cfg(all(test, feature = "tokio", feature = "movingai"))]
+mod tests {
+    use std::path::Path;
+    use std::sync::Arc;
+
+    use movingai::parser::parse_map_file;
+
+    use blitz_path::{
+        a_star_path_async, jps_path, jps_path_async, CancelToken, PathError, SearchOptions,
+    };
+
+    #[test]
+    fn async_searches_match_blocking() {
+        let map = Arc::new(parse_map_file(Path::new("./tests/map/maze512-32-9.map")).unwrap());
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+
+        let expected = jps_path(&map, (1, 1), (20, 20)).unwrap();
+        let (jps, a_star) = runtime.block_on(async {
+            let jps = jps_path_async(map.clone(), (1, 1), (20, 20), SearchOptions::new());
+            let a_star = a_star_path_async(map.clone(), (1, 1), (20, 20), SearchOptions::new());
+            (jps.await.unwrap(), a_star.await.unwrap())
+        });
+        assert_eq!(jps.steps(), expected.steps());
+        assert!((a_star.distance() - jps.distance()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn cancellation() {
+        let map = Arc::new(parse_map_file(Path::new("./tests/map/maze512-32-9.map")).unwrap());
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+
+        //A token passed in through the options is the one polled
+        let token = CancelToken::new();
+        token.cancel();
+        let options = SearchOptions::new().cancel_token(token);
+        let cancelled = runtime
+            .block_on(async { jps_path_async(map.clone(), (1, 1), (20, 20), options).await });
+        assert_eq!(cancelled.err(), Some(PathError::Cancelled));
+
+        //Dropping an unfinished future cancels its search, but finishing one does not
+        let _guard = runtime.enter();
+        let dropped = jps_path_async(map.clone(), (1, 1), (20, 20), SearchOptions::new());
+        let token = dropped.cancel_token().clone();
+        drop(dropped);
+        assert!(token.is_cancelled());
+
+        let finished = jps_path_async(map, (1, 1), (20, 20), SearchOptions::new());
+        let token = finished.cancel_token().clone();
+        assert!(runtime.block_on(finished).is_ok());
+        assert!(!token.is_cancelled());
+    }
+}
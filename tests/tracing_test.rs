@@ -0,0 +1,99 @@
+#[cfg(all(test, feature = "tracing", feature = "movingai"))]
+mod tests {
+    use std::path::Path;
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::{Arc, Mutex};
+
+    use movingai::parser::parse_map_file;
+    use tracing::field::{Field, Visit};
+    use tracing::span::{Attributes, Id, Record};
+    use tracing::{Event, Metadata, Subscriber};
+
+    use blitz_path::{a_star_path, jps_path, PathError};
+
+    const MAP: &str = "./tests/map/maze512-32-9.map";
+
+    //Writes the fields of a span or event as "name=value" pairs
+    struct Fields(String);
+
+    impl Visit for Fields {
+        fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+            self.0.push_str(&format!(" {}={:?}", field.name(), value));
+        }
+    }
+
+    //Keeps a line for every span created and event raised
+    #[derive(Default)]
+    struct Capture {
+        lines: Arc<Mutex<Vec<String>>>,
+        next_id: AtomicU64,
+    }
+
+    impl Subscriber for Capture {
+        fn enabled(&self, _: &Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, span: &Attributes<'_>) -> Id {
+            let mut fields = Fields(format!("span {}", span.metadata().name()));
+            span.record(&mut fields);
+            self.lines.lock().unwrap().push(fields.0);
+            Id::from_u64(self.next_id.fetch_add(1, Ordering::Relaxed) + 1)
+        }
+
+        fn record(&self, _: &Id, _: &Record<'_>) {}
+
+        fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+        fn event(&self, event: &Event<'_>) {
+            let mut fields = Fields(String::from("event"));
+            event.record(&mut fields);
+            self.lines.lock().unwrap().push(fields.0);
+        }
+
+        fn enter(&self, _: &Id) {}
+
+        fn exit(&self, _: &Id) {}
+    }
+
+    //Runs a search, returning the lines it was traced with
+    fn traced<R>(search: impl FnOnce() -> R) -> (R, Vec<String>) {
+        let capture = Capture::default();
+        let lines = Arc::clone(&capture.lines);
+        let result = tracing::subscriber::with_default(capture, search);
+        let lines = lines.lock().unwrap().clone();
+        (result, lines)
+    }
+
+    #[test]
+    fn search_spans() {
+        let map = parse_map_file(Path::new(MAP)).unwrap();
+
+        //Each search opens a span naming the algorithm and endpoints, and reports how much it expanded
+        let (route, lines) = traced(|| a_star_path(&map, (1, 1), (5, 5)).unwrap());
+        assert_eq!(
+            lines[0],
+            "span search algorithm=\"a_star\" start=(1, 1) goal=(5, 5)"
+        );
+        assert_eq!(lines[1], "event message=search started");
+        assert!(lines.contains(&String::from("span prepare")));
+        let finished = lines.last().unwrap();
+        assert!(finished.starts_with("event message=search finished expanded="));
+        assert!(!finished.contains("expanded=0 "));
+        assert!(finished.ends_with(&format!("distance={:?}", route.distance())));
+
+        let (_, lines) = traced(|| jps_path(&map, (1, 1), (5, 5)).unwrap());
+        assert!(lines[0].starts_with("span search algorithm=\"jps\""));
+        assert!(lines
+            .last()
+            .unwrap()
+            .starts_with("event message=search finished"));
+
+        //Failures are reported with their error
+        let (result, lines) = traced(|| a_star_path(&map, (0, 0), (5, 5)));
+        assert_eq!(result.err(), Some(PathError::StartBlocked((0, 0))));
+        let failed = lines.last().unwrap();
+        assert!(failed.starts_with("event message=search failed expanded=0 discovered=0"));
+        assert!(failed.ends_with("error=start (0, 0) is not traversable"));
+    }
+}
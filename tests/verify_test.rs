@@ -0,0 +1,67 @@
+#[cfg(all(test, feature = "verify", feature = "movingai"))]
+mod tests {
+    use std::path::Path;
+    use std::sync::Arc;
+
+    use movingai::parser::{parse_map_file, parse_scen_file};
+    use movingai::{Coords2D, MovingAiMap};
+
+    use blitz_path::{
+        jps_distance, jps_path, jps_path_with, verify_jps, CongestionMap, PruningRule,
+        SearchOptions,
+    };
+
+    const MAP: &str = "./tests/map/maze512-32-9.map";
+    const SCEN: &str = "./tests/map/maze512-32-9.map.scen";
+
+    #[derive(Debug)]
+    struct NoForcedNeighbours;
+
+    impl PruningRule for NoForcedNeighbours {
+        fn forced_horizontal(&self, _: &MovingAiMap, _: Coords2D, _: i32, _: &mut Vec<Coords2D>) {}
+
+        fn forced_vertical(&self, _: &MovingAiMap, _: Coords2D, _: i32, _: &mut Vec<Coords2D>) {}
+    }
+
+    fn walled_map() -> MovingAiMap {
+        let rows = ["@@@@@@", "@....@", "@.@@@@", "@....@", "@@@@@@"];
+        MovingAiMap::new(
+            String::from("octile"),
+            rows.len(),
+            rows[0].len(),
+            rows.iter().flat_map(|row| row.chars()).collect(),
+        )
+    }
+
+    #[test]
+    fn optimal_searches_pass() {
+        let map = parse_map_file(Path::new(MAP)).unwrap();
+        let scenes = parse_scen_file(Path::new(SCEN)).unwrap();
+        for scene in scenes.iter().take(10) {
+            jps_path(&map, scene.start_pos, scene.goal_pos).unwrap();
+            jps_distance(&map, scene.start_pos, scene.goal_pos, &SearchOptions::new()).unwrap();
+        }
+    }
+
+    #[test]
+    fn step_costs_ignored() {
+        //JPS takes every step at its length, so the congestion along the bottom corridor is not compared
+        let map = walled_map();
+        let mut congestion = CongestionMap::new(&map, 1.0, 0.5);
+        congestion.claim_route(&jps_path(&map, (1, 3), (4, 3)).unwrap());
+        let options = SearchOptions::new()
+            .congestion(Arc::new(congestion))
+            .avoid_swamps(true);
+
+        let route = jps_path_with(&map, (1, 1), (4, 3), &options).unwrap();
+        assert_eq!(route.distance(), 5.0);
+        assert_eq!(verify_jps(&map, (1, 1), (4, 3), &options), Ok(()));
+    }
+
+    #[test]
+    #[should_panic(expected = "from (4, 1) to (4, 3) on a 6x5 map")]
+    fn discrepancies_panic() {
+        let options = SearchOptions::new().jps_pruning(NoForcedNeighbours);
+        let _ = jps_path_with(&walled_map(), (4, 1), (4, 3), &options);
+    }
+}